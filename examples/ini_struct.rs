@@ -0,0 +1,108 @@
+//! Bind the properties of an INI file to the fields of a struct with `#[derive(Bind)]`, where the schema defines a rule
+//! for the value of each known key, so that the value is validated by the syntax, and then by a guard.
+//!
+//! ```text
+//! cargo run --example ini_struct
+//! ```
+use std::time::Duration;
+use terp::bind::{bind, BindError};
+use terp::schema::chars::{ascii_digit, ch, newline, none_of_chars, one_of_chars, token};
+use terp::schema::{id, Schema};
+use terp::Error;
+use terp_derive::Bind;
+
+const SAMPLE: &str = r#"; the front server
+[server]
+host = example.com
+port = 8080
+timeout = 1500ms
+allow = 10.0.0.0/8
+allow = 192.168.0.0/16
+"#;
+
+#[derive(Debug, Default, PartialEq, Bind)]
+struct Server {
+  #[terp(rule = "Name")]
+  section: Option<String>,
+  #[terp(rule = "Host")]
+  host: String,
+  #[terp(rule = "Port", guard = "nonzero")]
+  port: u16,
+  #[terp(rule = "Workers")]
+  workers: Option<usize>,
+  #[terp(rule = "Timeout", with = "duration")]
+  timeout: Duration,
+  #[terp(rule = "Allow")]
+  allows: Vec<String>,
+}
+
+fn nonzero(port: &u16) -> bool {
+  *port != 0
+}
+
+fn duration(text: &str) -> Result<Duration, std::num::ParseIntError> {
+  match text.strip_suffix("ms") {
+    Some(millis) => millis.parse().map(Duration::from_millis),
+    None => text.trim_end_matches('s').parse().map(Duration::from_secs),
+  }
+}
+
+/// The INI file whose properties are limited to the known keys, each of which has the syntax of its value.
+///
+fn schema() -> Schema<&'static str, char> {
+  let property = |key: &'static str, value: &'static str| token(key) & id("WS") & ch('=') & id("WS") & id(value);
+  let text = || none_of_chars(" \t\r\n;#") * (1..);
+  Schema::new("Server INI")
+    .define("File", id("Line") & ((newline() & id("Line")) * (0..)))
+    .define("Line", id("WS") & ((id("Section") | id("Property") | id("Comment")) * (0..=1)) & id("WS"))
+    .define("Section", ch('[') & id("Name") & ch(']'))
+    .define("Name", none_of_chars("]\r\n") * (1..))
+    .define(
+      "Property",
+      property("host", "Host")
+        | property("port", "Port")
+        | property("workers", "Workers")
+        | property("timeout", "Timeout")
+        | property("allow", "Allow"),
+    )
+    .define("Host", text())
+    .define("Port", ascii_digit() * (1..=5))
+    .define("Workers", ascii_digit() * (1..))
+    .define("Timeout", (ascii_digit() * (1..)) & ((token("ms") | token("s")) * (0..=1)))
+    .define("Allow", text())
+    .define("Comment", one_of_chars(";#") & (none_of_chars("\r\n") * (0..)))
+    .define("WS", one_of_chars(" \t") * (0..))
+}
+
+fn main() {
+  let schema = schema();
+  let server = bind::<Server, _>(&schema, "File", SAMPLE).unwrap();
+  assert_eq!(
+    Server {
+      section: Some(String::from("server")),
+      host: String::from("example.com"),
+      port: 8080,
+      workers: None,
+      timeout: Duration::from_millis(1500),
+      allows: vec![String::from("10.0.0.0/8"), String::from("192.168.0.0/16")],
+    },
+    server
+  );
+  println!("{:?}", server);
+
+  // the syntax accepts the port 0, but the guard rejects it
+  match bind::<Server, _>(&schema, "File", "port = 0\n") {
+    Err(Error::Handler(err)) => {
+      let err = err.get_ref().downcast_ref::<BindError>().unwrap();
+      assert_eq!(("Port", "0"), (err.rule.as_str(), err.text.as_str()));
+      println!("{}", err);
+    }
+    unexpected => panic!("{:?}", unexpected),
+  }
+
+  // the unknown key is rejected by the syntax
+  match bind::<Server, _>(&schema, "File", "user = root\n") {
+    Err(err) => println!("{}", err),
+    unexpected => panic!("{:?}", unexpected),
+  }
+}
@@ -0,0 +1,93 @@
+//! Parse S-expressions and build a generic nested value from the events.
+//!
+//! ```text
+//! cargo run --example sexpr
+//! ```
+use terp::parser::{Context, Event, EventKind};
+use terp::schema::sexpr::{schema, ID};
+
+const SAMPLE: &str = r#"
+; the factorial
+(define (fact n)
+  (if (<= n 1)
+      1
+      (* n (fact (- n 1)))))
+(display "fact(5) = \"120\"")
+"#;
+
+/// A generic nested value of the S-expressions.
+///
+#[derive(Debug, PartialEq)]
+enum Value {
+  Atom(String),
+  String(String),
+  List(Vec<Value>),
+}
+
+/// Build the [`Value`]s from the Begin and End events, keeping the lists under construction on a stack.
+///
+struct TreeBuilder {
+  stack: Vec<Vec<Value>>,
+  text: Option<String>,
+}
+
+impl TreeBuilder {
+  fn new() -> Self {
+    TreeBuilder { stack: vec![Vec::new()], text: None }
+  }
+
+  fn handle(&mut self, e: &Event<ID, char>) {
+    match &e.kind {
+      EventKind::Begin(ID::List) => self.stack.push(Vec::new()),
+      EventKind::End(ID::List) => {
+        let list = self.stack.pop().unwrap();
+        self.push(Value::List(list));
+      }
+      EventKind::Begin(ID::Atom | ID::String) => self.text = Some(String::new()),
+      EventKind::End(ID::Atom) => {
+        let atom = self.text.take().unwrap();
+        self.push(Value::Atom(atom));
+      }
+      EventKind::End(ID::String) => {
+        let string = self.text.take().unwrap();
+        self.push(Value::String(unescape(&string[1..string.len() - 1])));
+      }
+      EventKind::Fragments(chars) => {
+        if let Some(text) = &mut self.text {
+          text.extend(chars);
+        }
+      }
+      _ => (),
+    }
+  }
+
+  fn push(&mut self, value: Value) {
+    self.stack.last_mut().unwrap().push(value);
+  }
+
+  fn build(mut self) -> Vec<Value> {
+    assert_eq!(1, self.stack.len());
+    self.stack.pop().unwrap()
+  }
+}
+
+fn unescape(text: &str) -> String {
+  let mut unescaped = String::with_capacity(text.len());
+  let mut chars = text.chars();
+  while let Some(ch) = chars.next() {
+    unescaped.push(if ch == '\\' { chars.next().unwrap_or(ch) } else { ch });
+  }
+  unescaped
+}
+
+fn main() {
+  let schema = schema();
+  let mut builder = TreeBuilder::new();
+  let handler = |e: &Event<ID, char>| builder.handle(e);
+  let mut parser = Context::new(&schema, ID::SExpr, handler).unwrap().ignore_events_for(&[ID::Comment, ID::WS]);
+  parser.push_str(SAMPLE).unwrap();
+  parser.finish().unwrap();
+  for value in builder.build() {
+    println!("{:?}", value);
+  }
+}
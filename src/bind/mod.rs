@@ -0,0 +1,131 @@
+//! Data binding that populates the fields of a struct with the text matched by the rules of a schema while parsing.
+//! The [`Bind`] implementation is usually generated by `#[derive(Bind)]` of the `terp-derive` crate, where each field
+//! is bound to a rule with `#[terp(rule = "...")]`:
+//!
+//! ```text
+//! #[derive(Default, Bind)]
+//! struct Server {
+//!   #[terp(rule = "Host")]
+//!   host: String,
+//!   #[terp(rule = "Port", guard = "nonzero")]
+//!   port: u16,
+//!   #[terp(rule = "Allow")]
+//!   allows: Vec<String>,
+//! }
+//! ```
+//!
+//! The text of a rule is captured from the fragments between its [`Begin`](EventKind::Begin) and
+//! [`End`](EventKind::End), decoded by [`FromStr`] or the function specified with `with = "..."`, and checked by the
+//! function specified with `guard = "..."`. A field of `Option<T>` is set to `Some` and a field of `Vec<T>` collects
+//! all the occurrences, otherwise the last occurrence is assigned.
+//!
+//! See `examples/ini_struct.rs` for the case study binding an INI file to a struct.
+//!
+use crate::parser::{Context, Event, EventKind};
+use crate::schema::chars::Location;
+use crate::schema::Schema;
+use crate::Result;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::str::FromStr;
+
+#[cfg(test)]
+mod test;
+
+/// A struct whose fields are populated with the text matched by the rules.
+///
+pub trait Bind {
+  /// The names of the rules bound to the fields, as displayed by the ID of the schema.
+  fn rules() -> &'static [&'static str];
+
+  /// Decode the `text` matched by the `rule` and assign it to the fields bound to it.
+  fn bind(&mut self, rule: &str, text: &str) -> std::result::Result<(), String>;
+}
+
+/// The failure to decode the text of a rule or the rejection of it by a guard, which is the source of
+/// [`Error::Handler`](crate::Error::Handler) returned by the parser.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BindError {
+  pub location: Location,
+  pub rule: String,
+  pub text: String,
+  pub message: String,
+}
+
+impl Display for BindError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} {} {:?} cannot be bound: {}", self.location, self.rule, self.text, self.message)
+  }
+}
+
+impl std::error::Error for BindError {}
+
+/// The event handler that captures the text of the rules bound to the fields of `T` and binds them.
+///
+pub struct Binder<T: Bind> {
+  target: T,
+  /// The rules being captured and the locations where they begin, nested if the bound rules are nested.
+  captures: Vec<(String, Location, String)>,
+}
+
+impl<T: Bind> Binder<T> {
+  pub fn new(target: T) -> Self {
+    Self { target, captures: Vec::new() }
+  }
+
+  pub fn handle<ID>(&mut self, e: &Event<ID, char>) -> std::result::Result<(), BindError>
+  where
+    ID: Clone + Display + Debug + Eq + Hash,
+  {
+    match &e.kind {
+      EventKind::Begin(id) => {
+        let rule = id.to_string();
+        if T::rules().contains(&rule.as_str()) {
+          self.captures.push((rule, e.location, String::new()));
+        }
+      }
+      EventKind::Fragments(chars) => {
+        for (_, _, text) in self.captures.iter_mut() {
+          text.extend(chars);
+        }
+      }
+      EventKind::End(id) => {
+        if matches!(self.captures.last(), Some((rule, _, _)) if *rule == id.to_string()) {
+          let (rule, location, text) = self.captures.pop().unwrap();
+          self.target.bind(&rule, &text).map_err(|message| BindError { location, rule, text, message })?;
+        }
+      }
+    }
+    Ok(())
+  }
+
+  pub fn into_inner(self) -> T {
+    self.target
+  }
+}
+
+/// Parse the `text` with the syntax `id` of the `schema` and populate a `T` with it. The error of the binding is
+/// returned as [`Error::Handler`](crate::Error::Handler) whose source is a [`BindError`].
+///
+pub fn bind<T, ID>(schema: &Schema<ID, char>, id: ID, text: &str) -> Result<char, T>
+where
+  T: Bind + Default,
+  ID: Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
+{
+  let mut binder = Binder::new(T::default());
+  let mut parser = Context::try_new(schema, id, |e: &Event<_, _>| binder.handle(e))?;
+  parser.push_str(text)?;
+  parser.finish()?;
+  Ok(binder.into_inner())
+}
+
+/// Decode the `text` by [`FromStr`], the default decoder of the fields.
+///
+pub fn decode<T>(text: &str) -> std::result::Result<T, String>
+where
+  T: FromStr,
+  T::Err: Display,
+{
+  text.parse::<T>().map_err(|err| err.to_string())
+}
@@ -0,0 +1,95 @@
+use super::{bind, decode, Bind, BindError};
+use crate::schema::chars::{ascii_digit, ch, token, Location};
+use crate::schema::{id, Schema};
+use crate::Error;
+
+/// The implementation that `#[derive(Bind)]` would generate.
+#[derive(Debug, Default, PartialEq)]
+struct Point {
+  x: i32,
+  y: Option<i32>,
+  tags: Vec<String>,
+}
+
+impl Bind for Point {
+  fn rules() -> &'static [&'static str] {
+    &["X", "Y", "Tag"]
+  }
+
+  fn bind(&mut self, rule: &str, text: &str) -> std::result::Result<(), String> {
+    match rule {
+      "X" => self.x = decode(text)?,
+      "Y" => {
+        let value = decode(text)?;
+        if value < 0 {
+          return Err(String::from("rejected by the guard"));
+        }
+        self.y = Some(value);
+      }
+      "Tag" => self.tags.push(decode(text)?),
+      _ => (),
+    }
+    Ok(())
+  }
+}
+
+fn schema() -> Schema<&'static str, char> {
+  Schema::new("Point")
+    .define("POINT", id("X") & ((ch(',') & id("Y")) * (0..=1)) & (id("TAG") * (0..)))
+    .define("X", id("NUMBER"))
+    .define("Y", id("NUMBER"))
+    .define("NUMBER", (ch('-') * (0..=1)) & (ascii_digit() * (1..)))
+    .define("TAG", ch('#') & id("Tag"))
+    .define("Tag", token("a") | token("b"))
+}
+
+#[test]
+fn bind_rules_to_fields() {
+  let schema = schema();
+  assert_eq!(Point { x: 12, y: None, tags: vec![] }, bind(&schema, "POINT", "12").unwrap());
+  assert_eq!(
+    Point { x: -1, y: Some(345), tags: vec![String::from("b"), String::from("a")] },
+    bind(&schema, "POINT", "-1,345#b#a").unwrap()
+  );
+}
+
+#[test]
+fn bind_error() {
+  let schema = schema();
+  for (text, expected) in [
+    (
+      "99999999999",
+      BindError {
+        location: Location::new(0, 0, 0),
+        rule: String::from("X"),
+        text: String::from("99999999999"),
+        message: String::from("number too large to fit in target type"),
+      },
+    ),
+    (
+      "1,-2",
+      BindError {
+        location: Location::new(2, 0, 2),
+        rule: String::from("Y"),
+        text: String::from("-2"),
+        message: String::from("rejected by the guard"),
+      },
+    ),
+  ] {
+    match bind::<Point, _>(&schema, "POINT", text) {
+      Err(Error::Handler(err)) => assert_eq!(Some(&expected), err.get_ref().downcast_ref::<BindError>()),
+      unexpected => panic!("{:?}", unexpected),
+    }
+  }
+  assert_eq!(
+    "(1,3) Y \"-2\" cannot be bound: rejected by the guard",
+    BindError {
+      location: Location::new(2, 0, 2),
+      rule: String::from("Y"),
+      text: String::from("-2"),
+      message: String::from("rejected by the guard"),
+    }
+    .to_string()
+  );
+  assert!(matches!(bind::<Point, _>(&schema, "POINT", "1,"), Err(Error::Unmatched(_))));
+}
@@ -0,0 +1,214 @@
+//! Lowering of the events into a lossless concrete syntax tree, such as a green tree of
+//! [rowan](https://crates.io/crates/rowan) or [cstree](https://crates.io/crates/cstree), so that terp can be the
+//! parser behind a language server. Each rule is lowered by a mapping function into a node, a token or nothing of its
+//! own, and the fragments are lowered into the tokens so that the text of the tree is exactly the input.
+//!
+//! The tree is built through [`GreenBuilder`], which is implemented for the builder of the tree library with a few
+//! lines, e.g. for rowan:
+//!
+//! ```text
+//! struct Rowan(rowan::GreenNodeBuilder<'static>);
+//!
+//! impl GreenBuilder for Rowan {
+//!   type Kind = SyntaxKind;
+//!   fn start_node(&mut self, kind: SyntaxKind) { self.0.start_node(kind.into()) }
+//!   fn token(&mut self, kind: SyntaxKind, text: &str) { self.0.token(kind.into(), text) }
+//!   fn finish_node(&mut self) { self.0.finish_node() }
+//! }
+//!
+//! let green = lower(&schema, "File", text, Rowan(GreenNodeBuilder::new()), SyntaxKind::Text, |id| ...)?.0.finish();
+//! ```
+//!
+//! The following builder writes the tree as an S-expression instead:
+//!
+//! ```rust
+//! use terp::cst::{lower, GreenBuilder, Lower};
+//! use terp::schema::{Schema, id};
+//! use terp::schema::chars::{ch, ascii_digit};
+//!
+//! #[derive(Default)]
+//! struct SExpr(String);
+//!
+//! impl GreenBuilder for SExpr {
+//!   type Kind = &'static str;
+//!   fn start_node(&mut self, kind: &'static str) { self.0.push_str(&format!("({}", kind)) }
+//!   fn token(&mut self, kind: &'static str, text: &str) { self.0.push_str(&format!(" {}:{:?}", kind, text)) }
+//!   fn finish_node(&mut self) { self.0.push(')') }
+//! }
+//!
+//! let schema = Schema::new("List")
+//!   .define("List", ch('[') & id("Item") & ((ch(',') & id("Item")) * (0..)) & ch(']'))
+//!   .define("Item", ascii_digit() * (1..));
+//! let tree = lower(&schema, "List", "[1,23]", SExpr::default(), "TEXT", |id: &&str| match *id {
+//!   "List" => Lower::Node("LIST"),
+//!   _ => Lower::Token("NUMBER"),
+//! }).unwrap();
+//! assert_eq!(r#"(LIST TEXT:"[" NUMBER:"1" TEXT:"," NUMBER:"23" TEXT:"]")"#, tree.0);
+//! ```
+//!
+use crate::parser::{Context, Event, EventKind};
+use crate::schema::chars::Location;
+use crate::schema::Schema;
+use crate::{Error, HandlerError, Result};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+#[cfg(test)]
+mod test;
+
+/// The builder of a green tree, which corresponds to `GreenNodeBuilder` of rowan and cstree.
+///
+pub trait GreenBuilder {
+  /// The kind of the nodes and tokens, such as `SyntaxKind`.
+  type Kind: Copy;
+
+  fn start_node(&mut self, kind: Self::Kind);
+
+  fn token(&mut self, kind: Self::Kind, text: &str);
+
+  fn finish_node(&mut self);
+}
+
+/// How a rule is lowered into the tree.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Lower<K> {
+  /// The rule becomes a node of the kind, whose children are lowered from the events within it.
+  Node(K),
+  /// The whole text of the rule becomes a single token of the kind, including the text of the rules within it.
+  Token(K),
+  /// The rule doesn't appear in the tree, so that its children are lowered in its place.
+  Inline,
+}
+
+/// The failure to preserve the spans of the input, which occurs if some fragments aren't delivered, e.g., hidden by
+/// [`Ignore::Drop`](crate::parser::Ignore::Drop). It's the source of [`Error::Handler`](crate::Error::Handler)
+/// returned by the parser.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpanError {
+  pub location: Location,
+  /// The number of characters lowered into the tree before the location.
+  pub offset: u64,
+}
+
+impl Display for SpanError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} the fragments since the {}-th character are missing from the tree", self.location, self.offset)
+  }
+}
+
+impl std::error::Error for SpanError {}
+
+/// The event handler that lowers the events into the tree built by `B`, where `lowering` maps each rule ID to how
+/// it's lowered, and the fragments that aren't in any [`Lower::Token`] rule become the tokens of `text`.
+///
+pub struct Lowerer<B: GreenBuilder, F> {
+  builder: B,
+  lowering: F,
+  text: B::Kind,
+  /// How the rules being lowered were lowered, where the rules within a token are `Inline`.
+  frames: Vec<Lower<B::Kind>>,
+  /// The kind of the token being captured, if any.
+  token: Option<B::Kind>,
+  /// The text not yet written to the builder, which is merged into a single token.
+  pending: String,
+  offset: u64,
+}
+
+impl<B: GreenBuilder, F> Lowerer<B, F> {
+  pub fn new(builder: B, text: B::Kind, lowering: F) -> Self {
+    Self { builder, lowering, text, frames: Vec::new(), token: None, pending: String::new(), offset: 0 }
+  }
+
+  pub fn handle<ID>(&mut self, e: &Event<ID, char>) -> std::result::Result<(), SpanError>
+  where
+    ID: Clone + Display + Debug + Eq + Hash,
+    F: FnMut(&ID) -> Lower<B::Kind>,
+  {
+    match &e.kind {
+      EventKind::Begin(id) => {
+        if self.token.is_some() {
+          self.frames.push(Lower::Inline);
+          return Ok(());
+        }
+        let lower = (self.lowering)(id);
+        match lower {
+          Lower::Node(kind) => {
+            self.flush();
+            self.builder.start_node(kind);
+          }
+          Lower::Token(kind) => {
+            self.flush();
+            self.token = Some(kind);
+          }
+          Lower::Inline => (),
+        }
+        self.frames.push(lower);
+      }
+      EventKind::Fragments(chars) => {
+        if e.location.chars != self.offset {
+          return Err(SpanError { location: e.location, offset: self.offset });
+        }
+        self.pending.extend(chars);
+        self.offset += chars.len() as u64;
+      }
+      EventKind::End(_) => match self.frames.pop() {
+        Some(Lower::Node(_)) => {
+          self.flush();
+          self.builder.finish_node();
+        }
+        Some(Lower::Token(_)) => {
+          self.flush();
+          self.token = None;
+        }
+        Some(Lower::Inline) | None => (),
+      },
+    }
+    Ok(())
+  }
+
+  /// Write the remaining text and return the builder.
+  ///
+  pub fn into_inner(mut self) -> B {
+    self.flush();
+    self.builder
+  }
+
+  /// Write the remaining text and return the builder, or fail if the fragments before `end` haven't been delivered,
+  /// e.g., at the end of the input, which the events don't tell. `end` is the location where the parse ended, such as
+  /// [`ParseSummary::location`](crate::parser::ParseSummary::location).
+  ///
+  pub fn finish(self, end: Location) -> std::result::Result<B, SpanError> {
+    if end.chars != self.offset {
+      return Err(SpanError { location: end, offset: self.offset });
+    }
+    Ok(self.into_inner())
+  }
+
+  fn flush(&mut self) {
+    if !self.pending.is_empty() {
+      self.builder.token(self.token.unwrap_or(self.text), &self.pending);
+      self.pending.clear();
+    }
+  }
+}
+
+/// Parse the `text` with the syntax `id` of the `schema` and lower the events into the tree built by `builder`, see
+/// [`Lowerer`]. The fragments outside the tokens become the tokens of `text_kind`. The tree is rooted at the node of
+/// `id` if it's lowered into a node. It fails with [`SpanError`] if any of the `text` is missing from the tree.
+///
+pub fn lower<B, F, ID>(
+  schema: &Schema<ID, char>, id: ID, text: &str, builder: B, text_kind: B::Kind, lowering: F,
+) -> Result<char, B>
+where
+  B: GreenBuilder,
+  F: FnMut(&ID) -> Lower<B::Kind>,
+  ID: Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
+{
+  let mut lowerer = Lowerer::new(builder, text_kind, lowering);
+  let mut parser = Context::try_new(schema, id, |e: &Event<_, _>| lowerer.handle(e))?;
+  parser.push_str(text)?;
+  let summary = parser.finish()?;
+  lowerer.finish(summary.location).map_err(|err| Error::Handler(HandlerError::new(err)))
+}
@@ -0,0 +1,119 @@
+use super::{lower, GreenBuilder, Lower, Lowerer, SpanError};
+use crate::parser::{Context, Event, Ignore};
+use crate::schema::chars::{ascii_digit, ch, one_of_chars, Location};
+use crate::schema::{id, Schema};
+use crate::Error;
+
+/// The builder that writes the tree as an S-expression.
+#[derive(Default)]
+struct SExpr(String);
+
+impl GreenBuilder for SExpr {
+  type Kind = &'static str;
+  fn start_node(&mut self, kind: &'static str) {
+    if !self.0.is_empty() {
+      self.0.push(' ');
+    }
+    self.0.push_str(&format!("({}", kind));
+  }
+  fn token(&mut self, kind: &'static str, text: &str) {
+    self.0.push_str(&format!(" {}:{:?}", kind, text));
+  }
+  fn finish_node(&mut self) {
+    self.0.push(')');
+  }
+}
+
+fn schema() -> Schema<&'static str, char> {
+  Schema::new("Sum")
+    .define("SUM", id("TERM") & ((id("OP") & id("TERM")) * (0..)))
+    .define("TERM", id("WS") & (id("NUMBER") | id("PAREN")) & id("WS"))
+    .define("PAREN", ch('(') & id("SUM") & ch(')'))
+    .define("OP", one_of_chars("+-"))
+    .define("NUMBER", (ch('-') * (0..=1)) & id("DIGITS"))
+    .define("DIGITS", ascii_digit() * (1..))
+    .define("WS", ch(' ') * (0..))
+}
+
+fn lowering(id: &&str) -> Lower<&'static str> {
+  match *id {
+    "SUM" => Lower::Node("SUM"),
+    "PAREN" => Lower::Node("PAREN"),
+    "OP" => Lower::Token("OP"),
+    "NUMBER" => Lower::Token("NUMBER"),
+    "WS" => Lower::Token("WS"),
+    _ => Lower::Inline,
+  }
+}
+
+#[test]
+fn lower_into_tree() {
+  let schema = schema();
+  for (text, expected) in [
+    ("1", r#"(SUM NUMBER:"1")"#),
+    ("1+-23", r#"(SUM NUMBER:"1" OP:"+" NUMBER:"-23")"#),
+    (
+      " 1 - (2+3)",
+      r#"(SUM WS:" " NUMBER:"1" WS:" " OP:"-" WS:" " (PAREN TEXT:"(" (SUM NUMBER:"2" OP:"+" NUMBER:"3") TEXT:")"))"#,
+    ),
+  ] {
+    let tree = lower(&schema, "SUM", text, SExpr::default(), "TEXT", lowering).unwrap();
+    assert_eq!(expected, tree.0, "{:?}", text);
+  }
+}
+
+#[test]
+fn lower_losslessly() {
+  let schema = schema();
+  let text = " 1 -( 2+ (3 - -45 ) )+6 ";
+
+  /// The builder that concatenates the text of the tokens.
+  struct Text(String, usize);
+  impl GreenBuilder for Text {
+    type Kind = ();
+    fn start_node(&mut self, _: ()) {
+      self.1 += 1;
+    }
+    fn token(&mut self, _: (), text: &str) {
+      self.0.push_str(text);
+    }
+    fn finish_node(&mut self) {
+      self.1 -= 1;
+    }
+  }
+
+  for lowering in [|_: &&str| Lower::Node(()), |_: &&str| Lower::Inline, |_: &&str| Lower::Token(())] {
+    let tree = lower(&schema, "SUM", text, Text(String::new(), 0), (), lowering).unwrap();
+    assert_eq!((text, 0), (tree.0.as_str(), tree.1));
+  }
+}
+
+#[test]
+fn span_error_for_dropped_fragments() {
+  let schema = schema();
+  let mut lowerer = Lowerer::new(SExpr::default(), "TEXT", lowering);
+  let mut parser = Context::try_new(&schema, "SUM", |e: &Event<_, _>| lowerer.handle(e))
+    .unwrap()
+    .ignore_events_with(&["WS"], Ignore::Drop);
+  let err = parser.push_str("1 +2").and_then(|_| parser.finish()).unwrap_err();
+  match err {
+    Error::Handler(err) => {
+      let err = err.get_ref().downcast_ref::<SpanError>().unwrap();
+      assert_eq!(SpanError { location: Location::new(2, 0, 2), offset: 1 }, *err);
+    }
+    unexpected => panic!("{:?}", unexpected),
+  }
+}
+
+#[test]
+fn span_error_for_dropped_trailing_fragments() {
+  let schema = schema();
+  let mut lowerer = Lowerer::new(SExpr::default(), "TEXT", lowering);
+  let mut parser = Context::try_new(&schema, "SUM", |e: &Event<_, _>| lowerer.handle(e))
+    .unwrap()
+    .ignore_events_with(&["WS"], Ignore::Drop);
+  parser.push_str("12   ").unwrap();
+  let summary = parser.finish().unwrap();
+  let err = lowerer.finish(summary.location).map(|_| ()).unwrap_err();
+  assert_eq!(SpanError { location: Location::new(5, 0, 5), offset: 2 }, err);
+}
@@ -39,63 +39,204 @@
 //! parser.finish().unwrap();
 //!
 //! let expected = vec![
-//!   Event{ kind: EventKind::Begin("String"),                location: Location{ chars: 0, lines: 0, columns: 0} },
-//!   Event{ kind: EventKind::Begin("Quote"),                 location: Location{ chars: 0, lines: 0, columns: 0} },
-//!   Event{ kind: EventKind::Fragments(vec!['\"']),          location: Location{ chars: 0, lines: 0, columns: 0} },
-//!   Event{ kind: EventKind::End("Quote"),                   location: Location{ chars: 1, lines: 0, columns: 1} },
-//!   Event{ kind: EventKind::Fragments(vec!['f', 'o', 'o']), location: Location{ chars: 1, lines: 0, columns: 1} },
-//!   Event{ kind: EventKind::Begin("Quote"),                 location: Location{ chars: 4, lines: 0, columns: 4} },
-//!   Event{ kind: EventKind::Fragments(vec!['\"']),          location: Location{ chars: 4, lines: 0, columns: 4} },
-//!   Event{ kind: EventKind::End("Quote"),                   location: Location{ chars: 5, lines: 0, columns: 5} },
-//!   Event{ kind: EventKind::End("String"),                  location: Location{ chars: 5, lines: 0, columns: 5} },
+//!   Event{ kind: EventKind::Begin("String"),                location: Location::new(0, 0, 0), end: None },
+//!   Event{ kind: EventKind::Begin("Quote"),                 location: Location::new(0, 0, 0), end: None },
+//!   Event{ kind: EventKind::Fragments(vec!['\"']),          location: Location::new(0, 0, 0), end: None },
+//!   Event{ kind: EventKind::End("Quote"),                   location: Location::new(1, 0, 1), end: None },
+//!   Event{ kind: EventKind::Fragments(vec!['f', 'o', 'o']), location: Location::new(1, 0, 1), end: None },
+//!   Event{ kind: EventKind::Begin("Quote"),                 location: Location::new(4, 0, 4), end: None },
+//!   Event{ kind: EventKind::Fragments(vec!['\"']),          location: Location::new(4, 0, 4), end: None },
+//!   Event{ kind: EventKind::End("Quote"),                   location: Location::new(5, 0, 5), end: None },
+//!   Event{ kind: EventKind::End("String"),                  location: Location::new(5, 0, 5), end: None },
 //! ];
 //! assert_eq!(expected, Event::normalize(&events));
 //! ```
 //!
+//! ## Stability
+//!
+//! The items exported by [`prelude`] are the stable API of terp. The other public modules, such as the schemas of the
+//! specific formats and the integrations with the other crates, may change in a minor version until they're promoted
+//! to the prelude. The internals of the parser, such as the paths being evaluated and their event buffers, aren't
+//! public at all.
+//!
 #![allow(uncommon_codepoints)]
 
 use schema::Symbol;
+use std::fmt::Display;
+use std::sync::Arc;
+
+/// Enter a `tracing` span that lasts until the end of the current block, if the `tracing` feature is enabled.
+///
+#[cfg(feature = "tracing")]
+macro_rules! span {
+  ($($arg:tt)*) => {
+    let _span = tracing::trace_span!($($arg)*).entered();
+  };
+}
 
+#[cfg(not(feature = "tracing"))]
+macro_rules! span {
+  ($($arg:tt)*) => {};
+}
+
+pub mod bind;
+pub mod cst;
 pub mod parser;
+pub mod pipelines;
+pub mod prelude;
 pub mod schema;
 
+#[cfg(feature = "serde")]
+mod serialize;
+
 #[cfg(test)]
 mod test;
 
-#[cfg(debug_assertions)]
-#[macro_export]
+#[cfg(feature = "tracing")]
+use tracing as __tracing;
+
+/// With the `tracing` feature, the parse decisions are reported as `tracing` events at the TRACE level instead.
+///
+#[cfg(feature = "tracing")]
+macro_rules! debug {
+  () => {};
+  ($fmt:expr) => { $crate::__tracing::trace!("{}", $fmt) };
+  ($fmt:expr, $($arg:tt)*) => { $crate::__tracing::trace!($fmt, $($arg)*) };
+}
+
+#[cfg(all(debug_assertions, not(feature = "tracing")))]
 macro_rules! debug {
   () => { eprintln!("[{:20}:{:3}]", file!(), line!()) };
   ($fmt:expr) => {{ eprintln!("[{:20}:{:3}] {}", file!(), line!(), $fmt) }};
   ($fmt:expr, $($arg:tt)*) => {{ let s = format!($fmt, $($arg)*); eprintln!("[{:20}:{:3}] {}", file!(), line!(), s); }};
 }
 
-#[cfg(not(debug_assertions))]
-#[macro_export]
+/// The arguments are type-checked but never evaluated, since they may allocate, e.g. the labels of the symbols.
+///
+#[cfg(all(not(debug_assertions), not(feature = "tracing")))]
 macro_rules! debug {
-  ($first:expr) => {{ let _ = &$first; }};
-  ($first:expr, $($arg:expr),*) => {{
-    debug!($first);
-    debug!($($arg),+);
+  () => {};
+  ($($arg:tt)*) => {{
+    if false {
+      let _ = format_args!($($arg)*);
+    }
   }};
 }
 
+pub(crate) use debug;
+
+/// The details of an [`Error::Unmatched`] or [`Error::MultipleMatches`].
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ErrorDetail<Σ: Symbol> {
+  pub location: Σ::Location,
+  /// The label of the symbols preceding the `location`.
+  pub prefix: String,
+  /// The labels of the symbols expected, or of the syntaxes matched, at the `location`.
+  pub expecteds: Vec<String>,
+  /// The stacks of the syntaxes being evaluated for each of the `expecteds`.
+  pub expected_syntaxes: Vec<String>,
+  /// The label of the symbols actually appeared at the `location`, followed by up to
+  /// [`Sampling::actual_length`](crate::parser::Sampling::actual_length) symbols.
+  pub actual: String,
+}
+
+/// The error returned by the handler of [`Context::try_new()`](crate::parser::Context::try_new). It's shared when the
+/// [`Error`] is cloned, and compared by identity.
+///
+#[derive(Clone, Debug)]
+pub struct HandlerError(Arc<dyn std::error::Error + Send + Sync>);
+
+impl HandlerError {
+  pub fn new<E: Into<Box<dyn std::error::Error + Send + Sync>>>(err: E) -> Self {
+    Self(Arc::from(err.into()))
+  }
+
+  /// The error returned by the handler, which can be downcast to its concrete type.
+  ///
+  pub fn get_ref(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+    self.0.as_ref()
+  }
+}
+
+impl Display for HandlerError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    Display::fmt(&self.0, f)
+  }
+}
+
+impl std::error::Error for HandlerError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    self.0.source()
+  }
+}
+
+impl PartialEq for HandlerError {
+  fn eq(&self, other: &Self) -> bool {
+    Arc::ptr_eq(&self.0, &other.0)
+  }
+}
+
+impl Eq for HandlerError {}
+
+/// The panic of an event handler caught by
+/// [`Context::catch_handler_panics()`](crate::parser::Context::catch_handler_panics), which is the source of
+/// [`Error::Handler`].
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandlerPanic {
+  /// The message of the panic, if it's a string.
+  pub message: String,
+}
+
+impl HandlerPanic {
+  pub(crate) fn new(payload: Box<dyn std::any::Any + Send>) -> Self {
+    let message = match payload.downcast::<String>() {
+      Ok(message) => *message,
+      Err(payload) => payload.downcast_ref::<&str>().map(|s| s.to_string()).unwrap_or_default(),
+    };
+    Self { message }
+  }
+}
+
+impl Display for HandlerPanic {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "panicked: {}", self.message)
+  }
+}
+
+impl std::error::Error for HandlerPanic {}
+
 pub type Result<Σ, T> = std::result::Result<T, Error<Σ>>;
 
 #[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
 pub enum Error<Σ: Symbol> {
-  #[error("{location} {prefix}{expecteds:?} expected, but {prefix}{actual} appeared")]
-  Unmatched {
-    location: Σ::Location,
-    prefix: String,
-    expecteds: Vec<String>,
-    expected_syntaxes: Vec<String>,
-    actual: String,
-  },
-  #[error("{location} multiple syntax matches were found")]
-  MultipleMatches { location: Σ::Location, prefix: String, expecteds: Vec<String>, actual: String },
+  #[error("{} {}{:?} expected, but {}{} appeared", .0.location, .0.prefix, .0.expecteds, .0.prefix, .0.actual)]
+  Unmatched(Box<ErrorDetail<Σ>>),
+  #[error("{} multiple syntax matches were found", .0.location)]
+  MultipleMatches(Box<ErrorDetail<Σ>>),
   #[error("{0}")]
   UndefinedID(String),
   #[error("the previous error prevented progress")]
   Previous,
+  #[error("{location} the limit of {name} ({limit}) was exceeded")]
+  LimitExceeded { location: Σ::Location, name: &'static str, limit: usize },
+  #[error("{location} {id} exceeded the maximum length of {limit} symbols")]
+  TooLong { location: Σ::Location, id: String, limit: usize },
+  /// The syntax errors recovered by the recovery sets. If the parse has failed after them, the last one is the error
+  /// that failed it.
+  #[error("{} syntax errors were recovered", .0.len())]
+  Recovered(Vec<Error<Σ>>),
+  #[error("{location} only {available} unconfirmed symbols can be unread")]
+  Unreadable { location: Σ::Location, available: usize },
+  #[error("{location} {remaining} symbols could not be passed through")]
+  Passthrough { location: Σ::Location, remaining: usize },
+  #[error("the event handler failed: {0}")]
+  Handler(#[source] HandlerError),
+  #[error("{operation} isn't available with the {engine:?} engine")]
+  Unsupported { operation: &'static str, engine: parser::Engine },
+  /// A violation of the invariant of the parser, which is a bug of terp rather than of the input. The parse fails
+  /// with it instead of panicking or continuing with the inconsistent state.
+  #[error("{location} internal error: {message}")]
+  Internal { location: Σ::Location, message: String },
 }
@@ -0,0 +1,102 @@
+use crate::parser::Event;
+use crate::schema::Symbol;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::sync::mpsc;
+
+/// The sending side of a channel that events can be forwarded to by [`forward()`]. A bounded channel blocks parsing
+/// until the receiver catches up, which gives backpressure to the producer of the input.
+///
+pub trait EventSender<ID, Σ: Symbol>
+where
+  ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+{
+  /// Send an event, and return false if the receiver has been dropped.
+  fn send_event(&mut self, e: Event<ID, Σ>) -> bool;
+}
+
+/// The error of the handler created by [`forward()`] once the receiver has been dropped.
+///
+#[derive(thiserror::Error, Clone, Copy, Debug, PartialEq, Eq)]
+#[error("the receiver of the events has been dropped")]
+pub struct Disconnected;
+
+/// Create an event handler for [`Context::try_new()`](crate::parser::Context::try_new) that forwards a copy of each
+/// event to `sender`, so that the events are consumed on another thread. Once the receiver is dropped, the parse is
+/// aborted with [`Error::Handler`](crate::Error::Handler) of [`Disconnected`].
+///
+/// ```rust
+/// use terp::parser::{forward, Context, Event, EventKind};
+/// use terp::schema::chars::ascii_digit;
+/// use terp::schema::Schema;
+///
+/// let schema = Schema::new("Foo").define("A", ascii_digit() * (1..));
+/// let (sender, receiver) = std::sync::mpsc::sync_channel(16);
+/// let consumer = std::thread::spawn(move || {
+///   receiver.iter().filter(|e: &Event<_, char>| e.kind == EventKind::End("A")).count()
+/// });
+/// let mut parser = Context::try_new(&schema, "A", forward(sender)).unwrap();
+/// parser.push_str("123").unwrap();
+/// parser.finish().unwrap();
+/// assert_eq!(1, consumer.join().unwrap());
+/// ```
+///
+pub fn forward<ID, Σ: Symbol, S: EventSender<ID, Σ>>(
+  mut sender: S,
+) -> impl FnMut(&Event<ID, Σ>) -> Result<(), Disconnected>
+where
+  ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+{
+  move |e: &Event<ID, Σ>| if sender.send_event(e.clone()) { Ok(()) } else { Err(Disconnected) }
+}
+
+impl<ID, Σ: Symbol> EventSender<ID, Σ> for mpsc::Sender<Event<ID, Σ>>
+where
+  ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+{
+  fn send_event(&mut self, e: Event<ID, Σ>) -> bool {
+    self.send(e).is_ok()
+  }
+}
+
+impl<ID, Σ: Symbol> EventSender<ID, Σ> for mpsc::SyncSender<Event<ID, Σ>>
+where
+  ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+{
+  fn send_event(&mut self, e: Event<ID, Σ>) -> bool {
+    self.send(e).is_ok()
+  }
+}
+
+#[cfg(feature = "crossbeam")]
+impl<ID, Σ: Symbol> EventSender<ID, Σ> for crossbeam_channel::Sender<Event<ID, Σ>>
+where
+  ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+{
+  fn send_event(&mut self, e: Event<ID, Σ>) -> bool {
+    self.send(e).is_ok()
+  }
+}
+
+/// Note that this blocks the current thread while the channel is full, so the parser must not run on an asynchronous
+/// runtime, but on such as [`tokio::task::spawn_blocking()`].
+///
+#[cfg(feature = "tokio")]
+impl<ID, Σ: Symbol> EventSender<ID, Σ> for tokio::sync::mpsc::Sender<Event<ID, Σ>>
+where
+  ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+{
+  fn send_event(&mut self, e: Event<ID, Σ>) -> bool {
+    self.blocking_send(e).is_ok()
+  }
+}
+
+#[cfg(feature = "tokio")]
+impl<ID, Σ: Symbol> EventSender<ID, Σ> for tokio::sync::mpsc::UnboundedSender<Event<ID, Σ>>
+where
+  ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+{
+  fn send_event(&mut self, e: Event<ID, Σ>) -> bool {
+    self.send(e).is_ok()
+  }
+}
@@ -0,0 +1,144 @@
+use crate::parser::{Context, Event, EventKind};
+use crate::schema::{Location, Schema, Symbol};
+use crate::Result;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::ops::Range;
+
+/// Split `input` into the chunks of at least `chunk_size` symbols, each of which ends with a `newline`, except for the
+/// last one. It's a splitter for [`parse_chunks()`] of the inputs whose records are lines, such as NDJSON.
+///
+pub fn split_lines<Σ: Symbol>(input: &[Σ], newline: Σ, chunk_size: usize) -> Vec<Range<usize>> {
+  split(input, chunk_size, |s| *s == newline)
+}
+
+/// Split `input` like [`split_lines()`], except that the `newline`s between the `quote`s don't separate the records,
+/// as in the quoted fields of CSV. An escaped quote doubled as `""` is counted twice and keeps the state.
+///
+pub fn split_quoted_lines<Σ: Symbol>(input: &[Σ], quote: Σ, newline: Σ, chunk_size: usize) -> Vec<Range<usize>> {
+  let mut quoted = false;
+  split(input, chunk_size, |s| {
+    if *s == quote {
+      quoted = !quoted;
+    }
+    !quoted && *s == newline
+  })
+}
+
+/// Split `input` after the symbols for which `boundary` returns true, which is called for all the symbols in order.
+///
+fn split<Σ: Symbol, F: FnMut(&Σ) -> bool>(input: &[Σ], chunk_size: usize, mut boundary: F) -> Vec<Range<usize>> {
+  let mut chunks = Vec::with_capacity(input.len() / std::cmp::max(chunk_size, 1) + 1);
+  let mut begin = 0;
+  for (i, s) in input.iter().enumerate() {
+    if boundary(s) && i + 1 - begin >= chunk_size {
+      chunks.push(begin..i + 1);
+      begin = i + 1;
+    }
+  }
+  if begin < input.len() {
+    chunks.push(begin..input.len());
+  }
+  chunks
+}
+
+/// Parse the `chunks` of `input` with separate contexts for `id`, on multiple threads with the `concurrent` feature,
+/// and deliver the events to the `handler` in order as if the whole input were parsed by a context.
+///
+/// The chunks must be split at the boundaries of the records, and `id` must match any concatenation of the records,
+/// e.g., `id("RECORD") * (0..)`, so that each chunk is parsed independently. The empty chunks are skipped. The events
+/// of a chunk are delivered after the preceding chunks, normalized as by [`Event::normalize()`], and the locations
+/// continue from them. If a chunk doesn't match, the error of the first such chunk is returned after the events of
+/// the chunks preceding it have been delivered.
+///
+/// ```rust
+/// use terp::parser::{parse_chunks, split_lines, Event, EventKind};
+/// use terp::schema::chars::{ascii_digit, ch};
+/// use terp::schema::{id, Schema};
+///
+/// let schema = Schema::new("Lines")
+///   .define("LINES", id("LINE") * (0..))
+///   .define("LINE", ascii_digit() * (1..) & ch('\n'));
+/// let input = "1\n22\n333\n4444\n".chars().collect::<Vec<_>>();
+/// let chunks = split_lines(&input, '\n', 4);
+/// assert_eq!(vec![0..5, 5..9, 9..14], chunks);
+///
+/// let mut lines = 0;
+/// parse_chunks(&schema, "LINES", &input, &chunks, |e: &Event<_, _>| {
+///   if e.kind == EventKind::End("LINE") {
+///     lines += 1;
+///   }
+/// })
+/// .unwrap();
+/// assert_eq!(4, lines);
+/// ```
+///
+pub fn parse_chunks<ID, Σ, H>(
+  schema: &Schema<ID, Σ>, id: ID, input: &[Σ], chunks: &[Range<usize>], mut handler: H,
+) -> Result<Σ, ()>
+where
+  ID: Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
+  Σ: 'static + Symbol,
+  H: FnMut(&Event<ID, Σ>),
+{
+  let mut chunks = chunks.iter().filter(|chunk| !chunk.is_empty()).cloned().collect::<Vec<_>>();
+  if chunks.is_empty() {
+    chunks.push(0..input.len());
+  }
+
+  // the locations where the chunks begin
+  let mut location = Σ::Location::default();
+  let mut position = 0;
+  let mut starts = Vec::with_capacity(chunks.len());
+  for chunk in chunks {
+    debug_assert!(position <= chunk.start);
+    location.increment_with_seq(&input[position..chunk.start]);
+    position = chunk.start;
+    starts.push((chunk, location));
+  }
+
+  let parse = |(chunk, location): &(Range<usize>, Σ::Location)| -> Result<Σ, Vec<Event<ID, Σ>>> {
+    let mut events = Vec::new();
+    let mut parser = Context::new_at(schema, id.clone(), |e: &Event<ID, Σ>| events.push(e.clone()), *location)?;
+    parser.push_seq(&input[chunk.clone()])?;
+    parser.finish()?;
+    Ok(Event::normalize(&events))
+  };
+
+  let last = starts.len() - 1;
+  for (i, batch) in starts.chunks(batch_size()).enumerate() {
+    #[cfg(feature = "concurrent")]
+    let results = {
+      use rayon::prelude::*;
+      batch.par_iter().map(parse).collect::<Vec<_>>()
+    };
+    #[cfg(not(feature = "concurrent"))]
+    let results = batch.iter().map(parse).collect::<Vec<_>>();
+
+    for (j, events) in results.into_iter().enumerate() {
+      let events = events?;
+      let n = i * batch_size() + j;
+      // the Begin and the End of `id` surround the whole input rather than each chunk, while a chunk that `id` matches
+      // with no symbols has no events
+      let begin = match events.first() {
+        Some(Event { kind: EventKind::Begin(b), .. }) if n > 0 && *b == id => 1,
+        _ => 0,
+      };
+      let end = match events.last() {
+        Some(Event { kind: EventKind::End(e), .. }) if n < last && *e == id => events.len() - 1,
+        _ => events.len(),
+      };
+      events[begin..end].iter().for_each(&mut handler);
+    }
+  }
+  Ok(())
+}
+
+/// The number of the chunks parsed at once, whose events are held until delivered.
+///
+fn batch_size() -> usize {
+  #[cfg(feature = "concurrent")]
+  return rayon::current_num_threads() * 2;
+  #[cfg(not(feature = "concurrent"))]
+  1
+}
@@ -0,0 +1,791 @@
+use crate::parser::forest::{Child, Node};
+use crate::parser::{
+  create_unmatched_label_actual, create_unmatched_label_prefix, Event, EventBuffer, EventKind, Forest, Ignore, Sampling,
+};
+use crate::schema::{Location, MatchCtx, MatchResult, Primary, Schema, Symbol, Syntax};
+use crate::{Error, ErrorDetail, Result};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::ops::Range;
+
+/// The parsing engine used by a [`Context`](crate::parser::Context).
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Engine {
+  /// Evaluates the possible paths as symbols are pushed, and delivers the events as soon as they're confirmed. The
+  /// number of paths can grow exponentially for ambiguous grammars.
+  #[default]
+  Streaming,
+  /// Buffers all symbols until [`finish()`](crate::parser::Context::finish) and parses them with the Earley
+  /// algorithm, which takes `O(n³)` time in the worst case even for highly ambiguous grammars, `O(n²)` for
+  /// unambiguous ones. The schema is interpreted as a context-free grammar, so that a failed branch or iteration never
+  /// affects the others. If the input can be split between adjacent syntaxes in several ways, the longest one is
+  /// adopted for the preceding syntax, as with the greedy repetition of `Streaming`.
+  Earley,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Sym {
+  /// A term, the index of [`Grammar::terms`].
+  T(usize),
+  /// A nonterminal, the index of [`Grammar::rules`].
+  N(usize),
+}
+
+/// The schema flattened into productions, where a repetition is expanded into nonterminals.
+///
+struct Grammar<'s, ID, Σ: Symbol> {
+  terms: Vec<&'s Syntax<ID, Σ>>,
+  prods: Vec<(usize, Vec<Sym>)>,
+  rules: Vec<Vec<usize>>,
+  names: Vec<Option<ID>>,
+  aliases: HashMap<ID, usize>,
+}
+
+impl<'s, ID, Σ: 'static + Symbol> Grammar<'s, ID, Σ>
+where
+  ID: Clone + Hash + Eq + Ord + Display + Debug,
+{
+  fn new(schema: &'s Schema<ID, Σ>, start: &ID) -> Result<Σ, (Self, usize)> {
+    let mut grammar =
+      Self { terms: Vec::new(), prods: Vec::new(), rules: Vec::new(), names: Vec::new(), aliases: HashMap::new() };
+    let start = grammar.alias(schema, start)?;
+    Ok((grammar, start))
+  }
+
+  fn nonterminal(&mut self, name: Option<ID>) -> usize {
+    self.rules.push(Vec::new());
+    self.names.push(name);
+    self.rules.len() - 1
+  }
+
+  fn production(&mut self, lhs: usize, rhs: Vec<Sym>) {
+    self.rules[lhs].push(self.prods.len());
+    self.prods.push((lhs, rhs));
+  }
+
+  fn alias(&mut self, schema: &'s Schema<ID, Σ>, id: &ID) -> Result<Σ, usize> {
+    if let Some(nt) = self.aliases.get(id) {
+      return Ok(*nt);
+    }
+    let nt = self.nonterminal(Some(id.clone()));
+    self.aliases.insert(id.clone(), nt);
+    if let Some(Syntax { primary: Primary::Seq(seq), .. }) = schema.get(id) {
+      let rhs = seq.iter().map(|s| self.symbol(schema, s)).collect::<Result<Σ, Vec<_>>>()?;
+      self.production(nt, rhs);
+      Ok(nt)
+    } else {
+      Err(Error::UndefinedID(id.to_string()))
+    }
+  }
+
+  fn symbol(&mut self, schema: &'s Schema<ID, Σ>, syntax: &'s Syntax<ID, Σ>) -> Result<Σ, Sym> {
+    let once = match &syntax.primary {
+      Primary::Term(..) => {
+        self.terms.push(syntax);
+        Sym::T(self.terms.len() - 1)
+      }
+      Primary::Alias(id) => Sym::N(self.alias(schema, id)?),
+      Primary::Seq(seq) => {
+        let nt = self.nonterminal(None);
+        let rhs = seq.iter().map(|s| self.symbol(schema, s)).collect::<Result<Σ, Vec<_>>>()?;
+        self.production(nt, rhs);
+        Sym::N(nt)
+      }
+      Primary::Or(branches) => {
+        let nt = self.nonterminal(None);
+        for branch in branches {
+          let rhs = vec![self.symbol(schema, branch)?];
+          self.production(nt, rhs);
+        }
+        Sym::N(nt)
+      }
+    };
+
+    let (min, max) = (*syntax.repetition.start(), *syntax.repetition.end());
+    if min == 1 && max == 1 {
+      return Ok(once);
+    }
+    let mut rhs = vec![once; min];
+    if max == usize::MAX {
+      // left recursion is handled efficiently by the Earley algorithm
+      let rest = self.nonterminal(None);
+      self.production(rest, vec![]);
+      self.production(rest, vec![Sym::N(rest), once]);
+      rhs.push(Sym::N(rest));
+    } else if max > min {
+      let mut optional = self.nonterminal(None);
+      self.production(optional, vec![]);
+      self.production(optional, vec![once]);
+      for _ in 1..(max - min) {
+        let nt = self.nonterminal(None);
+        self.production(nt, vec![]);
+        self.production(nt, vec![once, Sym::N(optional)]);
+        optional = nt;
+      }
+      rhs.push(Sym::N(optional));
+    }
+    let nt = self.nonterminal(None);
+    self.production(nt, rhs);
+    Ok(Sym::N(nt))
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Item {
+  prod: usize,
+  dot: usize,
+  origin: usize,
+}
+
+/// The number of derivations to be evaluated by [`Chart::evaluate()`].
+///
+#[derive(Clone, Copy, Debug)]
+enum Goal {
+  /// The nonterminal over `begin..end`.
+  Nt(usize, usize, usize),
+  /// The symbols after the dot of the production over `begin..end`.
+  Seq(usize, usize, usize, usize),
+}
+
+/// The evaluation of the end position of the symbol after the dot, which tries the end positions in descending order.
+///
+struct Choice {
+  prod: usize,
+  dot: usize,
+  begin: usize,
+  end: usize,
+  /// The number of the end positions not yet tried.
+  cursor: usize,
+  mid: usize,
+  /// The number of derivations of the symbol after the dot up to `mid`, or 0 if it's being evaluated.
+  sym_ways: usize,
+}
+
+/// The goal being evaluated on the stack of [`Chart::evaluate()`].
+///
+enum Frame {
+  Nt { nt: usize, begin: usize, end: usize, next: usize, ways: usize, depth: usize, outer_cut: usize },
+  Seq { choice: Choice, outer_cut: usize },
+}
+
+enum Step {
+  Call(Goal),
+  Return(usize),
+}
+
+/// The node being built on the stack of [`Chart::build()`].
+///
+struct BuildFrame {
+  nt: usize,
+  begin: usize,
+  end: usize,
+  /// The productions deriving the node, where `current` is being built.
+  prods: Vec<usize>,
+  current: usize,
+  dot: usize,
+  position: usize,
+  /// The end of the child being built.
+  mid: usize,
+  children: Vec<Child>,
+  alternatives: Vec<Vec<Child>>,
+}
+
+impl BuildFrame {
+  fn next_alternative(&mut self) {
+    self.current += 1;
+    self.dot = 0;
+    self.position = self.begin;
+    self.children.clear();
+  }
+}
+
+/// The result of the recognition, which holds the spans of the nonterminals matched.
+///
+struct Chart<'g, 's, 'b, ID, Σ: Symbol> {
+  grammar: &'g Grammar<'s, ID, Σ>,
+  buffer: &'b [Σ],
+  locations: &'b [Σ::Location],
+  sets: Vec<Vec<Item>>,
+  seen: Vec<HashSet<Item>>,
+  /// The end positions of each nonterminal that begins at a position, in ascending order.
+  spans: HashMap<(usize, usize), Vec<usize>>,
+  scans: HashMap<(usize, usize), Option<usize>>,
+  ways: HashMap<(usize, usize, usize, usize), usize>,
+  nt_ways: HashMap<(usize, usize, usize), usize>,
+  /// The nonterminals being counted, with their depth of the evaluation.
+  evaluating: HashMap<(usize, usize, usize), usize>,
+  /// The shallowest depth of the nonterminals whose cyclic derivation was cut, which the result of the evaluation in
+  /// progress depends on, so it can't be memoized until the evaluation at that depth completes.
+  cut: usize,
+}
+
+impl<'g, 's, 'b, ID, Σ: 'static + Symbol> Chart<'g, 's, 'b, ID, Σ>
+where
+  ID: Clone + Hash + Eq + Ord + Display + Debug,
+{
+  fn new(grammar: &'g Grammar<'s, ID, Σ>, buffer: &'b [Σ], locations: &'b [Σ::Location]) -> Self {
+    Self {
+      grammar,
+      buffer,
+      locations,
+      sets: vec![Vec::new(); buffer.len() + 1],
+      seen: vec![HashSet::new(); buffer.len() + 1],
+      spans: HashMap::new(),
+      scans: HashMap::new(),
+      ways: HashMap::new(),
+      nt_ways: HashMap::new(),
+      evaluating: HashMap::new(),
+      cut: usize::MAX,
+    }
+  }
+
+  fn add(&mut self, k: usize, item: Item) {
+    if self.seen[k].insert(item) {
+      self.sets[k].push(item);
+    }
+  }
+
+  fn next(&self, item: &Item) -> Option<Sym> {
+    self.grammar.prods[item.prod].1.get(item.dot).copied()
+  }
+
+  fn is_completed(&self, nt: usize, begin: usize, end: usize) -> bool {
+    self.spans.get(&(nt, begin)).map(|ends| ends.binary_search(&end).is_ok()).unwrap_or(false)
+  }
+
+  /// The length of the term `t` that matches at the position `k`, as all symbols are available.
+  ///
+  fn scan(&mut self, t: usize, k: usize) -> Result<Σ, Option<usize>> {
+    if let Some(length) = self.scans.get(&(t, k)) {
+      return Ok(*length);
+    }
+    let primary = &self.grammar.terms[t].primary;
+    let length = match primary {
+      Primary::Term(term) => {
+        match (term.matcher)(primary.window(&self.buffer[k..]), &MatchCtx::new(true, self.locations[k]))? {
+          MatchResult::Match(length) | MatchResult::MatchAndCanAcceptMore(length) => Some(length),
+          MatchResult::Unmatch | MatchResult::UnmatchAndCanAcceptMore | MatchResult::Partial(_) => None,
+        }
+      }
+      _ => {
+        let message = format!("the scanned syntax is not a term: {}", self.grammar.terms[t]);
+        return Err(Error::Internal { location: self.locations[k], message });
+      }
+    };
+    self.scans.insert((t, k), length);
+    Ok(length)
+  }
+
+  fn recognize(&mut self, start: usize) -> Result<Σ, ()> {
+    for prod in self.grammar.rules[start].clone() {
+      self.add(0, Item { prod, dot: 0, origin: 0 });
+    }
+    for k in 0..self.sets.len() {
+      let mut x = 0;
+      while x < self.sets[k].len() {
+        let item = self.sets[k][x];
+        x += 1;
+        match self.next(&item) {
+          None => {
+            let lhs = self.grammar.prods[item.prod].0;
+            // the positions are completed in ascending order
+            let ends = self.spans.entry((lhs, item.origin)).or_default();
+            if ends.last() != Some(&k) {
+              ends.push(k);
+            }
+            let mut y = 0;
+            while y < self.sets[item.origin].len() {
+              let waiting = self.sets[item.origin][y];
+              y += 1;
+              if self.next(&waiting) == Some(Sym::N(lhs)) {
+                self.add(k, Item { dot: waiting.dot + 1, ..waiting });
+              }
+            }
+          }
+          Some(Sym::N(nt)) => {
+            for prod in self.grammar.rules[nt].clone() {
+              self.add(k, Item { prod, dot: 0, origin: k });
+            }
+            // the nonterminal that has already been completed with no symbols
+            if self.is_completed(nt, k, k) {
+              self.add(k, Item { dot: item.dot + 1, ..item });
+            }
+          }
+          Some(Sym::T(t)) => {
+            if let Some(length) = self.scan(t, k)? {
+              self.add(k + length, Item { dot: item.dot + 1, ..item });
+            }
+          }
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// The number of derivations, saturated at 2, in which the nonterminal `nt` spans `begin..end`.
+  ///
+  fn nt_ways(&mut self, nt: usize, begin: usize, end: usize) -> usize {
+    self.evaluate(Goal::Nt(nt, begin, end))
+  }
+
+  /// The number of derivations, saturated at 2, in which the symbols after `dot` of the production span `begin..end`.
+  /// The split between the symbols is not counted as ambiguity, and the longest one is adopted for the preceding
+  /// symbol like the greedy repetition of [`Engine::Streaming`].
+  ///
+  fn seq_ways(&mut self, prod: usize, dot: usize, begin: usize, end: usize) -> usize {
+    self.evaluate(Goal::Seq(prod, dot, begin, end))
+  }
+
+  /// Evaluate the number of derivations of the `goal` with an explicit stack instead of recursion, since a repetition
+  /// is derived as deep as the number of its occurrences.
+  ///
+  fn evaluate(&mut self, goal: Goal) -> usize {
+    let mut stack = Vec::new();
+    if let Some(ways) = self.enter(goal, &mut stack) {
+      return ways;
+    }
+    let mut ret = None;
+    while let Some(mut frame) = stack.pop() {
+      match self.step(&mut frame, ret.take()) {
+        Step::Call(goal) => {
+          stack.push(frame);
+          ret = self.enter(goal, &mut stack);
+        }
+        Step::Return(ways) => ret = Some(ways),
+      }
+    }
+    ret.unwrap()
+  }
+
+  /// Begin the evaluation of the `goal`, and return the number of derivations if it's known without evaluating the
+  /// symbols, otherwise push the frame to evaluate them.
+  ///
+  fn enter(&mut self, goal: Goal, stack: &mut Vec<Frame>) -> Option<usize> {
+    match goal {
+      Goal::Nt(nt, begin, end) => {
+        if !self.is_completed(nt, begin, end) {
+          return Some(0);
+        }
+        if let Some(ways) = self.nt_ways.get(&(nt, begin, end)) {
+          return Some(*ways);
+        }
+        if let Some(depth) = self.evaluating.get(&(nt, begin, end)) {
+          // a cyclic derivation is never adopted
+          self.cut = std::cmp::min(self.cut, *depth);
+          return Some(0);
+        }
+        let depth = self.evaluating.len();
+        self.evaluating.insert((nt, begin, end), depth);
+        let outer_cut = std::mem::replace(&mut self.cut, usize::MAX);
+        stack.push(Frame::Nt { nt, begin, end, next: 0, ways: 0, depth, outer_cut });
+      }
+      Goal::Seq(prod, dot, begin, end) => {
+        if dot == self.grammar.prods[prod].1.len() {
+          return Some((begin == end) as usize);
+        }
+        if let Some(ways) = self.ways.get(&(prod, dot, begin, end)) {
+          return Some(*ways);
+        }
+        let outer_cut = std::mem::replace(&mut self.cut, usize::MAX);
+        let cursor = self.mids(prod, dot, begin, end);
+        let choice = Choice { prod, dot, begin, end, cursor, mid: 0, sym_ways: 0 };
+        stack.push(Frame::Seq { choice, outer_cut });
+      }
+    }
+    None
+  }
+
+  /// Proceed the evaluation of the `frame` with the number of derivations `ret` returned by the goal it called.
+  ///
+  fn step(&mut self, frame: &mut Frame, ret: Option<usize>) -> Step {
+    match frame {
+      Frame::Nt { nt, begin, end, next, ways, depth, outer_cut } => {
+        if let Some(ret) = ret {
+          *ways = std::cmp::min(2, *ways + ret);
+        }
+        if let Some(prod) = self.grammar.rules[*nt].get(*next) {
+          *next += 1;
+          return Step::Call(Goal::Seq(*prod, 0, *begin, *end));
+        }
+        self.evaluating.remove(&(*nt, *begin, *end));
+        if self.cut >= *depth {
+          self.nt_ways.insert((*nt, *begin, *end), *ways);
+          self.cut = *outer_cut;
+        } else {
+          self.cut = std::cmp::min(self.cut, *outer_cut);
+        }
+        Step::Return(*ways)
+      }
+      Frame::Seq { choice, outer_cut } => match self.proceed_choice(choice, ret) {
+        Ok(goal) => Step::Call(goal),
+        Err(ways) => {
+          if self.cut == usize::MAX {
+            self.ways.insert((choice.prod, choice.dot, choice.begin, choice.end), ways);
+          }
+          self.cut = std::cmp::min(self.cut, *outer_cut);
+          Step::Return(ways)
+        }
+      },
+    }
+  }
+
+  /// Proceed the `choice` with the number of derivations `ret` of the goal it called, and return the next goal to
+  /// evaluate, or the number of derivations of the choice if it has been decided.
+  ///
+  fn proceed_choice(&mut self, choice: &mut Choice, ret: Option<usize>) -> std::result::Result<Goal, usize> {
+    match ret {
+      // the symbol after the dot matched with `ret` derivations
+      Some(ret) if choice.sym_ways == 0 && ret > 0 => {
+        choice.sym_ways = ret;
+        return Ok(Goal::Seq(choice.prod, choice.dot + 1, choice.mid, choice.end));
+      }
+      // the symbols after it matched
+      Some(ret) if choice.sym_ways > 0 && ret > 0 => return Err(std::cmp::min(2, choice.sym_ways * ret)),
+      _ => (),
+    }
+    choice.sym_ways = 0;
+    let mid = match self.next_mid(choice) {
+      Some(mid) => mid,
+      None => return Err(0),
+    };
+    choice.mid = mid;
+    match self.grammar.prods[choice.prod].1[choice.dot] {
+      Sym::T(_) => {
+        choice.sym_ways = 1;
+        Ok(Goal::Seq(choice.prod, choice.dot + 1, mid, choice.end))
+      }
+      Sym::N(nt) => Ok(Goal::Nt(nt, choice.begin, mid)),
+    }
+  }
+
+  /// The cursor of the end positions of the symbol after `dot` that begins at `begin`, which [`Chart::next_mid()`]
+  /// takes in descending order from `end`.
+  ///
+  fn mids(&self, prod: usize, dot: usize, begin: usize, end: usize) -> usize {
+    match self.grammar.prods[prod].1[dot] {
+      Sym::T(t) => match self.scans.get(&(t, begin)) {
+        Some(Some(length)) if begin + length <= end => 1,
+        _ => 0,
+      },
+      Sym::N(nt) => self.spans.get(&(nt, begin)).map(|ends| ends.partition_point(|mid| *mid <= end)).unwrap_or(0),
+    }
+  }
+
+  fn next_mid(&self, choice: &mut Choice) -> Option<usize> {
+    if choice.cursor == 0 {
+      return None;
+    }
+    choice.cursor -= 1;
+    match self.grammar.prods[choice.prod].1[choice.dot] {
+      Sym::T(t) => self.scans[&(t, choice.begin)].map(|length| choice.begin + length),
+      Sym::N(nt) => Some(self.spans[&(nt, choice.begin)][choice.cursor]),
+    }
+  }
+
+  /// The end position of the symbol after `dot` in the derivation of the production, and the number of derivations.
+  ///
+  fn choose(&mut self, prod: usize, dot: usize, begin: usize, end: usize) -> Option<(usize, usize)> {
+    let cursor = self.mids(prod, dot, begin, end);
+    let mut choice = Choice { prod, dot, begin, end, cursor, mid: 0, sym_ways: 0 };
+    let mut ret = None;
+    loop {
+      match self.proceed_choice(&mut choice, ret) {
+        Ok(goal) => ret = Some(self.evaluate(goal)),
+        Err(0) => return None,
+        Err(ways) => return Some((choice.mid, ways)),
+      }
+    }
+  }
+
+  /// Find the position and the innermost rule where the derivation of `nt` over `begin..end` is ambiguous.
+  ///
+  fn ambiguity(&mut self, mut nt: usize, mut begin: usize, mut end: usize) -> (usize, Option<ID>) {
+    let mut rule = None;
+    'descend: loop {
+      rule = self.grammar.names[nt].clone().or(rule);
+      let prods =
+        self.grammar.rules[nt].clone().into_iter().filter(|p| self.seq_ways(*p, 0, begin, end) > 0).collect::<Vec<_>>();
+      if prods.len() == 1 {
+        let prod = prods[0];
+        let mut position = begin;
+        for dot in 0..self.grammar.prods[prod].1.len() {
+          let (mid, _) = self.choose(prod, dot, position, end).unwrap();
+          if let Sym::N(child) = self.grammar.prods[prod].1[dot] {
+            if self.nt_ways(child, position, mid) > 1 {
+              (nt, begin, end) = (child, position, mid);
+              continue 'descend;
+            }
+          }
+          position = mid;
+        }
+      }
+      return (begin, rule);
+    }
+  }
+
+  fn walk<H: FnMut(Event<ID, Σ>)>(
+    &mut self, nt: usize, begin: usize, end: usize, locations: &[Σ::Location], f: &mut H,
+  ) {
+    let mut stack = vec![self.walk_enter(nt, begin, end, locations, f)];
+    while let Some(&mut (nt, prod, dot, position, end)) = stack.last_mut() {
+      if dot == self.grammar.prods[prod].1.len() {
+        if let Some(id) = &self.grammar.names[nt] {
+          f(Event { location: locations[end], kind: EventKind::End(id.clone()), end: None });
+        }
+        stack.pop();
+        continue;
+      }
+      let (mid, _) = self.choose(prod, dot, position, end).unwrap();
+      let top = stack.last_mut().unwrap();
+      (top.2, top.3) = (dot + 1, mid);
+      match self.grammar.prods[prod].1[dot] {
+        Sym::T(_) => {
+          if mid > position {
+            let fragments = self.buffer[position..mid].to_vec();
+            f(Event { location: locations[position], kind: EventKind::Fragments(fragments), end: None });
+          }
+        }
+        Sym::N(child) => stack.push(self.walk_enter(child, position, mid, locations, f)),
+      }
+    }
+  }
+
+  /// Begin the walk of the nonterminal `nt` over `begin..end`, and return the nonterminal, the production adopted, the
+  /// dot, the position of the dot, and the end.
+  ///
+  fn walk_enter<H: FnMut(Event<ID, Σ>)>(
+    &mut self, nt: usize, begin: usize, end: usize, locations: &[Σ::Location], f: &mut H,
+  ) -> (usize, usize, usize, usize, usize) {
+    if let Some(id) = &self.grammar.names[nt] {
+      f(Event { location: locations[begin], kind: EventKind::Begin(id.clone()), end: None });
+    }
+    let prod = self.grammar.rules[nt].clone().into_iter().find(|p| self.seq_ways(*p, 0, begin, end) > 0).unwrap();
+    (nt, prod, 0, begin, end)
+  }
+
+  /// Build the node of the forest for the nonterminal `nt` over `begin..end`, or `None` for a cyclic derivation.
+  ///
+  fn build(&mut self, nt: usize, begin: usize, end: usize, nodes: &mut Vec<Node<ID>>) -> Option<usize> {
+    let mut index = HashMap::new();
+    let mut stack = Vec::new();
+    let mut ret = self.build_enter(nt, begin, end, &mut index, &mut stack);
+    while let Some(frame) = stack.last_mut() {
+      match ret.take() {
+        Some(Some(node)) => {
+          frame.children.push(Child::Node(node));
+          frame.position = frame.mid;
+        }
+        // the alternative deriving cyclically is abandoned
+        Some(None) => frame.next_alternative(),
+        None => (),
+      }
+      match self.build_step(frame) {
+        Some((child, position, mid)) => ret = self.build_enter(child, position, mid, &mut index, &mut stack),
+        None => {
+          let frame = stack.pop().unwrap();
+          ret = Some(self.build_node(frame, nodes, &mut index));
+        }
+      }
+    }
+    ret.unwrap()
+  }
+
+  /// Begin to build the node of `nt` over `begin..end`, and return it if it's known without building the children.
+  ///
+  fn build_enter(
+    &mut self, nt: usize, begin: usize, end: usize, index: &mut HashMap<(usize, usize, usize), Option<usize>>,
+    stack: &mut Vec<BuildFrame>,
+  ) -> Option<Option<usize>> {
+    if let Some(node) = index.get(&(nt, begin, end)) {
+      return Some(*node);
+    }
+    index.insert((nt, begin, end), None);
+    let prods = self.grammar.rules[nt].clone().into_iter().filter(|p| self.seq_ways(*p, 0, begin, end) > 0).collect();
+    stack.push(BuildFrame {
+      nt,
+      begin,
+      end,
+      prods,
+      current: 0,
+      dot: 0,
+      position: begin,
+      mid: begin,
+      children: Vec::new(),
+      alternatives: Vec::new(),
+    });
+    None
+  }
+
+  /// Proceed the `frame` to the next nonterminal of its alternatives, and return it with its span to be built as the
+  /// child, or `None` if all the alternatives have been built.
+  ///
+  fn build_step(&mut self, frame: &mut BuildFrame) -> Option<(usize, usize, usize)> {
+    while let Some(prod) = frame.prods.get(frame.current).copied() {
+      if frame.dot == self.grammar.prods[prod].1.len() {
+        frame.alternatives.push(std::mem::take(&mut frame.children));
+        frame.next_alternative();
+        continue;
+      }
+      let (mid, _) = self.choose(prod, frame.dot, frame.position, frame.end).unwrap();
+      let sym = self.grammar.prods[prod].1[frame.dot];
+      frame.dot += 1;
+      match sym {
+        Sym::T(_) => {
+          frame.children.push(Child::Fragments(frame.position, mid));
+          frame.position = mid;
+        }
+        Sym::N(child) => {
+          frame.mid = mid;
+          return Some((child, frame.position, mid));
+        }
+      }
+    }
+    None
+  }
+
+  fn build_node(
+    &self, frame: BuildFrame, nodes: &mut Vec<Node<ID>>, index: &mut HashMap<(usize, usize, usize), Option<usize>>,
+  ) -> Option<usize> {
+    let BuildFrame { nt, begin, end, alternatives, .. } = frame;
+    if alternatives.is_empty() {
+      return None;
+    }
+    let count = alternatives
+      .iter()
+      .map(|children| {
+        children.iter().fold(1u64, |count, child| match child {
+          Child::Node(i) => count.saturating_mul(nodes[*i].count),
+          Child::Fragments(..) => count,
+        })
+      })
+      .fold(0u64, |sum, count| sum.saturating_add(count));
+    let name = self.grammar.names[nt].clone();
+    nodes.push(Node { name, begin, end, alternatives, count });
+    index.insert((nt, begin, end), Some(nodes.len() - 1));
+    Some(nodes.len() - 1)
+  }
+
+  fn error_unmatch(
+    &mut self, start: usize, locations: &[Σ::Location], redactions: &[Range<usize>], sampling: &Sampling,
+  ) -> Error<Σ> {
+    let farthest = (0..self.sets.len()).rev().find(|k| !self.sets[*k].is_empty()).unwrap();
+    let mut expecteds = Vec::new();
+    for item in self.sets[farthest].clone() {
+      if let Some(Sym::T(t)) = self.next(&item) {
+        let label = format!("[{}]", self.grammar.terms[t]);
+        if self.scans.get(&(t, farthest)) == Some(&None) && !expecteds.contains(&label) {
+          expecteds.push(label);
+        }
+      }
+    }
+    if farthest < self.buffer.len() && self.is_completed(start, 0, farthest) {
+      expecteds.push(format!("[{}]", super::EOF_SYMBOL));
+    }
+    Error::Unmatched(Box::new(ErrorDetail {
+      location: seek(locations[farthest], self.buffer.get(farthest)),
+      prefix: create_unmatched_label_prefix(self.buffer, 0, farthest, redactions, sampling),
+      expected_syntaxes: expecteds.clone(),
+      expecteds,
+      actual: create_unmatched_label_actual(self.buffer, farthest, redactions, sampling),
+    }))
+  }
+}
+
+/// Parse the whole `buffer` with the syntax `id` and deliver the events to the `handler`.
+///
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn parse<ID, Σ, H>(
+  schema: &Schema<ID, Σ>, id: &ID, origin: Σ::Location, buffer: &[Σ], ignore: &[(ID, Ignore)], fragment_ends: bool,
+  sampling: &Sampling, handler: &mut H,
+) -> Result<Σ, ()>
+where
+  ID: Clone + Hash + Eq + Ord + Display + Debug,
+  Σ: 'static + Symbol,
+  H: FnMut(&Event<ID, Σ>),
+{
+  let (grammar, start) = Grammar::new(schema, id)?;
+  let locations = locations(origin, buffer);
+  let mut chart = Chart::new(&grammar, buffer, &locations);
+  chart.recognize(start)?;
+
+  match chart.nt_ways(start, 0, buffer.len()) {
+    0 => Err(chart.error_unmatch(start, &locations, &redactions(schema, buffer), sampling)),
+    1 => {
+      let mut events = EventBuffer::new(64);
+      events.ignore_events(ignore);
+      events.locate_fragment_ends(fragment_ends);
+      chart.walk(start, 0, buffer.len(), &locations, &mut |e| events.push(e));
+      events.flush_to(events.len(), handler);
+      Ok(())
+    }
+    _ => {
+      let (position, rule) = chart.ambiguity(start, 0, buffer.len());
+      let expecteds = rule.map(|id| vec![format!("[{}]", id)]).unwrap_or_default();
+      Err(Error::MultipleMatches(Box::new(ErrorDetail {
+        location: seek(locations[position], buffer.get(position)),
+        prefix: create_unmatched_label_prefix(buffer, 0, position, &redactions(schema, buffer), sampling),
+        expected_syntaxes: expecteds.clone(),
+        expecteds,
+        actual: create_unmatched_label_actual(buffer, position, &redactions(schema, buffer), sampling),
+      })))
+    }
+  }
+}
+
+/// Parse the whole `buffer` with the syntax `id` and build the forest of all parses.
+///
+pub(crate) fn parse_forest<ID, Σ>(
+  schema: &Schema<ID, Σ>, id: &ID, origin: Σ::Location, buffer: &[Σ], ignore: &[(ID, Ignore)], fragment_ends: bool,
+  sampling: &Sampling,
+) -> Result<Σ, Forest<ID, Σ>>
+where
+  ID: Clone + Hash + Eq + Ord + Display + Debug,
+  Σ: 'static + Symbol,
+{
+  let (grammar, start) = Grammar::new(schema, id)?;
+  let locations = locations(origin, buffer);
+  let mut chart = Chart::new(&grammar, buffer, &locations);
+  chart.recognize(start)?;
+
+  let mut nodes = Vec::new();
+  match chart.build(start, 0, buffer.len(), &mut nodes) {
+    Some(root) => {
+      let ignore = ignore.to_vec();
+      Ok(Forest { nodes, root, buffer: buffer.to_vec(), locations, ignore, fragment_ends })
+    }
+    None => Err(chart.error_unmatch(start, &locations, &redactions(schema, buffer), sampling)),
+  }
+}
+
+/// The whole input is redacted from the errors if the schema has sensitive syntaxes, since the chart doesn't keep
+/// which of the symbols have been matched within them.
+///
+fn redactions<ID: Ord, Σ: 'static + Symbol>(schema: &Schema<ID, Σ>, buffer: &[Σ]) -> Vec<Range<usize>> {
+  if schema.has_sensitives() {
+    std::iter::once(0..buffer.len()).collect()
+  } else {
+    Vec::new()
+  }
+}
+
+/// The `location` moved to the beginning of the symbol at it, if any.
+///
+fn seek<Σ: Symbol>(mut location: Σ::Location, next: Option<&Σ>) -> Σ::Location {
+  if let Some(next) = next {
+    location.seek(next);
+  }
+  location
+}
+
+fn locations<Σ: Symbol>(origin: Σ::Location, buffer: &[Σ]) -> Vec<Σ::Location> {
+  let mut locations = Vec::with_capacity(buffer.len() + 1);
+  let mut location = origin;
+  locations.push(location);
+  for item in buffer {
+    location.increment_with(*item);
+    locations.push(location);
+  }
+  locations
+}
@@ -1,11 +1,17 @@
 use std::{
-  collections::HashSet,
+  collections::HashMap,
   fmt::{Debug, Display},
   hash::Hash,
+  sync::Arc,
 };
 
-use crate::schema::Symbol;
+use crate::schema::{Location, Symbol};
 
+/// An event delivered to the handler of a [`Context`](crate::parser::Context) as the parse proceeds.
+///
+/// The `Debug` of an event prints its fragments verbatim, even within the syntaxes marked by
+/// [`Schema::sensitive()`](crate::schema::Schema::sensitive), which redacts only the errors and the parser's logs.
+///
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Event<ID, Σ: Symbol>
 where
@@ -13,6 +19,9 @@ where
 {
   pub location: Σ::Location,
   pub kind: EventKind<ID, Σ>,
+  /// The location just after the last symbol of Fragments, so that `location..end` is the range of the fragments. It's
+  /// only set if enabled by [`Context::with_fragment_ends()`](crate::parser::Context::with_fragment_ends).
+  pub end: Option<Σ::Location>,
 }
 
 impl<ID, Σ: Symbol> Event<ID, Σ>
@@ -28,6 +37,23 @@ where
   }
 }
 
+impl<ID, Σ: Symbol> Event<&ID, Σ>
+where
+  ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+{
+  /// Clone the ID of the event delivered by a context parsing with [`Schema::by_ref()`](crate::schema::Schema::by_ref),
+  /// e.g., to keep the event after the handler returns.
+  ///
+  pub fn to_owned_ids(&self) -> Event<ID, Σ> {
+    let kind = match &self.kind {
+      EventKind::Begin(id) => EventKind::Begin((*id).clone()),
+      EventKind::End(id) => EventKind::End((*id).clone()),
+      EventKind::Fragments(values) => EventKind::Fragments(values.clone()),
+    };
+    Event { location: self.location, kind, end: self.end }
+  }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub enum EventKind<ID, Σ: Symbol>
 where
@@ -38,17 +64,94 @@ where
   Fragments(Vec<Σ>),
 }
 
+/// How the events of an ignored syntax are hidden, see
+/// [`Context::ignore_events_with()`](crate::parser::Context::ignore_events_with).
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Ignore {
+  /// Hide only the Begin and End events of the syntax, so that the events of its children appear in its place.
+  #[default]
+  Node,
+  /// Hide the Begin and End events of the syntax and all its descendants, so that their fragments are merged into the
+  /// parent.
+  Subtree,
+  /// Hide all the events of the syntax including its fragments, as if it didn't appear.
+  Drop,
+}
+
+/// The events held by an [`EventBuffer`]. A recognizer holds no events, or only the structure of the events to count
+/// the syntaxes.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Recording {
+  All,
+  /// Begin and End events, and Fragments without symbols.
+  Structure,
+  Nothing,
+}
+
+/// The verification that each End event closes the last Begin event, which is done once on the delivered events
+/// rather than on each path.
+///
+#[derive(Debug)]
+pub(crate) struct Pairing<ID> {
+  /// The IDs of the Begin events delivered so far without their End.
+  open: Vec<ID>,
+  /// The first inconsistency of the events, which is reported as [`Error::Internal`](crate::Error::Internal).
+  violation: Option<String>,
+}
+
+impl<ID: Clone + Display + Debug + PartialEq> Pairing<ID> {
+  pub fn new() -> Self {
+    Self { open: Vec::with_capacity(16), violation: None }
+  }
+
+  /// Verify the event of `kind`, and return false if the events have been inconsistent.
+  ///
+  pub fn verify<Σ: Symbol>(&mut self, kind: &EventKind<ID, Σ>) -> bool {
+    if self.violation.is_some() {
+      return false;
+    }
+    let violation = match kind {
+      EventKind::Begin(id) => {
+        self.open.push(id.clone());
+        return true;
+      }
+      EventKind::Fragments(_) => return true,
+      EventKind::End(actual) => match self.open.pop() {
+        Some(expected) if *actual == expected => return true,
+        Some(expected) => format!("inconsistent event: End({}) expected, but End({}) appeared", expected, actual),
+        None => format!("inconsistent event: End({}) appeared without Begin", actual),
+      },
+    };
+    self.violation = Some(violation);
+    false
+  }
+
+  /// The first inconsistency of the verified events, if any.
+  ///
+  pub fn violation(&self) -> Option<&str> {
+    self.violation.as_deref()
+  }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct EventBuffer<ID, Σ: Symbol>
 where
   ID: Clone + Display + Debug + PartialEq + Eq + Hash,
 {
   events: Vec<Event<ID, Σ>>,
-  ignore: HashSet<ID>,
-
-  // to verify Begin/End conbinations
-  #[cfg(debug_assertions)]
-  _event_stack: Vec<ID>,
+  /// Shared by the forked paths, since it doesn't change while parsing.
+  ignore: Arc<HashMap<ID, Ignore>>,
+  /// The numbers of the syntaxes being evaluated whose subtrees are hidden by [`Ignore::Subtree`] and
+  /// [`Ignore::Drop`].
+  hidden: usize,
+  dropped: usize,
+  /// The positions just after the Begin events of the left-recursive syntaxes being evaluated, where the Begin events
+  /// are inserted when their parses grow. The events after them cannot be flushed.
+  pins: Vec<(usize, Σ::Location)>,
+  recording: Recording,
+  fragment_ends: bool,
 }
 
 impl<ID, Σ: Symbol> EventBuffer<ID, Σ>
@@ -58,9 +161,12 @@ where
   pub fn new(capacity: usize) -> Self {
     Self {
       events: Vec::with_capacity(capacity),
-      ignore: HashSet::new(),
-      #[cfg(debug_assertions)]
-      _event_stack: Vec::with_capacity(16),
+      ignore: Arc::new(HashMap::new()),
+      hidden: 0,
+      dropped: 0,
+      pins: Vec::new(),
+      recording: Recording::All,
+      fragment_ends: false,
     }
   }
 
@@ -68,52 +174,168 @@ where
     self.events.len()
   }
 
-  pub fn ignore_events_for(&mut self, ids: &[ID]) {
-    for id in ids {
-      self.ignore.insert(id.clone());
+  pub fn ignore_events(&mut self, ignore: &[(ID, Ignore)]) {
+    Arc::make_mut(&mut self.ignore).extend(ignore.iter().cloned());
+  }
+
+  pub fn locate_fragment_ends(&mut self, enabled: bool) {
+    self.fragment_ends = enabled;
+  }
+
+  pub fn set_recording(&mut self, recording: Recording) {
+    self.recording = recording;
+  }
+
+  pub fn recording(&self) -> Recording {
+    self.recording
+  }
+
+  pub fn is_ignored(&self, id: &ID) -> bool {
+    self.ignore.contains_key(id)
+  }
+
+  /// Whether the Begin and End events of `id` are hidden at this point.
+  ///
+  fn is_concealed(&self, id: &ID) -> bool {
+    self.ignore.contains_key(id) || self.hidden > 0 || self.dropped > 0
+  }
+
+  /// Whether `e` is hidden by the ignored syntaxes, tracking the subtrees being hidden.
+  ///
+  fn conceal(&mut self, e: &Event<ID, Σ>) -> bool {
+    match &e.kind {
+      EventKind::Begin(id) => match self.ignore.get(id) {
+        Some(Ignore::Subtree) => self.hidden += 1,
+        Some(Ignore::Drop) => self.dropped += 1,
+        _ => return self.is_concealed(id),
+      },
+      EventKind::End(id) => match self.ignore.get(id) {
+        Some(Ignore::Subtree) => self.hidden -= 1,
+        Some(Ignore::Drop) => self.dropped -= 1,
+        _ => return self.is_concealed(id),
+      },
+      EventKind::Fragments(_) => return self.dropped > 0,
+    }
+    true
+  }
+
+  /// Pin the position just after the Begin event of the left-recursive syntax `id` that has been pushed last.
+  ///
+  pub fn pin(&mut self, id: &ID) {
+    if !self.is_concealed(id) {
+      let location = self.events.last().map(|e| e.location).unwrap_or_default();
+      self.pins.push((self.events.len(), location));
+    }
+  }
+
+  pub fn unpin(&mut self, id: &ID) {
+    if !self.is_concealed(id) {
+      self.pins.pop();
+    }
+  }
+
+  /// Enclose the events after the last pinned Begin event with Begin and `end`, so that the parse so far is nested in
+  /// the growing left-recursive syntax.
+  ///
+  pub fn nest(&mut self, end: Event<ID, Σ>) {
+    if let (EventKind::End(id), Some((pin, location))) = (&end.kind, self.pins.last()) {
+      if self.is_concealed(id) || self.recording == Recording::Nothing {
+        return;
+      }
+      self.events.insert(*pin, Event { location: *location, kind: EventKind::Begin(id.clone()), end: None });
+      self.push(end);
+    }
+  }
+
+  /// The number of events that can be flushed, that is, the events that are never enclosed by a growing syntax.
+  ///
+  pub fn flushable_len(&self) -> usize {
+    self.pins.first().map(|(pin, _)| *pin).unwrap_or(self.events.len())
+  }
+
+  /// The number of flushable events that the following events never change: the trailing Fragments may be extended
+  /// by the next Fragments, and the trailing Begins may be removed by their End with no content.
+  ///
+  pub fn settled_len(&self) -> usize {
+    let flushable = self.flushable_len();
+    match self.events[..flushable].last() {
+      Some(Event { kind: EventKind::Fragments(_), .. }) => flushable - 1,
+      _ => self.retained_len(),
+    }
+  }
+
+  /// The number of flushable events that the following events never remove, that is, except for the trailing Begins.
+  /// The trailing Fragments are included, since how they're split doesn't matter once they're delivered.
+  ///
+  pub fn retained_len(&self) -> usize {
+    let flushable = self.flushable_len();
+    if self.recording == Recording::Nothing {
+      // no End is recorded to remove them
+      return flushable;
     }
+    self.events[..flushable].iter().rposition(|e| !matches!(e.kind, EventKind::Begin(_))).map_or(0, |i| i + 1)
   }
 
   pub fn push(&mut self, mut e: Event<ID, Σ>) {
+    if let (true, EventKind::Fragments(items)) = (self.fragment_ends, &e.kind) {
+      let mut end = e.location;
+      end.increment_with_seq(items);
+      e.end = Some(end);
+    }
+    if let EventKind::Fragments(items) = &e.kind {
+      if let Some(first) = items.first() {
+        self.seek(&mut e.location, first);
+      }
+    }
+    match (self.recording, &mut e.kind) {
+      (Recording::Nothing, _) => return,
+      (_, EventKind::Fragments(_)) if self.dropped > 0 => return,
+      (Recording::Structure, EventKind::Fragments(items)) => items.clear(),
+      _ => (),
+    }
     match (&mut e, self.events.last_mut()) {
-      (Event { kind: EventKind::Fragments(items), .. }, Some(Event { kind: EventKind::Fragments(current), .. })) => {
+      (
+        Event { kind: EventKind::Fragments(items), end, .. },
+        Some(Event { kind: EventKind::Fragments(current), end: current_end, .. }),
+      ) => {
         // append items to buffer tail Fragment's sequence
         current.append(items);
+        *current_end = *end;
       }
       (Event { kind: EventKind::End(i1), .. }, Some(Event { kind: EventKind::Begin(i2), .. })) if i1 == i2 => {
-        #[cfg(debug_assertions)]
-        debug_assert_eq!(self._event_stack.pop().unwrap(), *i2);
-
         // delete buffer tail for Begin/End with no content
         self.events.pop();
       }
       _ => {
-        #[cfg(debug_assertions)]
-        match &e {
-          Event { kind: EventKind::Begin(id), .. } => self._event_stack.push(id.clone()),
-          Event { kind: EventKind::End(actual), .. } => match self._event_stack.pop() {
-            Some(expected) if *actual == expected => (),
-            Some(expected) => {
-              panic!("inconsisnt event is detected: End({}) expected, but End({}) appeared", expected, actual)
-            }
-            None => panic!("inconsist event is detected: End({}) appeared on empty stack", actual),
-          },
-          _ => (),
-        }
-
-        match &e {
-          Event { kind: EventKind::Begin(id), .. } if self.ignore.contains(id) => (),
-          Event { kind: EventKind::End(id), .. } if self.ignore.contains(id) => (),
-          _ => self.events.push(e),
+        if !self.conceal(&e) {
+          self.events.push(e);
         }
       }
     }
   }
 
-  pub fn normalize(mut self) -> Self {
-    self.events = Event::normalize(&self.events);
-    self.events.shrink_to_fit();
-    self
+  /// Move the location of the fragments and the Begin events waiting for them to the beginning of their `first` symbol.
+  ///
+  fn seek(&mut self, location: &mut Σ::Location, first: &Σ) {
+    location.seek(first);
+    let len = self.events.len();
+    for e in self.events.iter_mut().rev().take_while(|e| matches!(e.kind, EventKind::Begin(_))) {
+      e.location.seek(first);
+    }
+    for (_, location) in self.pins.iter_mut().rev().take_while(|(pin, _)| *pin == len) {
+      location.seek(first);
+    }
+  }
+
+  /// Whether the events are as [`Event::normalize()`] would leave them, without checking the consistency of Begin and
+  /// End, since the Begin events of the buffer may have already been delivered, see [`Pairing`].
+  ///
+  pub fn is_normalized(&self) -> bool {
+    self.events.windows(2).all(|w| match (&w[0].kind, &w[1].kind) {
+      (EventKind::Fragments(_), EventKind::Fragments(_)) => false,
+      (EventKind::Begin(begin), EventKind::End(end)) => begin != end,
+      _ => true,
+    })
   }
 
   pub fn flush_to<H: FnMut(&Event<ID, Σ>)>(&mut self, n: usize, handler: &mut H) {
@@ -121,6 +343,9 @@ where
       (handler)(&self.events[i]);
     }
     self.events.drain(..n);
+    for (pin, _) in &mut self.pins {
+      *pin -= n;
+    }
   }
 
   pub fn forward_matching_length(&self, other: &Self) -> usize {
@@ -0,0 +1,131 @@
+use crate::parser::{Event, EventBuffer, EventKind, Ignore};
+use crate::schema::Symbol;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+/// `Forest` is a shared packed parse forest that represents all the parses of an ambiguous input, obtained by
+/// [`Context::finish_forest()`](crate::parser::Context::finish_forest). A node for the same syntax over the same range
+/// is shared by all parses, so the forest has polynomial size even if the number of parses is exponential.
+///
+#[derive(Clone, Debug)]
+pub struct Forest<ID, Σ: Symbol>
+where
+  ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+{
+  pub(crate) nodes: Vec<Node<ID>>,
+  pub(crate) root: usize,
+  pub(crate) buffer: Vec<Σ>,
+  pub(crate) locations: Vec<Σ::Location>,
+  pub(crate) ignore: Vec<(ID, Ignore)>,
+  pub(crate) fragment_ends: bool,
+}
+
+/// A syntax over `begin..end`, with the alternative sequences of children that derive it.
+///
+#[derive(Clone, Debug)]
+pub(crate) struct Node<ID> {
+  pub name: Option<ID>,
+  pub begin: usize,
+  pub end: usize,
+  pub alternatives: Vec<Vec<Child>>,
+  pub count: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Child {
+  Node(usize),
+  Fragments(usize, usize),
+}
+
+impl<ID, Σ: Symbol> Forest<ID, Σ>
+where
+  ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+{
+  /// The number of parses, saturated at `u64::MAX`.
+  ///
+  pub fn count(&self) -> u64 {
+    self.nodes[self.root].count
+  }
+
+  pub fn is_ambiguous(&self) -> bool {
+    self.count() > 1
+  }
+
+  /// The events of the `n`-th parse, or `None` if `n` is out of range.
+  ///
+  pub fn events(&self, n: u64) -> Option<Vec<Event<ID, Σ>>> {
+    if n >= self.count() {
+      return None;
+    }
+    let mut buffer = EventBuffer::new(64);
+    buffer.ignore_events(&self.ignore);
+    buffer.locate_fragment_ends(self.fragment_ends);
+    self.walk(self.root, n, &mut buffer);
+    let mut events = Vec::with_capacity(buffer.len());
+    buffer.flush_to(buffer.len(), &mut |e: &Event<ID, Σ>| events.push(e.clone()));
+    Some(events)
+  }
+
+  /// Enumerate the events of each parse lazily.
+  ///
+  pub fn parses(&self) -> impl Iterator<Item = Vec<Event<ID, Σ>>> + '_ {
+    (0..self.count()).map_while(|n| self.events(n))
+  }
+
+  /// Push the events of the `n`-th parse of the `node`. The nodes are walked with an explicit stack, since the forest
+  /// of a repetition is as deep as its occurrences.
+  ///
+  fn walk(&self, node: usize, n: u64, buffer: &mut EventBuffer<ID, Σ>) {
+    let mut stack = vec![self.walk_enter(node, n, buffer)];
+    while let Some((node, children, next, n)) = stack.last_mut() {
+      let Some(child) = children.get(*next) else {
+        let Node { name, end, .. } = &self.nodes[*node];
+        if let Some(id) = name {
+          buffer.push(Event { location: self.locations[*end], kind: EventKind::End(id.clone()), end: None });
+        }
+        stack.pop();
+        continue;
+      };
+      *next += 1;
+      match child {
+        Child::Node(i) => {
+          // decompose n into the index of each child in mixed radix
+          let count = self.nodes[*i].count;
+          let child_n = *n % count;
+          *n /= count;
+          stack.push(self.walk_enter(*i, child_n, buffer));
+        }
+        Child::Fragments(b, e) if b < e => {
+          let fragments = self.buffer[*b..*e].to_vec();
+          buffer.push(Event { location: self.locations[*b], kind: EventKind::Fragments(fragments), end: None });
+        }
+        Child::Fragments(..) => (),
+      }
+    }
+  }
+
+  /// Push the Begin event of the `node`, and return the children of the alternative of the `n`-th parse with the
+  /// index within it.
+  ///
+  fn walk_enter(&self, node: usize, mut n: u64, buffer: &mut EventBuffer<ID, Σ>) -> (usize, &[Child], usize, u64) {
+    let Node { name, begin, alternatives, .. } = &self.nodes[node];
+    if let Some(id) = name {
+      buffer.push(Event { location: self.locations[*begin], kind: EventKind::Begin(id.clone()), end: None });
+    }
+    for children in alternatives {
+      let count = self.count_of(children);
+      if n < count {
+        return (node, children, 0, n);
+      }
+      n -= count;
+    }
+    (node, &[], 0, n)
+  }
+
+  pub(crate) fn count_of(&self, children: &[Child]) -> u64 {
+    children.iter().fold(1u64, |count, child| match child {
+      Child::Node(i) => count.saturating_mul(self.nodes[*i].count),
+      Child::Fragments(..) => count,
+    })
+  }
+}
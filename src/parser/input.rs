@@ -0,0 +1,435 @@
+//! The input adapters that feed a parser from a file by [`Context::parse_path()`], or from an encoded stream by
+//! [`DecodedInput`], for the pipelines that process large logs or JSON files. [`DecodedInput`] reads a stream through a
+//! decoder and pushes the decoded bytes or text to a parser, e.g., for gzip or zstd files. The decoder is any [`Read`]
+//! that wraps the encoded stream, such as `flate2::read::MultiGzDecoder` or `zstd::Decoder`, so terp itself depends on
+//! no codec:
+//!
+//! ```rust
+//! use std::io::Read;
+//! use terp::parser::{Context, DecodedInput, Event};
+//! use terp::schema::chars::{ascii_digit, ch};
+//! use terp::schema::{id, Schema};
+//!
+//! let schema = Schema::new("Log").define("LOG", (ascii_digit() * (1..) & ch('\n')) * (0..));
+//! let encoded = b"1\n22\n333\n".as_slice();
+//! // e.g., `flate2::read::MultiGzDecoder::new` for gzip, or here a decoder that ends at 5 bytes
+//! let mut input = DecodedInput::new(encoded, |reader| reader.take(5));
+//! let mut parser = Context::new(&schema, "LOG", |_: &Event<_, _>| ()).unwrap();
+//! input.push_text_to(&mut parser).unwrap();
+//! parser.finish().unwrap();
+//! assert_eq!((5, 5), input.offsets());
+//! ```
+//!
+//! The locations of the events and the errors are in the decoded coordinates, and [`DecodedInput::encoded_offset()`]
+//! maps them to the encoded ones.
+//!
+use crate::parser::{Context, Event};
+use crate::schema::{Location, Symbol};
+use crate::Error;
+use std::fmt::{Debug, Display};
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+const READ_SIZE: usize = 64 * 1024;
+
+/// The encoded stream passed to the decoder, which counts the bytes that the decoder has read.
+///
+pub struct CountingReader<R: Read> {
+  inner: R,
+  count: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let n = self.inner.read(buf)?;
+    self.count.fetch_add(n as u64, Ordering::Relaxed);
+    Ok(n)
+  }
+}
+
+/// The error of [`DecodedInput`], with the number of the encoded bytes read when it occurred.
+///
+#[derive(thiserror::Error, Debug)]
+pub enum InputError<Σ: Symbol> {
+  /// The encoded stream couldn't be read or decoded, or the decoded text isn't UTF-8.
+  #[error("{source} (at {encoded} bytes of the encoded input)")]
+  Io {
+    encoded: u64,
+    #[source]
+    source: io::Error,
+  },
+  #[error("{error} (at {encoded} bytes of the encoded input)")]
+  Parse { encoded: u64, error: Error<Σ> },
+}
+
+/// The input read through a decoder, which pushes the decoded content to a parser while tracking the offsets in both
+/// the encoded and the decoded streams.
+///
+pub struct DecodedInput<D: Read> {
+  decoder: D,
+  encoded: Arc<AtomicU64>,
+  decoded: u64,
+  /// The pairs of the decoded and the encoded offsets after each read, in ascending order.
+  checkpoints: Vec<(u64, u64)>,
+  buffer: Vec<u8>,
+  text: Utf8Decoder,
+}
+
+impl<D: Read> DecodedInput<D> {
+  /// Read the `encoded` stream through the decoder that `decoder` creates from it.
+  ///
+  pub fn new<R: Read, F: FnOnce(CountingReader<R>) -> D>(encoded: R, decoder: F) -> Self {
+    let count = Arc::new(AtomicU64::new(0));
+    let decoder = decoder(CountingReader { inner: encoded, count: count.clone() });
+    Self {
+      decoder,
+      encoded: count,
+      decoded: 0,
+      checkpoints: Vec::new(),
+      buffer: vec![0; READ_SIZE],
+      text: Utf8Decoder::default(),
+    }
+  }
+
+  /// The numbers of the encoded bytes read and the decoded bytes produced so far.
+  ///
+  pub fn offsets(&self) -> (u64, u64) {
+    (self.encoded.load(Ordering::Relaxed), self.decoded)
+  }
+
+  /// The number of the encoded bytes that had been read when the byte at the `decoded` offset was produced.
+  /// Since the decoders read ahead, it's an upper bound of the offset in the encoded stream, which is exact only at
+  /// the boundaries such as the gzip members or the zstd frames that the decoder reads one at a time.
+  ///
+  pub fn encoded_offset(&self, decoded: u64) -> u64 {
+    let i = self.checkpoints.partition_point(|(end, _)| *end <= decoded);
+    match self.checkpoints.get(i) {
+      Some((_, encoded)) => *encoded,
+      None => self.encoded.load(Ordering::Relaxed),
+    }
+  }
+
+  /// Push all the decoded bytes to the `parser`, without finishing it.
+  ///
+  pub fn push_to<ID, H>(&mut self, parser: &mut Context<'_, ID, u8, H>) -> Result<(), InputError<u8>>
+  where
+    ID: Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
+    H: FnMut(&Event<ID, u8>),
+  {
+    while let Some(n) = self.read()? {
+      if let Err(error) = parser.push_seq(&self.buffer[..n]) {
+        return Err(self.parse_error(error));
+      }
+    }
+    Ok(())
+  }
+
+  /// Push all the decoded bytes to the `parser` as UTF-8 text, without finishing it.
+  ///
+  pub fn push_text_to<ID, H>(&mut self, parser: &mut Context<'_, ID, char, H>) -> Result<(), InputError<char>>
+  where
+    ID: Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
+    H: FnMut(&Event<ID, char>),
+  {
+    while let Some(n) = self.read()? {
+      if let Err(offset) = self.text.decode(&self.buffer[..n]) {
+        return Err(self.invalid_utf8(offset));
+      }
+      if let Err(error) = parser.push_seq(self.text.chars()) {
+        return Err(self.parse_error(error));
+      }
+    }
+    match self.text.finish() {
+      Ok(()) => Ok(()),
+      Err(offset) => Err(self.invalid_utf8(offset)),
+    }
+  }
+
+  /// Read the next decoded bytes into the buffer, and return their length, or `None` at the end of the stream.
+  ///
+  fn read<Σ: Symbol>(&mut self) -> Result<Option<usize>, InputError<Σ>> {
+    let n = loop {
+      match self.decoder.read(&mut self.buffer) {
+        Ok(n) => break n,
+        Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+        Err(source) => return Err(InputError::Io { encoded: self.offsets().0, source }),
+      }
+    };
+    if n == 0 {
+      return Ok(None);
+    }
+    self.decoded += n as u64;
+    let encoded = self.offsets().0;
+    match self.checkpoints.last_mut() {
+      Some((end, last)) if *last == encoded => *end = self.decoded,
+      _ => self.checkpoints.push((self.decoded, encoded)),
+    }
+    Ok(Some(n))
+  }
+
+  fn parse_error<Σ: Symbol>(&self, error: Error<Σ>) -> InputError<Σ> {
+    let encoded = match location(&error).and_then(|location| location.byte_offset()) {
+      Some(decoded) => self.encoded_offset(decoded),
+      None => self.offsets().0,
+    };
+    InputError::Parse { encoded, error }
+  }
+
+  fn invalid_utf8<Σ: Symbol>(&self, decoded: u64) -> InputError<Σ> {
+    let message = format!("the decoded text isn't UTF-8 at {} bytes", decoded);
+    let source = io::Error::new(io::ErrorKind::InvalidData, message);
+    InputError::Io { encoded: self.encoded_offset(decoded), source }
+  }
+}
+
+/// The location where the `error` was detected, if any.
+///
+fn location<Σ: Symbol>(error: &Error<Σ>) -> Option<Σ::Location> {
+  match error {
+    Error::Unmatched(detail) | Error::MultipleMatches(detail) => Some(detail.location),
+    Error::LimitExceeded { location, .. }
+    | Error::TooLong { location, .. }
+    | Error::Unreadable { location, .. }
+    | Error::Passthrough { location, .. }
+    | Error::Internal { location, .. } => Some(*location),
+    Error::Recovered(errors) => errors.first().and_then(location),
+    Error::UndefinedID(_) | Error::Previous | Error::Handler(_) | Error::Unsupported { .. } => None,
+  }
+}
+
+// ---------------------------------
+
+impl<'s, ID, H: FnMut(&Event<ID, u8>)> Context<'s, ID, u8, H>
+where
+  ID: 's + Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
+{
+  /// Push the content of the file at `path` without finishing the parse. The file is read in large chunks.
+  ///
+  /// A parse error is returned as an [`io::Error`] of [`InvalidData`](io::ErrorKind::InvalidData) that wraps the
+  /// [`Error`].
+  ///
+  pub fn parse_path<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+    for_each_chunk(path.as_ref(), |bytes| self.push_file_chunk(bytes))
+  }
+
+  /// Push the content of the file at `path` as `parse_path()` does, but memory-mapped and pushed in large slices
+  /// without copying. An empty file or one that isn't a regular file is read instead.
+  ///
+  /// # Safety
+  ///
+  /// The file must not be modified or truncated by this or any other process while it's parsed: the mapped bytes are
+  /// borrowed as an immutable `&[u8]`, so a write through another mapping or `write(2)` is undefined behavior, not
+  /// merely a garbled input, and a truncation raises `SIGBUS`. Use `parse_path()` for the files that may be written
+  /// concurrently, such as the logs being appended.
+  ///
+  #[cfg(all(feature = "mmap", unix))]
+  pub unsafe fn parse_path_mmap<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+    // SAFETY: the file is left unmodified while it's mapped, as required of the caller
+    unsafe { for_each_mapped_chunk(path.as_ref(), |bytes| self.push_file_chunk(bytes)) }
+  }
+
+  fn push_file_chunk(&mut self, bytes: &[u8]) -> io::Result<()> {
+    self.push_seq(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+  }
+}
+
+impl<'s, ID, H: FnMut(&Event<ID, char>)> Context<'s, ID, char, H>
+where
+  ID: 's + Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
+{
+  /// Push the UTF-8 text of the file at `path` without finishing the parse. The file is read as the byte version of
+  /// `parse_path()` does, and decoded a chunk at a time into a reused buffer rather than into the characters of the
+  /// whole file, where the runs of ASCII are widened without validation.
+  ///
+  /// A parse error is returned as an [`io::Error`] of [`InvalidData`](io::ErrorKind::InvalidData) that wraps the
+  /// [`Error`], as well as a text that isn't UTF-8.
+  ///
+  pub fn parse_path<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+    let mut text = Utf8Decoder::default();
+    for_each_chunk(path.as_ref(), |bytes| self.push_file_text(&mut text, bytes))?;
+    text.finish().map_err(invalid_utf8)
+  }
+
+  /// Push the UTF-8 text of the file at `path` as `parse_path()` does, but memory-mapped as the byte version of
+  /// `parse_path_mmap()` does.
+  ///
+  /// # Safety
+  ///
+  /// The file must not be modified or truncated by this or any other process while it's parsed, as the byte version
+  /// of `parse_path_mmap()` requires.
+  ///
+  #[cfg(all(feature = "mmap", unix))]
+  pub unsafe fn parse_path_mmap<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+    let mut text = Utf8Decoder::default();
+    // SAFETY: the file is left unmodified while it's mapped, as required of the caller
+    unsafe { for_each_mapped_chunk(path.as_ref(), |bytes| self.push_file_text(&mut text, bytes))? };
+    text.finish().map_err(invalid_utf8)
+  }
+
+  fn push_file_text(&mut self, text: &mut Utf8Decoder, bytes: &[u8]) -> io::Result<()> {
+    text.decode(bytes).map_err(invalid_utf8)?;
+    self.push_seq(text.chars()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+  }
+}
+
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Call `f` with the content of the file at `path` in order, which is split into chunks of [`CHUNK_SIZE`].
+///
+fn for_each_chunk<F: FnMut(&[u8]) -> io::Result<()>>(path: &Path, f: F) -> io::Result<()> {
+  read_chunks(File::open(path)?, f)
+}
+
+/// Call `f` with the content of the file at `path` as [`for_each_chunk()`] does, but memory-mapped if it's a regular
+/// file that isn't empty.
+///
+/// # Safety
+///
+/// The file must not be modified or truncated while it's mapped.
+///
+#[cfg(all(feature = "mmap", unix))]
+unsafe fn for_each_mapped_chunk<F: FnMut(&[u8]) -> io::Result<()>>(path: &Path, f: F) -> io::Result<()> {
+  let file = File::open(path)?;
+  // SAFETY: the file is left unmodified while it's mapped, as required of the caller
+  match unsafe { mmap::Mapped::new(&file)? } {
+    Some(mapped) => mapped.as_slice().chunks(CHUNK_SIZE).try_for_each(f),
+    None => read_chunks(file, f),
+  }
+}
+
+fn read_chunks<F: FnMut(&[u8]) -> io::Result<()>>(mut file: File, mut f: F) -> io::Result<()> {
+  let mut buffer = vec![0; CHUNK_SIZE];
+  loop {
+    match file.read(&mut buffer) {
+      Ok(0) => return Ok(()),
+      Ok(n) => f(&buffer[..n])?,
+      Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+      Err(e) => return Err(e),
+    }
+  }
+}
+
+fn invalid_utf8(offset: u64) -> io::Error {
+  io::Error::new(io::ErrorKind::InvalidData, format!("the text isn't UTF-8 at {} bytes", offset))
+}
+
+/// The decoder of UTF-8 text given in chunks, which may split a character.
+///
+#[derive(Default)]
+pub(crate) struct Utf8Decoder {
+  /// The characters decoded from the last chunk.
+  chars: Vec<char>,
+  /// The incomplete UTF-8 sequence at the end of the last chunk.
+  pending: Vec<u8>,
+  /// The number of the bytes given so far.
+  offset: u64,
+}
+
+impl Utf8Decoder {
+  /// Decode the `bytes` following the previous ones into [`chars()`](Self::chars), or return the offset of the invalid
+  /// UTF-8 sequence. A character split at the end is decoded with the next chunk.
+  ///
+  pub fn decode(&mut self, mut bytes: &[u8]) -> Result<(), u64> {
+    self.chars.clear();
+    let begin = self.offset - self.pending.len() as u64;
+    self.offset += bytes.len() as u64;
+    while !self.pending.is_empty() && !bytes.is_empty() {
+      self.pending.push(bytes[0]);
+      bytes = &bytes[1..];
+      match std::str::from_utf8(&self.pending) {
+        Ok(s) => {
+          self.chars.extend(s.chars());
+          self.pending.clear();
+        }
+        Err(e) if e.error_len().is_none() => (),
+        Err(_) => return Err(begin),
+      }
+    }
+    if !self.pending.is_empty() {
+      return Ok(());
+    }
+
+    let begin = self.offset - bytes.len() as u64;
+    let ascii = bytes.iter().position(|b| !b.is_ascii()).unwrap_or(bytes.len());
+    self.chars.extend(bytes[..ascii].iter().map(|b| *b as char));
+    let rest = &bytes[ascii..];
+    let valid = match std::str::from_utf8(rest) {
+      Ok(_) => rest.len(),
+      Err(e) if e.error_len().is_none() => e.valid_up_to(),
+      Err(e) => return Err(begin + (ascii + e.valid_up_to()) as u64),
+    };
+    self.chars.extend(std::str::from_utf8(&rest[..valid]).unwrap().chars());
+    self.pending.extend_from_slice(&rest[valid..]);
+    Ok(())
+  }
+
+  pub fn chars(&self) -> &[char] {
+    &self.chars
+  }
+
+  /// Return the offset of the incomplete UTF-8 sequence at the end, if any.
+  ///
+  pub fn finish(&self) -> Result<(), u64> {
+    if self.pending.is_empty() {
+      Ok(())
+    } else {
+      Err(self.offset - self.pending.len() as u64)
+    }
+  }
+}
+
+#[cfg(all(feature = "mmap", unix))]
+mod mmap {
+  use std::fs::File;
+  use std::io;
+  use std::os::unix::io::AsRawFd;
+
+  /// The read-only mapping of a whole file. `MAP_PRIVATE` doesn't snapshot the file: the pages not yet touched still
+  /// reflect the writes by other processes, so the slice of this mapping is sound only while nobody modifies the file.
+  pub struct Mapped {
+    ptr: *mut libc::c_void,
+    len: usize,
+  }
+
+  impl Mapped {
+    /// Map the `file`, or return `None` if it's empty or not a regular file, which is read instead.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated until the mapping is dropped.
+    ///
+    pub unsafe fn new(file: &File) -> io::Result<Option<Self>> {
+      let metadata = file.metadata()?;
+      if !metadata.is_file() || metadata.len() == 0 {
+        return Ok(None);
+      }
+      let len = usize::try_from(metadata.len()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+      // SAFETY: a new mapping doesn't alias any memory of this process
+      let ptr =
+        unsafe { libc::mmap(std::ptr::null_mut(), len, libc::PROT_READ, libc::MAP_PRIVATE, file.as_raw_fd(), 0) };
+      if ptr == libc::MAP_FAILED {
+        return Err(io::Error::last_os_error());
+      }
+      Ok(Some(Self { ptr, len }))
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+      // SAFETY: the mapping of `len` bytes is readable until it's dropped, and immutable as long as the file isn't
+      // modified, as required by `Mapped::new()`
+      unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+  }
+
+  impl Drop for Mapped {
+    fn drop(&mut self) {
+      // SAFETY: the mapping was created by `mmap` and is no longer borrowed
+      unsafe {
+        libc::munmap(self.ptr, self.len);
+      }
+    }
+  }
+}
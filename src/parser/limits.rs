@@ -0,0 +1,85 @@
+/// The upper limits of the resources that a [`Context`](crate::parser::Context) may consume while parsing. Parsing
+/// fails with [`Error::LimitExceeded`](crate::Error::LimitExceeded) when any of them is exceeded.
+///
+/// All of them are unlimited by default. [`Limits::hardened()`] is a preset intended for untrusted input.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Limits {
+  /// The maximum depth of nested syntaxes being evaluated on a single path.
+  pub max_depth: usize,
+  /// The maximum number of paths evaluated at the same time, i.e., the degree of ambiguity.
+  pub max_paths: usize,
+  /// The maximum number of symbols retained in the internal buffer until they're confirmed.
+  pub max_buffer_size: usize,
+  /// The maximum number of events held by a single path until they're confirmed.
+  pub max_pending_events: usize,
+  /// The maximum number of symbols matched by a single term, including those held while it can accept more. The path
+  /// exceeding it is dropped, and parsing fails with it unless another path gets farther. The lengths of the
+  /// individual rules are limited by [`Schema::limit_length()`](crate::schema::Schema::limit_length).
+  pub max_fragment_length: usize,
+}
+
+impl Limits {
+  pub const UNLIMITED: Limits = Limits {
+    max_depth: usize::MAX,
+    max_paths: usize::MAX,
+    max_buffer_size: usize::MAX,
+    max_pending_events: usize::MAX,
+    max_fragment_length: usize::MAX,
+  };
+
+  /// The limits for untrusted input. With these, the memory held by a context is bounded by
+  /// `O(max_paths × (max_depth + max_pending_events) + max_buffer_size)`, regardless of the input. A pushed symbol is
+  /// evaluated by `O(max_paths × max_depth)` calls of the matchers, but a term that can accept more, such as
+  /// [`line()`](crate::schema::chars::line) or the skip of [`Schema::recover_at()`](crate::schema::Schema::recover_at),
+  /// is called again with all the symbols it has held so far, up to `max_fragment_length`. So the time to evaluate a
+  /// symbol is bounded by `O(max_paths × (max_depth + max_fragment_length))` for the matchers linear in their window.
+  ///
+  pub const fn hardened() -> Limits {
+    Limits {
+      max_depth: 256,
+      max_paths: 256,
+      max_buffer_size: 1024 * 1024,
+      max_pending_events: 64 * 1024,
+      max_fragment_length: 64 * 1024,
+    }
+  }
+
+  /// These limits with [`max_depth`](Self::max_depth) replaced, since the fields can't be set by a struct expression
+  /// outside terp, e.g., `Limits::hardened().with_max_depth(64)`.
+  ///
+  pub const fn with_max_depth(self, max_depth: usize) -> Limits {
+    Limits { max_depth, ..self }
+  }
+
+  /// These limits with [`max_paths`](Self::max_paths) replaced.
+  ///
+  pub const fn with_max_paths(self, max_paths: usize) -> Limits {
+    Limits { max_paths, ..self }
+  }
+
+  /// These limits with [`max_buffer_size`](Self::max_buffer_size) replaced.
+  ///
+  pub const fn with_max_buffer_size(self, max_buffer_size: usize) -> Limits {
+    Limits { max_buffer_size, ..self }
+  }
+
+  /// These limits with [`max_pending_events`](Self::max_pending_events) replaced.
+  ///
+  pub const fn with_max_pending_events(self, max_pending_events: usize) -> Limits {
+    Limits { max_pending_events, ..self }
+  }
+
+  /// These limits with [`max_fragment_length`](Self::max_fragment_length) replaced.
+  ///
+  pub const fn with_max_fragment_length(self, max_fragment_length: usize) -> Limits {
+    Limits { max_fragment_length, ..self }
+  }
+}
+
+impl Default for Limits {
+  fn default() -> Self {
+    Self::UNLIMITED
+  }
+}
@@ -1,15 +1,51 @@
 use crate::schema::{Location, Primary, Schema, Symbol, Syntax};
-use crate::{debug, Error, Result};
+use crate::{debug, Error, ErrorDetail, HandlerError, HandlerPanic, Result};
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Instant;
 
 mod path;
 pub(crate) use path::*;
 
+mod channel;
+pub use channel::*;
+
+mod chunked;
+pub use chunked::*;
+
+mod earley;
+pub use earley::Engine;
+
 mod event;
 pub use event::*;
 
+mod forest;
+pub use forest::Forest;
+
+mod input;
+pub use input::*;
+
+mod limits;
+pub use limits::*;
+
+mod profile;
+pub use profile::*;
+
+mod record;
+pub use record::*;
+
+mod sampling;
+pub use sampling::*;
+
+mod summary;
+pub use summary::ParseSummary;
+
+pub mod testing;
+
 #[cfg(test)]
 pub mod test;
 
@@ -17,14 +53,154 @@ pub struct Context<'s, ID, Σ: Symbol, H: FnMut(&Event<ID, Σ>)>
 where
   ID: Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
 {
+  schema: &'s Schema<ID, Σ>,
   id: ID,
   event_handler: H,
+  /// The location where the input starts.
+  origin: Σ::Location,
   location: Σ::Location,
   buffer: Vec<Σ>,
   offset_of_buffer_head: u64,
   ongoing: Vec<Path<'s, ID, Σ>>,
   prev_completed: Vec<Path<'s, ID, Σ>>,
   prev_unmatched: Vec<Path<'s, ID, Σ>>,
+  recovered: Vec<Error<Σ>>,
+  /// The farthest path dropped for exceeding a maximum length, see [`NextPaths::too_long`].
+  too_long: Option<(Σ::Location, Error<Σ>)>,
+  last_recovery: Option<(u64, usize)>,
+  limits: Limits,
+  sampling: Sampling,
+  parallel: bool,
+  engine: Engine,
+  ignore: Vec<(ID, Ignore)>,
+  fragment_ends: bool,
+  accept_empty: bool,
+  profile: Option<Profile<ID>>,
+  delivery: Delivery<'s, ID, Σ>,
+  passthrough: usize,
+}
+
+/// The state of the delivery of the confirmed events to the handler.
+///
+struct Delivery<'s, ID, Σ: Symbol>
+where
+  ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+{
+  subscriptions: Vec<Subscription<'s, ID, Σ>>,
+  counting: bool,
+  counts: BTreeMap<ID, usize>,
+  until: Option<ID>,
+  /// The end of the syntax specified by `until_first()` once it has completed.
+  reached: Option<Σ::Location>,
+  events: usize,
+  ends: usize,
+  try_handler: Option<TryHandler<'s, ID, Σ>>,
+  /// The error of the `try_handler` that hasn't been reported yet.
+  failure: Option<HandlerError>,
+  catch_panics: bool,
+  /// Whether a handler is being called, which remains set if it has panicked.
+  delivering: bool,
+  pairing: Pairing<ID>,
+  /// The location of the event that has violated the pairing of Begin and End, which isn't delivered.
+  violated: Option<Σ::Location>,
+}
+
+type Handler<'s, ID, Σ> = Box<dyn FnMut(&Event<ID, Σ>) + 's>;
+type TryHandler<'s, ID, Σ> = Box<dyn FnMut(&Event<ID, Σ>) -> std::result::Result<(), HandlerError> + 's>;
+
+/// A handler registered by [`Context::on()`] with the syntaxes whose events it receives.
+///
+struct Subscription<'s, ID, Σ: Symbol>
+where
+  ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+{
+  ids: Vec<ID>,
+  handler: Handler<'s, ID, Σ>,
+  /// The number of the subscribed syntaxes being delivered.
+  depth: usize,
+}
+
+impl<ID: Clone + Hash + Eq + Ord + Display + Debug, Σ: Symbol> Delivery<'_, ID, Σ> {
+  fn deliver<H: FnMut(&Event<ID, Σ>)>(&mut self, handler: &mut H, e: &Event<ID, Σ>) {
+    if self.reached.is_some() || self.failure.is_some() || self.violated.is_some() {
+      return;
+    }
+    if !self.pairing.verify(&e.kind) {
+      self.violated = Some(e.location);
+      return;
+    }
+    self.delivering = true;
+    if self.catch_panics {
+      if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| self.dispatch(handler, e))) {
+        self.failure = Some(HandlerError::new(HandlerPanic::new(payload)));
+      }
+    } else {
+      self.dispatch(handler, e);
+    }
+    self.delivering = false;
+  }
+
+  fn dispatch<H: FnMut(&Event<ID, Σ>)>(&mut self, handler: &mut H, e: &Event<ID, Σ>) {
+    self.events += 1;
+    if let EventKind::End(id) = &e.kind {
+      self.ends += 1;
+      if self.counting {
+        *self.counts.entry(id.clone()).or_default() += 1;
+      }
+      if self.until.as_ref() == Some(id) {
+        self.reached = Some(e.location);
+      }
+    }
+    (handler)(e);
+    if let Some(Err(err)) = self.try_handler.as_mut().map(|try_handler| (try_handler)(e)) {
+      self.failure = Some(err);
+      return;
+    }
+    for subscription in &mut self.subscriptions {
+      match &e.kind {
+        EventKind::Begin(id) if subscription.ids.contains(id) => subscription.depth += 1,
+        EventKind::End(id) if subscription.ids.contains(id) => {
+          (subscription.handler)(e);
+          subscription.depth -= 1;
+          continue;
+        }
+        _ => (),
+      }
+      if subscription.depth > 0 {
+        (subscription.handler)(e);
+      }
+    }
+  }
+}
+
+impl<'s, ID, Σ: 'static + Symbol> Context<'s, ID, Σ, fn(&Event<ID, Σ>)>
+where
+  ID: 's + Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
+{
+  /// Create a context that only validates the input. It holds no events at all, so it's faster than a context with a
+  /// handler that discards events. The result and the errors are the same as those of [`new()`](Self::new).
+  ///
+  pub fn recognizer(schema: &'s Schema<ID, Σ>, id: ID) -> Result<Σ, Self> {
+    let mut context = Self::new(schema, id, |_| ())?;
+    for ongoing in &mut context.ongoing {
+      ongoing.event_buffer_mut().set_recording(Recording::Nothing);
+    }
+    Ok(context)
+  }
+
+  /// Create a context with a `handler` that may fail, e.g. writing the events to a socket or a file. Once it returns
+  /// an error, no more events are delivered and the parse is aborted with [`Error::Handler`].
+  ///
+  pub fn try_new<F, E>(schema: &'s Schema<ID, Σ>, id: ID, mut handler: F) -> Result<Σ, Self>
+  where
+    F: FnMut(&Event<ID, Σ>) -> std::result::Result<(), E> + 's,
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+  {
+    let mut context = Self::new(schema, id, |_| ())?;
+    let try_handler = move |e: &Event<ID, Σ>| handler(e).map_err(|err| HandlerError::new(err));
+    context.delivery.try_handler = Some(Box::new(try_handler));
+    Ok(context)
+  }
 }
 
 impl<'s, ID, Σ: 'static + Symbol, H: FnMut(&Event<ID, Σ>)> Context<'s, ID, Σ, H>
@@ -32,41 +208,317 @@ where
   ID: 's + Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
 {
   pub fn new(schema: &'s Schema<ID, Σ>, id: ID, event_handler: H) -> Result<Σ, Self> {
+    Self::new_at(schema, id, event_handler, Σ::Location::default())
+  }
+
+  /// Create a context whose input starts at `location`, e.g., a location with the
+  /// [`Newline`](crate::schema::chars::Newline) convention of the text.
+  ///
+  pub fn new_at(schema: &'s Schema<ID, Σ>, id: ID, event_handler: H, location: Σ::Location) -> Result<Σ, Self> {
     let buffer = Vec::with_capacity(1024);
 
     let mut first = Path::new(&id, schema)?;
+    first.current_mut().location = location;
     first.events_push(first.current().event(EventKind::Begin(id.clone())));
     let mut ongoing = Vec::with_capacity(16);
     ongoing.push(first);
 
-    let location = Σ::Location::default();
     let prev_completed = Vec::with_capacity(16);
     let prev_unmatched = Vec::with_capacity(16);
-    Ok(Self { id, event_handler, location, buffer, offset_of_buffer_head: 0, ongoing, prev_completed, prev_unmatched })
+    Ok(Self {
+      schema,
+      id,
+      event_handler,
+      origin: location,
+      location,
+      buffer,
+      offset_of_buffer_head: location.position(),
+      ongoing,
+      prev_completed,
+      prev_unmatched,
+      recovered: Vec::new(),
+      too_long: None,
+      last_recovery: None,
+      limits: Limits::default(),
+      sampling: Sampling::of::<Σ>(),
+      parallel: true,
+      engine: Engine::default(),
+      ignore: Vec::new(),
+      fragment_ends: false,
+      accept_empty: true,
+      profile: None,
+      delivery: Delivery {
+        subscriptions: Vec::new(),
+        counting: false,
+        counts: BTreeMap::new(),
+        until: None,
+        reached: None,
+        events: 0,
+        ends: 0,
+        try_handler: None,
+        failure: None,
+        catch_panics: false,
+        delivering: false,
+        pairing: Pairing::new(),
+        violated: None,
+      },
+      passthrough: 0,
+    })
+  }
+
+  /// Hide the Begin and End events of the syntaxes `ids`, the same as [`Ignore::Node`] of
+  /// [`ignore_events_with()`](Self::ignore_events_with).
+  ///
+  pub fn ignore_events_for(self, ids: &[ID]) -> Self {
+    self.ignore_events_with(ids, Ignore::Node)
+  }
+
+  /// Hide the events of the syntaxes `ids` in the way of `ignore`, such as only their Begin and End events or all the
+  /// events within them.
+  ///
+  pub fn ignore_events_with(mut self, ids: &[ID], ignore: Ignore) -> Self {
+    let ignore = ids.iter().map(|id| (id.clone(), ignore)).collect::<Vec<_>>();
+    for ongoing in &mut self.ongoing {
+      ongoing.event_buffer_mut().ignore_events(&ignore);
+    }
+    self.ignore.extend(ignore);
+    self
+  }
+
+  /// Set the [`end`](Event::end) of each Fragments event to the location just after its last symbol, for consumers
+  /// that need the range of the fragments such as highlighting.
+  ///
+  pub fn with_fragment_ends(mut self, enabled: bool) -> Self {
+    for ongoing in &mut self.ongoing {
+      ongoing.event_buffer_mut().locate_fragment_ends(enabled);
+    }
+    self.fragment_ends = enabled;
+    self
+  }
+
+  /// Whether an entirely empty input is accepted if the syntax of this context is nullable, i.e., it appears in
+  /// [`Schema::nullable_rules()`](crate::schema::Schema::nullable_rules). It's accepted by default, with only the
+  /// Begin and End events of the syntax. If disabled, [`finish()`](Self::finish) without any symbols fails with
+  /// [`Error::Unmatched`] at the beginning of the input, expecting the first syntaxes of the rule, for the formats in
+  /// which an empty file is more likely a truncated one. A non-nullable syntax never accepts an empty input.
+  ///
+  pub fn accept_empty(mut self, accept: bool) -> Self {
+    self.accept_empty = accept;
+    self
+  }
+
+  /// Switch the parsing engine. Note that [`Engine::Earley`] delivers no events until [`finish()`](Self::finish).
+  ///
+  pub fn with_engine(mut self, engine: Engine) -> Self {
+    self.engine = engine;
+    self
+  }
+
+  /// Set the limits of the resources that this context may consume.
+  ///
+  pub fn limits(mut self, limits: Limits) -> Self {
+    self.limits = limits;
+    self
+  }
+
+  /// Configure how the input is sampled into the errors, e.g., [`Sampling::omitted()`] not to echo the input back.
+  ///
+  pub fn sampling(mut self, sampling: Sampling) -> Self {
+    self.sampling = sampling;
+    self
+  }
+
+  /// Configure this context to parse untrusted input. This applies [`Limits::hardened()`] and evaluates paths
+  /// serially even if the `concurrent` feature is enabled, so that a crafted input cannot occupy all threads.
+  ///
+  pub fn hardened(mut self) -> Self {
+    self.parallel = false;
+    self.limits(Limits::hardened())
+  }
+
+  /// Catch the panics of the handlers and abort the parse with [`Error::Handler`] whose source is a [`HandlerPanic`],
+  /// as if the handler of [`try_new()`](Self::try_new) had failed, instead of unwinding through this context. The
+  /// panic hook still reports the panic as usual.
+  ///
+  /// Without this, a context through which a handler's panic has unwound is poisoned, see
+  /// [`is_poisoned()`](Self::is_poisoned).
+  ///
+  pub fn catch_handler_panics(mut self) -> Self {
+    self.delivery.catch_panics = true;
+    self
   }
 
-  pub fn ignore_events_for(mut self, ids: &[ID]) -> Self {
+  /// Whether a handler has panicked while this context was delivering an event to it, so that the state of the
+  /// parse is inconsistent. All the operations of a poisoned context fail with [`Error::Previous`], and a long-lived
+  /// service that catches the panic outside should discard it and start with a new context.
+  ///
+  pub fn is_poisoned(&self) -> bool {
+    self.delivery.delivering
+  }
+
+  /// Register a handler that receives only the events of the syntaxes `ids`, from their Begin events to their End
+  /// events including the nested ones, in addition to the handler of this context. Each of the handlers receives the
+  /// events in the same order.
+  ///
+  pub fn on<F: FnMut(&Event<ID, Σ>) + 's>(mut self, ids: &[ID], handler: F) -> Self {
+    self.delivery.subscriptions.push(Subscription { ids: ids.to_vec(), handler: Box::new(handler), depth: 0 });
+    self
+  }
+
+  /// Count how many times each syntax has completed. The counts are available from [`counts()`](Self::counts) even
+  /// for a [`recognizer()`](Self::recognizer), which then holds the structure of the events, but not the symbols.
+  ///
+  pub fn counting(mut self) -> Self {
     for ongoing in &mut self.ongoing {
-      ongoing.event_buffer_mut().ignore_events_for(ids);
+      if ongoing.event_buffer().recording() == Recording::Nothing {
+        ongoing.event_buffer_mut().set_recording(Recording::Structure);
+      }
     }
+    self.delivery.counting = true;
+    self
+  }
+
+  /// Collect the costs of parsing attributed to the rules, such as the time to evaluate their terms and the number of
+  /// paths they fork, which are available from [`profile()`](Self::profile). It's intended to find the hot spots of a
+  /// schema, and isn't supported by [`Engine::Earley`].
+  ///
+  pub fn profiling(mut self) -> Self {
+    self.profile = Some(Profile::new(self.id.clone()));
     self
   }
 
+  /// The costs of parsing attributed to the rules so far, if [`profiling()`](Self::profiling) is specified.
+  ///
+  pub fn profile(&self) -> Option<&Profile<ID>> {
+    self.profile.as_ref()
+  }
+
+  /// The number of times each syntax has completed so far, that is, the number of End events delivered for it. This
+  /// is empty unless [`counting()`](Self::counting) is specified.
+  ///
+  pub fn counts(&self) -> &BTreeMap<ID, usize> {
+    &self.delivery.counts
+  }
+
+  /// Stop parsing as soon as the syntax `id` completes for the first time. The End event of `id` is the last event
+  /// delivered, and the rest of the input is discarded without being parsed, so [`finish()`](Self::finish) succeeds
+  /// even if the input doesn't match the whole syntax. Note that the events of `id` must not be ignored, and that the
+  /// Begin events of the enclosing syntaxes aren't closed. The symbols following `id` that have already been pushed can
+  /// be taken back by [`unread()`](Self::unread).
+  ///
+  /// [`Engine::Earley`] parses the whole input on `finish()`, so the input must match, but no events are delivered
+  /// after that.
+  ///
+  pub fn until_first(mut self, id: ID) -> Self {
+    self.delivery.until = Some(id);
+    self
+  }
+
+  /// Whether the syntax specified by [`until_first()`](Self::until_first) has completed, so that the rest of the input
+  /// is no longer needed.
+  ///
+  pub fn is_reached(&self) -> bool {
+    self.delivery.reached.is_some()
+  }
+
+  /// Take back the last `n` symbols that haven't been confirmed, so that they can be passed to another parser. The
+  /// symbols following the syntax specified by [`until_first()`](Self::until_first) are unconfirmed once it has
+  /// completed, and so are all the symbols pushed to [`Engine::Earley`] before [`finish()`](Self::finish). The
+  /// streaming engines evaluate each symbol as soon as it's pushed, so otherwise no symbol can be unread.
+  ///
+  pub fn unread(&mut self, n: usize) -> Result<Σ, Vec<Σ>> {
+    let available =
+      if self.delivery.reached.is_some() || self.engine == Engine::Earley { self.buffer.len() } else { 0 };
+    if n > available {
+      return Err(Error::Unreadable { location: self.location, available });
+    }
+    let symbols = self.buffer.split_off(self.buffer.len() - n);
+    self.location = self.delivery.reached.unwrap_or(self.origin);
+    self.location.increment_with_seq(&self.buffer);
+    Ok(symbols)
+  }
+
+  /// The symbols that this context is still holding because some path hasn't consumed them yet, and the absolute
+  /// offset of the first one, e.g. to resynchronize the input by a recovery of its own, or to pass them to another
+  /// parser. These are the symbols following the syntax specified by [`until_first()`](Self::until_first) once it has
+  /// completed, and all the symbols pushed to [`Engine::Earley`] before [`finish()`](Self::finish). After an error,
+  /// these are all the symbols retained in the internal buffer.
+  ///
+  pub fn buffered(&self) -> (u64, &[Σ]) {
+    let paths = self.ongoing.iter().chain(&self.prev_completed).chain(&self.prev_unmatched);
+    let head = paths.map(|path| path.min_match_begin()).min().unwrap_or(0);
+    (self.offset_of_buffer_head + head as u64, &self.buffer[head..])
+  }
+
   pub fn id(&self) -> &ID {
     &self.id
   }
 
+  /// The errors that have been recovered so far by the recovery sets declared with
+  /// [`Schema::recover_at()`](crate::schema::Schema::recover_at).
+  ///
+  pub fn recovered_errors(&self) -> &[Error<Σ>] {
+    &self.recovered
+  }
+
+  /// Deliver the next `n` symbols to the handler as Fragments events without matching them, such as a binary body
+  /// whose length has been parsed. The parsing then resumes as if they didn't exist, except for the locations. All the
+  /// symbols pushed so far must have been matched, e.g. up to the delimiter following the length. It fails with
+  /// [`Error::Unsupported`] if the engine is [`Engine::Earley`], which doesn't parse until [`finish()`](Self::finish).
+  ///
+  pub fn passthrough(&mut self, n: usize) -> Result<Σ, ()> {
+    if self.engine == Engine::Earley {
+      return Err(Error::Unsupported { operation: "passthrough", engine: self.engine });
+    }
+    self.check_for_previous_error()?;
+    let remaining = self.passthrough + n;
+    if self.ongoing.is_empty() || self.ongoing.iter().any(|path| path.current().match_begin < self.buffer.len()) {
+      return Err(Error::Passthrough { location: self.location, remaining });
+    }
+    self.passthrough = remaining;
+    Ok(())
+  }
+
   pub fn push(&mut self, item: Σ) -> Result<Σ, ()> {
     let buffer = [item];
     self.push_seq(&buffer)
   }
 
+  /// Parse the symbols `items` following those pushed so far. The events and the errors don't depend on how the input
+  /// is split into the pushes.
+  ///
+  /// An unmatched symbol is therefore reported, or recovered, once as many symbols as
+  /// [`Sampling::actual_length`] follow it for [`ErrorDetail::actual`], or on [`finish()`](Self::finish) if the input
+  /// ends before that. A sampling with no following symbols, e.g. `Sampling::of::<char>().with_lengths(12, 0)`,
+  /// reports it as soon as it's pushed.
+  ///
   pub fn push_seq(&mut self, items: &[Σ]) -> Result<Σ, ()> {
+    if self.is_poisoned() {
+      return Err(Error::Previous);
+    } else if self.delivery.reached.is_some() {
+      return Ok(());
+    } else if self.passthrough > 0 {
+      let n = std::cmp::min(self.passthrough, items.len());
+      self.pass_through(&items[..n])?;
+      return if n < items.len() { self.push_seq(&items[n..]) } else { Ok(()) };
+    } else if self.delivery.until.is_some() && self.engine != Engine::Earley && items.len() > 1 {
+      // the symbols following the completion must not be parsed, since they may not match
+      for (i, item) in items.iter().enumerate() {
+        self.push(*item)?;
+        if self.delivery.reached.is_some() {
+          self.buffer.extend_from_slice(&items[i + 1..]);
+          self.location.increment_with_seq(&items[i + 1..]);
+          break;
+        }
+      }
+      return Ok(());
+    }
+
     debug!(
       "PUSH: {:?}, buf_size={}, {}",
-      Σ::debug_symbols(items),
+      if self.schema.has_sensitives() { format!("{} symbols", items.len()) } else { Σ::debug_symbols(items) },
       self.buffer.len(),
-      if cfg!(feature = "concurrent") { "concurrent" } else { "serial" }
+      if cfg!(feature = "concurrent") && self.parallel { "concurrent" } else { "serial" }
     );
     for (i, path) in self.ongoing.iter().enumerate() {
       debug!("  ONGOING[{}]: {}", i, path)
@@ -84,54 +536,215 @@ where
     if items.is_empty() {
       return Ok(());
     }
+    if self.engine == Engine::Earley {
+      // all symbols are parsed at once on finish
+      return self.check_buffer_size();
+    }
 
-    self.proceed(false)?;
+    self.proceed(false).or_else(|e| self.error(e))?;
+    while self.recover(false) {
+      self.proceed(false).or_else(|e| self.error(e))?;
+    }
 
-    self.deliver_confirmed_events();
+    self.deliver_confirmed_events()?;
+    if let Some(reached) = self.delivery.reached {
+      self.discard(reached);
+      return Ok(());
+    }
 
     self.check_whether_unmatch_confirmed()?;
 
     // reduce internal buffer if possible
     self.fit_buffer_to_min_size(items.len());
+    self.check_buffer_size()?;
 
     Ok(())
   }
 
-  pub fn finish(mut self) -> Result<Σ, ()> {
+  /// Finish parsing and return the summary of the parse.
+  ///
+  pub fn finish(mut self) -> Result<Σ, ParseSummary<Σ>> {
+    self.finish_parsing()?;
+    let location = self.delivery.reached.unwrap_or(self.location);
+    Ok(ParseSummary {
+      symbols: location.position() - self.origin.position(),
+      events: self.delivery.events,
+      location,
+      rules: self.delivery.ends,
+    })
+  }
+
+  /// Finish parsing without the summary, like [`finish()`](Self::finish).
+  ///
+  pub fn finish_unit(mut self) -> Result<Σ, ()> {
+    self.finish_parsing()
+  }
+
+  /// Finish parsing and return the number of times each syntax has completed, like [`counts()`](Self::counts) after
+  /// all events are delivered. This requires [`counting()`](Self::counting) to be specified.
+  ///
+  pub fn finish_counts(mut self) -> Result<Σ, BTreeMap<ID, usize>> {
+    self.finish_parsing()?;
+    Ok(self.delivery.counts)
+  }
+
+  /// Finish parsing and return the costs attributed to the rules throughout the parse, which has no rules unless
+  /// [`profiling()`](Self::profiling) is specified.
+  ///
+  pub fn finish_profile(mut self) -> Result<Σ, Profile<ID>> {
+    self.finish_parsing()?;
+    Ok(self.profile.unwrap_or_else(|| Profile::new(self.id)))
+  }
+
+  /// Finish parsing with this schema and continue parsing the rest of the input with another one, for a layered
+  /// protocol such as the headers and the body of HTTP. If the syntax specified by [`until_first()`](Self::until_first)
+  /// has completed, the symbols following it are parsed by the returned context immediately, otherwise all the symbols
+  /// must have matched this schema as [`finish()`](Self::finish). The returned context inherits the limits of this
+  /// context, and reports the locations following those of this context.
+  ///
+  pub fn handoff<'t, ID2, H2>(
+    mut self, schema: &'t Schema<ID2, Σ>, id: ID2, event_handler: H2,
+  ) -> Result<Σ, Context<'t, ID2, Σ, H2>>
+  where
+    ID2: 't + Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
+    H2: FnMut(&Event<ID2, Σ>),
+  {
+    let (location, rest) = match self.delivery.reached {
+      Some(reached) => (reached, std::mem::take(&mut self.buffer)),
+      None => {
+        self.finish_parsing()?;
+        (self.location, Vec::new())
+      }
+    };
+    let mut next = Context::new_at(schema, id, event_handler, location)?;
+    next.limits = self.limits;
+    next.parallel = self.parallel;
+    next.push_seq(&rest)?;
+    Ok(next)
+  }
+
+  fn finish_parsing(&mut self) -> Result<Σ, ()> {
     debug!("FINISH");
 
+    if self.is_poisoned() {
+      return Err(Error::Previous);
+    } else if self.delivery.reached.is_some() {
+      return Ok(());
+    }
+
     self.check_for_previous_error()?;
+    if self.passthrough > 0 {
+      return self.error(Error::Passthrough { location: self.location, remaining: self.passthrough });
+    }
+    if !self.accept_empty && self.location.position() == self.origin.position() && self.buffer.is_empty() {
+      let paths = [&self.ongoing, &self.prev_completed, &self.prev_unmatched];
+      let expecteds = paths.into_iter().find(|paths| !paths.is_empty()).unwrap();
+      return self.error(self.error_unmatch(expecteds));
+    }
 
-    while !self.ongoing.is_empty() {
-      self.proceed(true)?;
+    if self.engine == Engine::Earley {
+      let (delivery, handler) = (&mut self.delivery, &mut self.event_handler);
+      let mut handler = |e: &Event<ID, Σ>| delivery.deliver(handler, e);
+      let (schema, id, origin, buffer) = (self.schema, &self.id, self.origin, &self.buffer);
+      let result =
+        earley::parse(schema, id, origin, buffer, &self.ignore, self.fragment_ends, &self.sampling, &mut handler);
+      result.or_else(|e| self.error(e))?;
+      return self.check_delivery_failure();
+    }
+
+    loop {
+      while !self.ongoing.is_empty() {
+        self.proceed(true).or_else(|e| self.error(e))?;
+      }
+      if !self.recover(true) {
+        break;
+      }
     }
 
     match self.prev_completed.len() {
       1 => {
         // notify all remaining events and success
+        self.prev_unmatched.clear();
         self.prev_completed[0].completed();
-        self.prev_completed[0].events_push(Event { location: self.location, kind: EventKind::End(self.id.clone()) });
-        self.deliver_confirmed_events();
+        self.prev_completed[0].events_push(Event {
+          location: self.location,
+          kind: EventKind::End(self.id.clone()),
+          end: None,
+        });
+        self.deliver_confirmed_events()?;
 
-        Ok(())
+        if self.recovered.is_empty() {
+          Ok(())
+        } else {
+          Err(Error::Recovered(std::mem::take(&mut self.recovered)))
+        }
+      }
+      0 => {
+        self.deliver_events_confirmed_by_unmatched()?;
+        let err = self.error_no_paths();
+        self.error(err)
       }
-      0 => self.error(self.error_unmatch(&self.prev_unmatched)),
       _ => {
-        let (prefix, expecteds, actual) =
-          create_unmatched_labels(&self.buffer, self.offset_of_buffer_head, &self.prev_completed);
-        self.error(Error::MultipleMatches { location: self.location, prefix, expecteds, actual })
+        let (prefix, expecteds, expected_syntaxes, actual) =
+          create_unmatched_labels(&self.buffer, self.offset_of_buffer_head, &self.prev_completed, &self.sampling);
+        let location = self.location;
+        self.error(Error::MultipleMatches(Box::new(ErrorDetail {
+          location,
+          prefix,
+          expecteds,
+          expected_syntaxes,
+          actual,
+        })))
       }
     }
   }
 
+  /// Finish parsing and build the forest of all parses instead of delivering events, so that an ambiguous input
+  /// doesn't result in [`Error::MultipleMatches`]. The events of each parse can be enumerated from the forest. It
+  /// fails with [`Error::Unsupported`] if the engine isn't [`Engine::Earley`], because the other engines have already
+  /// delivered events.
+  ///
+  pub fn finish_forest(self) -> Result<Σ, Forest<ID, Σ>> {
+    if self.engine != Engine::Earley {
+      return Err(Error::Unsupported { operation: "forest", engine: self.engine });
+    }
+    self.check_for_previous_error()?;
+    earley::parse_forest(
+      self.schema,
+      &self.id,
+      self.origin,
+      &self.buffer,
+      &self.ignore,
+      self.fragment_ends,
+      &self.sampling,
+    )
+  }
+
   fn proceed(&mut self, eof: bool) -> Result<Σ, ()> {
+    span!("proceed", eof, position = self.location.position(), paths = self.ongoing.len());
     if !eof {
-      self.prev_completed.truncate(0);
-      self.prev_unmatched.truncate(0);
+      // the paths completed before the symbols pushed now have failed to match them, as if pushed at once
+      for mut path in std::mem::take(&mut self.prev_completed) {
+        if Self::uncapture_exists(&path, &self.buffer) {
+          path.overrun();
+          self.push_unmatched(path);
+        }
+      }
     }
     let mut evaluating: Vec<Path<'s, ID, Σ>> = Vec::with_capacity(self.ongoing.len());
-    for path in self.ongoing.drain(..) {
-      evaluating.append(&mut Self::move_ongoing_paths_to_next_term(path)?);
+    for path in std::mem::take(&mut self.ongoing) {
+      let profile = self.profile.as_mut();
+      let (mut term_reached, completed) =
+        Self::move_ongoing_paths_to_next_term(path, &self.buffer, eof, &self.limits, profile)?;
+      evaluating.append(&mut term_reached);
+      for mut path in completed {
+        if Self::uncapture_exists(&path, &self.buffer) {
+          path.overrun();
+          self.push_unmatched(path);
+        } else {
+          self.prev_completed.push(path);
+        }
+      }
     }
 
     let mut i = 0;
@@ -139,91 +752,176 @@ where
       debug!("--- iteration[{}] ---", i + 1);
       i += 1;
 
+      // proceed only the paths behind the others, so that the paths reaching the same position are merged there
+      let behind = evaluating.iter().map(|path| path.current().match_begin).min().unwrap_or_default();
+      let (mut proceeding, ahead) =
+        std::mem::take(&mut evaluating).into_iter().partition::<Vec<_>, _>(|path| path.current().match_begin == behind);
+      evaluating = ahead;
+
+      let limits = &self.limits;
+      let root = self.profile.as_ref().map(|profile| profile.root());
       let nexts = {
         #[cfg(feature = "concurrent")]
-        if evaluating.len() == 1 {
-          vec![Self::proceed_on_path(evaluating.pop().unwrap(), &self.buffer, eof)]
+        if proceeding.len() == 1 || !self.parallel {
+          proceeding
+            .drain(..)
+            .map(|path| Self::proceed_on_path(path, &self.buffer, eof, limits, root))
+            .collect::<Vec<_>>()
         } else {
           use rayon::prelude::*;
-          evaluating.par_drain(..).map(|path| Self::proceed_on_path(path, &self.buffer, eof)).collect::<Vec<_>>()
+          proceeding
+            .par_drain(..)
+            .map(|path| Self::proceed_on_path(path, &self.buffer, eof, limits, root))
+            .collect::<Vec<_>>()
         }
 
         #[cfg(not(feature = "concurrent"))]
-        evaluating.drain(..).map(|path| Self::proceed_on_path(path, &self.buffer, eof)).collect::<Vec<_>>()
+        proceeding
+          .drain(..)
+          .map(|path| Self::proceed_on_path(path, &self.buffer, eof, limits, root))
+          .collect::<Vec<_>>()
       };
 
       for next in nexts {
-        let NextPaths { mut need_to_be_reevaluated, mut ongoing, unmatched, completed } = next?;
+        let NextPaths { mut need_to_be_reevaluated, mut ongoing, unmatched, mut completed, too_long, profile } = next?;
         evaluating.append(&mut need_to_be_reevaluated);
+        if let Some((location, err)) = too_long {
+          self.push_too_long(location, err);
+        }
         self.ongoing.append(&mut ongoing);
-        if let Some(unmatched) = unmatched {
+        for unmatched in unmatched {
           self.push_unmatched(unmatched);
         }
-        if let Some(completed) = completed {
-          self.prev_completed.push(completed);
+        self.prev_completed.append(&mut completed);
+        if let (Some(total), Some(profile)) = (self.profile.as_mut(), profile) {
+          total.merge(profile);
         }
       }
-      Self::merge_paths(&mut evaluating);
+      // the ongoing paths are merged as well, since the paths forked within an ignored syntax converge once it ends
+      Self::merge_paths(&mut evaluating, self.profile.as_mut());
+      Self::merge_paths(&mut self.ongoing, self.profile.as_mut());
+      if evaluating.len() + self.ongoing.len() > self.limits.max_paths {
+        return Err(self.error_limit_exceeded(self.location, "max_paths", self.limits.max_paths));
+      }
+
+      // flush the events in the middle of the pushed symbols, or every fork of the path copies all of them
+      if evaluating.len() + self.ongoing.len() + self.prev_completed.len() == 1 {
+        self.deliver_events_confirmed_with(&mut evaluating, true)?;
+      }
     }
 
-    Self::merge_paths(&mut self.ongoing);
-    Self::merge_paths(&mut self.prev_completed);
+    Self::merge_completed_paths(&mut self.prev_completed, self.profile.as_mut());
+    self.discard_unmatched_behind();
+    if let Some(path) = self.ongoing.iter().find(|p| p.event_buffer().len() > self.limits.max_pending_events) {
+      let location = path.current().location;
+      return Err(self.error_limit_exceeded(location, "max_pending_events", self.limits.max_pending_events));
+    }
     Ok(())
   }
 
-  fn proceed_on_path(mut path: Path<'s, ID, Σ>, buffer: &[Σ], eof: bool) -> Result<Σ, NextPaths<'s, ID, Σ>> {
+  /// Evaluate the term that `path` has reached. If `root` is specified, the costs are attributed to the rule of the
+  /// term in the profile of the returned paths.
+  ///
+  fn proceed_on_path(
+    mut path: Path<'s, ID, Σ>, buffer: &[Σ], eof: bool, limits: &Limits, root: Option<&ID>,
+  ) -> Result<Σ, NextPaths<'s, ID, Σ>> {
     debug_assert!(matches!(path.current().syntax().primary, Primary::Term(..)));
-    debug!("~ === proceed_on_path({}, {}, {})", path, Σ::debug_symbols(&buffer[path.current().match_begin..]), eof);
+    span!("match", syntax = %path.current().syntax(), position = path.current().location.position());
+    debug!("~ === proceed_on_path({}, {}, {})", path, path.debug_symbols(&buffer[path.current().match_begin..]), eof);
 
     let mut next = NextPaths {
-      need_to_be_reevaluated: Vec::with_capacity(1),
-      ongoing: Vec::with_capacity(1),
-      unmatched: None,
-      completed: None,
+      need_to_be_reevaluated: Vec::new(),
+      ongoing: Vec::new(),
+      unmatched: Vec::new(),
+      completed: Vec::new(),
+      too_long: None,
+      profile: None,
     };
+    let mut profile = root.map(|root| (Profile::new(root.clone()), path.rule(), Instant::now()));
 
     let matched = match path.matches(buffer, eof)? {
-      Matching::Match(_length, event) => {
+      Matching::Match(length, event) => {
+        if let Err(err) = path.check_length(path.current().match_begin + length, limits.max_fragment_length) {
+          next.too_long = Some((path.current().location, err));
+          return Ok(next.profiled(profile));
+        }
         if let Some(event) = event {
           path.events_push(event);
         }
         debug_assert!(matches!(path.current().syntax().primary, Primary::Term(..)));
+        span!("match", syntax = %path.current().syntax(), position = path.current().location.position());
         true
       }
       Matching::Unmatch => false,
       Matching::More => {
+        if let Err(err) = path.check_length(buffer.len(), limits.max_fragment_length) {
+          next.too_long = Some((path.current().location, err));
+          return Ok(next.profiled(profile));
+        }
         next.ongoing.push(path);
-        return Ok(next);
+        return Ok(next.profiled(profile));
       }
     };
 
     match path.move_to_next(buffer, matched, eof) {
-      (true, true) => {
-        let uncapture_exists = path.current().match_begin + path.current().match_length < buffer.len();
-        if uncapture_exists {
-          next.unmatched = Some(path);
-        } else {
-          next.completed = Some(path);
-        }
-      }
+      // an optional term that failed to match is expected rather than EOF
+      (true, true) => next.push_completed(path, buffer, matched),
       (true, _) => {
-        let uncapture_exists = path.current().match_begin + path.current().match_length < buffer.len();
-        let mut nexts = Self::move_ongoing_paths_to_next_term(path)?;
+        let uncapture_exists = Self::uncapture_exists(&path, buffer);
+        let spawns = profile.as_mut().map(|(profile, ..)| profile);
+        let (mut nexts, completed) = Self::move_ongoing_paths_to_next_term(path, buffer, eof, limits, spawns)?;
         if uncapture_exists {
           next.need_to_be_reevaluated.append(&mut nexts);
         } else {
           next.ongoing.append(&mut nexts);
         }
+        for path in completed {
+          next.push_completed(path, buffer, true);
+        }
       }
-      (false, _) => next.unmatched = Some(path),
+      (false, _) => next.unmatched.push(path),
     }
-    Ok(next)
+    Ok(next.profiled(profile))
+  }
+
+  fn uncapture_exists(path: &Path<'s, ID, Σ>, buffer: &[Σ]) -> bool {
+    path.current().match_begin + path.current().match_length < buffer.len()
   }
 
-  fn move_ongoing_paths_to_next_term(path: Path<'s, ID, Σ>) -> Result<Σ, Vec<Path<'s, ID, Σ>>> {
+  /// Returns the paths that reached a term, and the paths that have been completed by omitting optional non-terminals.
+  ///
+  #[allow(clippy::type_complexity)]
+  fn move_ongoing_paths_to_next_term(
+    path: Path<'s, ID, Σ>, buffer: &[Σ], eof: bool, limits: &Limits, mut profile: Option<&mut Profile<ID>>,
+  ) -> Result<Σ, (Vec<Path<'s, ID, Σ>>, Vec<Path<'s, ID, Σ>>)> {
     let mut ongoing = vec![path];
     let mut term_reached = Vec::with_capacity(ongoing.len());
+    let mut completed = Vec::new();
     while let Some(mut eval_path) = ongoing.pop() {
+      if eval_path.depth() > limits.max_depth {
+        let location = eval_path.current().location;
+        return Err(Error::LimitExceeded { location, name: "max_depth", limit: limits.max_depth });
+      }
+      let syntax = eval_path.current().syntax();
+      let appearances = eval_path.current().appearances;
+      if !matches!(syntax.primary, Primary::Term(..)) && appearances >= *syntax.repetition.start() {
+        // fork the path that omits this non-terminal, because the iteration that fails on the way cannot go back
+        let mut omitted = eval_path.clone();
+        if let Some(profile) = &mut profile {
+          profile.rule_mut(eval_path.rule()).spawns += 1;
+        }
+        match omitted.skip_current(buffer, eof) {
+          (true, true) => completed.push(omitted),
+          (true, false) => ongoing.push(omitted),
+          (false, _) => {
+            let message = format!("the omitted syntax cannot be skipped: {}", syntax);
+            return Err(Error::Internal { location: eval_path.current().location, message });
+          }
+        }
+        if appearances == Path::max_appearances(eval_path.schema(), syntax) {
+          continue;
+        }
+      }
       match &eval_path.current().syntax().primary {
         Primary::Term(..) => {
           term_reached.push(eval_path);
@@ -238,47 +936,209 @@ where
           ongoing.push(eval_path);
         }
         Primary::Or(branches) => {
-          for branch in branches {
-            debug_assert!(matches!(branch, Syntax { primary: Primary::Seq(..), .. }));
-            if let Syntax { primary: Primary::Seq(seq), .. } = branch {
-              let mut next = eval_path.clone();
-              next.stack_push(seq);
-              ongoing.push(next);
+          let forked = ongoing.len();
+          match eval_path.left_recursion() {
+            // the seed has already matched: grow it with the rest of the recursive branches
+            Some(id) if appearances > 0 => {
+              eval_path.grow(id);
+              for branch in branches.iter().filter(|b| b.is_left_recursive_on(id) && eval_path.can_grow_with(b)) {
+                if let Syntax { primary: Primary::Seq(seq), .. } = branch {
+                  let mut next = eval_path.clone();
+                  next.stack_push_at(seq, 1);
+                  ongoing.push(next);
+                }
+              }
             }
+            left_recursion => {
+              for branch in branches {
+                debug_assert!(matches!(branch, Syntax { primary: Primary::Seq(..), .. }));
+                if left_recursion.map(|id| branch.begins_with(id)).unwrap_or(false) {
+                  continue;
+                }
+                if let Syntax { primary: Primary::Seq(seq), .. } = branch {
+                  let mut next = eval_path.clone();
+                  next.stack_push(seq);
+                  ongoing.push(next);
+                }
+              }
+            }
+          }
+          if let Some(profile) = &mut profile {
+            profile.rule_mut(eval_path.rule()).spawns += (ongoing.len() - forked).saturating_sub(1);
           }
         }
       }
     }
-    debug_assert!(!term_reached.is_empty());
+    debug_assert!(!term_reached.is_empty() || !completed.is_empty());
     debug_assert!(term_reached.iter().all(|t| matches!(t.current().syntax().primary, Primary::Term(..))));
-    Ok(term_reached)
+    Ok((term_reached, completed))
+  }
+
+  /// If all paths have failed, resumes the one that failed within the innermost syntax with a recovery set. Returns
+  /// false if no path can be recovered. The recovery at the same position is only allowed for a shallower syntax than
+  /// the previous one, so that it always makes progress. Unless at `eof`, it waits for the symbols that the recovered
+  /// error samples, as well as the unrecovered one.
+  ///
+  fn recover(&mut self, eof: bool) -> bool {
+    if !self.ongoing.is_empty() || !self.prev_completed.is_empty() || self.prev_unmatched.is_empty() {
+      return false;
+    }
+    if !eof && !self.is_unmatch_sampled() {
+      // the error to recover from isn't sampled yet
+      return false;
+    }
+    let position = self.prev_unmatched[0].current().location.position();
+    let candidate = self
+      .prev_unmatched
+      .iter()
+      .enumerate()
+      .filter_map(|(i, path)| path.recovery_depth().map(|depth| (i, depth)))
+      .filter(|(_, depth)| match self.last_recovery {
+        Some((last_position, last_depth)) => {
+          position > last_position || (position == last_position && *depth < last_depth)
+        }
+        None => true,
+      })
+      .max_by_key(|(i, depth)| (*depth, std::cmp::Reverse(*i)));
+    if let Some((i, depth)) = candidate {
+      let error = self.error_unmatch(&self.prev_unmatched);
+      debug!("RECOVER: {}", error);
+      self.recovered.push(error);
+      self.last_recovery = Some((position, depth));
+      let mut path = self.prev_unmatched.swap_remove(i);
+      path.recover(depth);
+      self.prev_unmatched.truncate(0);
+      self.ongoing.push(path);
+      true
+    } else {
+      false
+    }
   }
 
-  fn deliver_confirmed_events(&mut self) {
-    let mut actives = self.ongoing.iter_mut().chain(self.prev_completed.iter_mut()).collect::<Vec<_>>();
+  fn deliver_confirmed_events(&mut self) -> Result<Σ, ()> {
+    self.deliver_events_confirmed_with(&mut [], false)
+  }
+
+  /// Deliver the events that are common to all the active paths, including the `evaluating` ones that haven't reached
+  /// the end of the pushed symbols yet and the unmatched ones that the error may be reported with. Only the `settled` events are delivered in the middle of them, so that the
+  /// events don't depend on how far the paths have proceeded.
+  ///
+  fn deliver_events_confirmed_with(&mut self, evaluating: &mut [Path<'s, ID, Σ>], settled: bool) -> Result<Σ, ()> {
+    span!("flush", paths = self.ongoing.len() + self.prev_completed.len() + evaluating.len());
+    let (delivery, handler) = (&mut self.delivery, &mut self.event_handler);
+    let mut handler = |e: &Event<ID, Σ>| delivery.deliver(handler, e);
+    let mut actives = self
+      .ongoing
+      .iter_mut()
+      .chain(self.prev_completed.iter_mut())
+      .chain(evaluating.iter_mut())
+      .chain(self.prev_unmatched.iter_mut())
+      .collect::<Vec<_>>();
+    let flushable = |path: &Path<'s, ID, Σ>| {
+      if settled {
+        path.events_settled_length()
+      } else {
+        path.events_retained_length()
+      }
+    };
     if actives.len() == 1 {
-      actives[0].events_flush_all_to(&mut self.event_handler);
+      let flushable = flushable(actives[0]);
+      actives[0].events_flush_forward_to(flushable, &mut handler);
     } else if !actives.is_empty() {
-      let mut matches = actives[0].event_buffer().len();
+      let mut matches = actives.iter().map(|active| flushable(active)).min().unwrap();
       for i in 1..actives.len() {
         let len = actives[0].events_forward_matching_length(actives[i]);
         matches = std::cmp::min(matches, len);
       }
       if matches > 0 {
-        actives[0].events_flush_forward_to(matches, &mut self.event_handler);
+        actives[0].events_flush_forward_to(matches, &mut handler);
         for active in actives.iter_mut().skip(1) {
           active.events_flush_forward_to(matches, &mut |_| {});
         }
       }
     }
+    self.check_delivery_failure()
+  }
+
+  /// Deliver the events confirmed by the unmatched paths before the error is reported, so that the handler receives
+  /// the same events as when the error is detected in the middle of a push.
+  ///
+  fn deliver_events_confirmed_by_unmatched(&mut self) -> Result<Σ, ()> {
+    let mut unmatched = std::mem::take(&mut self.prev_unmatched);
+    let result = self.deliver_events_confirmed_with(&mut unmatched, false);
+    self.prev_unmatched = unmatched;
+    result
+  }
+
+  /// Abort parsing if the handler registered by [`try_new()`](Context::try_new) has failed, or the events to be
+  /// delivered have been inconsistent.
+  ///
+  fn check_delivery_failure(&mut self) -> Result<Σ, ()> {
+    if let Some(location) = self.delivery.violated {
+      let message = self.delivery.pairing.violation().unwrap_or_default().to_string();
+      return self.error(Error::Internal { location, message });
+    }
+    match self.delivery.failure.take() {
+      Some(source) => self.error(Error::Handler(source)),
+      None => Ok(()),
+    }
   }
 
-  fn merge_paths(paths: &mut Vec<Path<ID, Σ>>) {
+  /// Discard all the paths and the confirmed symbols after the syntax specified by `until_first()` has completed. The
+  /// symbols following it remain in the buffer to be unread.
+  ///
+  fn discard(&mut self, reached: Σ::Location) {
+    self.ongoing.clear();
+    self.prev_completed.clear();
+    self.prev_unmatched.clear();
+    let confirmed = (reached.position() - self.offset_of_buffer_head) as usize;
+    self.buffer.drain(..confirmed);
+    self.offset_of_buffer_head = reached.position();
+  }
+
+  fn pass_through(&mut self, raw: &[Σ]) -> Result<Σ, ()> {
+    debug!("PASSTHROUGH: {:?}", Σ::debug_symbols(raw));
+    self.prev_completed.truncate(0);
+    self.prev_unmatched.truncate(0);
+    let consumed = self.buffer.len();
+    self.buffer.clear();
+    self.offset_of_buffer_head += (consumed + raw.len()) as u64;
+    self.location.increment_with_seq(raw);
+    self.passthrough -= raw.len();
+    for path in &mut self.ongoing {
+      path.pass_through(consumed, raw);
+    }
+    self.deliver_confirmed_events()
+  }
+
+  fn merge_paths(paths: &mut Vec<Path<ID, Σ>>, mut profile: Option<&mut Profile<ID>>) {
+    span!("merge", paths = paths.len());
     for i in 0..paths.len() {
       let mut j = i + 1;
       while j < paths.len() {
         if paths[i].can_merge(&paths[j]) {
           debug!("~ duplicated: [{},{}]{}", i, j, paths[j]);
+          if let Some(profile) = &mut profile {
+            profile.rule_mut(paths[j].rule()).merges += 1;
+          }
+          paths.remove(j);
+        } else {
+          j += 1;
+        }
+      }
+    }
+  }
+
+  fn merge_completed_paths(paths: &mut Vec<Path<ID, Σ>>, mut profile: Option<&mut Profile<ID>>) {
+    span!("merge_completed", paths = paths.len());
+    for i in 0..paths.len() {
+      let mut j = i + 1;
+      while j < paths.len() {
+        if paths[i].can_merge_completed(&paths[j]) {
+          debug!("~ duplicated completion: [{},{}]{}", i, j, paths[j]);
+          if let Some(profile) = &mut profile {
+            profile.rule_mut(paths[j].rule()).merges += 1;
+          }
           paths.remove(j);
         } else {
           j += 1;
@@ -294,7 +1154,9 @@ where
           self.prev_unmatched.truncate(0);
           true
         }
-        Ordering::Equal => !self.prev_unmatched.iter().any(|c| c.can_merge(&path)),
+        Ordering::Equal => {
+          !self.prev_unmatched.iter().any(|c| c.is_overrun() == path.is_overrun() && c.can_merge(&path))
+        }
         Ordering::Less => false,
       }
     } else {
@@ -305,12 +1167,39 @@ where
     }
   }
 
+  /// Remember the path dropped at `location` for exceeding a maximum length like an unmatched path, so that the
+  /// error is reported only if no other path gets farther.
+  ///
+  fn push_too_long(&mut self, location: Σ::Location, err: Error<Σ>) {
+    if self.too_long.as_ref().map(|(current, _)| location > *current).unwrap_or(true) {
+      self.too_long = Some((location, err));
+    }
+  }
+
+  /// Discard the unmatched paths behind all the active paths, since any path failing later is ahead of them and the
+  /// error reports the furthest ones. The others are kept over the pushes so that the error doesn't depend on how the
+  /// input is split.
+  ///
+  fn discard_unmatched_behind(&mut self) {
+    let actives = self.ongoing.iter().chain(self.prev_completed.iter());
+    if let Some(min) = actives.map(|path| path.current().location).min() {
+      self.prev_unmatched.retain(|path| path.current().location >= min);
+      if matches!(&self.too_long, Some((location, _)) if *location < min) {
+        self.too_long = None;
+      }
+    }
+  }
+
   fn fit_buffer_to_min_size(&mut self, incremental: usize) {
     // reduce internal buffer if possible
     // TODO: how often the buffer is reduced?
     if (self.location.position() - incremental as u64) >> 8 == self.location.position() >> 8 {
       return;
     }
+    self.shrink_buffer();
+  }
+
+  fn shrink_buffer(&mut self) {
     let paths = self
       .ongoing
       .iter_mut()
@@ -327,36 +1216,73 @@ where
     }
   }
 
+  fn check_buffer_size(&mut self) -> Result<Σ, ()> {
+    if self.buffer.len() > self.limits.max_buffer_size {
+      // the buffer may not have been reduced yet
+      self.shrink_buffer();
+    }
+    if self.buffer.len() > self.limits.max_buffer_size {
+      self.error(self.error_limit_exceeded(self.location, "max_buffer_size", self.limits.max_buffer_size))
+    } else {
+      Ok(())
+    }
+  }
+
   fn check_whether_possible_to_proceed(&mut self) -> Result<Σ, ()> {
     self.check_for_previous_error()?;
 
-    debug_assert!(!self.ongoing.is_empty() || !self.prev_completed.is_empty() || self.prev_unmatched.is_empty());
-    if self.ongoing.is_empty() {
-      debug_assert!(!self.prev_completed.is_empty());
+    if self.ongoing.is_empty() && self.prev_completed.is_empty() {
+      // all the paths have failed, and the error is waiting for the symbols to sample
+      debug_assert!(!self.prev_unmatched.is_empty());
+      self.check_whether_unmatch_confirmed()
+    } else if self.ongoing.is_empty() {
       // `items` appeared, but the parser state was already complete and waiting for EOF
       let pos = self.prev_completed.iter().map(|p| p.current().location.position()).max().unwrap();
       let buffer_pos = (pos - self.offset_of_buffer_head) as usize;
       if self.buffer.len() == buffer_pos {
         Ok(())
       } else {
-        self.error(self.error_eof_expected(&self.prev_completed))
+        for mut path in std::mem::take(&mut self.prev_completed) {
+          path.overrun();
+          self.push_unmatched(path);
+        }
+        self.check_whether_unmatch_confirmed()
       }
     } else {
       Ok(())
     }
   }
 
+  /// Fail if all the paths have failed and the symbols following the unmatched one have been pushed as many as the
+  /// error samples, so that the error doesn't depend on how the input is split into the pushes. If the input ends
+  /// before that, the error is returned on finish.
+  ///
   fn check_whether_unmatch_confirmed(&mut self) -> Result<Σ, ()> {
-    debug_assert!(!self.ongoing.is_empty() || !self.prev_completed.is_empty() || !self.prev_unmatched.is_empty());
-    if self.ongoing.is_empty() && self.prev_completed.is_empty() {
-      self.error(self.error_unmatch(&self.prev_unmatched))
+    if self.ongoing.is_empty() && self.prev_completed.is_empty() && self.is_unmatch_sampled() {
+      self.deliver_events_confirmed_by_unmatched()?;
+      let err = self.error_no_paths();
+      self.error(err)
     } else {
       Ok(())
     }
   }
 
+  /// Whether the symbols following the farthest unmatched one have been pushed as many as
+  /// [`Sampling::actual_length`], which [`ErrorDetail::actual`] samples.
+  ///
+  fn is_unmatch_sampled(&self) -> bool {
+    match self.prev_unmatched.first() {
+      Some(path) if self.sampling.redaction != Redaction::Omit => {
+        path.current().match_begin.saturating_add(1).saturating_add(self.sampling.actual_length) <= self.buffer.len()
+      }
+      _ => true,
+    }
+  }
+
   fn check_for_previous_error(&self) -> Result<Σ, ()> {
-    if self.ongoing.is_empty() && self.prev_completed.is_empty() && self.prev_unmatched.is_empty() {
+    if self.is_poisoned()
+      || (self.ongoing.is_empty() && self.prev_completed.is_empty() && self.prev_unmatched.is_empty())
+    {
       Err(Error::Previous)
     } else {
       Ok(())
@@ -364,32 +1290,53 @@ where
   }
 
   fn error_unmatch(&self, expecteds: &[Path<ID, Σ>]) -> Error<Σ> {
-    let location = expecteds.first().map(|p| p.current().location).unwrap_or(self.location);
-    let expected_syntaxes = expecteds.iter().map(|p| p.to_string()).collect::<Vec<_>>();
-    let (prefix, expecteds, actual) = create_unmatched_labels(&self.buffer, self.offset_of_buffer_head, expecteds);
-    Error::Unmatched { location, prefix, expecteds, expected_syntaxes, actual }
+    let mut location = expecteds.first().map(|p| p.current().location).unwrap_or(self.location);
+    if let Some(next) = expecteds.first().and_then(|p| self.buffer.get(p.current().match_begin)) {
+      location.seek(next);
+    }
+    let (prefix, expecteds, expected_syntaxes, actual) =
+      create_unmatched_labels(&self.buffer, self.offset_of_buffer_head, expecteds, &self.sampling);
+    Error::Unmatched(Box::new(ErrorDetail { location, prefix, expecteds, expected_syntaxes, actual }))
+  }
+
+  /// The error when all the paths have failed: the maximum length exceeded by the farthest path, or the unmatch.
+  ///
+  fn error_no_paths(&mut self) -> Error<Σ> {
+    let unmatched = self.prev_unmatched.first().map(|path| path.current().location);
+    match self.too_long.take() {
+      Some((location, err)) if unmatched.map(|unmatched| location >= unmatched).unwrap_or(true) => err,
+      _ => self.error_unmatch(&self.prev_unmatched),
+    }
   }
 
-  fn error_eof_expected(&self, completed: &[Path<ID, Σ>]) -> Error<Σ> {
-    let location = completed.first().map(|p| p.current().location).unwrap_or(self.location);
-    let match_length = completed.first().map(|p| p.current().match_begin).unwrap_or(self.buffer.len());
-    let prefix = create_unmatched_label_prefix(&self.buffer, self.offset_of_buffer_head, match_length);
-    let expected = format!("[{}]", EOF_SYMBOL);
-    let actual = create_unmatched_label_actual(&self.buffer, match_length);
-    Error::Unmatched { location, prefix, expecteds: vec![expected], expected_syntaxes: vec![], actual }
+  fn error_limit_exceeded(&self, location: Σ::Location, name: &'static str, limit: usize) -> Error<Σ> {
+    Error::LimitExceeded { location, name, limit }
   }
 
+  /// Discard all the paths to fail with `err`, which follows the errors recovered so far, if any.
+  ///
   fn error<T>(&mut self, err: Error<Σ>) -> Result<Σ, T> {
     self.ongoing.truncate(0);
     self.prev_unmatched.truncate(0);
     self.prev_completed.truncate(0);
-    Err(err)
+    if self.recovered.is_empty() {
+      Err(err)
+    } else {
+      let mut errors = std::mem::take(&mut self.recovered);
+      errors.push(err);
+      Err(Error::Recovered(errors))
+    }
   }
 }
 
+/// The labels of the prefix, the expected symbols, the stacks of the expected syntaxes, and the actual symbols. The
+/// expected ones are sorted by their stacks, so that they don't depend on the order in which the paths failed, and
+/// the paths that have completed before the actual symbols expect EOF.
+///
+#[allow(clippy::type_complexity)]
 fn create_unmatched_labels<ID, Σ: Symbol>(
-  buffer: &[Σ], buf_offset: u64, expecteds: &[Path<ID, Σ>],
-) -> (String, Vec<String>, String)
+  buffer: &[Σ], buf_offset: u64, expecteds: &[Path<ID, Σ>], sampling: &Sampling,
+) -> (String, Vec<String>, Vec<String>, String)
 where
   ID: Clone + Display + Debug + PartialEq + Ord + Eq + Hash,
 {
@@ -397,36 +1344,67 @@ where
   debug_assert!(expecteds.iter().all(|p| p.current().match_begin == match_length));
 
   debug_assert!(!expecteds.is_empty());
-  let expecteds = expecteds.iter().map(|path| format!("[{}]", path.current().syntax())).collect::<Vec<_>>();
+  let mut labels = expecteds
+    .iter()
+    .filter(|path| !path.is_overrun())
+    .map(|path| (path.to_string(), format!("[{}]", path.current().syntax())))
+    .collect::<Vec<_>>();
+  labels.sort();
+  let (expected_syntaxes, mut labels): (Vec<_>, Vec<_>) = labels.into_iter().unzip();
+  if expecteds.iter().any(|path| path.is_overrun()) {
+    labels.push(format!("[{}]", EOF_SYMBOL));
+  }
+
+  // the symbols following the location are also sensitive if any path has failed within a sensitive syntax
+  let mut redactions = expecteds.iter().flat_map(|path| path.redactions().iter().cloned()).collect::<Vec<_>>();
+  if expecteds.iter().any(|path| path.is_sensitive()) {
+    redactions.push(match_length..buffer.len());
+  }
 
   (
-    create_unmatched_label_prefix(buffer, buf_offset, match_length),
-    expecteds,
-    create_unmatched_label_actual(buffer, match_length),
+    create_unmatched_label_prefix(buffer, buf_offset, match_length, &redactions, sampling),
+    labels,
+    expected_syntaxes,
+    create_unmatched_label_actual(buffer, match_length, &redactions, sampling),
   )
 }
 
 const ELLAPSE_LENGTH: usize = 3;
 const EOF_SYMBOL: &str = "EOF";
+const REDACTED: &str = "***";
 
-fn create_unmatched_label_prefix<Σ: Symbol>(buffer: &[Σ], buf_offset: u64, match_length: usize) -> String {
+fn create_unmatched_label_prefix<Σ: Symbol>(
+  buffer: &[Σ], buf_offset: u64, match_length: usize, redactions: &[Range<usize>], sampling: &Sampling,
+) -> String {
   debug_assert!(match_length <= buffer.len());
-  let sample_length = Σ::SAMPLING_UNIT_AT_ERROR;
+  if sampling.redaction == Redaction::Omit {
+    return String::new();
+  }
   let sample_end = match_length;
-  let sample_begin = sample_end - std::cmp::min(sample_length, sample_end);
+  let sample_begin = sample_end - std::cmp::min(sampling.prefix_length, sample_end);
   let ellapse_length = std::cmp::min(ELLAPSE_LENGTH as u64, buf_offset + sample_begin as u64) as usize;
   let ellapse = (0..ellapse_length).map(|_| ".").collect::<String>();
-  let sample = Σ::debug_symbols(&buffer[sample_begin..sample_end]);
+  let sample = redacted_symbols(buffer, sample_begin..sample_end, redactions, sampling);
   format!("{}{}", ellapse, sample)
 }
 
-fn create_unmatched_label_actual<Σ: Symbol>(buffer: &[Σ], match_length: usize) -> String {
-  let sample_length = Σ::SAMPLING_UNIT_AT_ERROR;
+fn create_unmatched_label_actual<Σ: Symbol>(
+  buffer: &[Σ], match_length: usize, redactions: &[Range<usize>], sampling: &Sampling,
+) -> String {
   if match_length < buffer.len() {
-    let target = Σ::debug_symbol(buffer[match_length]);
+    if sampling.redaction == Redaction::Omit {
+      return format!("[{}]", REDACTED);
+    }
+    let target = if redactions.iter().any(|r| r.contains(&match_length)) {
+      String::from(REDACTED)
+    } else if sampling.redaction == Redaction::Off {
+      Σ::debug_symbol(buffer[match_length])
+    } else {
+      sampling.label(&buffer[match_length..][..1])
+    };
     if match_length + 1 < buffer.len() {
-      let suffix_length = std::cmp::min(sample_length, buffer.len() - match_length - 1);
-      let suffix = Σ::debug_symbols(&buffer[match_length + 1..][..suffix_length]);
+      let suffix_length = std::cmp::min(sampling.actual_length, buffer.len() - match_length - 1);
+      let suffix = redacted_symbols(buffer, match_length + 1..match_length + 1 + suffix_length, redactions, sampling);
       format!("[{}]{}...", target, suffix)
     } else {
       format!("[{}]...", target)
@@ -437,6 +1415,28 @@ fn create_unmatched_label_actual<Σ: Symbol>(buffer: &[Σ], match_length: usize)
   }
 }
 
+/// The label of the symbols in the `range` of the `buffer` written by the `sampling`, where each run of the symbols
+/// overlapping the `redactions` is replaced with [`REDACTED`].
+///
+fn redacted_symbols<Σ: Symbol>(
+  buffer: &[Σ], range: Range<usize>, redactions: &[Range<usize>], sampling: &Sampling,
+) -> String {
+  let mut label = String::new();
+  let mut begin = range.start;
+  while begin < range.end {
+    let redacted = |i: &usize| redactions.iter().any(|r| r.contains(i));
+    let sensitive = redacted(&begin);
+    let end = (begin + 1..range.end).find(|i| redacted(i) != sensitive).unwrap_or(range.end);
+    if sensitive {
+      label.push_str(REDACTED);
+    } else {
+      label.push_str(&sampling.label(&buffer[begin..end]));
+    }
+    begin = end;
+  }
+  label
+}
+
 impl<'s, ID, H: FnMut(&Event<ID, char>)> Context<'s, ID, char, H>
 where
   ID: 's + Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
@@ -444,6 +1444,24 @@ where
   pub fn push_str(&mut self, s: &str) -> Result<char, ()> {
     self.push_seq(&s.chars().collect::<Vec<_>>())
   }
+
+  /// Push a single line. A line feed is appended if the `line` doesn't end with a line break, so that lines read by
+  /// [`BufRead::lines()`](std::io::BufRead::lines) can be fed as-is.
+  ///
+  pub fn push_line(&mut self, line: &str) -> Result<char, ()> {
+    let mut items = line.chars().collect::<Vec<_>>();
+    if !matches!(items.last(), Some('\n' | '\r')) {
+      items.push('\n');
+    }
+    self.push_seq(&items)
+  }
+
+  pub fn push_lines<S: AsRef<str>, I: IntoIterator<Item = S>>(&mut self, lines: I) -> Result<char, ()> {
+    for line in lines {
+      self.push_line(line.as_ref())?;
+    }
+    Ok(())
+  }
 }
 
 struct NextPaths<'s, ID, Σ: Symbol>
@@ -452,6 +1470,38 @@ where
 {
   pub need_to_be_reevaluated: Vec<Path<'s, ID, Σ>>,
   pub ongoing: Vec<Path<'s, ID, Σ>>,
-  pub unmatched: Option<Path<'s, ID, Σ>>,
-  pub completed: Option<Path<'s, ID, Σ>>,
+  pub unmatched: Vec<Path<'s, ID, Σ>>,
+  pub completed: Vec<Path<'s, ID, Σ>>,
+  /// The location of the path dropped for exceeding a maximum length, and the error to report.
+  pub too_long: Option<(Σ::Location, Error<Σ>)>,
+  pub profile: Option<Profile<ID>>,
+}
+
+impl<'s, ID, Σ: Symbol> NextPaths<'s, ID, Σ>
+where
+  ID: 's + Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
+{
+  /// Attribute the time since the evaluation began to the rule of the evaluated term.
+  ///
+  fn profiled(mut self, profile: Option<(Profile<ID>, Option<&ID>, Instant)>) -> Self {
+    if let Some((mut profile, rule, began)) = profile {
+      let rule = profile.rule_mut(rule);
+      rule.time += began.elapsed();
+      rule.evaluations += 1;
+      self.profile = Some(profile);
+    }
+    self
+  }
+
+  fn push_completed(&mut self, mut path: Path<'s, ID, Σ>, buffer: &[Σ], overrun: bool) {
+    let uncapture_exists = path.current().match_begin + path.current().match_length < buffer.len();
+    if uncapture_exists {
+      if overrun {
+        path.overrun();
+      }
+      self.unmatched.push(path);
+    } else {
+      self.completed.push(path);
+    }
+  }
 }
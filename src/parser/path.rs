@@ -1,8 +1,9 @@
-use crate::parser::{Event, EventBuffer, EventKind};
-use crate::schema::{Location, MatchResult, Primary, Schema, Symbol, Syntax};
+use crate::parser::{Event, EventBuffer, EventKind, REDACTED};
+use crate::schema::{Location, MatchCtx, MatchResult, Primary, Schema, Symbol, Syntax};
 use crate::{debug, Error, Result};
 use std::fmt::{Debug, Display, Write};
 use std::hash::Hash;
+use std::ops::Range;
 
 #[derive(Clone, Debug)]
 pub(crate) struct Path<'s, ID, Σ: Symbol>
@@ -12,6 +13,10 @@ where
   schema: &'s Schema<ID, Σ>,
   event_buffer: EventBuffer<ID, Σ>,
   stack: Vec<StackFrame<'s, ID, Σ>>,
+  /// Whether this path has completed before the end of the input, so that it expects EOF instead of the symbols.
+  overrun: bool,
+  /// The ranges of the buffer matched within the sensitive syntaxes.
+  redactions: Vec<Range<usize>>,
 
   // For variable watch during step execution.
   #[cfg(debug_assertions)]
@@ -32,6 +37,8 @@ where
       schema,
       event_buffer,
       stack,
+      overrun: false,
+      redactions: Vec::new(),
       #[cfg(debug_assertions)]
       _debug: String::from(""),
       #[cfg(debug_assertions)]
@@ -41,6 +48,10 @@ where
     Ok(path)
   }
 
+  pub fn schema(&self) -> &'s Schema<ID, Σ> {
+    self.schema
+  }
+
   pub fn current(&self) -> &State<'s, ID, Σ> {
     &self.stack.last().unwrap().state
   }
@@ -49,6 +60,10 @@ where
     &mut self.stack.last_mut().unwrap().state
   }
 
+  pub fn depth(&self) -> usize {
+    self.stack.len()
+  }
+
   pub fn event_buffer(&self) -> &EventBuffer<ID, Σ> {
     &self.event_buffer
   }
@@ -60,32 +75,38 @@ where
   /// return false if the end of reached.
   /// returns (matched, confirmed), where matched=true, it needs to move to term and continue
   /// processing, and confirmed=true
-  /// Note that if called by matched=false, it may be overriden by matched=true at the upper layer
-  /// of the stack.
+  /// Note that if called by matched=false, it may be overriden by matched=true if the current term is allowed not
+  /// to appear.
   ///
   pub fn move_to_next(&mut self, buffer: &[Σ], mut matched: bool, eof: bool) -> (bool, bool) {
+    let end_of_match = self.current().match_begin + self.current().match_length;
+    // the symbols that remain in the buffer at the end of input, such as those looked ahead, may still repeat it
+    let eof = eof && end_of_match == buffer.len();
     for i in 0..self.stack.len() {
       let stack_position = self.stack.len() - i - 1;
-      let StackFrame { state, current, parent, _debug } = &mut self.stack[stack_position];
-      debug_assert!(state.appearances <= *state.syntax().repetition.end());
+      let StackFrame { state, current, parent, .. } = &mut self.stack[stack_position];
+      let max = Self::max_appearances(self.schema, state.syntax());
+      debug_assert!(state.appearances <= max);
 
-      if matched && state.appearances < *state.syntax().repetition.end() {
+      if matched && state.appearances < max {
         state.appearances += 1;
       }
 
       matched = match (matched, eof) {
         (true, true) => state.appearances >= *state.syntax().repetition.start(),
         (true, false) => {
-          if state.appearances < *state.syntax().repetition.end() {
+          // an iteration that consumed nothing would be repeated forever
+          if state.appearances < max && state.match_begin < end_of_match {
             debug!("~ repeated: {} / {}", state.syntax(), state.appearances);
             state.proceed_along_buffer(buffer);
             self.stack_pop(i);
             self.complete_eval_of_current_position(false);
             return (true, false);
           }
-          debug_assert_eq!(state.appearances, *state.syntax().repetition.end());
           true
         }
+        // the iteration of a non-terminal failed on the way; the path that omits it has been forked when it began
+        (false, _) if i > 0 => return (false, true),
         (false, _) => state.appearances >= *state.syntax.repetition.start(),
       };
 
@@ -103,15 +124,73 @@ where
     (matched, true)
   }
 
+  /// The maximum number of times the syntax can appear. The choice of a left-recursive rule appears once for the seed
+  /// and then once for each growth.
+  ///
+  pub fn max_appearances(schema: &Schema<ID, Σ>, syntax: &Syntax<ID, Σ>) -> usize {
+    if schema.left_recursion(syntax).is_some() {
+      usize::MAX
+    } else {
+      *syntax.repetition.end()
+    }
+  }
+
+  /// The left-recursive rule whose parse grows if the current choice is evaluated again.
+  ///
+  pub fn left_recursion(&self) -> Option<&'s ID> {
+    self.schema.left_recursion(self.current().syntax())
+  }
+
+  /// Whether the current left-recursive rule can grow with the `branch` of an operator. The operator must bind tighter
+  /// than the minimum of the expression, and must not be bound by the operand that has been matched last.
+  ///
+  pub fn can_grow_with(&self, branch: &Syntax<ID, Σ>) -> bool {
+    match self.schema.left_binding(branch) {
+      Some(left) => left > self.stack.last().unwrap().min_binding.unwrap_or(0) && left <= self.current().right_binding,
+      None => true,
+    }
+  }
+
+  /// Nest the events of the current left-recursive rule which has already matched, so that the parse so far becomes
+  /// the leftmost child of the rule.
+  ///
+  pub fn grow(&mut self, id: &ID) {
+    debug!("~ grown: {}", id);
+    let end = self.current().event(EventKind::End(id.clone()));
+    self.event_buffer.nest(end);
+  }
+
+  /// Move to the next position without evaluating the current non-terminal, which has already appeared enough times.
+  /// The return value is the same as [`move_to_next()`](Self::move_to_next).
+  ///
+  pub fn skip_current(&mut self, buffer: &[Σ], eof: bool) -> (bool, bool) {
+    debug_assert!(self.current().appearances >= *self.current().syntax().repetition.start());
+    if let Primary::Alias(id) = &self.current().syntax().primary {
+      // the End event emitted on leaving this position cancels it as an empty pair
+      self.events_push(self.current().event(EventKind::Begin(id.clone())));
+    }
+    self.move_to_next(buffer, false, eof)
+  }
+
   #[inline]
   pub fn matches(&mut self, buffer: &[Σ], eof: bool) -> Result<Σ, Matching<ID, Σ>> {
-    let result = self.current_mut().matches(buffer, eof);
+    let sensitive = self.is_sensitive();
+    let result = self.current_mut().matches(buffer, eof, sensitive);
+    if sensitive {
+      if let Ok(Matching::Match(length, _)) = &result {
+        let begin = self.current().match_begin;
+        match self.redactions.last_mut() {
+          Some(last) if last.end >= begin => last.end = std::cmp::max(last.end, begin + length),
+          _ => self.redactions.push(begin..begin + length),
+        }
+      }
+    }
     #[cfg(debug_assertions)]
     {
       self._eval = format!(
         "{}(\"{}\") => {:?}",
         self.current().syntax(),
-        Σ::debug_symbols(
+        self.debug_symbols(
           &buffer[self.current().match_begin..std::cmp::min(buffer.len(), self.current().match_begin + 8)]
         ),
         result.as_ref().ok().map(|r| format!("{:?}", r)).unwrap_or_else(|| String::from("ERR"))
@@ -120,6 +199,40 @@ where
     result
   }
 
+  /// Whether this path is evaluating within a syntax marked by [`Schema::sensitive()`].
+  ///
+  pub fn is_sensitive(&self) -> bool {
+    self.schema.has_sensitives()
+      && self
+        .stack
+        .iter()
+        .any(|sf| matches!(&sf.parent[sf.current].primary, Primary::Alias(id) if self.schema.is_sensitive(id)))
+  }
+
+  /// The ranges of the buffer that this path has matched within the sensitive syntaxes.
+  ///
+  pub fn redactions(&self) -> &[Range<usize>] {
+    &self.redactions
+  }
+
+  /// The label of the symbols for the debug output, which is redacted within the sensitive syntaxes.
+  ///
+  pub fn debug_symbols(&self, items: &[Σ]) -> String {
+    if self.is_sensitive() {
+      String::from(REDACTED)
+    } else {
+      Σ::debug_symbols(items)
+    }
+  }
+
+  pub fn overrun(&mut self) {
+    self.overrun = true;
+  }
+
+  pub fn is_overrun(&self) -> bool {
+    self.overrun
+  }
+
   pub fn completed(&mut self) {
     self.stack_pop(self.stack.len() - 1);
     debug_assert!(self.stack.len() == 1);
@@ -129,6 +242,34 @@ where
     debug_assert!(self.stack[0].current + 1 == self.stack[0].parent.len());
   }
 
+  /// The depth of the innermost stack frame that is the body of a syntax with a recovery set, if any.
+  ///
+  pub fn recovery_depth(&self) -> Option<usize> {
+    (1..self.stack.len()).rev().find(|k| {
+      let StackFrame { parent, current, .. } = &self.stack[k - 1];
+      matches!(&parent[*current].primary, Primary::Alias(id) if self.schema.recovery(id).is_some())
+    })
+  }
+
+  /// Discard the evaluation within the syntax at `depth` and replace its body with the recovery sequence, so that the
+  /// input is skipped until the syntax can be resynchronized.
+  ///
+  pub fn recover(&mut self, depth: usize) {
+    debug_assert!(depth >= 1 && depth < self.stack.len());
+    self.stack_pop(self.stack.len() - depth);
+    let StackFrame { parent, current, .. } = self.stack.last().unwrap();
+    if let Primary::Alias(id) = &parent[*current].primary {
+      debug!("~ recovered: {}", id);
+      let seq = self.schema.recovery(id).unwrap();
+      self.stack_push(seq);
+    }
+  }
+
+  /// Whether the paths that haven't completed are indistinguishable: they're at the same position of the same syntax
+  /// with the same pending events. The paths diverging within an ignored syntax aren't merged until it ends, even if
+  /// their events are the same, because the rest of the syntax to be matched, the beginnings of the iterations that
+  /// stop an empty repetition, and the lengths limited by [`Schema::limit_length()`] still depend on the frames.
+  ///
   pub fn can_merge(&self, other: &Path<'s, ID, Σ>) -> bool {
     // points the same syntax
     debug_assert_eq!(self.stack[0].parent.len(), other.stack[0].parent.len()); // their root must be same
@@ -144,19 +285,68 @@ where
       }
     }
 
-    // holds the same events
-    debug_assert_eq!(self.event_buffer.clone().normalize(), self.event_buffer);
+    self.has_same_events(other)
+  }
+
+  /// Whether the paths that have completed the whole syntax are indistinguishable. The paths that matched the same
+  /// input are merged if they deliver the same events and they diverge only in the syntaxes whose events are ignored,
+  /// so that an ambiguity which never appears in the events isn't reported as multiple matches.
+  ///
+  pub fn can_merge_completed(&self, other: &Path<'s, ID, Σ>) -> bool {
+    let end = self.current().match_begin + self.current().match_length;
+    if end != other.current().match_begin + other.current().match_length || !self.has_same_events(other) {
+      return false;
+    }
+    let depth = std::cmp::min(self.stack.len(), other.stack.len());
+    let i = (0..depth).find(|i| self.stack[*i].state.syntax().id != other.stack[*i].state.syntax().id).unwrap_or(depth);
+    if i == self.stack.len() && i == other.stack.len() {
+      return true;
+    }
+    // the syntaxes where the paths diverge, or where the shorter one stopped
+    let divergent = |path: &Self| path.stack[std::cmp::min(i, path.stack.len() - 1)].state.syntax();
+    self.stack[..i].iter().any(|f| self.is_ignored(f.state.syntax()))
+      || (self.contains_ignored(divergent(self)) && other.contains_ignored(divergent(other)))
+  }
+
+  fn is_ignored(&self, syntax: &Syntax<ID, Σ>) -> bool {
+    matches!(&syntax.primary, Primary::Alias(id) if self.event_buffer.is_ignored(id))
+  }
+
+  fn contains_ignored(&self, syntax: &Syntax<ID, Σ>) -> bool {
+    match &syntax.primary {
+      Primary::Seq(seq) | Primary::Or(seq) => seq.iter().any(|s| self.contains_ignored(s)),
+      _ => self.is_ignored(syntax),
+    }
+  }
+
+  /// Compare the pending events, which are normalized and exclude the ignored syntaxes when pushed.
+  ///
+  fn has_same_events(&self, other: &Path<'s, ID, Σ>) -> bool {
+    debug_assert!(self.event_buffer.is_normalized());
     self.event_buffer == other.event_buffer
   }
 
   pub fn stack_push_alias(&mut self, id: &ID) -> Result<Σ, ()> {
     debug!("~ begined: {}", id);
+    // an operator expression as the operand of another operator binds only the tighter operators
+    let min_binding = if self.schema.is_pratt(id) {
+      Some(self.stack.last().and_then(|sf| self.schema.right_binding(sf.state.syntax())).unwrap_or(0))
+    } else {
+      None
+    };
     self.stack_push(Self::get_definition(id, self.schema)?);
+    self.stack.last_mut().unwrap().min_binding = min_binding;
     Ok(())
   }
 
   pub fn stack_push(&mut self, seq: &'s Vec<Syntax<ID, Σ>>) {
-    let mut sf = StackFrame::new(seq);
+    self.stack_push_at(seq, 0);
+  }
+
+  /// Push `seq` to be evaluated from the `current`-th syntax.
+  ///
+  pub fn stack_push_at(&mut self, seq: &'s Vec<Syntax<ID, Σ>>, current: usize) {
+    let mut sf = StackFrame::new(seq, current);
     if !self.stack.is_empty() {
       sf.state.location = self.current().location;
       sf.state.match_begin = self.current().match_begin;
@@ -176,9 +366,10 @@ where
       // debug_assert!(current + 1 == parent.len());
       self.complete_eval_of_current_position(false);
 
-      let StackFrame { state, .. } = self.stack.pop().unwrap();
+      let StackFrame { state, min_binding, .. } = self.stack.pop().unwrap();
       self.current_mut().match_begin = state.match_begin;
       self.current_mut().location = state.location;
+      self.current_mut().right_binding = min_binding.unwrap_or(state.right_binding);
     }
     #[cfg(debug_assertions)]
     {
@@ -187,7 +378,7 @@ where
   }
 
   fn complete_eval_of_current_position(&mut self, move_next: bool) {
-    let StackFrame { state, current, parent, _debug } = self.stack.last_mut().unwrap();
+    let StackFrame { state, current, parent, .. } = self.stack.last_mut().unwrap();
     let event = if let Primary::Alias(id) = &parent[*current].primary {
       debug!("~ ended: {}", id);
       Some(state.event(EventKind::End(id.clone())))
@@ -200,6 +391,7 @@ where
       *current += 1;
       state.syntax = &parent[*current];
       state.appearances = 0;
+      state.right_binding = usize::MAX;
     }
     if let Some(e) = event {
       self.events_push(e);
@@ -207,21 +399,91 @@ where
   }
 
   pub fn events_push(&mut self, e: Event<ID, Σ>) {
-    self.event_buffer.push(e)
-  }
-
-  pub fn events_flush_all_to<H: FnMut(&Event<ID, Σ>)>(&mut self, handler: &mut H) {
-    self.events_flush_forward_to(self.event_buffer.len(), handler)
+    match &e.kind {
+      EventKind::Begin(id) if self.schema.is_left_recursive(id) => {
+        let id = id.clone();
+        self.event_buffer.push(e);
+        self.event_buffer.pin(&id);
+      }
+      EventKind::End(id) if self.schema.is_left_recursive(id) => {
+        let id = id.clone();
+        self.event_buffer.push(e);
+        self.event_buffer.unpin(&id);
+      }
+      _ => self.event_buffer.push(e),
+    }
   }
 
   pub fn events_flush_forward_to<H: FnMut(&Event<ID, Σ>)>(&mut self, n: usize, handler: &mut H) {
     self.event_buffer.flush_to(n, handler)
   }
 
+  /// The number of events that can be delivered, excluding those that may be nested in a growing left-recursive rule
+  /// or removed by the next events.
+  ///
+  pub fn events_retained_length(&self) -> usize {
+    self.event_buffer.retained_len()
+  }
+
+  /// The number of events that can be delivered in the middle of the pushed symbols, excluding those that the next
+  /// events may merge or remove.
+  ///
+  pub fn events_settled_length(&self) -> usize {
+    self.event_buffer.settled_len()
+  }
+
   pub fn events_forward_matching_length(&self, other: &Self) -> usize {
     self.event_buffer().forward_matching_length(other.event_buffer())
   }
 
+  /// Skip the `raw` symbols following all of the `consumed` symbols in the buffer, which is cleared, as if they had
+  /// appeared as a fragment of the current syntax.
+  ///
+  pub fn pass_through(&mut self, consumed: usize, raw: &[Σ]) {
+    for sf in &mut self.stack {
+      sf.state.match_begin = sf.state.match_begin.saturating_sub(consumed);
+    }
+    let e = self.current().event(EventKind::Fragments(raw.to_vec()));
+    self.events_push(e);
+    self.current_mut().location.increment_with_seq(raw);
+  }
+
+  /// Check that neither the current term nor the rules being evaluated have exceeded their maximum lengths, where
+  /// `end` is the end of the symbols in the buffer that the current term has matched or is holding.
+  ///
+  pub fn check_length(&self, end: usize, max_fragment_length: usize) -> Result<Σ, ()> {
+    let State { location, match_begin, .. } = self.current();
+    if end - match_begin > max_fragment_length {
+      let limit = max_fragment_length;
+      return Err(Error::LimitExceeded { location: *location, name: "max_fragment_length", limit });
+    }
+    if self.schema.has_max_lengths() {
+      for StackFrame { state, parent, current, .. } in &self.stack {
+        if let Primary::Alias(id) = &parent[*current].primary {
+          match self.schema.max_length(id) {
+            Some(limit) if end - state.match_begin > limit => {
+              return Err(Error::TooLong { location: state.location, id: id.to_string(), limit });
+            }
+            _ => (),
+          }
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// The innermost rule whose definition contains the current syntax, or `None` if it's the syntax of the context.
+  ///
+  pub fn rule(&self) -> Option<&'s ID> {
+    self.stack.iter().rev().skip(1).find_map(|sf| {
+      let parent: &'s Vec<Syntax<ID, Σ>> = sf.parent;
+      match &parent[sf.current].primary {
+        Primary::Alias(id) => Some(id),
+        _ => None,
+      }
+    })
+  }
+
   pub fn min_match_begin(&self) -> usize {
     self.stack.iter().map(|sf| sf.state.match_begin).min().unwrap()
   }
@@ -230,6 +492,10 @@ where
     for sf in &mut self.stack {
       sf.state.match_begin -= amount;
     }
+    self.redactions.retain(|r| r.end > amount);
+    for r in &mut self.redactions {
+      *r = r.start.saturating_sub(amount)..r.end - amount;
+    }
   }
 
   fn get_definition(id: &ID, schema: &'s Schema<ID, Σ>) -> Result<Σ, &'s Vec<Syntax<ID, Σ>>> {
@@ -268,7 +534,11 @@ where
   state: State<'s, ID, Σ>,
   parent: &'s Vec<Syntax<ID, Σ>>,
   current: usize,
+  /// The minimum binding power of the operators if this is the body of a rule defined by
+  /// [`Schema::define_pratt()`].
+  min_binding: Option<usize>,
 
+  #[cfg(debug_assertions)]
   _debug: String,
 }
 
@@ -276,17 +546,24 @@ impl<'s, ID, Σ: Symbol> StackFrame<'s, ID, Σ>
 where
   ID: Clone + Hash + Ord + Display + Debug,
 {
-  pub fn new(parent: &'s Vec<Syntax<ID, Σ>>) -> Self {
-    debug_assert!(!parent.is_empty());
-    let state = State::new(&parent[0]);
-    Self { state, parent, current: 0, _debug: format!("{}", parent[0]) }
+  pub fn new(parent: &'s Vec<Syntax<ID, Σ>>, current: usize) -> Self {
+    debug_assert!(current < parent.len());
+    let state = State::new(&parent[current]);
+    Self {
+      state,
+      parent,
+      current,
+      min_binding: None,
+      #[cfg(debug_assertions)]
+      _debug: format!("{}", parent[current]),
+    }
   }
 }
 
 /// The `Cursor` advances step by step, evaluating [`Syntax`] matches.
 ///
 #[derive(Clone, Debug)]
-pub struct State<'s, ID, Σ: Symbol>
+pub(crate) struct State<'s, ID, Σ: Symbol>
 where
   ID: Clone + Display + Debug,
 {
@@ -294,6 +571,11 @@ where
   pub match_begin: usize,
   pub match_length: usize,
   pub appearances: usize,
+  /// The minimum binding power of the operator expression that has been matched last at the end of this syntax, which
+  /// binds all the operators tighter than it.
+  pub right_binding: usize,
+  /// The number of the symbols from `match_begin` that the term reported as matched by [`MatchResult::Partial`].
+  pub partial: usize,
 
   /// The [`Syntax`] must be `Syntax::Seq`.
   syntax: &'s Syntax<ID, Σ>,
@@ -304,49 +586,71 @@ where
   ID: Clone + Display + Debug + PartialEq + Eq + Hash,
 {
   pub fn new(syntax: &'s Syntax<ID, Σ>) -> Self {
-    Self { location: Σ::Location::default(), match_begin: 0, match_length: 0, appearances: 0, syntax }
+    Self {
+      location: Σ::Location::default(),
+      match_begin: 0,
+      match_length: 0,
+      appearances: 0,
+      right_binding: usize::MAX,
+      partial: 0,
+      syntax,
+    }
   }
 
   pub fn syntax(&self) -> &'s Syntax<ID, Σ> {
     self.syntax
   }
 
-  fn matches(&mut self, buffer: &[Σ], eof: bool) -> Result<Σ, Matching<ID, Σ>> {
+  fn matches(&mut self, buffer: &[Σ], eof: bool, sensitive: bool) -> Result<Σ, Matching<ID, Σ>> {
     debug_assert!(buffer.len() >= self.match_begin + self.match_length);
 
     let items = &buffer[self.match_begin..];
+    let label = |items: &[Σ]| if sensitive { String::from(REDACTED) } else { Σ::debug_symbols(items) };
     let reps = &self.syntax.repetition;
     debug_assert!(self.appearances <= *reps.end());
     if !self.can_repeate_more() {
-      debug!("~ matched: {}({}) -> no data", self.syntax(), Σ::debug_symbols(items));
+      debug!("~ matched: {}({}) -> no data", self.syntax(), label(items));
       return Ok(Matching::Match(0, None));
     }
 
-    let matcher = if let Primary::Term(_, matcher) = &self.syntax.primary {
-      matcher
+    let matcher = if let Primary::Term(term) = &self.syntax.primary {
+      &term.matcher
     } else {
-      unreachable!("Current syntax is not Primary::Term(matcher): {:?}", self.syntax)
+      let message = format!("the current syntax is not a term: {}", self.syntax);
+      return Err(Error::Internal { location: self.location, message });
     };
 
-    let result = match matcher(items)? {
-      MatchResult::UnmatchAndCanAcceptMore if eof => MatchResult::Unmatch,
+    let ctx = MatchCtx { matched: self.partial, ..MatchCtx::new(eof, self.location) };
+    let result = match matcher(self.syntax.primary.window(items), &ctx)? {
+      MatchResult::UnmatchAndCanAcceptMore | MatchResult::Partial(_) if eof => MatchResult::Unmatch,
       MatchResult::MatchAndCanAcceptMore(length) if eof => MatchResult::Match(length),
       result => result,
     };
 
     let result = match result {
+      MatchResult::Match(0) => {
+        debug!("~ matched: {}({}) -> []", self.syntax(), label(items));
+        self.match_length = 0;
+        Matching::Match(0, None)
+      }
       MatchResult::Match(length) => {
         self.match_length = length;
         let values = self.extract(buffer).to_vec();
-        debug!("~ matched: {}({}) -> [{}]", self.syntax(), Σ::debug_symbols(items), Σ::debug_symbols(&values));
+        debug!("~ matched: {}({}) -> [{}]", self.syntax(), label(items), label(&values));
         Matching::Match(length, Some(self.event(EventKind::Fragments(values))))
       }
       MatchResult::Unmatch => {
-        debug!("~ unmatched: {}({})", self.syntax(), Σ::debug_symbols(items));
+        debug!("~ unmatched: {}({})", self.syntax(), label(items));
         Matching::Unmatch
       }
       MatchResult::MatchAndCanAcceptMore(_) | MatchResult::UnmatchAndCanAcceptMore => Matching::More,
+      MatchResult::Partial(matched) => {
+        debug_assert!(matched <= items.len());
+        self.partial = matched;
+        return Ok(Matching::More);
+      }
     };
+    self.partial = 0;
 
     Ok(result)
   }
@@ -373,12 +677,12 @@ where
   }
 
   pub fn event(&self, kind: EventKind<ID, Σ>) -> Event<ID, Σ> {
-    Event { location: self.location, kind }
+    Event { location: self.location, kind, end: None }
   }
 }
 
 #[derive(Debug)]
-pub enum Matching<ID, Σ: Symbol>
+pub(crate) enum Matching<ID, Σ: Symbol>
 where
   ID: Clone + Display + Debug + PartialEq + Eq + Hash,
 {
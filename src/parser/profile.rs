@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+use std::ops::AddAssign;
+use std::time::Duration;
+
+/// The costs of parsing attributed to the rules, collected by a context with
+/// [`profiling()`](crate::parser::Context::profiling). The evaluations of the terms that appear directly in the
+/// definition of the syntax of the context itself are attributed to it.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Profile<ID> {
+  root: ID,
+  rules: BTreeMap<ID, RuleProfile>,
+}
+
+/// The costs of parsing attributed to a rule by [`Profile`].
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RuleProfile {
+  /// The cumulative time to evaluate the terms in the definition of the rule, including the time to move the paths
+  /// that matched them to the next terms.
+  pub time: Duration,
+  /// The number of times the terms in the definition of the rule have been evaluated.
+  pub evaluations: usize,
+  /// The number of paths forked by the choices and the optional syntaxes in the definition of the rule.
+  pub spawns: usize,
+  /// The number of paths discarded within the rule because they had become identical to another one.
+  pub merges: usize,
+}
+
+impl AddAssign for RuleProfile {
+  fn add_assign(&mut self, rhs: Self) {
+    self.time += rhs.time;
+    self.evaluations += rhs.evaluations;
+    self.spawns += rhs.spawns;
+    self.merges += rhs.merges;
+  }
+}
+
+impl<ID: Clone + Ord> Profile<ID> {
+  pub(crate) fn new(root: ID) -> Self {
+    Self { root, rules: BTreeMap::new() }
+  }
+
+  pub(crate) fn root(&self) -> &ID {
+    &self.root
+  }
+
+  /// The costs attributed to the rule `id`, if any.
+  ///
+  pub fn get(&self, id: &ID) -> Option<&RuleProfile> {
+    self.rules.get(id)
+  }
+
+  /// The rules in descending order of their cumulative time, so that the hot spots come first.
+  ///
+  pub fn hottest(&self) -> Vec<(&ID, &RuleProfile)> {
+    let mut rules = self.rules.iter().collect::<Vec<_>>();
+    rules.sort_by_key(|(_, rule)| std::cmp::Reverse(rule.time));
+    rules
+  }
+
+  /// The sum of the costs of all the rules.
+  ///
+  pub fn total(&self) -> RuleProfile {
+    let mut total = RuleProfile::default();
+    for rule in self.rules.values() {
+      total += *rule;
+    }
+    total
+  }
+
+  /// The costs of the rule `id`, or of the syntax of the context if `None`.
+  ///
+  pub(crate) fn rule_mut(&mut self, id: Option<&ID>) -> &mut RuleProfile {
+    let id = id.unwrap_or(&self.root);
+    if !self.rules.contains_key(id) {
+      self.rules.insert(id.clone(), RuleProfile::default());
+    }
+    self.rules.get_mut(id).unwrap()
+  }
+
+  pub(crate) fn merge(&mut self, other: Profile<ID>) {
+    for (id, rule) in other.rules {
+      *self.rules.entry(id).or_default() += rule;
+    }
+  }
+}
@@ -0,0 +1,280 @@
+//! Handlers that write the events to an [`io::Write`] as soon as they're delivered, so that the parse output can be
+//! recorded without a custom handler. A write error aborts the parse when the handler is passed to
+//! [`Context::try_new()`](crate::parser::Context::try_new):
+//!
+//! ```rust
+//! use terp::parser::{Context, Event, JsonLines};
+//! use terp::schema::chars::ascii_digit;
+//! use terp::schema::Schema;
+//!
+//! let schema = Schema::new("Foo").define("NUM", ascii_digit() * (1..));
+//! let mut sink = JsonLines::new(Vec::new());
+//! let mut parser = Context::try_new(&schema, "NUM", |e: &Event<_, _>| sink.handle(e)).unwrap();
+//! parser.push_str("42").unwrap();
+//! parser.finish().unwrap();
+//! assert_eq!(3, String::from_utf8(sink.into_inner()).unwrap().lines().count());
+//! ```
+//!
+//! With the `serde` feature, [`Event`] also implements `Serialize` in the same structure as [`JsonLines`], so that the
+//! events can be written to any `Serializer`.
+//!
+use crate::parser::{Event, EventKind};
+use crate::pipelines::json_string;
+use crate::schema::{Location, Symbol};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::io::{self, Write};
+
+/// The symbols that can be recorded as bytes.
+///
+pub trait Encodable: Symbol {
+  /// Whether the encoded symbols are UTF-8 text, which are recorded as a string instead of bytes.
+  const TEXT: bool;
+
+  fn encode(values: &[Self], buffer: &mut Vec<u8>);
+}
+
+impl Encodable for char {
+  const TEXT: bool = true;
+
+  fn encode(values: &[Self], buffer: &mut Vec<u8>) {
+    let mut bytes = [0u8; 4];
+    for ch in values {
+      buffer.extend_from_slice(ch.encode_utf8(&mut bytes).as_bytes());
+    }
+  }
+}
+
+impl Encodable for u8 {
+  const TEXT: bool = false;
+
+  fn encode(values: &[Self], buffer: &mut Vec<u8>) {
+    buffer.extend_from_slice(values);
+  }
+}
+
+/// Write each event as a line of JSON object with the `kind`, the `id` of Begin and End or the `fragments`, the
+/// `location`, and the `end` if available. The fragments are a string for text, otherwise an array of bytes.
+///
+/// ```text
+/// {"kind":"Begin","id":"NUM","location":{"position":0,"line":0,"column":0,"byte_offset":0}}
+/// ```
+///
+pub struct JsonLines<W: Write> {
+  writer: W,
+  buffer: Vec<u8>,
+}
+
+impl<W: Write> JsonLines<W> {
+  pub fn new(writer: W) -> Self {
+    Self { writer, buffer: Vec::new() }
+  }
+
+  pub fn handle<ID, Σ: Encodable>(&mut self, e: &Event<ID, Σ>) -> io::Result<()>
+  where
+    ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+  {
+    let mut line = String::from("{");
+    match &e.kind {
+      EventKind::Begin(id) | EventKind::End(id) => {
+        let kind = if matches!(e.kind, EventKind::Begin(_)) { "Begin" } else { "End" };
+        line.push_str(&format!("\"kind\":\"{}\",\"id\":{}", kind, json_string(&id.to_string())));
+      }
+      EventKind::Fragments(items) => {
+        self.buffer.clear();
+        Σ::encode(items, &mut self.buffer);
+        let fragments = if Σ::TEXT {
+          json_string(&String::from_utf8_lossy(&self.buffer))
+        } else {
+          format!("[{}]", self.buffer.iter().map(|b| b.to_string()).collect::<Vec<_>>().join(","))
+        };
+        line.push_str(&format!("\"kind\":\"Fragments\",\"fragments\":{}", fragments));
+      }
+    }
+    line.push_str(&format!(",\"location\":{}", json_location::<Σ>(&e.location)));
+    if let Some(end) = &e.end {
+      line.push_str(&format!(",\"end\":{}", json_location::<Σ>(end)));
+    }
+    line.push_str("}\n");
+    self.writer.write_all(line.as_bytes())
+  }
+
+  pub fn into_inner(self) -> W {
+    self.writer
+  }
+}
+
+fn json_location<Σ: Symbol>(location: &Σ::Location) -> String {
+  let mut fields = vec![format!("\"position\":{}", location.position())];
+  let optionals = [("line", location.line()), ("column", location.column()), ("byte_offset", location.byte_offset())];
+  for (name, value) in optionals {
+    if let Some(value) = value {
+      fields.push(format!("\"{}\":{}", name, value));
+    }
+  }
+  format!("{{{}}}", fields.join(","))
+}
+
+/// Write each event as a MessagePack map in the same structure as [`JsonLines`], except that the fragments of non-text
+/// symbols are a binary.
+///
+pub struct MessagePack<W: Write> {
+  writer: W,
+  buffer: Vec<u8>,
+  fragments: Vec<u8>,
+}
+
+impl<W: Write> MessagePack<W> {
+  pub fn new(writer: W) -> Self {
+    Self { writer, buffer: Vec::new(), fragments: Vec::new() }
+  }
+
+  pub fn handle<ID, Σ: Encodable>(&mut self, e: &Event<ID, Σ>) -> io::Result<()>
+  where
+    ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+  {
+    let buffer = &mut self.buffer;
+    buffer.clear();
+    msgpack_map(buffer, if e.end.is_some() { 4 } else { 3 });
+    msgpack_str(buffer, "kind");
+    match &e.kind {
+      EventKind::Begin(id) | EventKind::End(id) => {
+        msgpack_str(buffer, if matches!(e.kind, EventKind::Begin(_)) { "Begin" } else { "End" });
+        msgpack_str(buffer, "id");
+        msgpack_str(buffer, &id.to_string());
+      }
+      EventKind::Fragments(items) => {
+        msgpack_str(buffer, "Fragments");
+        msgpack_str(buffer, "fragments");
+        self.fragments.clear();
+        Σ::encode(items, &mut self.fragments);
+        msgpack_bytes(buffer, &self.fragments, Σ::TEXT);
+      }
+    }
+    msgpack_str(buffer, "location");
+    msgpack_location::<Σ>(buffer, &e.location);
+    if let Some(end) = &e.end {
+      msgpack_str(buffer, "end");
+      msgpack_location::<Σ>(buffer, end);
+    }
+    self.writer.write_all(buffer)
+  }
+
+  pub fn into_inner(self) -> W {
+    self.writer
+  }
+}
+
+fn msgpack_location<Σ: Symbol>(buffer: &mut Vec<u8>, location: &Σ::Location) {
+  let optionals = [("line", location.line()), ("column", location.column()), ("byte_offset", location.byte_offset())];
+  msgpack_map(buffer, 1 + optionals.iter().filter(|(_, value)| value.is_some()).count());
+  msgpack_str(buffer, "position");
+  msgpack_uint(buffer, location.position());
+  for (name, value) in optionals {
+    if let Some(value) = value {
+      msgpack_str(buffer, name);
+      msgpack_uint(buffer, value);
+    }
+  }
+}
+
+fn msgpack_map(buffer: &mut Vec<u8>, len: usize) {
+  debug_assert!(len < 16);
+  buffer.push(0x80 | len as u8);
+}
+
+fn msgpack_str(buffer: &mut Vec<u8>, text: &str) {
+  msgpack_bytes(buffer, text.as_bytes(), true);
+}
+
+fn msgpack_bytes(buffer: &mut Vec<u8>, bytes: &[u8], text: bool) {
+  let len = bytes.len();
+  match (text, len) {
+    (true, 0..=31) => buffer.push(0xA0 | len as u8),
+    (true, 32..=0xFF) => buffer.extend_from_slice(&[0xD9, len as u8]),
+    (false, 0..=0xFF) => buffer.extend_from_slice(&[0xC4, len as u8]),
+    (_, 0x100..=0xFFFF) => {
+      buffer.push(if text { 0xDA } else { 0xC5 });
+      buffer.extend_from_slice(&(len as u16).to_be_bytes());
+    }
+    _ => {
+      buffer.push(if text { 0xDB } else { 0xC6 });
+      buffer.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+  }
+  buffer.extend_from_slice(bytes);
+}
+
+fn msgpack_uint(buffer: &mut Vec<u8>, value: u64) {
+  match value {
+    0..=0x7F => buffer.push(value as u8),
+    0x80..=0xFF => buffer.extend_from_slice(&[0xCC, value as u8]),
+    0x100..=0xFFFF => {
+      buffer.push(0xCD);
+      buffer.extend_from_slice(&(value as u16).to_be_bytes());
+    }
+    0x10000..=0xFFFF_FFFF => {
+      buffer.push(0xCE);
+      buffer.extend_from_slice(&(value as u32).to_be_bytes());
+    }
+    _ => {
+      buffer.push(0xCF);
+      buffer.extend_from_slice(&value.to_be_bytes());
+    }
+  }
+}
+
+/// Write each event in a compact binary form: a tag `B`, `E` or `F`, the position as an unsigned LEB128, and the
+/// length of the payload as an unsigned LEB128 followed by the payload, which is the UTF-8 `id` of Begin and End, or
+/// the encoded fragments. The other attributes of the locations are not recorded.
+///
+pub struct BinaryTrace<W: Write> {
+  writer: W,
+  buffer: Vec<u8>,
+  payload: Vec<u8>,
+}
+
+impl<W: Write> BinaryTrace<W> {
+  pub fn new(writer: W) -> Self {
+    Self { writer, buffer: Vec::new(), payload: Vec::new() }
+  }
+
+  pub fn handle<ID, Σ: Encodable>(&mut self, e: &Event<ID, Σ>) -> io::Result<()>
+  where
+    ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+  {
+    self.payload.clear();
+    let tag = match &e.kind {
+      EventKind::Begin(id) | EventKind::End(id) => {
+        self.payload.extend_from_slice(id.to_string().as_bytes());
+        if matches!(e.kind, EventKind::Begin(_)) {
+          b'B'
+        } else {
+          b'E'
+        }
+      }
+      EventKind::Fragments(items) => {
+        Σ::encode(items, &mut self.payload);
+        b'F'
+      }
+    };
+    self.buffer.clear();
+    self.buffer.push(tag);
+    leb128(&mut self.buffer, e.location.position());
+    leb128(&mut self.buffer, self.payload.len() as u64);
+    self.buffer.extend_from_slice(&self.payload);
+    self.writer.write_all(&self.buffer)
+  }
+
+  pub fn into_inner(self) -> W {
+    self.writer
+  }
+}
+
+fn leb128(buffer: &mut Vec<u8>, mut value: u64) {
+  while value >= 0x80 {
+    buffer.push((value as u8 & 0x7F) | 0x80);
+    value >>= 7;
+  }
+  buffer.push(value as u8);
+}
@@ -0,0 +1,69 @@
+use crate::schema::Symbol;
+
+/// How the input is sampled into the [`prefix`](crate::ErrorDetail::prefix) and the
+/// [`actual`](crate::ErrorDetail::actual) of the errors reported by a [`Context`](crate::parser::Context), e.g. to
+/// avoid echoing the user data back in the errors. The symbols matched within the syntaxes marked by
+/// [`Schema::sensitive()`](crate::schema::Schema::sensitive) are redacted regardless of this.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Sampling {
+  /// The maximum number of symbols preceding the location of the error.
+  pub prefix_length: usize,
+  /// The maximum number of symbols following the symbol at the location of the error. The parser waits for them to be
+  /// pushed before it reports the error.
+  pub actual_length: usize,
+  pub redaction: Redaction,
+}
+
+/// How the sampled symbols are written in the errors.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Redaction {
+  /// The symbols are written as they are.
+  Off,
+  /// Each symbol is replaced with the character, e.g. `'*'`, so that only the shape of the input is revealed.
+  Mask(char),
+  /// The symbols are omitted entirely; the prefix is empty and the actual is written as `[***]` unless it's EOF.
+  Omit,
+}
+
+impl Sampling {
+  /// The default sampling of the symbol type `Σ`, which writes [`Symbol::SAMPLING_UNIT_AT_ERROR`] symbols on each
+  /// side of the location as they are.
+  ///
+  pub const fn of<Σ: Symbol>() -> Sampling {
+    Sampling {
+      prefix_length: Σ::SAMPLING_UNIT_AT_ERROR,
+      actual_length: Σ::SAMPLING_UNIT_AT_ERROR,
+      redaction: Redaction::Off,
+    }
+  }
+
+  /// The sampling that never writes the input in the errors.
+  ///
+  pub const fn omitted() -> Sampling {
+    Sampling { prefix_length: 0, actual_length: 0, redaction: Redaction::Omit }
+  }
+
+  /// This sampling with the maximum numbers of the symbols written before and after the location replaced, e.g.,
+  /// `Sampling::of::<char>().with_lengths(8, 8)`.
+  ///
+  pub const fn with_lengths(self, prefix_length: usize, actual_length: usize) -> Sampling {
+    Sampling { prefix_length, actual_length, ..self }
+  }
+
+  /// This sampling with the [`Redaction`] replaced.
+  ///
+  pub const fn with_redaction(self, redaction: Redaction) -> Sampling {
+    Sampling { redaction, ..self }
+  }
+
+  pub(crate) fn label<Σ: Symbol>(&self, items: &[Σ]) -> String {
+    match self.redaction {
+      Redaction::Off => Σ::debug_symbols(items),
+      Redaction::Mask(mask) => items.iter().map(|_| mask).collect(),
+      Redaction::Omit => String::new(),
+    }
+  }
+}
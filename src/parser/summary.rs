@@ -0,0 +1,17 @@
+use crate::schema::Symbol;
+
+/// The result of a successful parse returned by [`Context::finish()`](crate::parser::Context::finish).
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseSummary<Σ: Symbol> {
+  /// The number of symbols consumed by the parse, that is, up to the end of the syntax specified by
+  /// [`until_first()`](crate::parser::Context::until_first) if it has completed.
+  pub symbols: u64,
+  /// The number of events delivered to the handler, which is 0 for a
+  /// [`recognizer()`](crate::parser::Context::recognizer).
+  pub events: usize,
+  /// The location where the parse ended.
+  pub location: Σ::Location,
+  /// The number of syntaxes that have completed, that is, the End events delivered to the handler.
+  pub rules: usize,
+}
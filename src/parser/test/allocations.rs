@@ -0,0 +1,63 @@
+//! Regression tests of the number of heap allocations while parsing, which dominates the cost of the hot paths. They
+//! only run without `debug_assertions`, since the debug logs allocate, e.g. `cargo test --release allocations`.
+//!
+use crate::parser::{Context, Event};
+use crate::schema::json;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAllocator;
+
+thread_local! {
+  static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    ALLOCATIONS.with(|n| n.set(n.get() + 1));
+    System.alloc(layout)
+  }
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout)
+  }
+  unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+    ALLOCATIONS.with(|n| n.set(n.get() + 1));
+    System.realloc(ptr, layout, new_size)
+  }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// The number of allocations on this thread while parsing `text` with the JSON schema.
+///
+fn allocations(schema: &crate::schema::Schema<json::ID, char>, text: &str) -> usize {
+  let mut events = 0;
+  let mut parser = Context::new(schema, json::ID::JsonText, |_: &Event<_, _>| events += 1)
+    .unwrap()
+    .ignore_events_for(&[json::ID::WS, json::ID::Char, json::ID::Unescaped, json::ID::Digit]);
+  let before = ALLOCATIONS.with(|n| n.get());
+  parser.push_str(text).unwrap();
+  parser.finish().unwrap();
+  ALLOCATIONS.with(|n| n.get()) - before
+}
+
+#[test]
+fn allocations_per_symbol() {
+  let schema = json::schema();
+  let text = r#"{"name": "terp", "tags": ["parser", "stream"], "version": 0.1, "stable": false}"#;
+  let n = allocations(&schema, text);
+  // 3,291 allocations were measured for these 79 symbols
+  assert!(n <= 3400, "{} allocations for {} symbols", n, text.len());
+}
+
+#[test]
+fn allocations_grow_linearly() {
+  // the events are confirmed while a long input is pushed at once, otherwise every fork of the path copies them
+  let schema = json::schema();
+  let item = r#"{"name": "terp", "tags": ["parser", "stream"], "version": 0.1}"#;
+  let n4 = allocations(&schema, &format!("[{}]", vec![item; 4].join(",")));
+  let n16 = allocations(&schema, &format!("[{}]", vec![item; 16].join(",")));
+  // 11,362 and 45,095 allocations were measured
+  assert!(n16 <= n4 * 41 / 10, "{} allocations for 4 items, {} for 16 items", n4, n16);
+}
@@ -0,0 +1,85 @@
+use crate::parser::test::json::SAMPLE_WIKIPEDIA;
+use crate::parser::{forward, Context, Disconnected, Event};
+use crate::schema::json;
+use crate::Error;
+use std::sync::mpsc;
+use std::thread;
+
+fn expected() -> Vec<Event<json::ID, char>> {
+  let schema = json::schema();
+  let mut events = Vec::new();
+  let mut parser = Context::new(&schema, json::ID::JsonText, |e: &Event<_, _>| events.push(e.clone())).unwrap();
+  parser.push_str(SAMPLE_WIKIPEDIA).unwrap();
+  parser.finish().unwrap();
+  events
+}
+
+#[test]
+fn channel_std_mpsc() {
+  let schema = json::schema();
+  let (sender, receiver) = mpsc::sync_channel(1);
+  let consumer = thread::spawn(move || receiver.iter().collect::<Vec<_>>());
+  let mut parser = Context::try_new(&schema, json::ID::JsonText, forward(sender)).unwrap();
+  parser.push_str(SAMPLE_WIKIPEDIA).unwrap();
+  parser.finish().unwrap();
+  assert_eq!(expected(), consumer.join().unwrap());
+
+  let (sender, receiver) = mpsc::channel();
+  let mut parser = Context::try_new(&schema, json::ID::JsonText, forward(sender)).unwrap();
+  parser.push_str(SAMPLE_WIKIPEDIA).unwrap();
+  parser.finish().unwrap();
+  assert_eq!(expected(), receiver.iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn channel_receiver_dropped() {
+  let schema = json::schema();
+  let (sender, receiver) = mpsc::sync_channel(1);
+  drop(receiver);
+  let mut parser = Context::try_new(&schema, json::ID::JsonText, forward(sender)).unwrap();
+  match parser.push_str(SAMPLE_WIKIPEDIA) {
+    Err(Error::Handler(source)) => assert_eq!(Some(&Disconnected), source.get_ref().downcast_ref::<Disconnected>()),
+    unexpected => panic!("{:?}", unexpected),
+  }
+  assert_eq!(Err(Error::Previous), parser.finish().map(|_| ()));
+}
+
+#[cfg(feature = "crossbeam")]
+#[test]
+fn channel_crossbeam() {
+  let schema = json::schema();
+  let (sender, receiver) = crossbeam_channel::bounded(1);
+  let consumer = thread::spawn(move || receiver.iter().collect::<Vec<_>>());
+  let mut parser = Context::try_new(&schema, json::ID::JsonText, forward(sender)).unwrap();
+  parser.push_str(SAMPLE_WIKIPEDIA).unwrap();
+  parser.finish().unwrap();
+  assert_eq!(expected(), consumer.join().unwrap());
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn channel_tokio() {
+  let schema = json::schema();
+  let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+  let consumer = thread::spawn(move || {
+    let mut events = Vec::new();
+    while let Some(e) = receiver.blocking_recv() {
+      events.push(e);
+    }
+    events
+  });
+  let mut parser = Context::try_new(&schema, json::ID::JsonText, forward(sender)).unwrap();
+  parser.push_str(SAMPLE_WIKIPEDIA).unwrap();
+  parser.finish().unwrap();
+  assert_eq!(expected(), consumer.join().unwrap());
+
+  let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+  let mut parser = Context::try_new(&schema, json::ID::JsonText, forward(sender)).unwrap();
+  parser.push_str(SAMPLE_WIKIPEDIA).unwrap();
+  parser.finish().unwrap();
+  let mut events = Vec::new();
+  while let Ok(e) = receiver.try_recv() {
+    events.push(e);
+  }
+  assert_eq!(expected(), events);
+}
@@ -0,0 +1,92 @@
+use crate::parser::{parse_chunks, split_lines, split_quoted_lines, Context, Event, EventKind};
+use crate::schema::chars::{ch, none_of_chars, token};
+use crate::schema::{id, Location as _, Schema};
+use crate::Error;
+use std::ops::Range;
+
+/// The records of CSV each of which includes its line break, so that any concatenation of them is a file.
+fn records() -> Schema<&'static str, char> {
+  Schema::new("Records")
+    .define("FILE", id("RECORD") * (0..))
+    .define("RECORD", id("FIELD") & ((ch(',') & id("FIELD")) * (0..)) & ch('\n'))
+    .define("FIELD", id("ESCAPED") | id("TEXT"))
+    .define("ESCAPED", ch('"') & ((none_of_chars("\"") | token("\"\"")) * (0..)) & ch('"'))
+    .define("TEXT", none_of_chars(",\"\n") * (0..))
+}
+
+const CSV: &str = "a,b\n\"c\nd\",e\n\"f\"\"\n\",\n1,2,3\n\n";
+
+#[test]
+fn split_at_line_breaks() {
+  let input = "ab\ncd\n\nef".chars().collect::<Vec<_>>();
+  assert_eq!(vec![0..3, 3..6, 6..7, 7..9], split_lines(&input, '\n', 0));
+  assert_eq!(vec![0..6, 6..9], split_lines(&input, '\n', 4));
+  assert_eq!(vec![0..9], split_lines(&input, '\n', 100));
+  assert_eq!(Vec::<Range<usize>>::new(), split_lines(&[], '\n', 4));
+
+  let input = CSV.chars().collect::<Vec<_>>();
+  assert_eq!(vec![0..4, 4..12, 12..20, 20..26, 26..27], split_quoted_lines(&input, '"', '\n', 1));
+  assert_eq!(vec![0..12, 12..20, 20..26, 26..27], split_quoted_lines(&input, '"', '\n', 5));
+}
+
+#[test]
+fn chunks_produce_the_same_events_as_a_whole() {
+  let schema = records();
+  let input = CSV.repeat(20).chars().collect::<Vec<_>>();
+  let expected = parse(&schema, &input, None).unwrap();
+  for chunk_size in [0, 1, 10, 50, 1000] {
+    let chunks = split_quoted_lines(&input, '"', '\n', chunk_size);
+    assert_eq!(expected, parse(&schema, &input, Some(&chunks)).unwrap(), "{}", chunk_size);
+  }
+  assert_eq!(expected, parse(&schema, &input, Some(&[])).unwrap());
+}
+
+#[test]
+fn empty_chunks_are_skipped() {
+  let schema = records();
+  let input = "a,b\nc,d\n".chars().collect::<Vec<_>>();
+  let expected = parse(&schema, &input, None).unwrap();
+  for chunks in [vec![0..4, 4..4, 4..8], vec![0..0, 0..8], vec![0..8, 8..8]] {
+    assert_eq!(expected, parse(&schema, &input, Some(&chunks)).unwrap(), "{:?}", chunks);
+  }
+  assert_eq!(parse(&schema, &[], None).unwrap(), parse(&schema, &[], Some(&[0..0, 0..0])).unwrap());
+}
+
+#[test]
+fn error_of_the_first_unmatched_chunk() {
+  let schema = records();
+  let input = format!("{}c\"d\ne,f\n", "a,b\n".repeat(3)).chars().collect::<Vec<_>>();
+  let chunks = split_lines(&input, '\n', 1);
+  let expected = parse(&schema, &input, None).unwrap_err();
+
+  let mut events = Vec::new();
+  let error = parse_chunks(&schema, "FILE", &input, &chunks, |e: &Event<_, _>| events.push(e.clone())).unwrap_err();
+  match (expected, error) {
+    (Error::Unmatched(expected), Error::Unmatched(detail)) => {
+      assert_eq!((Some(3), Some(1)), (detail.location.line(), detail.location.column()));
+      assert_eq!(expected.location, detail.location);
+      assert_eq!(expected.expecteds, detail.expecteds);
+    }
+    (expected, error) => panic!("{:?}, {:?}", expected, error),
+  }
+
+  // the events of the records preceding the error have been delivered
+  assert_eq!(Some(&EventKind::Begin("FILE")), events.first().map(|e| &e.kind));
+  assert_eq!(3, events.iter().filter(|e| e.kind == EventKind::End("RECORD")).count());
+}
+
+fn parse(
+  schema: &Schema<&'static str, char>, input: &[char], chunks: Option<&[Range<usize>]>,
+) -> crate::Result<char, Vec<Event<&'static str, char>>> {
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  match chunks {
+    Some(chunks) => parse_chunks(schema, "FILE", input, chunks, handler)?,
+    None => {
+      let mut parser = Context::new(schema, "FILE", handler)?;
+      parser.push_seq(input)?;
+      parser.finish()?;
+    }
+  }
+  Ok(Event::normalize(&events))
+}
@@ -0,0 +1,58 @@
+//! The check that any partition of the input into the chunks pushed delivers the same events and errors.
+//!
+use crate::parser::test::{for_each_mutation, parse_chunks, Random};
+use crate::parser::Event;
+use crate::schema::Schema;
+use crate::Result;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+/// The events and the result that don't depend on how the input is split into the pushes, including the events
+/// delivered before an error and the error itself.
+///
+#[derive(Debug, PartialEq)]
+struct Parse<ID: Clone + Display + Debug + Eq + Hash> {
+  events: Vec<Event<ID, char>>,
+  result: Result<char, ()>,
+}
+
+impl<ID: Clone + Display + Debug + Eq + Hash> Parse<ID> {
+  fn new(schema: &Schema<ID, char>, id: ID, chunks: &[&str]) -> Self
+  where
+    ID: 'static + Ord + Send + Sync,
+  {
+    let (events, result) = parse_chunks(schema, id, chunks, true);
+    Parse { events: Event::normalize(&events), result }
+  }
+}
+
+/// Split `input` at random positions, including empty chunks.
+///
+fn partition<'a>(random: &mut Random, input: &'a str) -> Vec<&'a str> {
+  let mut bounds = input.char_indices().map(|(i, _)| i).filter(|_| random.below(3) == 0).collect::<Vec<_>>();
+  bounds.push(input.len());
+  let mut chunks = Vec::with_capacity(bounds.len());
+  let mut begin = 0;
+  for end in bounds {
+    chunks.push(&input[begin..end]);
+    begin = end;
+  }
+  chunks
+}
+
+/// Parse the mutations of each of the `samples` at once and in random partitions, and assert the same results.
+///
+pub(super) fn assert_invariant<ID>(schema: &Schema<ID, char>, id: ID, samples: &[&str], alphabet: &str)
+where
+  ID: 'static + Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
+{
+  for_each_mutation(&mut Random::new(0xC4A7), samples, alphabet, 16, |random, input| {
+    let expected = Parse::new(schema, id.clone(), &[input]);
+    for _ in 0..4 {
+      let chunks = partition(random, input);
+      assert_eq!(expected, Parse::new(schema, id.clone(), &chunks), "diverged for {:?}", chunks);
+    }
+    let chars = input.char_indices().map(|(i, ch)| &input[i..i + ch.len_utf8()]).collect::<Vec<_>>();
+    assert_eq!(expected, Parse::new(schema, id.clone(), &chars), "diverged for {:?}", chars);
+  });
+}
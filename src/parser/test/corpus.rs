@@ -0,0 +1,60 @@
+//! The grammars and the samples over which the parses are checked not to depend on how the input is split into the
+//! pushes nor on whether the paths are evaluated concurrently.
+//!
+use crate::parser::test::chunking::assert_invariant;
+#[cfg(feature = "concurrent")]
+use crate::parser::test::equivalence::assert_equivalent;
+use crate::schema::chars::{ascii_digit, ch};
+use crate::schema::{csv, id, ini, json, sexpr, Schema};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+/// Run all the checks over the mutations of each of the `samples`.
+///
+fn assert_corpus<ID>(schema: &Schema<ID, char>, id: ID, samples: &[&str], alphabet: &str)
+where
+  ID: 'static + Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
+{
+  assert_invariant(schema, id.clone(), samples, alphabet);
+  #[cfg(feature = "concurrent")]
+  assert_equivalent(schema, id, samples, alphabet);
+}
+
+#[test]
+fn corpus_json() {
+  let samples = [r#"{"a": [1, -2.5e3, true], "b": {"c": null}}"#, r#"[ "x\"y", {}, [[]], 0 ]"#];
+  assert_corpus(&json::schema(), json::ID::JsonText, &samples, "{}[],:\" 0123.e-trufalsn\\");
+}
+
+#[test]
+fn corpus_csv() {
+  let samples = ["a,b\n1,\"2,3\"\n", "\"x\"\"y\",,z\r\n"];
+  assert_corpus(&csv::schema(), csv::ID::File, &samples, "ab,\"\r\n ");
+}
+
+#[test]
+fn corpus_ini() {
+  let samples = ["[section]\nkey = value\n; comment\n", "a=1\n\n[b]\nc=2"];
+  assert_corpus(&ini::schema(), ini::ID::File, &samples, "[]=;# \nabc12");
+}
+
+#[test]
+fn corpus_sexpr() {
+  let samples = ["(define (f x) (* x \"two\")) ; done\n", "(a (b (c)) d)"];
+  assert_corpus(&sexpr::schema(), sexpr::ID::SExpr, &samples, "() \";\\\nabx");
+}
+
+#[test]
+fn corpus_ambiguous_and_left_recursive() {
+  let schema = Schema::new("Foo")
+    .define("EXPR", (id("EXPR") & ch('+') & id("TERM")) | id("TERM"))
+    .define("TERM", (id("NUM") & ch('*') & id("NUM")) | id("NUM") | (ascii_digit() & id("NUM")))
+    .define("NUM", ascii_digit() * (1..));
+  assert_corpus(&schema, "EXPR", &["1+23*4+5", "12*3"], "0123+*");
+}
+
+#[test]
+fn corpus_after_completion() {
+  let schema = Schema::new("Foo").define("PAIR", ascii_digit() & ch(',') & ascii_digit());
+  assert_corpus(&schema, "PAIR", &["1,2"], "12,");
+}
@@ -0,0 +1,128 @@
+use crate::parser::test::json::SAMPLE_WIKIPEDIA;
+use crate::parser::test::{assert_unmatch, assert_unmatches, location, Events};
+use crate::parser::{Context, Engine, Event, EventKind};
+use crate::schema::chars::{ascii_alphabetic, ascii_digit, ch};
+use crate::schema::{id, json, Schema};
+use crate::Error;
+
+#[test]
+fn earley_same_events_as_streaming() {
+  let schema = json::schema();
+  let mut expected = Vec::new();
+  let mut parser = Context::new(&schema, json::ID::JsonText, |e: &Event<_, _>| expected.push(e.clone())).unwrap();
+  parser.push_str(SAMPLE_WIKIPEDIA).unwrap();
+  parser.finish().unwrap();
+
+  let mut actual = Vec::new();
+  let mut parser = Context::new(&schema, json::ID::JsonText, |e: &Event<_, _>| actual.push(e.clone()))
+    .unwrap()
+    .with_engine(Engine::Earley);
+  parser.push_str(SAMPLE_WIKIPEDIA).unwrap();
+  parser.finish().unwrap();
+  assert_eq!(Event::normalize(&expected), actual);
+}
+
+#[test]
+fn earley_failed_iteration() {
+  // the iteration of (a b) fails halfway, but doesn't affect the following syntax
+  let schema = Schema::new("Foo").define("A", ((ch('a') & ch('b')) * (0..)) & ch('a') & ch('c'));
+  for chars in ["ac", "abac", "ababac"] {
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| events.push(e.clone());
+    let mut parser = Context::new(&schema, "A", handler).unwrap().with_engine(Engine::Earley);
+    parser.push_str(chars).unwrap();
+    parser.finish().unwrap();
+    Events::new().begin("A").fragments(chars).end().assert_eq(&events);
+  }
+}
+
+#[test]
+fn earley_left_recursion() {
+  let schema = Schema::new("Foo")
+    .define("EXPR", (id("EXPR") & ch('-') & id("NUM")) | id("NUM"))
+    .define("NUM", ascii_digit() * (1..));
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "EXPR", handler).unwrap().with_engine(Engine::Earley);
+  parser.push_str("9-12-3").unwrap();
+  parser.finish().unwrap();
+  Events::new()
+    .begin("EXPR")
+    .begin("EXPR")
+    .begin("EXPR")
+    .begin("NUM")
+    .fragments("9")
+    .end()
+    .end()
+    .fragments("-")
+    .begin("NUM")
+    .fragments("12")
+    .end()
+    .end()
+    .fragments("-")
+    .begin("NUM")
+    .fragments("3")
+    .end()
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn earley_ambiguous() {
+  // exponentially many paths for the streaming engine
+  let schema = Schema::new("Foo")
+    .define("A", (id("X") | id("Y")) * (0..))
+    .define("X", ascii_alphabetic())
+    .define("Y", ascii_alphabetic() | ascii_digit());
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap().with_engine(Engine::Earley);
+  parser.push_str(&"1".repeat(64)).unwrap();
+  parser.push_str("a").unwrap();
+  match parser.finish() {
+    Err(Error::MultipleMatches(detail)) => {
+      assert_eq!(location(64, 0, 64), detail.location);
+      assert_eq!(vec![String::from("[A]")], detail.expecteds);
+    }
+    unexpected => panic!("{:?}", unexpected),
+  }
+}
+
+#[test]
+fn earley_unmatch() {
+  let schema = Schema::new("Foo").define("A", ch('a') & (ascii_digit() * (1..)) & ch(';'));
+  for (chars, l, prefix, expecteds, actual) in [
+    ("a12x", location(3, 0, 3), "a12", &["[ASCII_DIGIT+]", "[';']"][..], "['x']..."),
+    ("ax", location(1, 0, 1), "a", &["[ASCII_DIGIT+]"][..], "['x']..."),
+    ("a1", location(2, 0, 2), "a1", &["[ASCII_DIGIT+]", "[';']"][..], "[EOF]"),
+  ] {
+    let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap().with_engine(Engine::Earley);
+    parser.push_str(chars).unwrap();
+    let expecteds = expecteds.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+    assert_unmatches(parser.finish(), l, prefix, &expecteds, actual);
+  }
+
+  let schema = Schema::new("Foo").define("A", ch('a'));
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap().with_engine(Engine::Earley);
+  parser.push_str("ab").unwrap();
+  assert_unmatch(parser.finish(), location(1, 0, 1), "a", "[EOF]", "['b']...");
+}
+
+#[test]
+fn earley_long_repetition() {
+  // the derivation of a repetition is as deep as its occurrences, which must not be evaluated recursively
+  let schema = Schema::new("Foo").define("A", id("W") * (0..)).define("W", ascii_alphabetic());
+  let text = "abcdefghij".repeat(10_000);
+  let mut words = 0;
+  let mut parser =
+    Context::new(&schema, "A", |e: &Event<_, _>| words += matches!(e.kind, EventKind::End("W")) as usize)
+      .unwrap()
+      .with_engine(Engine::Earley);
+  parser.push_str(&text).unwrap();
+  parser.finish().unwrap();
+  assert_eq!(text.len(), words);
+
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap().with_engine(Engine::Earley);
+  parser.push_str(&text).unwrap();
+  let forest = parser.finish_forest().unwrap();
+  assert_eq!(1, forest.count());
+  assert_eq!(2 + 3 * text.len(), forest.events(0).unwrap().len());
+}
@@ -0,0 +1,92 @@
+use crate::parser::test::{assert_unmatch, location, Events};
+use crate::parser::{Context, Event};
+use crate::schema::chars::{ascii_alphabetic, ch, token};
+use crate::schema::{eof, id, MatchResult, Schema, Syntax};
+
+#[test]
+fn eof_in_branch() {
+  // a shebang-only file or a shebang followed by a script body
+  let schema = Schema::new("Foo")
+    .define("SCRIPT", id("SHEBANG") & (eof() | (ch('\n') & id("BODY"))))
+    .define("SHEBANG", token("#!") & (ascii_alphabetic() * (1..)))
+    .define("BODY", ascii_alphabetic() * (1..));
+
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "SCRIPT", handler).unwrap();
+  parser.push_str("#!sh").unwrap();
+  parser.finish().unwrap();
+  Events::new().begin("SCRIPT").begin("SHEBANG").fragments("#!sh").end().end().assert_eq(&events);
+
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "SCRIPT", handler).unwrap();
+  parser.push_str("#!sh\nfoo").unwrap();
+  parser.finish().unwrap();
+  Events::new()
+    .begin("SCRIPT")
+    .begin("SHEBANG")
+    .fragments("#!sh")
+    .end()
+    .fragments("\n")
+    .begin("BODY")
+    .fragments("foo")
+    .end()
+    .end()
+    .assert_eq(&events);
+
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "SCRIPT", handler).unwrap();
+  parser.push_str("#!sh\n").unwrap();
+  assert_unmatch(parser.finish(), location(5, 1, 0), "#!sh\\n", "[ASCII_ALPHA+]", "[EOF]");
+}
+
+#[test]
+fn eof_followed_by_symbols() {
+  let schema = Schema::new("Foo").define("A", ch('a') & eof());
+
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "A", handler).unwrap();
+  parser.push('a').unwrap();
+  parser.finish().unwrap();
+  Events::new().begin("A").fragments("a").end().assert_eq(&events);
+
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "A", handler).unwrap();
+  parser.push('a').unwrap();
+  assert_unmatch(parser.push('b').and_then(|_| parser.finish()), location(1, 0, 1), "a", "[EOF]", "['b']...");
+}
+
+#[test]
+fn eof_only() {
+  let schema = Schema::new("Foo").define("A", eof());
+
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let parser = Context::new(&schema, "A", handler).unwrap();
+  parser.finish().unwrap();
+  Events::new().begin("A").end().assert_eq(&events);
+
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "A", handler).unwrap();
+  assert_unmatch(parser.push('a').and_then(|_| parser.finish()), location(0, 0, 0), "", "[EOF]", "['a']...");
+}
+
+#[test]
+fn repetition_over_symbols_looked_ahead_until_eof() {
+  // the lookahead doesn't resolve until the end of input, so the repetitions are evaluated all at once on finish
+  let lookahead = Syntax::from_fn("&ALL", |_: &[char]| Ok(MatchResult::MatchAndCanAcceptMore(0)));
+  let schema =
+    Schema::new("Foo").define("A", lookahead & (ch('a') * (1..)) & (((ch('-') * (0..=1)) & ch('b')) * (0..)));
+
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "A", handler).unwrap();
+  parser.push_str("aaab-bb").unwrap();
+  parser.finish().unwrap();
+  Events::new().begin("A").fragments("aaab-bb").end().assert_eq(&Event::normalize(&events));
+}
@@ -0,0 +1,19 @@
+//! The check that the concurrent evaluation of the paths delivers the same events and errors as the serial one.
+//!
+use crate::parser::test::{for_each_mutation, parse_chunks, Random};
+use crate::schema::Schema;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+/// Parse the mutations of each of the `samples` both concurrently and serially, and assert the same results.
+///
+pub(super) fn assert_equivalent<ID>(schema: &Schema<ID, char>, id: ID, samples: &[&str], alphabet: &str)
+where
+  ID: 'static + Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
+{
+  for_each_mutation(&mut Random::new(0x5EED), samples, alphabet, 64, |_, input| {
+    let concurrent = parse_chunks(schema, id.clone(), &[input], true);
+    let serial = parse_chunks(schema, id.clone(), &[input], false);
+    assert_eq!(serial, concurrent, "diverged for {:?}", input);
+  });
+}
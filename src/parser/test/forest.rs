@@ -0,0 +1,79 @@
+use crate::parser::test::{assert_unmatch, location, Events};
+use crate::parser::{Context, Engine, Event};
+use crate::schema::chars::{ascii_alphabetic, ascii_digit, ch};
+use crate::schema::{id, Schema};
+use crate::Error;
+
+#[test]
+fn forest_enumerates_ambiguous_parses() {
+  let schema = Schema::new("Foo")
+    .define("A", (id("X") | id("Y")) * (0..))
+    .define("X", ascii_alphabetic())
+    .define("Y", ascii_alphabetic() | ascii_digit());
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap().with_engine(Engine::Earley);
+  parser.push_str("a1b").unwrap();
+  let forest = parser.finish_forest().unwrap();
+  assert_eq!(4, forest.count());
+  assert!(forest.is_ambiguous());
+
+  let parses = forest.parses().collect::<Vec<_>>();
+  assert_eq!(4, parses.len());
+  for (i, parse) in parses.iter().enumerate() {
+    assert!(!parses[..i].contains(parse), "duplicate parse: {:?}", parse);
+  }
+  let expected = Events::new()
+    .begin("A")
+    .begin("X")
+    .fragments("a")
+    .end()
+    .begin("Y")
+    .fragments("1")
+    .end()
+    .begin("Y")
+    .fragments("b")
+    .end()
+    .end()
+    .to_vec();
+  assert!(parses.contains(&expected), "{:?}", parses);
+  assert_eq!(None, forest.events(4));
+}
+
+#[test]
+fn forest_unambiguous() {
+  let schema = Schema::new("Foo")
+    .define("EXPR", (id("EXPR") & ch('-') & id("NUM")) | id("NUM"))
+    .define("NUM", ascii_digit() * (1..));
+  let mut parser = Context::new(&schema, "EXPR", |_: &Event<_, _>| ()).unwrap().with_engine(Engine::Earley);
+  parser.push_str("9-12").unwrap();
+  let forest = parser.finish_forest().unwrap();
+  assert_eq!(1, forest.count());
+  assert!(!forest.is_ambiguous());
+  Events::new()
+    .begin("EXPR")
+    .begin("EXPR")
+    .begin("NUM")
+    .fragments("9")
+    .end()
+    .end()
+    .fragments("-")
+    .begin("NUM")
+    .fragments("12")
+    .end()
+    .end()
+    .assert_eq(&forest.events(0).unwrap());
+}
+
+#[test]
+fn forest_unmatch() {
+  let schema = Schema::new("Foo").define("A", ch('a'));
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap().with_engine(Engine::Earley);
+  parser.push_str("ab").unwrap();
+  assert_unmatch(parser.finish_forest(), location(1, 0, 1), "a", "[EOF]", "['b']...");
+}
+
+#[test]
+fn forest_requires_earley_engine() {
+  let schema = Schema::new("Foo").define("A", ch('a'));
+  let parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+  assert_eq!(Some(Error::Unsupported { operation: "forest", engine: Engine::Streaming }), parser.finish_forest().err());
+}
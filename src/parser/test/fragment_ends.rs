@@ -0,0 +1,76 @@
+use crate::parser::{Context, Engine, Event, EventKind, Ignore};
+use crate::schema::chars::{ascii_alphabetic, ascii_digit, ch, Location};
+use crate::schema::{id, Schema};
+
+fn schema() -> Schema<&'static str, char> {
+  Schema::new("Foo")
+    .define("LIST", ch('[') & id("ITEM") & ((ch(',') & id("ITEM")) * (0..)) & ch(']'))
+    .define("ITEM", id("KEY") & ch('=') & id("VALUE"))
+    .define("KEY", ascii_alphabetic() * (1..))
+    .define("VALUE", (ascii_digit() | ch('\n')) * (1..))
+}
+
+fn parse(text: &str, ids: &[&'static str], fragment_ends: bool) -> Vec<Vec<Event<&'static str, char>>> {
+  let schema = schema();
+  let mut results = Vec::new();
+  for engine in [Engine::Streaming, Engine::Earley] {
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| events.push(e.clone());
+    let mut parser = Context::new(&schema, "LIST", handler)
+      .unwrap()
+      .with_engine(engine)
+      .ignore_events_with(ids, Ignore::Subtree)
+      .with_fragment_ends(fragment_ends);
+    parser.push_str(text).unwrap();
+    parser.finish().unwrap();
+    results.push(events);
+  }
+  let mut parser = Context::new(&schema, "LIST", |_: &Event<_, _>| ())
+    .unwrap()
+    .with_engine(Engine::Earley)
+    .ignore_events_with(ids, Ignore::Subtree)
+    .with_fragment_ends(fragment_ends);
+  parser.push_str(text).unwrap();
+  results.push(parser.finish_forest().unwrap().events(0).unwrap());
+  results
+}
+
+fn ranges(events: &[Event<&'static str, char>]) -> Vec<(String, Location, Location)> {
+  events
+    .iter()
+    .filter_map(|e| match &e.kind {
+      EventKind::Fragments(items) => Some((items.iter().collect(), e.location, e.end.unwrap())),
+      _ => None,
+    })
+    .collect()
+}
+
+#[test]
+fn fragment_ends_disabled_by_default() {
+  for events in parse("[a=1]", &[], false) {
+    assert!(events.iter().all(|e| e.end.is_none()));
+  }
+}
+
+#[test]
+fn fragment_ends_of_each_fragments() {
+  let expected = vec![
+    ("[".to_string(), Location::new(0, 0, 0), Location::new(1, 0, 1)),
+    ("ab".to_string(), Location::new(1, 0, 1), Location::new(3, 0, 3)),
+    ("=".to_string(), Location::new(3, 0, 3), Location::new(4, 0, 4)),
+    ("1\n2".to_string(), Location::new(4, 0, 4), Location::new(7, 1, 1)),
+    ("]".to_string(), Location::new(7, 1, 1), Location::new(8, 1, 2)),
+  ];
+  for events in parse("[ab=1\n2]", &[], true) {
+    assert_eq!(expected, ranges(&events));
+    assert!(events.iter().all(|e| matches!(e.kind, EventKind::Fragments(_)) == e.end.is_some()));
+  }
+}
+
+#[test]
+fn fragment_ends_of_merged_fragments() {
+  let expected = vec![("[a=1,b=\n2]".to_string(), Location::new(0, 0, 0), Location::new(10, 1, 2))];
+  for events in parse("[a=1,b=\n2]", &["ITEM"], true) {
+    assert_eq!(expected, ranges(&events));
+  }
+}
@@ -0,0 +1,76 @@
+use crate::parser::test::{assert_unmatch, location};
+use crate::parser::{Context, Event, EventKind};
+use crate::schema::chars::{ascii_alphabetic, ascii_digit, ch};
+use crate::schema::{id, Schema};
+
+fn header() -> Schema<&'static str, char> {
+  Schema::new("Header")
+    .define("MESSAGE", id("HEADER") & (ascii_digit() * (0..)))
+    .define("HEADER", (ascii_alphabetic() * (1..)) & ch(':'))
+}
+
+fn body() -> Schema<usize, char> {
+  Schema::new("Body").define(1, (ascii_digit() * (1..)) & ch(';')).define(0, id(1) * (1..))
+}
+
+#[test]
+fn handoff_after_until_first() {
+  let (header, body) = (header(), body());
+  for chunks in [&["abc:12;3;"][..], &["abc:", "12;3;"][..], &["ab", "c:1", "2;", "3;"][..]] {
+    let mut headers = Vec::new();
+    let mut bodies = Vec::new();
+    let handler = |e: &Event<_, _>| headers.push(e.clone());
+    let mut parser = Context::new(&header, "MESSAGE", handler).unwrap().until_first("HEADER");
+    let mut chunks = chunks.iter();
+    while !parser.is_reached() {
+      parser.push_str(chunks.next().unwrap()).unwrap();
+    }
+    let handler = |e: &Event<_, _>| bodies.push(e.clone());
+    let mut parser = parser.handoff(&body, 0, handler).unwrap();
+    for chunk in chunks {
+      parser.push_str(chunk).unwrap();
+    }
+    parser.finish().unwrap();
+
+    assert_eq!(Some(&EventKind::End("HEADER")), headers.last().map(|e| &e.kind));
+    let bodies = Event::normalize(&bodies);
+    let begins = bodies.iter().filter(|e| e.kind == EventKind::Begin(1)).map(|e| e.location).collect::<Vec<_>>();
+    assert_eq!(vec![location(4, 0, 4), location(7, 0, 7)], begins);
+    assert_eq!(Some(&EventKind::End(0)), bodies.last().map(|e| &e.kind));
+  }
+}
+
+#[test]
+fn handoff_after_finish() {
+  let (header, body) = (header(), body());
+  let mut parser = Context::new(&header, "MESSAGE", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("abc:12").unwrap();
+  let mut parser = parser.handoff(&body, 0, |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("3;").unwrap();
+  parser.finish().unwrap();
+
+  let mut parser = Context::new(&header, "MESSAGE", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("abc").unwrap();
+  assert_unmatch(
+    parser.handoff(&body, 0, |_: &Event<_, _>| ()).map(|_| ()),
+    location(3, 0, 3),
+    "abc",
+    "[':']",
+    "[EOF]",
+  );
+}
+
+#[test]
+fn handoff_unmatch_in_next_schema() {
+  let (header, body) = (header(), body());
+  let mut parser = Context::new(&header, "MESSAGE", |_: &Event<_, _>| ()).unwrap().until_first("HEADER");
+  parser.push_str("abc:").unwrap();
+  let mut parser = parser.handoff(&body, 0, |_: &Event<_, _>| ()).unwrap();
+  assert_unmatch(
+    parser.push_str("1x").and_then(|_| parser.finish()).map(|_| ()),
+    location(5, 0, 5),
+    "...1",
+    "[';']",
+    "['x']...",
+  );
+}
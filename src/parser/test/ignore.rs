@@ -0,0 +1,109 @@
+use crate::parser::test::Events;
+use crate::parser::{Context, Engine, Event, EventKind, Ignore};
+use crate::schema::chars::{ascii_alphabetic, ascii_digit, ch};
+use crate::schema::{id, Schema};
+
+fn schema() -> Schema<&'static str, char> {
+  Schema::new("Foo")
+    .define("LIST", ch('[') & id("ITEM") & ((ch(',') & id("ITEM")) * (0..)) & ch(']'))
+    .define("ITEM", id("KEY") & ch('=') & id("VALUE"))
+    .define("KEY", ascii_alphabetic() * (1..))
+    .define("VALUE", ascii_digit() * (1..))
+    .define("EXPR", (id("EXPR") & ch('+') & id("VALUE")) | id("VALUE"))
+    .define("SUM", ch('[') & id("EXPR") & ch(']'))
+}
+
+fn parse(
+  schema: &Schema<&'static str, char>, id: &'static str, text: &str, ids: &[&'static str], ignore: Ignore,
+) -> Vec<Vec<Event<&'static str, char>>> {
+  let mut results = Vec::new();
+  for engine in [Engine::Streaming, Engine::Earley] {
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| events.push(e.clone());
+    let mut parser = Context::new(schema, id, handler).unwrap().with_engine(engine).ignore_events_with(ids, ignore);
+    parser.push_str(text).unwrap();
+    parser.finish().unwrap();
+    results.push(Event::normalize(&events));
+  }
+  let mut parser =
+    Context::new(schema, id, |_: &Event<_, _>| ()).unwrap().with_engine(Engine::Earley).ignore_events_with(ids, ignore);
+  parser.push_str(text).unwrap();
+  results.push(parser.finish_forest().unwrap().events(0).unwrap());
+  results
+}
+
+/// The kinds of the `events`, since the locations skip the dropped symbols.
+///
+fn kinds(events: &[Event<&'static str, char>]) -> Vec<EventKind<&'static str, char>> {
+  events.iter().map(|e| e.kind.clone()).collect()
+}
+
+fn list(fragments: &str) -> Vec<EventKind<&'static str, char>> {
+  vec![EventKind::Begin("LIST"), EventKind::Fragments(fragments.chars().collect()), EventKind::End("LIST")]
+}
+
+#[test]
+fn ignore_node_only() {
+  let schema = schema();
+  for events in parse(&schema, "LIST", "[a=1,b=2]", &["ITEM"], Ignore::Node) {
+    Events::new()
+      .begin("LIST")
+      .fragments("[")
+      .begin("KEY")
+      .fragments("a")
+      .end()
+      .fragments("=")
+      .begin("VALUE")
+      .fragments("1")
+      .end()
+      .fragments(",")
+      .begin("KEY")
+      .fragments("b")
+      .end()
+      .fragments("=")
+      .begin("VALUE")
+      .fragments("2")
+      .end()
+      .fragments("]")
+      .end()
+      .assert_eq(&events);
+  }
+}
+
+#[test]
+fn ignore_subtree() {
+  let schema = schema();
+  for events in parse(&schema, "LIST", "[a=1,b=2]", &["ITEM"], Ignore::Subtree) {
+    Events::new().begin("LIST").fragments("[a=1,b=2]").end().assert_eq(&events);
+  }
+  for events in parse(&schema, "SUM", "[1+2+3]", &["EXPR"], Ignore::Subtree) {
+    Events::new().begin("SUM").fragments("[1+2+3]").end().assert_eq(&events);
+  }
+}
+
+#[test]
+fn ignore_drop() {
+  let schema = schema();
+  for events in parse(&schema, "LIST", "[a=1,b=2]", &["ITEM"], Ignore::Drop) {
+    assert_eq!(list("[,]"), kinds(&events));
+  }
+  for events in parse(&schema, "SUM", "[1+2+3]", &["EXPR"], Ignore::Drop) {
+    let expected = vec![EventKind::Begin("SUM"), EventKind::Fragments(vec!['[', ']']), EventKind::End("SUM")];
+    assert_eq!(expected, kinds(&events));
+  }
+}
+
+#[test]
+fn ignore_modes_per_id() {
+  let schema = schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "LIST", handler)
+    .unwrap()
+    .ignore_events_with(&["KEY"], Ignore::Drop)
+    .ignore_events_with(&["ITEM"], Ignore::Node)
+    .ignore_events_for(&["VALUE"]);
+  parser.push_str("[a=1,b=2]").unwrap();
+  parser.finish().unwrap();
+  assert_eq!(list("[=1,=2]"), kinds(&events));
+}
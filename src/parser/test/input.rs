@@ -0,0 +1,233 @@
+use crate::parser::{Context, DecodedInput, Event, EventKind, InputError, Sampling, Utf8Decoder};
+use crate::schema::chars::{ascii_digit, ch, none_of_chars};
+use crate::schema::{range, single, Location, Schema};
+use crate::Error;
+use std::io::{self, Read};
+
+/// The decoder of a run-length encoding, where each pair of bytes is a count and a byte to repeat. It reads a pair at a
+/// time, so that the encoded offsets are exact.
+struct RunLength<R: Read>(R);
+
+impl<R: Read> Read for RunLength<R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let mut pair = [0u8; 2];
+    match self.0.read(&mut pair[..1])? {
+      0 => return Ok(0),
+      _ => self.0.read_exact(&mut pair[1..])?,
+    }
+    let n = std::cmp::min(pair[0] as usize, buf.len());
+    buf[..n].fill(pair[1]);
+    Ok(n)
+  }
+}
+
+#[test]
+fn decoded_input_bytes() {
+  let schema = Schema::new("Log").define("LOG", ((range(b'0'..=b'9') * (1..)) & single(b'\n')) * (0..));
+  let encoded = [3, b'1', 1, b'\n', 2, b'2', 1, b'\n'];
+  let mut input = DecodedInput::new(encoded.as_slice(), RunLength);
+  let mut lines = Vec::new();
+  let mut parser = Context::new(&schema, "LOG", |e: &Event<_, u8>| {
+    if let EventKind::Fragments(bytes) = &e.kind {
+      lines.push((e.location.0, bytes.clone()));
+    }
+  })
+  .unwrap();
+  input.push_to(&mut parser).unwrap();
+  parser.finish().unwrap();
+  let expected = [(0, &b"111"[..]), (3, b"\n"), (4, b"22"), (6, b"\n")].map(|(at, bytes)| (at, bytes.to_vec()));
+  assert_eq!(expected.to_vec(), lines);
+  assert_eq!((8, 7), input.offsets());
+  let offsets = [0, 2, 3, 4, 6, 7, 100].map(|decoded| input.encoded_offset(decoded));
+  assert_eq!([2, 2, 4, 6, 8, 8, 8], offsets);
+}
+
+#[test]
+fn decoded_input_text() {
+  let schema = Schema::new("Log").define("LOG", ((none_of_chars("\n") * (1..)) & ch('\n')) * (0..));
+  // é is split between the reads
+  let encoded = [1, b'a', 1, 0xC3, 1, 0xA9, 1, b'\n'];
+  let mut input = DecodedInput::new(encoded.as_slice(), RunLength);
+  let mut text = String::new();
+  let mut parser = Context::new(&schema, "LOG", |e: &Event<_, char>| {
+    if let EventKind::Fragments(chars) = &e.kind {
+      text.extend(chars);
+    }
+  })
+  .unwrap();
+  input.push_text_to(&mut parser).unwrap();
+  parser.finish().unwrap();
+  assert_eq!("aé\n", text);
+  assert_eq!((8, 4), input.offsets());
+}
+
+#[test]
+fn decoded_input_errors() {
+  let schema = Schema::new("Log").define("LOG", ((ascii_digit() * (1..)) & ch('\n')) * (0..));
+  let push = |encoded: &[u8]| {
+    let mut input = DecodedInput::new(encoded, RunLength);
+    // the unmatch is returned as soon as it's pushed, without waiting for the symbols following it
+    let sampling = Sampling::of::<char>().with_lengths(12, 0);
+    let mut parser = Context::new(&schema, "LOG", |_: &Event<_, char>| ()).unwrap().sampling(sampling);
+    input.push_text_to(&mut parser)
+  };
+
+  // the unmatched character is located in both coordinates
+  match push(&[1, b'1', 1, b'2', 1, b'\n', 1, b'x', 1, b'\n']) {
+    Err(InputError::Parse { encoded: 8, error: Error::Unmatched(detail) }) => {
+      assert_eq!(Some(3), detail.location.byte_offset());
+    }
+    result => panic!("{:?}", result),
+  }
+  let error = push(&[1, b'x']).unwrap_err();
+  assert!(error.to_string().ends_with(" (at 2 bytes of the encoded input)"), "{}", error);
+
+  // a broken stream, an invalid or an incomplete UTF-8
+  for (encoded, expected_offset, expected_kind) in [
+    (&[1, b'1', 1][..], 3, io::ErrorKind::UnexpectedEof),
+    (&[1, b'1', 1, 0xFF][..], 4, io::ErrorKind::InvalidData),
+    (&[1, b'1', 1, 0xC3][..], 4, io::ErrorKind::InvalidData),
+  ] {
+    match push(encoded) {
+      Err(InputError::Io { encoded, source }) => {
+        assert_eq!((expected_offset, expected_kind), (encoded, source.kind()));
+      }
+      result => panic!("{:?}", result),
+    }
+  }
+}
+
+#[test]
+fn parse_path_text() {
+  let schema = Schema::new("Log").define("LOG", ((none_of_chars("\n") * (1..)) & ch('\n')) * (0..));
+  let text = "12\né😀\nabc\n";
+  let file = TempFile::new("parse_path_text", text.as_bytes());
+  let mut expected = Vec::new();
+  let mut parser = Context::new(&schema, "LOG", |e: &Event<_, char>| expected.push(e.clone())).unwrap();
+  parser.push_str(text).unwrap();
+  parser.finish().unwrap();
+
+  let mut events = Vec::new();
+  let mut parser = Context::new(&schema, "LOG", |e: &Event<_, char>| events.push(e.clone())).unwrap();
+  parser.parse_path(&file.0).unwrap();
+  parser.finish().unwrap();
+  assert_eq!(Event::normalize(&expected), Event::normalize(&events));
+
+  // a parse error, a text that isn't UTF-8, an empty file and a missing file
+  let error = |bytes: &[u8]| {
+    let file = TempFile::new("parse_path_error", bytes);
+    let sampling = Sampling::of::<char>().with_lengths(12, 0);
+    let mut parser = Context::new(&schema, "LOG", |_: &Event<_, char>| ()).unwrap().sampling(sampling);
+    parser.parse_path(&file.0).and_then(|_| parser.finish().map(|_| ()).map_err(|e| io::Error::other(e.to_string())))
+  };
+  let e = error(b"1\n\n").unwrap_err();
+  assert_eq!(io::ErrorKind::InvalidData, e.kind());
+  assert!(matches!(e.get_ref().unwrap().downcast_ref::<Error<char>>(), Some(Error::Unmatched(_))), "{:?}", e);
+  assert_eq!("the text isn't UTF-8 at 2 bytes", error(b"1\n\xFF\n").unwrap_err().to_string());
+  assert_eq!("the text isn't UTF-8 at 2 bytes", error(b"1\n\xE3\x81").unwrap_err().to_string());
+  assert!(error(b"").is_ok());
+  let mut parser = Context::new(&schema, "LOG", |_: &Event<_, char>| ()).unwrap();
+  assert_eq!(
+    io::ErrorKind::NotFound,
+    parser.parse_path(std::env::temp_dir().join("terp-not-found")).unwrap_err().kind()
+  );
+}
+
+#[test]
+fn parse_path_bytes() {
+  let schema = Schema::new("Log").define("LOG", ((range(b'0'..=b'9') * (1..)) & single(b'\n')) * (0..));
+  let file = TempFile::new("parse_path_bytes", b"1\n23\n");
+  let mut expected = Vec::new();
+  let mut parser = Context::new(&schema, "LOG", |e: &Event<_, u8>| expected.push(e.clone())).unwrap();
+  parser.push_seq(b"1\n23\n").unwrap();
+  parser.finish().unwrap();
+
+  let mut events = Vec::new();
+  let mut parser = Context::new(&schema, "LOG", |e: &Event<_, u8>| events.push(e.clone())).unwrap();
+  parser.parse_path(&file.0).unwrap();
+  parser.finish().unwrap();
+  assert_eq!(Event::normalize(&expected), Event::normalize(&events));
+}
+
+#[cfg(all(feature = "mmap", unix))]
+#[test]
+fn parse_path_mmap() {
+  let schema = Schema::new("Log").define("LOG", ((none_of_chars("\n") * (1..)) & ch('\n')) * (0..));
+  let text = "12\né😀\nabc\n";
+  let file = TempFile::new("parse_path_mmap", text.as_bytes());
+  let mut expected = Vec::new();
+  let mut parser = Context::new(&schema, "LOG", |e: &Event<_, char>| expected.push(e.clone())).unwrap();
+  parser.parse_path(&file.0).unwrap();
+  parser.finish().unwrap();
+
+  let mut events = Vec::new();
+  let mut parser = Context::new(&schema, "LOG", |e: &Event<_, char>| events.push(e.clone())).unwrap();
+  // SAFETY: the temporary file isn't modified while it's parsed
+  unsafe { parser.parse_path_mmap(&file.0) }.unwrap();
+  parser.finish().unwrap();
+  assert_eq!(Event::normalize(&expected), Event::normalize(&events));
+
+  // the bytes, an empty file that isn't mapped, and a missing file
+  let schema = Schema::new("Log").define("LOG", ((range(b'0'..=b'9') * (1..)) & single(b'\n')) * (0..));
+  for (name, bytes) in [("parse_path_mmap_bytes", &b"1\n23\n"[..]), ("parse_path_mmap_empty", b"")] {
+    let file = TempFile::new(name, bytes);
+    let mut expected = Vec::new();
+    let mut parser = Context::new(&schema, "LOG", |e: &Event<_, u8>| expected.push(e.clone())).unwrap();
+    parser.push_seq(bytes).unwrap();
+    parser.finish().unwrap();
+
+    let mut events = Vec::new();
+    let mut parser = Context::new(&schema, "LOG", |e: &Event<_, u8>| events.push(e.clone())).unwrap();
+    // SAFETY: the temporary file isn't modified while it's parsed
+    unsafe { parser.parse_path_mmap(&file.0) }.unwrap();
+    parser.finish().unwrap();
+    assert_eq!(Event::normalize(&expected), Event::normalize(&events), "{}", name);
+  }
+  let mut parser = Context::new(&schema, "LOG", |_: &Event<_, u8>| ()).unwrap();
+  let missing = std::env::temp_dir().join("terp-not-found");
+  assert_eq!(io::ErrorKind::NotFound, unsafe { parser.parse_path_mmap(missing) }.unwrap_err().kind());
+}
+
+#[test]
+fn utf8_decoder() {
+  let text = "aé😀\u{7FF}z";
+  let bytes = text.as_bytes();
+  for split in 0..=bytes.len() {
+    let mut decoder = Utf8Decoder::default();
+    let mut decoded = String::new();
+    for chunk in [&bytes[..split], &bytes[split..]] {
+      decoder.decode(chunk).unwrap();
+      decoded.extend(decoder.chars());
+    }
+    decoder.finish().unwrap();
+    assert_eq!(text, decoded, "{}", split);
+  }
+
+  // the offsets of the invalid sequences, including one split between the chunks
+  let mut decoder = Utf8Decoder::default();
+  decoder.decode(b"ab\xF0\x9F").unwrap();
+  assert_eq!(Err(2), decoder.decode(b"x"));
+  let mut decoder = Utf8Decoder::default();
+  decoder.decode(b"ab").unwrap();
+  assert_eq!(Err(5), decoder.decode(b"c\xC3\xA9\xFF"));
+  let mut decoder = Utf8Decoder::default();
+  decoder.decode(b"ab\xC3").unwrap();
+  assert_eq!(Err(2), decoder.finish());
+}
+
+/// The file in the temporary directory, which is removed when dropped.
+struct TempFile(std::path::PathBuf);
+
+impl TempFile {
+  fn new(name: &str, content: &[u8]) -> Self {
+    let path = std::env::temp_dir().join(format!("terp-{}-{}", std::process::id(), name));
+    std::fs::write(&path, content).unwrap();
+    Self(path)
+  }
+}
+
+impl Drop for TempFile {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_file(&self.0);
+  }
+}
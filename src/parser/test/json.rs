@@ -298,7 +298,6 @@ where
 fn files(prefix: &str, suffixes: &[&str]) -> HashMap<String, PathBuf> {
   fs::read_dir(Path::new("src").join("parser").join("test").join("data"))
     .unwrap()
-    .into_iter()
     .filter_map(|path| {
       let path = path.unwrap().path();
       if path.is_file() {
@@ -0,0 +1,133 @@
+use crate::parser::test::{assert_unmatch, location, Events};
+use crate::parser::{Context, Engine, Event};
+use crate::schema::chars::{ascii_digit, ch};
+use crate::schema::{id, Schema};
+
+fn schema() -> Schema<&'static str, char> {
+  Schema::new("Foo")
+    .define("EXPR", (id("EXPR") & ch('+') & id("NUM")) | (id("EXPR") & ch('-') & id("NUM")) | id("NUM"))
+    .define("NUM", ascii_digit() * (1..))
+}
+
+#[test]
+fn left_recursion_nests_to_the_left() {
+  let schema = schema();
+  let expected = Events::new()
+    .begin("EXPR")
+    .begin("EXPR")
+    .begin("EXPR")
+    .begin("NUM")
+    .fragments("9")
+    .end()
+    .end()
+    .fragments("-")
+    .begin("NUM")
+    .fragments("12")
+    .end()
+    .end()
+    .fragments("+")
+    .begin("NUM")
+    .fragments("3")
+    .end()
+    .end();
+
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "EXPR", handler).unwrap();
+  parser.push_str("9-12+3").unwrap();
+  parser.finish().unwrap();
+  expected.assert_eq(&events);
+
+  // the events are delivered the same even if pushed one by one
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "EXPR", handler).unwrap();
+  for ch in "9-12+3".chars() {
+    parser.push(ch).unwrap();
+  }
+  parser.finish().unwrap();
+  expected.assert_eq(&events);
+}
+
+#[test]
+fn left_recursion_same_events_as_earley() {
+  let schema = schema();
+  for sample in ["1", "1+2", "10-20+30-40", "1+2+3+4+5+6+7+8+9"] {
+    let mut expected = Vec::new();
+    let handler = |e: &Event<_, _>| expected.push(e.clone());
+    let mut parser = Context::new(&schema, "EXPR", handler).unwrap().with_engine(Engine::Earley);
+    parser.push_str(sample).unwrap();
+    parser.finish().unwrap();
+
+    let mut actual = Vec::new();
+    let handler = |e: &Event<_, _>| actual.push(e.clone());
+    let mut parser = Context::new(&schema, "EXPR", handler).unwrap();
+    parser.push_str(sample).unwrap();
+    parser.finish().unwrap();
+    assert_eq!(Event::normalize(&expected), Event::normalize(&actual), "{}", sample);
+  }
+}
+
+#[test]
+fn left_recursion_within_other_syntax() {
+  let schema = schema().define("LIST", ch('[') & id("EXPR") & ((ch(',') & id("EXPR")) * (0..)) & ch(']'));
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "LIST", handler).unwrap().ignore_events_for(&["NUM"]);
+  parser.push_str("[1-2,3]").unwrap();
+  parser.finish().unwrap();
+  Events::new()
+    .begin("LIST")
+    .fragments("[")
+    .begin("EXPR")
+    .begin("EXPR")
+    .fragments("1")
+    .end()
+    .fragments("-2")
+    .end()
+    .fragments(",")
+    .begin("EXPR")
+    .fragments("3")
+    .end()
+    .fragments("]")
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn left_recursion_ignored() {
+  let schema = schema().define("LIST", ch('[') & id("EXPR") & ch(']'));
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "LIST", handler).unwrap().ignore_events_for(&["EXPR"]);
+  parser.push_str("[1+2-3]").unwrap();
+  parser.finish().unwrap();
+  Events::new()
+    .begin("LIST")
+    .fragments("[")
+    .begin("NUM")
+    .fragments("1")
+    .end()
+    .fragments("+")
+    .begin("NUM")
+    .fragments("2")
+    .end()
+    .fragments("-")
+    .begin("NUM")
+    .fragments("3")
+    .end()
+    .fragments("]")
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn left_recursion_unmatch() {
+  let schema = schema();
+  let mut parser = Context::new(&schema, "EXPR", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("1+2*3").and_then(|_| parser.finish()).unwrap_err();
+
+  let mut parser = Context::new(&schema, "EXPR", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("1+").unwrap();
+  assert_unmatch(parser.finish(), location(2, 0, 2), "1+", "[ASCII_DIGIT+]", "[EOF]");
+}
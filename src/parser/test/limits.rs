@@ -0,0 +1,138 @@
+use crate::parser::test::Events;
+use crate::parser::{Context, Event, Limits};
+use crate::schema::chars::{ascii_alphabetic, ch, line, none_of_chars, Location};
+use crate::schema::{id, Schema};
+use crate::Error;
+
+fn limits() -> Limits {
+  Limits { max_depth: 16, max_paths: 16, max_buffer_size: 16, max_pending_events: 16, max_fragment_length: 32 }
+}
+
+fn assert_limit_exceeded<T>(r: crate::Result<char, T>, name: &str) {
+  match r {
+    Err(Error::LimitExceeded { name: actual, .. }) => assert_eq!(name, actual),
+    Err(err) => panic!("{:?}", err),
+    Ok(_) => panic!("limit {} was not exceeded", name),
+  }
+}
+
+#[test]
+fn limits_max_depth() {
+  let schema = Schema::new("Foo").define("P", ch('(') & (id("P") * (0..=1)) & ch(')'));
+
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "P", handler).unwrap().limits(limits());
+  parser.push_str("((()))").unwrap();
+  parser.finish().unwrap();
+  Events::new()
+    .begin("P")
+    .fragments("(")
+    .begin("P")
+    .fragments("(")
+    .begin("P")
+    .fragments("()")
+    .end()
+    .fragments(")")
+    .end()
+    .fragments(")")
+    .end()
+    .assert_eq(&events);
+
+  let mut parser = Context::new(&schema, "P", |_: &Event<_, _>| ()).unwrap().limits(limits());
+  assert_limit_exceeded(parser.push_str(&"(".repeat(32)), "max_depth");
+  assert_eq!(Err(Error::Previous), parser.push('('));
+}
+
+#[test]
+fn limits_max_depth_of_left_recursion() {
+  // the indirect left recursion never reaches a term, but the hardened context stops it
+  let schema = Schema::new("Foo").define("A", (id("B") & ch('x')) | ch('x')).define("B", id("A"));
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap().hardened();
+  assert_limit_exceeded(parser.push('x'), "max_depth");
+}
+
+#[test]
+fn limits_max_paths() {
+  let schema = Schema::new("Foo").define("A", (id("X") | id("Y")) * (0..)).define("X", ch('a')).define("Y", ch('a'));
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap().limits(limits());
+  parser.push_str("aaa").unwrap();
+  assert_limit_exceeded(parser.push_str("aa"), "max_paths");
+}
+
+#[test]
+fn limits_max_buffer_size() {
+  let schema = Schema::new("Foo").define("A", line());
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap().limits(limits());
+  parser.push_str(&"a".repeat(16)).unwrap();
+  assert_limit_exceeded(parser.push('a'), "max_buffer_size");
+}
+
+#[test]
+fn limits_max_pending_events() {
+  let schema = Schema::new("Foo")
+    .define("A", ((id("X") * (0..)) & ch('b')) | ((id("Y") * (0..)) & ch('c')))
+    .define("X", ch('a'))
+    .define("Y", ch('a'));
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap().limits(limits());
+  assert_limit_exceeded(parser.push_str(&"a".repeat(16)), "max_pending_events");
+}
+
+#[test]
+fn limits_hardened() {
+  let schema = crate::schema::json::schema();
+  let mut parser = Context::new(&schema, crate::schema::json::ID::JsonText, |_: &Event<_, _>| ()).unwrap().hardened();
+  parser.push_str(crate::parser::test::json::SAMPLE_WIKIPEDIA).unwrap();
+  parser.finish().unwrap();
+}
+
+#[test]
+fn limits_max_fragment_length() {
+  let schema = Schema::new("Foo").define("A", line());
+  let limits = limits().with_max_buffer_size(64);
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap().limits(limits);
+  parser.push_str(&"a".repeat(32)).unwrap();
+  assert_limit_exceeded(parser.push('a'), "max_fragment_length");
+}
+
+#[test]
+fn limit_length_of_rule() {
+  let schema = Schema::new("Foo")
+    .define("A", id("S") * (0..))
+    .define("S", ch('"') & (none_of_chars("\"") * (0..)) & ch('"'))
+    .limit_length("S", 8);
+
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("\"abcdef\"\"\"").unwrap();
+  parser.finish().unwrap();
+
+  // the unterminated string is rejected before the end of the input, although each character is a separate fragment
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("\"ab\"\"abcdef").unwrap();
+  parser.push('g').unwrap();
+  let error = parser.push('h').unwrap_err();
+  assert_eq!(Error::TooLong { location: Location::new(4, 0, 4), id: String::from("S"), limit: 8 }, error);
+  assert_eq!("(1,5) S exceeded the maximum length of 8 symbols", error.to_string());
+  assert_eq!(Err(Error::Previous), parser.push('"'));
+}
+
+#[test]
+fn limit_length_with_surviving_alternative() {
+  let schema = Schema::new("Foo")
+    .define("A", id("S") | id("T"))
+    .define("S", ch('"') & (ascii_alphabetic() * (0..)))
+    .define("T", ch('"') & (ascii_alphabetic() * (0..)) & ch('!'))
+    .limit_length("S", 4);
+
+  // S exceeds its length, but T survives
+  let mut events = Vec::new();
+  let mut parser = Context::new(&schema, "A", |e: &Event<_, _>| events.push(e.clone())).unwrap();
+  parser.push_str("\"abcdefg!").unwrap();
+  parser.finish().unwrap();
+  Events::new().begin("A").begin("T").fragments("\"abcdefg!").end().end().assert_eq(&events);
+
+  // T fails farther than S exceeds its length
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("\"abcdefg").unwrap();
+  assert!(matches!(parser.finish(), Err(Error::Unmatched(_))));
+}
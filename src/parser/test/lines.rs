@@ -0,0 +1,73 @@
+use crate::parser::test::{assert_unmatch, location, Events};
+use crate::parser::{Context, Engine, Event, EventKind};
+use crate::schema::chars::{self, ascii_digit, ch, line, newline, Newline};
+use crate::schema::{id, Schema};
+
+#[test]
+fn line_oriented_log() {
+  let schema = Schema::new("Log")
+    .define("LOG", id("ENTRY") * (0..))
+    .define("ENTRY", id("LEVEL") & ch(' ') & id("MESSAGE") & newline())
+    .define("LEVEL", ascii_digit())
+    .define("MESSAGE", line());
+
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "LOG", handler).unwrap();
+  parser.push_lines(["1 foo", "2 bar baz", "3 "]).unwrap();
+  parser.finish().unwrap();
+  let mut expected = Events::new().begin("LOG");
+  for (level, message) in [("1", "foo"), ("2", "bar baz"), ("3", "")] {
+    expected = expected.begin("ENTRY").begin("LEVEL").fragments(level).end().fragments(" ");
+    expected = expected.begin("MESSAGE").fragments(message).end().fragments("\n").end();
+  }
+  expected.end().assert_eq(&events);
+}
+
+#[test]
+fn push_line_with_line_breaks() {
+  let schema = Schema::new("Log").define("LOG", (line() & newline()) * (0..));
+
+  // a line that already has a line break isn't given another one
+  for lines in [vec!["foo\n", "bar"], vec!["foo\r\n", "bar\r"], vec!["foo\r", "bar\n"]] {
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| events.push(e.clone());
+    let mut parser = Context::new(&schema, "LOG", handler).unwrap();
+    parser.push_lines(&lines).unwrap();
+    parser.finish().unwrap();
+    let text = lines.iter().map(|l| if l.ends_with(['\n', '\r']) { l.to_string() } else { format!("{}\n", l) });
+    Events::new().begin("LOG").fragments(&text.collect::<String>()).end().assert_eq(&events);
+  }
+}
+
+#[test]
+fn line_without_line_break_at_eof() {
+  let schema = Schema::new("Log").define("LOG", line() & newline());
+
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "LOG", handler).unwrap();
+  parser.push_str("foo").unwrap();
+  assert_unmatch(parser.finish(), location(3, 0, 3), "foo", "[NEWLINE]", "[EOF]");
+}
+
+#[test]
+fn line_locations_by_newline_convention() {
+  let schema = Schema::new("Log").define("LOG", (id("LINE") & newline()) * (0..)).define("LINE", line());
+  for (newline, expected) in [
+    (Newline::Any, [location(0, 0, 0), location(3, 1, 0), location(7, 2, 0)]),
+    (Newline::Lf, [location(0, 0, 0), location(3, 0, 0), location(7, 1, 0)]),
+    (Newline::Crlf, [location(0, 0, 0), location(3, 0, 3), location(7, 1, 0)]),
+  ] {
+    for engine in [Engine::Streaming, Engine::Earley] {
+      let mut events = Vec::new();
+      let handler = |e: &Event<_, _>| events.push(e.clone());
+      let origin = chars::Location::default().with_newline(newline);
+      let mut parser = Context::new_at(&schema, "LOG", handler, origin).unwrap().with_engine(engine);
+      parser.push_str("ab\rcd\r\nef\n").unwrap();
+      parser.finish().unwrap();
+      let begins = events.iter().filter(|e| e.kind == EventKind::Begin("LINE")).map(|e| e.location).collect::<Vec<_>>();
+      assert_eq!(&expected[..], &begins[..], "{:?}", newline);
+    }
+  }
+}
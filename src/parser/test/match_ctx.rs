@@ -0,0 +1,123 @@
+use crate::parser::test::Events;
+use crate::parser::{Context, Engine, Event};
+use crate::schema::chars::ch;
+use crate::schema::{id, Location, MatchCtx, MatchResult, Schema, Syntax};
+use crate::Result;
+use std::sync::{Arc, Mutex};
+
+/// A term that matches a single character only at the even or odd position of the input.
+///
+fn parity(label: &str, even: bool) -> Syntax<&'static str, char> {
+  Syntax::from_fn_ctx(label, move |values: &[char], ctx: &MatchCtx<char>| -> Result<char, MatchResult> {
+    assert_eq!(ctx.location.position(), ctx.absolute_offset);
+    Ok(match values.first() {
+      None => MatchResult::UnmatchAndCanAcceptMore,
+      Some(_) if ctx.absolute_offset.is_multiple_of(2) == even => MatchResult::Match(1),
+      Some(_) => MatchResult::Unmatch,
+    })
+  })
+}
+
+#[test]
+fn match_ctx_absolute_offset() {
+  let schema = Schema::new("Foo")
+    .define("A", (id("EVEN") | id("ODD")) * (0..))
+    .define("EVEN", parity("EVEN", true))
+    .define("ODD", parity("ODD", false));
+  for engine in [Engine::Streaming, Engine::Earley] {
+    for chunk in 1..=4 {
+      let mut events = Vec::new();
+      let handler = |e: &Event<_, _>| events.push(e.clone());
+      let mut parser = Context::new(&schema, "A", handler).unwrap().with_engine(engine);
+      for part in ['a', 'b', 'c', 'd', 'e'].chunks(chunk) {
+        parser.push_seq(part).unwrap();
+      }
+      parser.finish().unwrap();
+      let mut expected = Events::new().begin("A");
+      for (i, ch) in "abcde".chars().enumerate() {
+        expected = expected.begin(if i % 2 == 0 { "EVEN" } else { "ODD" }).fragments(&ch.to_string()).end();
+      }
+      expected.end().assert_eq(&Event::normalize(&events));
+    }
+  }
+}
+
+#[test]
+fn match_ctx_eof() {
+  // the rest of the input that is confirmed only when the input ends
+  let rest = Syntax::from_fn_ctx("REST", |values: &[char], ctx: &MatchCtx<char>| -> Result<char, MatchResult> {
+    Ok(if ctx.eof { MatchResult::Match(values.len()) } else { MatchResult::UnmatchAndCanAcceptMore })
+  });
+  let schema = Schema::new("Foo").define("A", ch('>') & id("REST")).define("REST", rest);
+  for engine in [Engine::Streaming, Engine::Earley] {
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| events.push(e.clone());
+    let mut parser = Context::new(&schema, "A", handler).unwrap().with_engine(engine);
+    parser.push_str(">abc").unwrap();
+    parser.push_str(">def").unwrap();
+    parser.finish().unwrap();
+    Events::new().begin("A").fragments(">").begin("REST").fragments("abc>def").end().end().assert_eq(&events);
+  }
+}
+
+#[test]
+fn match_ctx_partial_resumes() {
+  // a literal that reports the prefix matched so far, and records where each evaluation resumes
+  let resumed = Arc::new(Mutex::new(Vec::new()));
+  let r = resumed.clone();
+  let literal =
+    Syntax::from_fn_ctx("ABCD", move |values: &[char], ctx: &MatchCtx<char>| -> Result<char, MatchResult> {
+      r.lock().unwrap().push(ctx.matched);
+      let literal = ['a', 'b', 'c', 'd'];
+      let length = std::cmp::min(literal.len(), values.len());
+      Ok(if values[..length] != literal[..length] {
+        MatchResult::Unmatch
+      } else if length < literal.len() {
+        MatchResult::Partial(length)
+      } else {
+        MatchResult::Match(length)
+      })
+    });
+  let schema = Schema::new("Foo").define("A", id("LITERAL") & ch(';')).define("LITERAL", literal);
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "A", handler).unwrap();
+  for ch in "abcd;".chars() {
+    parser.push(ch).unwrap();
+  }
+  parser.finish().unwrap();
+  Events::new().begin("A").begin("LITERAL").fragments("abcd").end().fragments(";").end().assert_eq(&events);
+  assert_eq!(vec![0, 1, 2, 3], *resumed.lock().unwrap());
+
+  // the partial match is unmatched at the end of the input
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("abc").unwrap();
+  assert!(parser.finish().is_err());
+}
+
+#[test]
+fn match_ctx_window() {
+  // a term that sees at most 2 symbols, however many are buffered
+  let seen = Arc::new(Mutex::new(0));
+  let s = seen.clone();
+  let pair = Syntax::from_fn("PAIR", move |values: &[char]| -> Result<char, MatchResult> {
+    let mut seen = s.lock().unwrap();
+    *seen = std::cmp::max(*seen, values.len());
+    Ok(if values.len() < 2 { MatchResult::UnmatchAndCanAcceptMore } else { MatchResult::Match(2) })
+  })
+  .with_window(2);
+  let schema = Schema::new("Foo").define("A", id("PAIR") * (1..)).define("PAIR", pair);
+  for engine in [Engine::Streaming, Engine::Earley] {
+    *seen.lock().unwrap() = 0;
+    let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap().with_engine(engine);
+    parser.push_str("abcdefgh").unwrap();
+    parser.finish().unwrap();
+    assert_eq!(2, *seen.lock().unwrap());
+  }
+}
+
+#[test]
+#[should_panic]
+fn match_ctx_window_for_non_term() {
+  let _ = (ch::<&str>('a') & ch('b')).with_window(2);
+}
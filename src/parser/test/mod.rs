@@ -1,44 +1,74 @@
 use itertools::Itertools;
 
+pub(crate) use crate::parser::testing::Events;
 use crate::parser::{
-  create_unmatched_label_actual, create_unmatched_label_prefix, Context, Event, EventBuffer, EventKind,
+  create_unmatched_label_actual, create_unmatched_label_prefix, Context, Event, EventBuffer, EventKind, Pairing,
+  Sampling,
 };
 use crate::schema::chars::{self, ascii_alphabetic, ascii_digit, ch, one_of_chars, one_of_tokens, token};
-use crate::schema::{id, Location, Schema, Syntax};
-use crate::{Error, Result};
+use crate::schema::{eof, id, Location, Schema, Syntax};
+use crate::{Error, ErrorDetail, Result};
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 
+#[cfg(not(debug_assertions))]
+mod allocations;
+mod channel;
+mod chunked;
+mod chunking;
 mod context_free_grammer;
+mod corpus;
+mod earley;
+mod eof;
+#[cfg(feature = "concurrent")]
+mod equivalence;
+mod forest;
+mod fragment_ends;
+mod handoff;
+mod ignore;
+mod input;
 mod json;
+mod left_recursion;
+mod limits;
+mod lines;
+mod match_ctx;
 mod or;
+mod passthrough;
+mod profile;
+mod recognizer;
+mod record;
+mod recovery;
+mod sampling;
+mod sensitive;
+mod subscription;
+mod summary;
+#[cfg(feature = "tracing")]
+mod trace;
+mod try_handler;
+mod unread;
+mod until_first;
 mod user_guide;
 mod zero_repetition;
 
 #[test]
 fn event() {
   let location = chars::Location::default();
-  for kind in
-    vec![EventKind::Begin("FOO"), EventKind::End("BAR"), EventKind::Fragments("XYZ".chars().collect::<Vec<_>>())]
+  for kind in [EventKind::Begin("FOO"), EventKind::End("BAR"), EventKind::Fragments("XYZ".chars().collect::<Vec<_>>())]
   {
-    let event = Event { location, kind };
+    let event = Event { location, kind, end: None };
     assert_eq!(event, event.clone());
     let _ = format!("{:?}", event);
   }
 }
 
 #[test]
-#[should_panic]
-#[cfg(debug_assertions)]
-fn event_buffer_inconsist_begin_end() {
-  let location = chars::Location::default();
-  let mut events = EventBuffer::new(1);
-  for kind in
-    vec![EventKind::Begin("FOO"), EventKind::Fragments("XYZ".chars().collect::<Vec<_>>()), EventKind::End("BAR")]
-  {
-    let event = Event { location, kind };
-    events.push(event);
+fn pairing_inconsist_begin_end() {
+  let mut pairing = Pairing::new();
+  for kind in [EventKind::Begin("FOO"), EventKind::Fragments("XYZ".chars().collect::<Vec<_>>())] {
+    assert!(pairing.verify(&kind));
   }
+  assert!(!pairing.verify(&EventKind::<_, char>::End("BAR")));
+  assert_eq!(Some("inconsistent event: End(FOO) expected, but End(BAR) appeared"), pairing.violation());
 }
 
 #[test]
@@ -48,33 +78,35 @@ fn event_buffer_equivalence_when_diffferent_event() {
   location2.increment_with('\n');
   let mut events1 = EventBuffer::new(1);
   let mut events2 = EventBuffer::new(1);
-  for kind in
-    vec![EventKind::Begin("FOO"), EventKind::Fragments("XYZ".chars().collect::<Vec<_>>()), EventKind::End("FOO")]
+  for kind in [EventKind::Begin("FOO"), EventKind::Fragments("XYZ".chars().collect::<Vec<_>>()), EventKind::End("FOO")]
   {
-    events1.push(Event { location: location1, kind: kind.clone() });
-    events2.push(Event { location: location2, kind: kind.clone() });
+    events1.push(Event { location: location1, kind: kind.clone(), end: None });
+    events2.push(Event { location: location2, kind: kind.clone(), end: None });
   }
   assert_ne!(events1, events2);
 }
 
 #[test]
-#[cfg(debug_assertions)]
-#[should_panic]
-fn event_buffer_inconsistent_stack() {
-  let location = chars::Location::default();
-  let mut buffer = EventBuffer::new(10);
-  buffer.push(Event { location, kind: EventKind::Begin("A") });
-  buffer.push(Event { location, kind: EventKind::Fragments(vec!['x']) });
-  buffer.push(Event { location, kind: EventKind::End("B") });
+fn pairing_inconsistent_stack() {
+  let mut pairing = Pairing::new();
+  assert!(pairing.verify(&EventKind::<_, char>::Begin("A")));
+  assert!(pairing.verify(&EventKind::<_, char>::Begin("B")));
+  assert!(pairing.verify(&EventKind::<_, char>::End("B")));
+  assert_eq!(None, pairing.violation());
+  assert!(pairing.verify(&EventKind::Fragments(vec!['x'])));
+  assert!(!pairing.verify(&EventKind::<_, char>::End("B")));
+  assert_eq!(Some("inconsistent event: End(A) expected, but End(B) appeared"), pairing.violation());
+
+  // only the first violation is retained
+  assert!(!pairing.verify(&EventKind::<_, char>::End("A")));
+  assert_eq!(Some("inconsistent event: End(A) expected, but End(B) appeared"), pairing.violation());
 }
 
 #[test]
-#[cfg(debug_assertions)]
-#[should_panic]
-fn event_buffer_unexpected_end_event() {
-  let location = chars::Location::default();
-  let mut buffer = EventBuffer::<_, char>::new(10);
-  buffer.push(Event { location, kind: EventKind::End("A") });
+fn pairing_unexpected_end_event() {
+  let mut pairing = Pairing::new();
+  assert!(!pairing.verify(&EventKind::<_, char>::End("A")));
+  assert_eq!(Some("inconsistent event: End(A) appeared without Begin"), pairing.violation());
 }
 
 #[test]
@@ -104,7 +136,7 @@ fn context_definition_not_found() {
     Err(Error::UndefinedID(a)) => assert_eq!("A", a),
     Ok(_) => unreachable!(),
     Err(unexpected) => unreachable!("{}", unexpected),
-  }
+  };
 }
 
 #[test]
@@ -138,6 +170,19 @@ fn context_eof_expected_but_valud_value_arrived() {
   parser.push('0').unwrap();
   parser.push('1').unwrap();
   parser.push('2').unwrap();
+  // the unmatch waits for the symbols following it to be sampled, up to the end of the input
+  parser.push('3').unwrap();
+  parser.push('4').unwrap();
+  assert_unmatch(parser.finish(), location(3, 0, 3), "012", "[EOF]", "['3']4...");
+
+  // or returned immediately if nothing following it is sampled
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let sampling = Sampling::of::<char>().with_lengths(12, 0);
+  let mut parser = Context::new(&schema, "A", handler).unwrap().sampling(sampling);
+  parser.push('0').unwrap();
+  parser.push('1').unwrap();
+  parser.push('2').unwrap();
   assert_unmatch(parser.push('3'), location(3, 0, 3), "012", "[EOF]", "['3']...");
   assert_prev_err(parser.push('4'));
   assert_prev_err(parser.finish());
@@ -168,8 +213,10 @@ fn context_multiple_match() {
   parser.push('1').unwrap();
   parser.push('2').unwrap();
   match parser.finish() {
-    Err(Error::MultipleMatches { location: l, prefix, expecteds, actual }) => {
+    Err(Error::MultipleMatches(detail)) => {
+      let ErrorDetail { location: l, prefix, expecteds, expected_syntaxes, actual } = *detail;
       assert_eq!(location(3, 0, 3), l);
+      assert_eq!(expecteds.len(), expected_syntaxes.len());
       assert_eq!("012", prefix);
       assert_eq_without_order(&["[ASCII_DIGIT{3}]", "[ASCII_DIGIT{3,4}]"], &expecteds);
       assert_eq!("[EOF]", actual);
@@ -178,6 +225,17 @@ fn context_multiple_match() {
   }
 }
 
+#[test]
+fn context_multiple_match_within_delivered_syntax() {
+  // the Begin of "B" has been delivered before the paths diverge, so their pending events begin in the middle of it
+  let b = id("C") | (one_of_chars("'x") * (1..));
+  let schema = Schema::new("Foo").define("A", id("B") & (ch(';') | eof())).define("B", b).define("C", token("'x'"));
+
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("'x'").unwrap();
+  assert!(matches!(parser.finish(), Err(Error::MultipleMatches(_))));
+}
+
 #[test]
 fn context_match_within_repetition_range() {
   let a = ascii_digit() * (1..=3);
@@ -207,7 +265,7 @@ fn context_match_within_repetition_range() {
   parser.push('0').unwrap();
   parser.push('1').unwrap();
   parser.push('2').unwrap();
-  assert_unmatch(parser.push('3'), location(3, 0, 3), "012", "[EOF]", "['3']...");
+  assert_unmatch(parser.push('3').and_then(|_| parser.finish()), location(3, 0, 3), "012", "[EOF]", "['3']...");
 }
 
 #[test]
@@ -218,7 +276,8 @@ fn context_match_following_match_within_repetition_range() {
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
-  assert_unmatch(parser.push('X'), location(0, 0, 0), "", "[ASCII_DIGIT{1,3}]", "['X']...");
+  let result = parser.push('X').and_then(|_| parser.finish());
+  assert_unmatch(result, location(0, 0, 0), "", "[ASCII_DIGIT{1,3}]", "['X']...");
 
   for digits in &["0", "01", "012"] {
     let mut events = Vec::new();
@@ -319,7 +378,7 @@ fn context_create_unmatched_label_prefix() {
     (4, 1, "...0"),
     (4, 2, "...01"),
   ] {
-    let actual = create_unmatched_label_prefix(&buffer, buf_offset, match_length);
+    let actual = create_unmatched_label_prefix(&buffer, buf_offset, match_length, &[], &Sampling::of::<char>());
     assert_eq!(expected, actual);
   }
 }
@@ -330,7 +389,7 @@ fn context_create_unmatched_label_actual() {
   for (match_length, expected) in
     [(0, "['0']123456789012..."), (1, "['1']234567890123..."), (18, "['8']9..."), (19, "['9']..."), (20, "[EOF]")]
   {
-    let actual = create_unmatched_label_actual(&buffer, match_length);
+    let actual = create_unmatched_label_actual(&buffer, match_length, &[], &Sampling::of::<char>());
     assert_eq!(expected, actual);
   }
 }
@@ -368,13 +427,14 @@ fn context_seq_keywords() {
     let handler = |e: &Event<_, _>| events.push(e.clone());
     let mut parser = Context::new(&schema, "A", handler).unwrap();
     let expecteds = keywords.iter().map(|kwd| format!("[{}]", kwd)).collect::<Vec<_>>();
-    assert_unmatches(parser.push('X'), location(0, 0, 0), "", &expecteds, "['X']...");
+    assert_unmatches(parser.push('X').and_then(|_| parser.finish()), location(0, 0, 0), "", &expecteds, "['X']...");
 
     let mut events = Vec::new();
     let handler = |e: &Event<_, _>| events.push(e.clone());
     let mut parser = Context::new(&schema, "A", handler).unwrap();
     parser.push_str(kwd).unwrap();
-    assert!(matches!(parser.push('X'), Err(Error::<char>::Unmatched { .. }))); // various errors
+    assert!(matches!(parser.push('X').and_then(|_| parser.finish()), Err(Error::<char>::Unmatched(_))));
+    // various errors
   }
 }
 
@@ -407,13 +467,14 @@ fn context_one_of_tokens() {
     let handler = |e: &Event<_, _>| events.push(e.clone());
     let mut parser = Context::new(&schema, "A", handler).unwrap();
     let expected = format!("[{}]", keywords.to_vec().join("|"));
-    assert_unmatch(parser.push('X'), location(0, 0, 0), "", &expected, "['X']...");
+    assert_unmatch(parser.push('X').and_then(|_| parser.finish()), location(0, 0, 0), "", &expected, "['X']...");
 
     let mut events = Vec::new();
     let handler = |e: &Event<_, _>| events.push(e.clone());
     let mut parser = Context::new(&schema, "A", handler).unwrap();
     parser.push_str(kwd).unwrap();
-    assert!(matches!(parser.push('X'), Err(Error::<char>::Unmatched { .. }))); // various errors
+    assert!(matches!(parser.push('X').and_then(|_| parser.finish()), Err(Error::<char>::Unmatched(_))));
+    // various errors
   }
 }
 
@@ -438,7 +499,7 @@ fn context_push_seq() {
   let mut parser = Context::new(&schema, "A", handler).unwrap();
   parser.push_seq(&[]).unwrap(); // empty sequence
   parser.push_str("012").unwrap();
-  assert_unmatch(parser.push_str("3"), location(3, 0, 3), "012", "[EOF]", "['3']...");
+  assert_unmatch(parser.push_str("3").and_then(|_| parser.finish()), location(3, 0, 3), "012", "[EOF]", "['3']...");
 }
 
 #[test]
@@ -475,7 +536,7 @@ fn check_whether_possible_to_proceed() {
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
   parser.push_str("012").unwrap(); // completed
-  assert_unmatch(parser.push_str("3"), location(3, 0, 3), "012", "[EOF]", "['3']...");
+  assert_unmatch(parser.push_str("3").and_then(|_| parser.finish()), location(3, 0, 3), "012", "[EOF]", "['3']...");
 
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
@@ -545,7 +606,8 @@ fn assert_unmatch<T: Debug>(r: Result<char, T>, l: chars::Location, p: &str, e:
 }
 
 fn assert_unmatches<T: Debug>(r: Result<char, T>, l: chars::Location, p: &str, e: &[String], a: &str) {
-  if let Err(Error::<char>::Unmatched { location, prefix, expecteds, actual, .. }) = &r {
+  if let Err(Error::<char>::Unmatched(detail)) = &r {
+    let ErrorDetail { location, prefix, expecteds, actual, .. } = detail.as_ref();
     assert_eq!((&l, p, a), (location, prefix.as_str(), actual.as_str()));
     assert_eq!(e.len(), expecteds.len());
     assert_eq_without_order(e, expecteds);
@@ -567,17 +629,6 @@ fn assert_eq_without_order<T: AsRef<str>, U: AsRef<str>>(expected: &[U], actual:
   assert!(actual.is_empty(), "expected {:?}, but {:?} exists", e, actual);
 }
 
-fn assert_events_eq<ID: Clone + Display + Debug + Eq + Eq + Hash>(
-  expected: &[Event<ID, char>], actual: &[Event<ID, char>],
-) {
-  let expected = normalize(expected);
-  let actual = normalize(actual);
-  let len = std::cmp::max(expected.len(), actual.len());
-  for i in 0..len {
-    assert_eq!(expected.get(i), actual.get(i), "unexpected event @{}:\n  {:?}\n  {:?}", i, expected, actual);
-  }
-}
-
 fn combination_div(s: &str) -> Vec<Vec<String>> {
   let chars = s.chars().collect::<Vec<_>>();
   let mut result = Vec::new();
@@ -616,67 +667,71 @@ fn combination_sum(sum: usize) -> Vec<Vec<usize>> {
   result
 }
 
-fn normalize<ID: Clone + Display + Debug + Eq + Eq + Hash>(events: &[Event<ID, char>]) -> Vec<Event<ID, char>> {
-  let mut buffer = EventBuffer::new(events.len());
-  for e in events {
-    buffer.push(e.clone());
+/// A deterministic pseudo-random generator (xorshift64*) to generate the inputs of the property tests reproducibly.
+///
+pub(crate) struct Random(u64);
+
+impl Random {
+  pub fn new(seed: u64) -> Self {
+    Self(seed.max(1))
+  }
+  pub fn next(&mut self) -> u64 {
+    self.0 ^= self.0 >> 12;
+    self.0 ^= self.0 << 25;
+    self.0 ^= self.0 >> 27;
+    self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+  }
+  pub fn below(&mut self, n: usize) -> usize {
+    (self.next() % n as u64) as usize
+  }
+  /// Replace, insert or delete a few characters of `sample` with those of `alphabet`, so that the result is often
+  /// close to a valid input but sometimes invalid.
+  pub fn mutate(&mut self, sample: &str, alphabet: &str) -> String {
+    let mut chars = sample.chars().collect::<Vec<_>>();
+    let alphabet = alphabet.chars().collect::<Vec<_>>();
+    for _ in 0..self.below(3) {
+      let ch = alphabet[self.below(alphabet.len())];
+      match (self.below(3), chars.len()) {
+        (_, 0) | (0, _) => chars.insert(self.below(chars.len() + 1), ch),
+        (1, len) => chars[self.below(len)] = ch,
+        (_, len) => {
+          chars.remove(self.below(len));
+        }
+      }
+    }
+    chars.into_iter().collect()
   }
-  let mut events = Vec::with_capacity(events.len());
-  buffer.flush_to(buffer.len(), &mut |e| events.push(e.clone()));
-  events
 }
 
-fn location(chars: u64, lines: u64, columns: u64) -> chars::Location {
-  chars::Location { chars, lines, columns }
-}
-
-pub(crate) struct Events<ID: Clone + Display + Debug + Eq + Eq + Hash> {
-  location: chars::Location,
-  events: Vec<Event<ID, char>>,
-  stack: Vec<ID>,
-}
-
-impl<ID: Clone + Display + Debug + Eq + Eq + Hash> Events<ID> {
-  pub fn new() -> Self {
-    let events = Vec::with_capacity(16);
-    let stack = Vec::with_capacity(4);
-    Self { location: chars::Location::default(), events, stack }
-  }
-  pub fn begin(mut self, id: ID) -> Self {
-    self.stack.push(id.clone());
-    self.events.push(Event { location: self.location, kind: EventKind::Begin(id) });
-    self
-  }
-  pub fn end(mut self) -> Self {
-    let id = self.stack.pop().unwrap();
-    self.events.push(Event { location: self.location, kind: EventKind::End(id) });
-    self
-  }
-  pub fn fragments(mut self, text: &str) -> Self {
-    for ch in text.chars() {
-      self.events.push(Event { location: self.location, kind: EventKind::Fragments(vec![ch]) });
-      self.location.increment_with(ch);
-    }
-    self
-  }
-  pub fn to_vec(&self) -> Vec<Event<ID, char>> {
-    assert!(self.stack.is_empty(), "`end()` missing in expected events building: {:?}", self.stack);
-    self.events.clone()
-  }
-  pub fn to_event_buffer(&self) -> EventBuffer<ID, char> {
-    let mut buffer = EventBuffer::new(self.events.len());
-    for e in &self.events {
-      buffer.push(e.clone());
+/// Call `f` with each of the `samples` and its `count - 1` mutations by the characters of `alphabet`, which is the
+/// harness of the property tests that compare the ways to parse the same input.
+///
+pub(crate) fn for_each_mutation<F: FnMut(&mut Random, &str)>(
+  random: &mut Random, samples: &[&str], alphabet: &str, count: usize, mut f: F,
+) {
+  for sample in samples {
+    for i in 0..count {
+      let input = if i == 0 { sample.to_string() } else { random.mutate(sample, alphabet) };
+      f(random, &input);
     }
-    buffer
-  }
-  pub fn assert_eq(&self, actual: &[Event<ID, char>]) {
-    assert_events_eq(&self.to_vec(), actual);
   }
 }
 
-impl<ID: Clone + Display + Debug + Eq + Eq + Hash> Default for Events<ID> {
-  fn default() -> Self {
-    Self::new()
-  }
+/// Push the `chunks` in order and finish, and return all the events delivered with the result.
+///
+pub(crate) fn parse_chunks<ID>(
+  schema: &Schema<ID, char>, id: ID, chunks: &[&str], parallel: bool,
+) -> (Vec<Event<ID, char>>, Result<char, ()>)
+where
+  ID: 'static + Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
+{
+  let mut events = Vec::new();
+  let mut parser = Context::new(schema, id, |e: &Event<_, _>| events.push(e.clone())).unwrap();
+  parser.parallel = parallel;
+  let result = chunks.iter().try_for_each(|chunk| parser.push_str(chunk)).and_then(|_| parser.finish_unit());
+  (events, result)
+}
+
+fn location(chars: u64, lines: u64, columns: u64) -> chars::Location {
+  chars::Location::new(chars, lines, columns)
 }
@@ -1,6 +1,8 @@
-use crate::parser::{Context, Event};
+use crate::parser::test::Events;
+use crate::parser::{Context, Event, Limits};
 use crate::schema::chars::{ascii_alphabetic, ascii_digit, ch};
-use crate::schema::Schema;
+use crate::schema::{id, Schema};
+use crate::Error;
 
 #[test]
 fn or() {
@@ -19,3 +21,67 @@ fn or() {
   parser.finish().unwrap();
   println!("{:?}", events);
 }
+
+#[test]
+fn or_merged_if_only_ignored_syntaxes_differ() {
+  // both branches match any input, but they can't be distinguished once W1 and W2 are ignored
+  let schema = Schema::new("Foo")
+    .define("A", ((id("W1") & id("W2")) | (id("W2") & id("W1"))) * (0..))
+    .define("W1", ascii_alphabetic() * (0..=1))
+    .define("W2", ascii_alphabetic() * (0..=1));
+
+  let mut events = Vec::new();
+  let event_handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "A", event_handler)
+    .unwrap()
+    .ignore_events_for(&["W1", "W2"])
+    .limits(Limits::UNLIMITED.with_max_paths(16));
+  parser.push_str(&"a".repeat(32)).unwrap();
+  parser.finish().unwrap();
+  Events::new().begin("A").fragments(&"a".repeat(32)).end().assert_eq(&events);
+
+  // the branches are still ambiguous if they are distinguished by events
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("aa").unwrap();
+  assert!(matches!(parser.finish(), Err(Error::MultipleMatches(_))));
+}
+
+#[test]
+fn or_merged_if_ambiguous_within_ignored_syntax() {
+  let schema = Schema::new("Foo").define("A", ch(';') & id("W")).define("W", ch('a') | ch('a'));
+  let mut events = Vec::new();
+  let event_handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "A", event_handler).unwrap().ignore_events_for(&["W"]);
+  parser.push_str(";a").unwrap();
+  parser.finish().unwrap();
+  Events::new().begin("A").fragments(";a").end().assert_eq(&events);
+
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str(";a").unwrap();
+  assert!(matches!(parser.finish(), Err(Error::MultipleMatches(_))));
+}
+
+#[test]
+fn or_ongoing_paths_merged_after_ignored_syntaxes() {
+  // each X forks three paths, which are merged as soon as they leave W1 and W2
+  let schema = Schema::new("Foo")
+    .define("A", (id("X") * (0..)) & ch(';'))
+    .define("X", (ch('(') & id("W1") & ch(')')) | (ch('(') & id("W2") & ch(')')))
+    .define("W1", (ascii_alphabetic() * (1..)) | (ch('a') & ch('b')))
+    .define("W2", ascii_alphabetic() * (1..));
+  let text = format!("{};", "(ab)".repeat(256));
+
+  let mut events = Vec::new();
+  let event_handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "A", event_handler)
+    .unwrap()
+    .ignore_events_for(&["W1", "W2"])
+    .limits(Limits::UNLIMITED.with_max_paths(16));
+  parser.push_str(&text).unwrap();
+  parser.finish().unwrap();
+  let mut expected = Events::new().begin("A");
+  for _ in 0..256 {
+    expected = expected.begin("X").fragments("(ab)").end();
+  }
+  expected.fragments(";").end().assert_eq(&events);
+}
@@ -0,0 +1,76 @@
+use crate::parser::test::{location, Events};
+use crate::parser::{Context, Engine, Event};
+use crate::schema::chars::{ascii_digit, ch, token};
+use crate::schema::{id, Schema};
+use crate::Error;
+
+fn schema() -> Schema<&'static str, char> {
+  Schema::new("Foo")
+    .define("FRAMES", id("FRAME") * (0..))
+    .define("FRAME", id("LENGTH") & ch(':') & ch(';'))
+    .define("LENGTH", ascii_digit() * (1..))
+}
+
+#[test]
+fn passthrough_raw_body() {
+  let schema = schema();
+  for chunks in [&["3:", "ab:;2", ":", "xy;"][..], &["3:", "a", "b:;2:", "x", "y", ";"][..]] {
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| events.push(e.clone());
+    let mut parser = Context::new(&schema, "FRAMES", handler).unwrap();
+    for chunk in chunks {
+      parser.push_str(chunk).unwrap();
+      if chunk.ends_with(':') {
+        let length = if chunk.starts_with('3') { 3 } else { 2 };
+        parser.passthrough(length).unwrap();
+      }
+    }
+    parser.finish().unwrap();
+    Events::new()
+      .begin("FRAMES")
+      .begin("FRAME")
+      .begin("LENGTH")
+      .fragments("3")
+      .end()
+      .fragments(":ab:;")
+      .end()
+      .begin("FRAME")
+      .begin("LENGTH")
+      .fragments("2")
+      .end()
+      .fragments(":xy;")
+      .end()
+      .end()
+      .assert_eq(&events);
+  }
+}
+
+#[test]
+fn passthrough_in_the_middle_of_match() {
+  let schema = Schema::new("Foo").define("A", token("ab") & ch(';'));
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("a").unwrap();
+  assert_eq!(Err(Error::Passthrough { location: location(1, 0, 1), remaining: 2 }), parser.passthrough(2));
+  parser.push_str("b").unwrap();
+  parser.passthrough(2).unwrap();
+  parser.push_str("xy;").unwrap();
+  parser.finish().unwrap();
+}
+
+#[test]
+fn passthrough_eof() {
+  let schema = schema();
+  let mut parser = Context::new(&schema, "FRAMES", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("3:").unwrap();
+  parser.passthrough(3).unwrap();
+  parser.push_str("ab").unwrap();
+  assert_eq!(Err(Error::Passthrough { location: location(4, 0, 4), remaining: 1 }), parser.finish());
+}
+
+#[test]
+fn passthrough_unsupported_by_earley() {
+  let schema = schema();
+  let mut parser = Context::new(&schema, "FRAMES", |_: &Event<_, _>| ()).unwrap().with_engine(Engine::Earley);
+  parser.push_str("3:").unwrap();
+  assert_eq!(Err(Error::Unsupported { operation: "passthrough", engine: Engine::Earley }), parser.passthrough(3));
+}
@@ -0,0 +1,43 @@
+use crate::parser::{Context, Event, Profile};
+use crate::schema::chars::ch;
+use crate::schema::{id, Schema};
+
+#[test]
+fn profile_rules() {
+  let schema = Schema::new("Foo")
+    .define("A", id("B") * (0..))
+    .define("B", (ch('x') & ch('1')) | (ch('x') & ch('2')) | id("C"))
+    .define("C", (ch('y') & ch('z')) | (ch('y') & ch('z')));
+
+  let mut expected = Vec::new();
+  let mut parser = Context::new(&schema, "A", |e: &Event<_, _>| expected.push(e.clone())).unwrap();
+  parser.push_str("x1x2yz").unwrap();
+  parser.finish().unwrap();
+
+  // the profiling doesn't change the events
+  let mut events = Vec::new();
+  let mut parser = Context::new(&schema, "A", |e: &Event<_, _>| events.push(e.clone())).unwrap().profiling();
+  parser.push_str("x1x2yz").unwrap();
+  let partial = parser.profile().unwrap().clone();
+  let profile = parser.finish_profile().unwrap();
+  assert_eq!(expected, events);
+
+  // the terms of A are those of B and C, and the identical branches of C are merged
+  let (a, b, c) = (profile.get(&"A").unwrap(), profile.get(&"B").unwrap(), profile.get(&"C").unwrap());
+  assert_eq!(0, a.evaluations);
+  assert!(a.spawns > 0);
+  assert!(b.evaluations >= 4 && b.spawns >= 8, "{:?}", b);
+  assert!(c.evaluations >= 4 && c.merges > 0, "{:?}", c);
+  assert!(partial.total().evaluations < profile.total().evaluations);
+  assert_eq!(a.time + b.time + c.time, profile.total().time);
+  let hottest = profile.hottest();
+  assert_eq!(3, hottest.len());
+  assert!(hottest.windows(2).all(|w| w[0].1.time >= w[1].1.time));
+  assert_eq!("A", *hottest[2].0);
+
+  // nothing is collected without profiling
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("x1").unwrap();
+  assert!(parser.profile().is_none());
+  assert_eq!(Profile::new("A"), parser.finish_profile().unwrap());
+}
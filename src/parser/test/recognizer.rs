@@ -0,0 +1,73 @@
+use crate::parser::test::json::SAMPLE_WIKIPEDIA;
+use crate::parser::test::{assert_unmatch, location};
+use crate::parser::{Context, Engine, Event, EventKind};
+use crate::schema::chars::{ascii_digit, ch};
+use crate::schema::{json, Schema};
+use std::collections::BTreeMap;
+
+#[test]
+fn recognizer_accepts() {
+  let schema = json::schema();
+  let mut parser = Context::recognizer(&schema, json::ID::JsonText).unwrap();
+  for ch in SAMPLE_WIKIPEDIA.chars() {
+    parser.push(ch).unwrap();
+    assert!(parser.ongoing.iter().all(|path| path.event_buffer().len() == 0));
+  }
+  parser.finish().unwrap();
+}
+
+#[test]
+fn recognizer_rejects_as_context() {
+  let schema = Schema::new("Foo").define("A", ch('a') & (ascii_digit() * (1..)) & ch(';'));
+  for sample in ["a12;", "a12x", "ax", "a1", "a1;b"] {
+    let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+    let expected = parser.push_str(sample).and_then(|_| parser.finish_unit());
+    let mut parser = Context::recognizer(&schema, "A").unwrap();
+    let actual = parser.push_str(sample).and_then(|_| parser.finish_unit());
+    assert_eq!(expected, actual, "{}", sample);
+  }
+
+  let mut parser = Context::recognizer(&schema, "A").unwrap();
+  parser.push_str("a1").unwrap();
+  assert_unmatch(parser.finish(), location(2, 0, 2), "a1", "[';']", "[EOF]");
+}
+
+#[test]
+fn recognizer_counts() {
+  let schema = json::schema();
+  let mut events = Vec::new();
+  let mut parser = Context::new(&schema, json::ID::JsonText, |e: &Event<_, _>| events.push(e.clone())).unwrap();
+  parser.push_str(SAMPLE_WIKIPEDIA).unwrap();
+  parser.finish().unwrap();
+  let count_ends = |events: &[Event<json::ID, char>]| {
+    let mut counts = BTreeMap::new();
+    for e in events {
+      if let EventKind::End(id) = &e.kind {
+        *counts.entry(id.clone()).or_insert(0usize) += 1;
+      }
+    }
+    counts
+  };
+
+  let mut parser = Context::recognizer(&schema, json::ID::JsonText).unwrap().counting();
+  parser.push_str(SAMPLE_WIKIPEDIA).unwrap();
+  assert_eq!(count_ends(&events), parser.finish_counts().unwrap());
+
+  let mut parser = Context::new(&schema, json::ID::JsonText, |_: &Event<_, _>| ()).unwrap().counting();
+  parser.push_str(SAMPLE_WIKIPEDIA).unwrap();
+  assert_eq!(count_ends(&events), parser.finish_counts().unwrap());
+
+  // the Earley engine delivers no syntax that matched nothing
+  let mut parser =
+    Context::new(&schema, json::ID::JsonText, |_: &Event<_, _>| ()).unwrap().with_engine(Engine::Earley).counting();
+  parser.push_str(SAMPLE_WIKIPEDIA).unwrap();
+  assert_eq!(count_ends(&Event::normalize(&events)), parser.finish_counts().unwrap());
+}
+
+#[test]
+fn recognizer_counts_nothing_unless_counting() {
+  let schema = Schema::new("Foo").define("A", ch('a') & (ascii_digit() * (1..)) & ch(';'));
+  let mut parser = Context::recognizer(&schema, "A").unwrap();
+  parser.push_str("a12;").unwrap();
+  assert!(parser.finish_counts().unwrap().is_empty());
+}
@@ -0,0 +1,122 @@
+use crate::parser::{BinaryTrace, Context, Event, JsonLines, MessagePack};
+use crate::schema::chars::{ascii_digit, ch};
+use crate::schema::{id, range, single, Schema};
+use crate::Error;
+use std::io::{self, Write};
+
+fn schema() -> Schema<&'static str, char> {
+  Schema::new("Foo").define("PAIR", id("NUM") & ch(',') & id("NUM")).define("NUM", ascii_digit() * (1..))
+}
+
+#[test]
+fn json_lines() {
+  let schema = schema();
+  let mut sink = JsonLines::new(Vec::new());
+  let mut parser =
+    Context::try_new(&schema, "PAIR", |e: &Event<_, _>| sink.handle(e)).unwrap().with_fragment_ends(true);
+  parser.push_str("1,23").unwrap();
+  parser.finish().unwrap();
+  let lines = String::from_utf8(sink.into_inner()).unwrap();
+  let location = |n: usize| format!("{{\"position\":{},\"line\":0,\"column\":{},\"byte_offset\":{}}}", n, n, n);
+  let expected = [
+    format!(r#"{{"kind":"Begin","id":"PAIR","location":{}}}"#, location(0)),
+    format!(r#"{{"kind":"Begin","id":"NUM","location":{}}}"#, location(0)),
+    format!(r#"{{"kind":"Fragments","fragments":"1","location":{},"end":{}}}"#, location(0), location(1)),
+    format!(r#"{{"kind":"End","id":"NUM","location":{}}}"#, location(1)),
+    format!(r#"{{"kind":"Fragments","fragments":",","location":{},"end":{}}}"#, location(1), location(2)),
+    format!(r#"{{"kind":"Begin","id":"NUM","location":{}}}"#, location(2)),
+    format!(r#"{{"kind":"Fragments","fragments":"23","location":{},"end":{}}}"#, location(2), location(4)),
+    format!(r#"{{"kind":"End","id":"NUM","location":{}}}"#, location(4)),
+    format!(r#"{{"kind":"End","id":"PAIR","location":{}}}"#, location(4)),
+  ];
+  assert_eq!(expected.to_vec(), lines.lines().collect::<Vec<_>>());
+}
+
+#[test]
+fn json_lines_of_bytes() {
+  let schema = Schema::new("Foo").define("BODY", single(0x01u8) & (range(0x00..=0xFF) * 2));
+  let mut sink = JsonLines::new(Vec::new());
+  let mut parser = Context::try_new(&schema, "BODY", |e: &Event<_, _>| sink.handle(e)).unwrap();
+  parser.push_seq(&[0x01, 0x22, 0xFF]).unwrap();
+  parser.finish().unwrap();
+  let lines = String::from_utf8(sink.into_inner()).unwrap();
+  assert_eq!(
+    r#"{"kind":"Fragments","fragments":[1,34,255],"location":{"position":0,"byte_offset":0}}"#,
+    lines.lines().nth(1).unwrap()
+  );
+}
+
+#[test]
+fn message_pack() {
+  let schema = schema();
+  let mut sink = MessagePack::new(Vec::new());
+  let mut parser = Context::try_new(&schema, "NUM", |e: &Event<_, _>| sink.handle(e)).unwrap();
+  parser.push_str("7").unwrap();
+  parser.finish().unwrap();
+
+  let mut expected = Vec::new();
+  let location = |n: u8| {
+    let mut location = vec![0x84, 0xA8];
+    location.extend_from_slice(b"position");
+    location.extend_from_slice(&[n, 0xA4]);
+    location.extend_from_slice(b"line");
+    location.extend_from_slice(&[0, 0xA6]);
+    location.extend_from_slice(b"column");
+    location.extend_from_slice(&[n, 0xAB]);
+    location.extend_from_slice(b"byte_offset");
+    location.push(n);
+    location
+  };
+  for (kind, key, value, n) in [("Begin", "id", "NUM", 0), ("Fragments", "fragments", "7", 0), ("End", "id", "NUM", 1)]
+  {
+    expected.extend_from_slice(&[0x83, 0xA4]);
+    expected.extend_from_slice(b"kind");
+    expected.push(0xA0 | kind.len() as u8);
+    expected.extend_from_slice(kind.as_bytes());
+    expected.push(0xA0 | key.len() as u8);
+    expected.extend_from_slice(key.as_bytes());
+    expected.push(0xA0 | value.len() as u8);
+    expected.extend_from_slice(value.as_bytes());
+    expected.push(0xA8);
+    expected.extend_from_slice(b"location");
+    expected.extend_from_slice(&location(n));
+  }
+  assert_eq!(expected, sink.into_inner());
+}
+
+#[test]
+fn binary_trace() {
+  let schema = schema();
+  let mut sink = BinaryTrace::new(Vec::new());
+  let mut parser = Context::try_new(&schema, "PAIR", |e: &Event<_, _>| sink.handle(e)).unwrap();
+  parser.push_str(&format!("1,{}", "2".repeat(200))).unwrap();
+  parser.finish().unwrap();
+
+  let mut expected = b"B\x00\x04PAIRB\x00\x03NUMF\x00\x011E\x01\x03NUMF\x01\x01,B\x02\x03NUMF\x02\xC8\x01".to_vec();
+  expected.extend_from_slice("2".repeat(200).as_bytes());
+  expected.extend_from_slice(b"E\xCA\x01\x03NUME\xCA\x01\x04PAIR");
+  assert_eq!(expected, sink.into_inner());
+}
+
+#[test]
+fn write_error_aborts_parse() {
+  struct Closed;
+  impl Write for Closed {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+      Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+    }
+    fn flush(&mut self) -> io::Result<()> {
+      Ok(())
+    }
+  }
+
+  let schema = schema();
+  let mut sink = JsonLines::new(Closed);
+  let mut parser = Context::try_new(&schema, "PAIR", |e: &Event<_, _>| sink.handle(e)).unwrap();
+  match parser.push_str("1,2") {
+    Err(Error::Handler(err)) => {
+      assert_eq!(io::ErrorKind::BrokenPipe, err.get_ref().downcast_ref::<io::Error>().unwrap().kind());
+    }
+    result => panic!("unexpected result: {:?}", result),
+  }
+}
@@ -0,0 +1,160 @@
+use crate::parser::test::{assert_unmatch, location, Events};
+use crate::parser::{Context, Event, Sampling};
+use crate::schema::chars::{ascii_alphabetic, ascii_digit, ch};
+use crate::schema::{id, Schema};
+use crate::Error;
+
+fn list_schema() -> Schema<&'static str, char> {
+  Schema::new("List")
+    .define("LIST", ch('[') & id("ITEM") & ((ch(',') & id("ITEM")) * (0..)) & ch(']'))
+    .define("ITEM", ascii_digit() * (1..))
+    .recover_at("ITEM", &[',', ']'])
+}
+
+#[test]
+fn recover_at_sync_symbol() {
+  let schema = list_schema();
+  for chunked in [false, true] {
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| events.push(e.clone());
+    let mut parser = Context::new(&schema, "LIST", handler).unwrap();
+    if chunked {
+      parser.push_str("[1,x2,3]").unwrap();
+    } else {
+      for ch in "[1,x2,3]".chars() {
+        parser.push(ch).unwrap();
+      }
+    }
+    // the error is sampled with the same symbols following it however the input is pushed
+    match parser.finish() {
+      Err(Error::Recovered(errors)) => {
+        assert_eq!(1, errors.len());
+        assert_unmatch::<()>(Err(errors[0].clone()), location(3, 0, 3), "[1,", "[ASCII_DIGIT+]", "['x']2,3]...");
+      }
+      unexpected => panic!("{:?}", unexpected),
+    }
+    Events::new()
+      .begin("LIST")
+      .fragments("[")
+      .begin("ITEM")
+      .fragments("1")
+      .end()
+      .fragments(",")
+      .begin("ITEM")
+      .fragments("x2")
+      .end()
+      .fragments(",")
+      .begin("ITEM")
+      .fragments("3")
+      .end()
+      .fragments("]")
+      .end()
+      .assert_eq(&events);
+  }
+}
+
+#[test]
+fn recover_multiple_times() {
+  let schema = list_schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let sampling = Sampling::of::<char>().with_lengths(12, 0);
+  let mut parser = Context::new(&schema, "LIST", handler).unwrap().sampling(sampling);
+  parser.push_str("[a,,3]").unwrap();
+  assert_eq!(2, parser.recovered_errors().len());
+  match parser.finish() {
+    Err(Error::Recovered(errors)) => assert_eq!(2, errors.len()),
+    unexpected => panic!("{:?}", unexpected),
+  }
+  Events::new()
+    .begin("LIST")
+    .fragments("[")
+    .begin("ITEM")
+    .fragments("a")
+    .end()
+    .fragments(",")
+    .begin("ITEM")
+    .end()
+    .fragments(",")
+    .begin("ITEM")
+    .fragments("3")
+    .end()
+    .fragments("]")
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn recover_at_innermost_rule() {
+  // the sync symbols are the ones that can follow each syntax
+  let schema = Schema::new("Foo")
+    .define("LINE", (id("GROUP") * (1..)) & ch(';'))
+    .define("GROUP", ch('(') & id("WORD") & ((ch(' ') & id("WORD")) * (0..)) & ch(')'))
+    .define("WORD", ascii_alphabetic() * (1..))
+    .recover_at("GROUP", &['(', ';'])
+    .recover_at("WORD", &[' ', ')']);
+
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "LINE", handler).unwrap();
+  parser.push_str("(ab 1c)(d;").unwrap();
+  assert!(matches!(parser.finish(), Err(Error::Recovered(errors)) if errors.len() == 2));
+  Events::new()
+    .begin("LINE")
+    .begin("GROUP")
+    .fragments("(")
+    .begin("WORD")
+    .fragments("ab")
+    .end()
+    .fragments(" ")
+    .begin("WORD")
+    .fragments("1c")
+    .end()
+    .fragments(")")
+    .end()
+    .begin("GROUP")
+    .fragments("(")
+    .begin("WORD")
+    .fragments("d")
+    .end()
+    .end()
+    .fragments(";")
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn recover_at_eof() {
+  let schema = list_schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "LIST", handler).unwrap();
+  parser.push_str("[1,x").unwrap();
+
+  // the errors recovered so far are reported with the one that failed the parse
+  match parser.finish() {
+    Err(Error::Recovered(errors)) => {
+      assert_eq!(2, errors.len());
+      assert_unmatch::<()>(Err(errors[0].clone()), location(3, 0, 3), "[1,", "[ASCII_DIGIT+]", "['x']...");
+      assert_unmatch::<()>(Err(errors[1].clone()), location(4, 0, 4), "[1,x", "[']']", "[EOF]");
+    }
+    unexpected => panic!("{:?}", unexpected),
+  }
+}
+
+#[test]
+fn no_recovery_set() {
+  let schema = Schema::new("List")
+    .define("LIST", ch('[') & id("ITEM") & ((ch(',') & id("ITEM")) * (0..)) & ch(']'))
+    .define("ITEM", ascii_digit() * (1..));
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "LIST", handler).unwrap();
+  assert_unmatch(
+    parser.push_str("[1,x2,3]").and_then(|_| parser.finish()),
+    location(3, 0, 3),
+    "[1,",
+    "[ASCII_DIGIT+]",
+    "['x']2,3]...",
+  );
+}
@@ -0,0 +1,37 @@
+use crate::parser::{Context, Engine, Redaction, Sampling};
+use crate::schema::chars::{ascii_digit, ch};
+use crate::schema::Schema;
+use crate::Error;
+
+fn labels(sampling: Sampling, engine: Engine, input: &str) -> (String, String) {
+  let schema = Schema::new("Foo").define("A", (ascii_digit() * (1..)) & ch(';'));
+  let mut parser = Context::recognizer(&schema, "A").unwrap().sampling(sampling).with_engine(engine);
+  let error = match parser.push_str(input) {
+    Ok(()) => parser.finish().map(|_| ()).unwrap_err(),
+    Err(error) => error,
+  };
+  match error {
+    Error::Unmatched(detail) => (detail.prefix, detail.actual),
+    error => panic!("{:?}", error),
+  }
+}
+
+#[test]
+fn sampling_lengths_and_redactions() {
+  let default = Sampling::of::<char>();
+  let mask = default.with_redaction(Redaction::Mask('*'));
+  let short = default.with_lengths(3, 1);
+  for engine in [Engine::Streaming, Engine::Earley] {
+    for (sampling, input, prefix, actual) in [
+      (default, "1234567x9", "1234567", "['x']9..."),
+      (mask, "1234567x9", "*******", "[*]*..."),
+      (short, "1234567x9", "...567", "['x']9..."),
+      (short, "1234567", "...567", "[EOF]"),
+      (Sampling::omitted(), "1234567x9", "", "[***]"),
+      (Sampling::omitted(), "1234567", "", "[EOF]"),
+    ] {
+      let expected = (prefix.to_string(), actual.to_string());
+      assert_eq!(expected, labels(sampling, engine, input), "{:?}, {:?}, {:?}", engine, sampling, input);
+    }
+  }
+}
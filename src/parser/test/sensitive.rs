@@ -0,0 +1,47 @@
+use crate::parser::{Context, Engine};
+use crate::schema::chars::{ascii_alphabetic, ascii_digit, ch, token};
+use crate::schema::{id, Schema};
+use crate::Error;
+
+fn schema() -> Schema<&'static str, char> {
+  Schema::new("Credentials")
+    .define("Credentials", id("Credential") * (0..))
+    .define("Credential", id("User") & ch(':') & id("Password") & ch(';'))
+    .define("User", ascii_alphabetic() * (1..))
+    .define("Password", ((ascii_alphabetic() | ascii_digit()) * (1..)) & (token("!!") * (0..=1)))
+    .sensitive("Password")
+}
+
+fn labels(schema: &Schema<&'static str, char>, engine: Engine, input: &str) -> (String, String) {
+  let mut parser = Context::recognizer(schema, "Credentials").unwrap().with_engine(engine);
+  let error = match parser.push_str(input) {
+    Ok(()) => parser.finish().map(|_| ()).unwrap_err(),
+    Err(error) => error,
+  };
+  match error {
+    Error::Unmatched(detail) => (detail.prefix, detail.actual),
+    error => panic!("{:?}", error),
+  }
+}
+
+#[test]
+fn sensitive_symbols_redacted_from_errors() {
+  let schema = schema();
+  for (input, prefix, actual) in [
+    // the symbols following the failure within the sensitive syntax may also be a part of it
+    ("root:s3cr3t!?x", "root:***", "[***]***..."),
+    ("root:s3cr3t;guest:pw-x", "...***;guest:***", "[***]***..."),
+    // after the sensitive syntax
+    ("root:s3cr3t;-", "root:***;", "['-']..."),
+  ] {
+    assert_eq!((prefix.to_string(), actual.to_string()), labels(&schema, Engine::Streaming, input), "{:?}", input);
+  }
+
+  // the user isn't sensitive
+  let (prefix, _) = labels(&schema, Engine::Streaming, "root-");
+  assert_eq!("root", prefix);
+
+  // the whole input is redacted by the Earley engine
+  let (prefix, actual) = labels(&schema, Engine::Earley, "root:s3cr3t;-");
+  assert_eq!(("***", "[***]..."), (prefix.as_str(), actual.as_str()));
+}
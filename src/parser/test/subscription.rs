@@ -0,0 +1,47 @@
+use crate::parser::test::json::SAMPLE_WIKIPEDIA;
+use crate::parser::{Context, Engine, Event, EventKind};
+use crate::schema::json::{self, ID};
+
+/// The events within the syntaxes `ids` filtered from the whole events.
+///
+fn filter(events: &[Event<ID, char>], ids: &[ID]) -> Vec<Event<ID, char>> {
+  let mut depth = 0;
+  let mut filtered = Vec::new();
+  for e in events {
+    if matches!(&e.kind, EventKind::Begin(id) if ids.contains(id)) {
+      depth += 1;
+    }
+    if depth > 0 {
+      filtered.push(e.clone());
+    }
+    if matches!(&e.kind, EventKind::End(id) if ids.contains(id)) {
+      depth -= 1;
+    }
+  }
+  filtered
+}
+
+#[test]
+fn subscription_by_ids() {
+  let schema = json::schema();
+  for engine in [Engine::Streaming, Engine::Earley] {
+    let mut events = Vec::new();
+    let mut members = Vec::new();
+    let mut numbers = Vec::new();
+    let mut nothing = Vec::new();
+    let mut parser = Context::new(&schema, ID::JsonText, |e: &Event<_, _>| events.push(e.clone()))
+      .unwrap()
+      .with_engine(engine)
+      .on(&[ID::Member], |e| members.push(e.clone()))
+      .on(&[ID::Number, ID::False], |e| numbers.push(e.clone()))
+      .on(&[ID::Null], |e| nothing.push(e.clone()));
+    parser.push_str(SAMPLE_WIKIPEDIA).unwrap();
+    parser.finish().unwrap();
+
+    assert_eq!(filter(&events, &[ID::Member]), members);
+    assert_eq!(filter(&events, &[ID::Number, ID::False]), numbers);
+    assert_eq!(10, members.iter().filter(|e| e.kind == EventKind::Begin(ID::Member)).count());
+    assert_eq!(9, numbers.iter().filter(|e| matches!(e.kind, EventKind::End(ID::Number | ID::False))).count());
+    assert!(nothing.is_empty());
+  }
+}
@@ -0,0 +1,47 @@
+use crate::parser::test::location;
+use crate::parser::{Context, Engine, Event, EventKind};
+use crate::schema::chars::{ascii_alphabetic, ascii_digit, ch};
+use crate::schema::{id, Schema};
+
+fn schema() -> Schema<&'static str, char> {
+  Schema::new("Foo")
+    .define("DOC", id("TITLE") & ch(';') & id("BODY"))
+    .define("TITLE", ascii_alphabetic() * (1..))
+    .define("BODY", ascii_digit() * (1..))
+}
+
+#[test]
+fn finish_returns_summary() {
+  let schema = schema();
+  for engine in [Engine::Streaming, Engine::Earley] {
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| events.push(e.clone());
+    let mut parser = Context::new(&schema, "DOC", handler).unwrap().with_engine(engine);
+    parser.push_str("abc;").unwrap();
+    parser.push_str("12").unwrap();
+    let summary = parser.finish().unwrap();
+    assert_eq!(6, summary.symbols, "{:?}", engine);
+    assert_eq!(location(6, 0, 6), summary.location, "{:?}", engine);
+    assert_eq!(events.len(), summary.events, "{:?}", engine);
+    let ends = events.iter().filter(|e| matches!(e.kind, EventKind::End(_))).count();
+    assert_eq!(ends, summary.rules, "{:?}", engine);
+  }
+}
+
+#[test]
+fn finish_summary_until_first() {
+  let schema = schema();
+  let mut parser = Context::new(&schema, "DOC", |_: &Event<_, _>| ()).unwrap().until_first("TITLE");
+  parser.push_str("abc;12x").unwrap();
+  let summary = parser.finish().unwrap();
+  assert_eq!(3, summary.symbols);
+  assert_eq!(location(3, 0, 3), summary.location);
+}
+
+#[test]
+fn finish_unit() {
+  let schema = schema();
+  let mut parser = Context::new(&schema, "DOC", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("abc;12").unwrap();
+  assert_eq!(Ok(()), parser.finish_unit());
+}
@@ -0,0 +1,50 @@
+use crate::parser::{Context, Event};
+use crate::schema::chars::{ascii_digit, ch};
+use crate::schema::Schema;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Metadata, Subscriber};
+
+/// A subscriber that counts the spans and the events by their names.
+///
+#[derive(Default)]
+struct Counter {
+  next_id: AtomicU64,
+  counts: Arc<Mutex<HashMap<&'static str, usize>>>,
+}
+
+impl Subscriber for Counter {
+  fn enabled(&self, _: &Metadata<'_>) -> bool {
+    true
+  }
+  fn new_span(&self, span: &Attributes<'_>) -> Id {
+    *self.counts.lock().unwrap().entry(span.metadata().name()).or_default() += 1;
+    Id::from_u64(self.next_id.fetch_add(1, Ordering::SeqCst) + 1)
+  }
+  fn record(&self, _: &Id, _: &Record<'_>) {}
+  fn record_follows_from(&self, _: &Id, _: &Id) {}
+  fn event(&self, _: &tracing::Event<'_>) {
+    *self.counts.lock().unwrap().entry("event").or_default() += 1;
+  }
+  fn enter(&self, _: &Id) {}
+  fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn trace_parse_decisions() {
+  let schema = Schema::new("Foo").define("A", (ascii_digit() * (1..)) & ch(';'));
+  let subscriber = Counter::default();
+  let counts = subscriber.counts.clone();
+  tracing::subscriber::with_default(subscriber, || {
+    let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+    parser.push_str("12").unwrap();
+    parser.push_str(";").unwrap();
+    parser.finish().unwrap();
+  });
+  let counts = counts.lock().unwrap();
+  for name in ["proceed", "match", "flush", "merge", "event"] {
+    assert!(counts.get(name).copied().unwrap_or_default() > 0, "{}: {:?}", name, counts);
+  }
+}
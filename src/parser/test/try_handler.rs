@@ -0,0 +1,116 @@
+use crate::parser::{Context, Engine, Event, EventKind};
+use crate::schema::chars::{ascii_digit, ch};
+use crate::schema::{id, Schema};
+use crate::{Error, HandlerPanic};
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+
+fn schema() -> Schema<&'static str, char> {
+  Schema::new("Foo").define("LIST", id("ITEM") & ((ch(',') & id("ITEM")) * (0..))).define("ITEM", ascii_digit() * (1..))
+}
+
+#[test]
+fn try_handler_delivers_events() {
+  let schema = schema();
+  for engine in [Engine::Streaming, Engine::Earley] {
+    let mut ends = 0;
+    let handler = |e: &Event<_, _>| {
+      ends += usize::from(matches!(e.kind, EventKind::End("ITEM")));
+      Ok::<_, io::Error>(())
+    };
+    let mut parser = Context::try_new(&schema, "LIST", handler).unwrap().with_engine(engine);
+    parser.push_str("1,2,3").unwrap();
+    parser.finish().unwrap();
+    assert_eq!(3, ends);
+  }
+}
+
+#[test]
+fn try_handler_aborts_parse() {
+  let schema = schema();
+  for engine in [Engine::Streaming, Engine::Earley] {
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| match e.kind {
+      EventKind::End("ITEM") if events.iter().any(|e: &Event<_, _>| e.kind == EventKind::End("ITEM")) => {
+        Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+      }
+      _ => {
+        events.push(e.clone());
+        Ok(())
+      }
+    };
+    let mut parser = Context::try_new(&schema, "LIST", handler).unwrap().with_engine(engine);
+    let result = parser.push_str("1,2,").and_then(|_| parser.push_str("3,4"));
+    let err = match engine {
+      Engine::Earley => {
+        assert!(result.is_ok());
+        parser.finish().unwrap_err()
+      }
+      _ => {
+        let err = result.unwrap_err();
+        assert_eq!(Err(Error::Previous), parser.push_str("5"));
+        assert_eq!(Err(Error::Previous), parser.finish().map(|_| ()));
+        err
+      }
+    };
+    match &err {
+      Error::Handler(source) => {
+        let source = source.get_ref().downcast_ref::<io::Error>().unwrap();
+        assert_eq!(io::ErrorKind::BrokenPipe, source.kind());
+      }
+      _ => panic!("unexpected error: {:?}", err),
+    }
+    assert_eq!(err, err.clone());
+    assert_eq!("the event handler failed: closed", err.to_string());
+    assert_eq!(1, events.iter().filter(|e| e.kind == EventKind::End("ITEM")).count());
+  }
+}
+
+#[test]
+fn handler_panic_poisons_context() {
+  let schema = schema();
+  let mut ends = 0;
+  let handler = |e: &Event<_, _>| {
+    if e.kind == EventKind::End("ITEM") {
+      ends += 1;
+      assert!(ends < 2, "boom");
+    }
+  };
+  let mut parser = Context::new(&schema, "LIST", handler).unwrap();
+  assert!(!parser.is_poisoned());
+  assert!(panic::catch_unwind(AssertUnwindSafe(|| parser.push_str("1,2,3"))).is_err());
+  assert!(parser.is_poisoned());
+  assert_eq!(Err(Error::Previous), parser.push_str("4"));
+  assert_eq!(Err(Error::Previous), parser.finish().map(|_| ()));
+}
+
+#[test]
+fn catch_handler_panics() {
+  let schema = schema();
+  for engine in [Engine::Streaming, Engine::Earley] {
+    let mut ends = 0;
+    let handler = |e: &Event<_, _>| {
+      if e.kind == EventKind::End("ITEM") {
+        ends += 1;
+        assert!(ends < 2, "boom at {}", ends);
+      }
+    };
+    let mut parser = Context::new(&schema, "LIST", handler).unwrap().with_engine(engine).catch_handler_panics();
+    let err = match parser.push_str("1,2,3") {
+      Ok(()) => parser.finish().unwrap_err(),
+      Err(err) => {
+        assert!(!parser.is_poisoned());
+        assert_eq!(Err(Error::Previous), parser.push_str("4"));
+        err
+      }
+    };
+    match &err {
+      Error::Handler(source) => {
+        let source = source.get_ref().downcast_ref::<HandlerPanic>().unwrap();
+        assert_eq!("boom at 2", source.message);
+      }
+      _ => panic!("unexpected error: {:?}", err),
+    }
+    assert_eq!("the event handler failed: panicked: boom at 2", err.to_string());
+  }
+}
@@ -0,0 +1,96 @@
+use crate::parser::test::location;
+use crate::parser::{Context, Engine, Event, EventKind, Sampling};
+use crate::schema::chars::{ascii_alphabetic, ascii_digit, ch, token};
+use crate::schema::{id, Schema};
+use crate::Error;
+
+fn schema() -> Schema<&'static str, char> {
+  Schema::new("Foo")
+    .define("MESSAGE", id("HEADER") & id("BODY"))
+    .define("HEADER", (ascii_alphabetic() * (1..)) & ch(':'))
+    .define("BODY", ascii_digit() * (0..))
+}
+
+#[test]
+fn unread_after_until_first() {
+  let schema = schema();
+  for chunks in [&["abc:12x"][..], &["abc:", "12x"][..], &["ab", "c", ":1", "2x"][..]] {
+    let mut ended = false;
+    let handler = |e: &Event<_, _>| ended |= e.kind == EventKind::End("HEADER");
+    let mut parser = Context::new(&schema, "MESSAGE", handler).unwrap().until_first("HEADER");
+    let mut rest = String::new();
+    for chunk in chunks {
+      if parser.is_reached() {
+        rest.push_str(chunk);
+      } else {
+        parser.push_str(chunk).unwrap();
+      }
+    }
+    assert!(parser.is_reached());
+    let available = match parser.unread(usize::MAX) {
+      Err(Error::Unreadable { available, .. }) => available,
+      unexpected => panic!("{:?}", unexpected),
+    };
+    let unread = parser.unread(available).unwrap().into_iter().collect::<String>();
+    assert_eq!("12x", unread + &rest);
+    assert_eq!(Err(Error::Unreadable { location: location(4, 0, 4), available: 0 }), parser.unread(1));
+    parser.finish().unwrap();
+    assert!(ended);
+  }
+}
+
+#[test]
+fn unread_earley() {
+  let schema = schema();
+  let mut parser = Context::new(&schema, "MESSAGE", |_: &Event<_, _>| ()).unwrap().with_engine(Engine::Earley);
+  parser.push_str("abc:12x").unwrap();
+  assert_eq!(vec!['x'], parser.unread(1).unwrap());
+  assert!(matches!(parser.unread(7), Err(Error::Unreadable { available: 6, .. })));
+  parser.finish().unwrap();
+}
+
+#[test]
+fn unread_confirmed() {
+  let schema = schema();
+  let mut parser = Context::new(&schema, "MESSAGE", |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("abc:12").unwrap();
+  assert_eq!(Ok(vec![]), parser.unread(0));
+  assert_eq!(Err(Error::Unreadable { location: location(6, 0, 6), available: 0 }), parser.unread(1));
+  parser.finish().unwrap();
+}
+
+#[test]
+fn unread_partially() {
+  let schema = schema();
+  let mut parser = Context::new(&schema, "MESSAGE", |_: &Event<_, _>| ()).unwrap().until_first("HEADER");
+  parser.push_str("abc:12x").unwrap();
+  assert_eq!(vec!['2', 'x'], parser.unread(2).unwrap());
+  assert_eq!(vec!['1'], parser.unread(1).unwrap());
+  assert!(parser.unread(1).is_err());
+}
+
+#[test]
+fn buffered_symbols() {
+  let schema =
+    Schema::new("Foo").define("KEYWORDS", id("KEYWORD") * (0..)).define("KEYWORD", token("begin") | token("bet"));
+  let sampling = Sampling::of::<char>().with_lengths(12, 0);
+  let mut parser = Context::new(&schema, "KEYWORDS", |_: &Event<_, _>| ()).unwrap().sampling(sampling);
+  assert_eq!((0, &[][..]), parser.buffered());
+  parser.push_str("betbe").unwrap();
+  assert_eq!((3, &['b', 'e'][..]), parser.buffered());
+  parser.push_str("gi").unwrap();
+  assert_eq!((3, &['b', 'e', 'g', 'i'][..]), parser.buffered());
+  parser.push('n').unwrap();
+  assert_eq!((8, &[][..]), parser.buffered());
+  parser.push_str("bex").unwrap_err();
+  assert_eq!((0, &['b', 'e', 't', 'b', 'e', 'g', 'i', 'n', 'b', 'e', 'x'][..]), parser.buffered());
+
+  let schema = self::schema();
+  let mut parser = Context::new(&schema, "MESSAGE", |_: &Event<_, _>| ()).unwrap().until_first("HEADER");
+  parser.push_str("abc:12x").unwrap();
+  assert_eq!((4, &['1', '2', 'x'][..]), parser.buffered());
+
+  let mut parser = Context::new(&schema, "MESSAGE", |_: &Event<_, _>| ()).unwrap().with_engine(Engine::Earley);
+  parser.push_str("abc:12").unwrap();
+  assert_eq!(6, parser.buffered().1.len());
+}
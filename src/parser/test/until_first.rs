@@ -0,0 +1,62 @@
+use crate::parser::test::{assert_unmatch, location};
+use crate::parser::{Context, Engine, Event, EventKind};
+use crate::schema::chars::{ascii_alphabetic, ascii_digit, ch};
+use crate::schema::{id, Schema};
+
+fn schema() -> Schema<&'static str, char> {
+  Schema::new("Foo")
+    .define("DOC", id("TITLE") & ch(';') & id("BODY"))
+    .define("TITLE", ascii_alphabetic() * (1..))
+    .define("BODY", ascii_digit() * (1..))
+}
+
+/// The events of the complete parse up to the End event of TITLE.
+///
+fn expected(schema: &Schema<&'static str, char>) -> Vec<Event<&'static str, char>> {
+  let mut events = Vec::new();
+  let mut parser = Context::new(schema, "DOC", |e: &Event<_, _>| events.push(e.clone())).unwrap();
+  parser.push_str("abc;12").unwrap();
+  parser.finish().unwrap();
+  let end = events.iter().position(|e| e.kind == EventKind::End("TITLE")).unwrap();
+  Event::normalize(&events[..=end])
+}
+
+#[test]
+fn until_first_discards_rest() {
+  let schema = schema();
+  for (i, chunks) in [&["abc;12x"][..], &["abc", ";", "12x"][..], &["ab", "c;1", "2x", "y"][..]].iter().enumerate() {
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| events.push(e.clone());
+    let mut parser = Context::new(&schema, "DOC", handler).unwrap().until_first("TITLE");
+    for chunk in chunks.iter() {
+      parser.push_str(chunk).unwrap();
+    }
+    assert!(parser.is_reached(), "{}", i);
+    parser.finish().unwrap();
+    assert_eq!(expected(&schema), Event::normalize(&events), "{}", i);
+  }
+}
+
+#[test]
+fn until_first_not_reached() {
+  let schema = schema();
+  let mut parser = Context::new(&schema, "DOC", |_: &Event<_, _>| ()).unwrap().until_first("BODY");
+  parser.push_str("abc;12").unwrap();
+  assert!(!parser.is_reached());
+
+  let mut parser = Context::new(&schema, "DOC", |_: &Event<_, _>| ()).unwrap().until_first("TITLE");
+  parser.push_str("abc").unwrap();
+  assert!(!parser.is_reached());
+  assert_unmatch(parser.finish(), location(3, 0, 3), "abc", "[';']", "[EOF]");
+}
+
+#[test]
+fn until_first_earley() {
+  let schema = schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "DOC", handler).unwrap().with_engine(Engine::Earley).until_first("TITLE");
+  parser.push_str("abc;12").unwrap();
+  parser.finish().unwrap();
+  assert_eq!(expected(&schema), events);
+}
@@ -17,14 +17,14 @@ fn parser_behavior() {
 
   assert_eq!(
     vec![
-      Event { location: Location { chars: 0, lines: 0, columns: 0 }, kind: EventKind::Begin("CARD") },
-      Event { location: Location { chars: 0, lines: 0, columns: 0 }, kind: EventKind::Begin("SUIT") },
-      Event { location: Location { chars: 0, lines: 0, columns: 0 }, kind: EventKind::Fragments(vec!['♠']) },
-      Event { location: Location { chars: 1, lines: 0, columns: 1 }, kind: EventKind::End("SUIT") },
-      Event { location: Location { chars: 1, lines: 0, columns: 1 }, kind: EventKind::Begin("RANK") },
-      Event { location: Location { chars: 1, lines: 0, columns: 1 }, kind: EventKind::Fragments(vec!['2']) },
-      Event { location: Location { chars: 2, lines: 0, columns: 2 }, kind: EventKind::End("RANK") },
-      Event { location: Location { chars: 2, lines: 0, columns: 2 }, kind: EventKind::End("CARD") },
+      Event { location: Location::new(0, 0, 0), kind: EventKind::Begin("CARD"), end: None },
+      Event { location: Location::new(0, 0, 0), kind: EventKind::Begin("SUIT"), end: None },
+      Event { location: Location::new(0, 0, 0), kind: EventKind::Fragments(vec!['♠']), end: None },
+      Event { location: Location::new(1, 0, 1), kind: EventKind::End("SUIT"), end: None },
+      Event { location: Location::new(1, 0, 1), kind: EventKind::Begin("RANK"), end: None },
+      Event { location: Location::new(1, 0, 1), kind: EventKind::Fragments(vec!['2']), end: None },
+      Event { location: Location::new(2, 0, 2), kind: EventKind::End("RANK"), end: None },
+      Event { location: Location::new(2, 0, 2), kind: EventKind::End("CARD"), end: None },
     ],
     events
   );
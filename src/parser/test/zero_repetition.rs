@@ -1,7 +1,8 @@
 use crate::parser::test::{assert_unmatch, location, Events};
-use crate::parser::{Context, Event};
-use crate::schema::chars::{ascii_alphabetic, ascii_digit};
-use crate::schema::Schema;
+use crate::parser::{Context, Engine, Event};
+use crate::schema::chars::{ascii_alphabetic, ascii_digit, ch};
+use crate::schema::{id, Schema};
+use crate::Error;
 
 #[test]
 fn context_zero_repetition_at_the_beginning() {
@@ -41,7 +42,7 @@ fn context_zero_repetition_option() {
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
-  assert_unmatch(parser.push('X'), location(0, 0, 0), "", "[ASCII_DIGIT?]", "['X']...");
+  assert_unmatch(parser.push('X').and_then(|_| parser.finish()), location(0, 0, 0), "", "[ASCII_DIGIT?]", "['X']...");
 }
 
 #[test]
@@ -70,7 +71,7 @@ fn context_zero_repetition_precedes() {
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
   parser.push('9').unwrap();
-  assert_unmatch(parser.push('8'), location(1, 0, 1), "9", "[ASCII_ALPHA]", "['8']...");
+  assert_unmatch(parser.push('8').and_then(|_| parser.finish()), location(1, 0, 1), "9", "[ASCII_ALPHA]", "['8']...");
 }
 
 #[test]
@@ -92,13 +93,13 @@ fn context_zero_repetition_trailing() {
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
-  assert_unmatch(parser.push('9'), location(0, 0, 0), "", "[ASCII_ALPHA]", "['9']...");
+  assert_unmatch(parser.push('9').and_then(|_| parser.finish()), location(0, 0, 0), "", "[ASCII_ALPHA]", "['9']...");
 
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
   parser.push('X').unwrap();
-  assert_unmatch(parser.push('Y'), location(1, 0, 1), "X", "[ASCII_DIGIT?]", "['Y']...");
+  assert_unmatch(parser.push('Y').and_then(|_| parser.finish()), location(1, 0, 1), "X", "[ASCII_DIGIT?]", "['Y']...");
 }
 
 #[test]
@@ -120,19 +121,19 @@ fn context_zero_repetition_sequence() {
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
-  assert_unmatch(parser.push('!'), location(0, 0, 0), "", "[ASCII_ALPHA?]", "['!']...");
+  assert_unmatch(parser.push('!').and_then(|_| parser.finish()), location(0, 0, 0), "", "[ASCII_ALPHA?]", "['!']...");
 
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
   parser.push('9').unwrap();
-  assert_unmatch(parser.push('!'), location(1, 0, 1), "9", "[ASCII_ALPHA?]", "['!']...");
+  assert_unmatch(parser.push('!').and_then(|_| parser.finish()), location(1, 0, 1), "9", "[ASCII_ALPHA?]", "['!']...");
 
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
   parser.push('Z').unwrap();
-  assert_unmatch(parser.push('!'), location(1, 0, 1), "Z", "[EOF]", "['!']...");
+  assert_unmatch(parser.push('!').and_then(|_| parser.finish()), location(1, 0, 1), "Z", "[EOF]", "['!']...");
 }
 
 #[test]
@@ -155,21 +156,21 @@ fn context_zero_repetition_injected() {
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
   parser.push('A').unwrap();
-  assert_unmatch(parser.push('!'), location(1, 0, 1), "A", "[ASCII_ALPHA]", "['!']...");
+  assert_unmatch(parser.push('!').and_then(|_| parser.finish()), location(1, 0, 1), "A", "[ASCII_ALPHA]", "['!']...");
 
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
   parser.push('A').unwrap();
   parser.push('0').unwrap();
-  assert_unmatch(parser.push('!'), location(2, 0, 2), "A0", "[ASCII_ALPHA]", "['!']...");
+  assert_unmatch(parser.push('!').and_then(|_| parser.finish()), location(2, 0, 2), "A0", "[ASCII_ALPHA]", "['!']...");
 
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
   parser.push('A').unwrap();
   parser.push('0').unwrap();
-  assert_unmatch(parser.push('1'), location(2, 0, 2), "A0", "[ASCII_ALPHA]", "['1']...");
+  assert_unmatch(parser.push('1').and_then(|_| parser.finish()), location(2, 0, 2), "A0", "[ASCII_ALPHA]", "['1']...");
 }
 
 #[test]
@@ -196,7 +197,7 @@ fn context_zero_repetition_caught_between() {
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
-  assert_unmatch(parser.push('!'), location(0, 0, 0), "", "[ASCII_ALPHA]", "['!']...");
+  assert_unmatch(parser.push('!').and_then(|_| parser.finish()), location(0, 0, 0), "", "[ASCII_ALPHA]", "['!']...");
 
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
@@ -208,33 +209,33 @@ fn context_zero_repetition_caught_between() {
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
   parser.push('0').unwrap();
-  assert_unmatch(parser.push('1'), location(1, 0, 1), "0", "[ASCII_ALPHA]", "['1']...");
+  assert_unmatch(parser.push('1').and_then(|_| parser.finish()), location(1, 0, 1), "0", "[ASCII_ALPHA]", "['1']...");
 
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
   parser.push('0').unwrap();
-  assert_unmatch(parser.push('!'), location(1, 0, 1), "0", "[ASCII_ALPHA]", "['!']...");
+  assert_unmatch(parser.push('!').and_then(|_| parser.finish()), location(1, 0, 1), "0", "[ASCII_ALPHA]", "['!']...");
 
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
   parser.push('0').unwrap();
-  assert_unmatch(parser.push('1'), location(1, 0, 1), "0", "[ASCII_ALPHA]", "['1']...");
+  assert_unmatch(parser.push('1').and_then(|_| parser.finish()), location(1, 0, 1), "0", "[ASCII_ALPHA]", "['1']...");
 
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
   parser.push('0').unwrap();
   parser.push('A').unwrap();
-  assert_unmatch(parser.push('B'), location(2, 0, 2), "0A", "[ASCII_DIGIT?]", "['B']...");
+  assert_unmatch(parser.push('B').and_then(|_| parser.finish()), location(2, 0, 2), "0A", "[ASCII_DIGIT?]", "['B']...");
 
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
   let mut parser = Context::new(&schema, "A", handler).unwrap();
   parser.push('0').unwrap();
   parser.push('A').unwrap();
-  assert_unmatch(parser.push('!'), location(2, 0, 2), "0A", "[ASCII_DIGIT?]", "['!']...");
+  assert_unmatch(parser.push('!').and_then(|_| parser.finish()), location(2, 0, 2), "0A", "[ASCII_DIGIT?]", "['!']...");
 
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
@@ -242,7 +243,7 @@ fn context_zero_repetition_caught_between() {
   parser.push('0').unwrap();
   parser.push('A').unwrap();
   parser.push('1').unwrap();
-  assert_unmatch(parser.push('2'), location(3, 0, 3), "0A1", "[EOF]", "['2']...");
+  assert_unmatch(parser.push('2').and_then(|_| parser.finish()), location(3, 0, 3), "0A1", "[EOF]", "['2']...");
 
   let mut events = Vec::new();
   let handler = |e: &Event<_, _>| events.push(e.clone());
@@ -250,5 +251,144 @@ fn context_zero_repetition_caught_between() {
   parser.push('0').unwrap();
   parser.push('A').unwrap();
   parser.push('1').unwrap();
-  assert_unmatch(parser.push('!'), location(3, 0, 3), "0A1", "[EOF]", "['!']...");
+  assert_unmatch(parser.push('!').and_then(|_| parser.finish()), location(3, 0, 3), "0A1", "[EOF]", "['!']...");
+}
+
+#[test]
+fn context_repetition_failed_on_the_way() {
+  let ab = || (ch('a') & ch('b')) * (0..);
+  let schema = Schema::new("Foo").define("A", ab() & ch('a') & ch('c')).define("B", ab() & ch('c'));
+
+  for chars in ["ac", "abac", "ababac"] {
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| events.push(e.clone());
+    let mut parser = Context::new(&schema, "A", handler).unwrap();
+    parser.push_str(chars).unwrap();
+    parser.finish().unwrap();
+    Events::new().begin("A").fragments(chars).end().assert_eq(&events);
+  }
+
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "B", handler).unwrap();
+  assert_unmatch(parser.push_str("ac").and_then(|_| parser.finish()), location(1, 0, 1), "a", "['b']", "['c']...");
+}
+
+#[test]
+fn context_empty_input_of_nullable_rule() {
+  let schema = Schema::new("Foo").define("A", id("B") * (0..)).define("B", ascii_digit() & (ch(',') * (0..=1)));
+  assert!(schema.is_nullable_rule(&"A"));
+
+  for engine in [Engine::default(), Engine::Earley] {
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| events.push(e.clone());
+    let parser = Context::new(&schema, "A", handler).unwrap().with_engine(engine);
+    parser.finish().unwrap();
+    Events::new().begin("A").end().assert_eq(&events);
+
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| events.push(e.clone());
+    let mut parser = Context::new(&schema, "A", handler).unwrap().with_engine(engine).accept_empty(false);
+    parser.push_str("").unwrap();
+    assert_unmatch(parser.finish(), location(0, 0, 0), "", "[B*]", "[EOF]");
+    assert!(events.is_empty());
+
+    // an input that consists of only nullable syntaxes isn't empty
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| events.push(e.clone());
+    let mut parser = Context::new(&schema, "A", handler).unwrap().with_engine(engine).accept_empty(false);
+    parser.push_str("1,2").unwrap();
+    parser.finish().unwrap();
+    Events::new().begin("A").begin("B").fragments("1,").end().begin("B").fragments("2").end().end().assert_eq(&events);
+  }
+
+  // the empty input of a non-nullable rule fails regardless of the flag
+  let parser = Context::new(&schema, "B", |_: &Event<_, _>| ()).unwrap();
+  assert_unmatch(parser.finish(), location(0, 0, 0), "", "[ASCII_DIGIT]", "[EOF]");
+}
+
+#[test]
+fn context_repeated_rule_failed_on_the_way() {
+  // the path that omits PAIR is forked when it begins, since a failed iteration cannot go back
+  let schema =
+    Schema::new("Foo").define("LIST", (id("PAIR") * (0..)) & ch('a') & ch('c')).define("PAIR", ch('a') & ch('b'));
+  for (chars, pairs) in [("ac", 0), ("abac", 1), ("ababac", 2)] {
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| events.push(e.clone());
+    let mut parser = Context::new(&schema, "LIST", handler).unwrap();
+    parser.push_str(chars).unwrap();
+    parser.finish().unwrap();
+    let mut expected = Events::new().begin("LIST");
+    for _ in 0..pairs {
+      expected = expected.begin("PAIR").fragments("ab").end();
+    }
+    expected.fragments("ac").end().assert_eq(&events);
+  }
+
+  // the failed paths keep the frames of the rule they failed in
+  let mut parser = Context::new(&schema, "LIST", |_: &Event<_, _>| ()).unwrap();
+  match parser.push_str("abad").and_then(|_| parser.finish()) {
+    Err(Error::Unmatched(detail)) => {
+      assert_eq!(location(3, 0, 3), detail.location);
+      assert_eq!(vec!["['c']", "['b']"], detail.expecteds);
+      assert_eq!(vec!["['c']", "[PAIR*]>>['b']"], detail.expected_syntaxes);
+    }
+    unexpected => panic!("{:?}", unexpected),
+  }
+}
+
+#[test]
+fn context_empty_iteration_not_repeated() {
+  let schema = Schema::new("Foo").define("A", (id("E") * (0..)) & ch('x')).define("E", ch('e') * (0..));
+  for chars in ["x", "ex", "eeex"] {
+    let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+    parser.push_str(chars).unwrap();
+    parser.finish().unwrap();
+  }
+}
+
+#[test]
+fn context_optional_sequences_not_merged() {
+  // `(a, b)?, (c, d)?` is two options, not a single option of `a, b, c, d`
+  let schema = Schema::new("Foo")
+    .define("A", ((ch('a') & ch('b')) * (0..=1)) & ((ch('c') & ch('d')) * (0..=1)))
+    .define("B", ((ch('a') | ch('b')) * (0..)) | (ch('c') * (0..)));
+  for engine in [Engine::default(), Engine::Earley] {
+    for (id, text) in [("A", ""), ("A", "ab"), ("A", "cd"), ("A", "abcd"), ("B", "abba"), ("B", "ccc")] {
+      let mut events = Vec::new();
+      let handler = |e: &Event<_, _>| events.push(e.clone());
+      let mut parser = Context::new(&schema, id, handler).unwrap().with_engine(engine);
+      parser.push_str(text).unwrap();
+      parser.finish().unwrap();
+      Events::new().begin(id).fragments(text).end().assert_eq(&events);
+    }
+
+    let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap().with_engine(engine);
+    assert!(parser.push_str("abc").and_then(|_| parser.finish().map(|_| ())).is_err());
+    let mut parser = Context::new(&schema, "B", |_: &Event<_, _>| ()).unwrap().with_engine(engine);
+    assert!(parser.push_str("abc").and_then(|_| parser.finish().map(|_| ())).is_err());
+  }
+}
+
+#[test]
+fn context_repetition_not_folded_into_neighbour() {
+  // `a*, (b, c)*` and `a* | (b | c)*` don't repeat the neighbour of the repeated sequence or choice
+  let schema = Schema::new("Foo")
+    .define("A", (ch('a') * (0..)) & ((ch('b') & ch('c')) * (0..)))
+    .define("B", (ch('a') * (0..)) | ((ch('b') | ch('c')) * (0..)));
+  for engine in [Engine::default(), Engine::Earley] {
+    for (id, text) in [("A", ""), ("A", "aa"), ("A", "bcbc"), ("A", "aabc"), ("B", "aa"), ("B", "bcb")] {
+      let mut events = Vec::new();
+      let handler = |e: &Event<_, _>| events.push(e.clone());
+      let mut parser = Context::new(&schema, id, handler).unwrap().with_engine(engine);
+      parser.push_str(text).unwrap();
+      parser.finish().unwrap();
+      Events::new().begin(id).fragments(text).end().assert_eq(&events);
+    }
+
+    let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap().with_engine(engine);
+    assert!(parser.push_str("abca").and_then(|_| parser.finish().map(|_| ())).is_err());
+    let mut parser = Context::new(&schema, "B", |_: &Event<_, _>| ()).unwrap().with_engine(engine);
+    assert!(parser.push_str("aba").and_then(|_| parser.finish().map(|_| ())).is_err());
+  }
 }
@@ -0,0 +1,122 @@
+//! The helpers to test the schemas that parse text, by comparing the events delivered by a [`Context`] with the ones
+//! built by [`Events`].
+//!
+//! ```rust
+//! use terp::prelude::*;
+//! use terp::schema::chars::{ascii_digit, ch};
+//!
+//! let schema = Schema::new("Pair")
+//!   .define("PAIR", id("NUM") & ch(',') & id("NUM"))
+//!   .define("NUM", ascii_digit() * (1..));
+//! let mut events = Vec::new();
+//! let mut parser = Context::new(&schema, "PAIR", |e: &Event<_, _>| events.push(e.clone())).unwrap();
+//! parser.push_str("12,345").unwrap();
+//! parser.finish().unwrap();
+//! Events::new()
+//!   .begin("PAIR")
+//!   .begin("NUM")
+//!   .fragments("12")
+//!   .end()
+//!   .fragments(",")
+//!   .begin("NUM")
+//!   .fragments("345")
+//!   .end()
+//!   .end()
+//!   .assert_eq(&events);
+//! ```
+//!
+//! [`Context`]: crate::parser::Context
+//!
+use crate::parser::{Event, EventKind};
+use crate::schema::chars;
+use crate::schema::Location;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+/// The builder of the events expected from a text, whose locations are counted from the fragments in order.
+///
+pub struct Events<ID: Clone + Display + Debug + Eq + Hash> {
+  location: chars::Location,
+  events: Vec<Event<ID, char>>,
+  stack: Vec<ID>,
+}
+
+impl<ID: Clone + Display + Debug + Eq + Hash> Events<ID> {
+  pub fn new() -> Self {
+    let events = Vec::with_capacity(16);
+    let stack = Vec::with_capacity(4);
+    Self { location: chars::Location::default(), events, stack }
+  }
+
+  pub fn begin(mut self, id: ID) -> Self {
+    self.stack.push(id.clone());
+    self.events.push(Event { location: self.location, kind: EventKind::Begin(id), end: None });
+    self
+  }
+
+  /// End the syntax begun last.
+  ///
+  /// # Panics
+  ///
+  /// If no syntax has begun.
+  ///
+  pub fn end(mut self) -> Self {
+    let id = self.stack.pop().expect("`end()` without `begin()` in expected events building");
+    self.events.push(Event { location: self.location, kind: EventKind::End(id), end: None });
+    self
+  }
+
+  pub fn fragments(mut self, text: &str) -> Self {
+    for ch in text.chars() {
+      self.events.push(Event { location: self.location, kind: EventKind::Fragments(vec![ch]), end: None });
+      self.location.increment_with(ch);
+    }
+    self
+  }
+
+  /// The events built so far.
+  ///
+  /// # Panics
+  ///
+  /// If a syntax hasn't ended.
+  ///
+  pub fn to_vec(&self) -> Vec<Event<ID, char>> {
+    assert!(self.stack.is_empty(), "`end()` missing in expected events building: {:?}", self.stack);
+    self.events.clone()
+  }
+
+  #[cfg(test)]
+  pub(crate) fn to_event_buffer(&self) -> crate::parser::EventBuffer<ID, char> {
+    let mut buffer = crate::parser::EventBuffer::new(self.events.len());
+    for e in &self.events {
+      buffer.push(e.clone());
+    }
+    buffer
+  }
+
+  /// Assert that the `actual` events are the same as the built ones, regardless of how the fragments are split.
+  ///
+  pub fn assert_eq(&self, actual: &[Event<ID, char>]) {
+    assert_events_eq(&self.to_vec(), actual);
+  }
+}
+
+impl<ID: Clone + Display + Debug + Eq + Hash> Default for Events<ID> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Assert that the `expected` and the `actual` events are the same after they're [normalized](Event::normalize), i.e.,
+/// regardless of how the fragments are split.
+///
+pub fn assert_events_eq<ID: Clone + Display + Debug + Eq + Hash>(
+  expected: &[Event<ID, char>], actual: &[Event<ID, char>],
+) {
+  let expected = Event::normalize(expected);
+  let actual = Event::normalize(actual);
+  let len = std::cmp::max(expected.len(), actual.len());
+  for i in 0..len {
+    assert_eq!(expected.get(i), actual.get(i), "unexpected event @{}:\n  {:?}\n  {:?}", i, expected, actual);
+  }
+}
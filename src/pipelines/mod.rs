@@ -0,0 +1,233 @@
+//! Transformers that consume the events of a schema and write the input in another format to a [`Sink`] while
+//! parsing, such as CSV to JSON.
+//!
+//! ```rust
+//! use terp::pipelines::csv_to_json;
+//! assert_eq!(r#"[["a","b"],["1","2,3"]]"#, csv_to_json("a,b\n1,\"2,3\"\n", false).unwrap());
+//! assert_eq!(r#"[{"a":"1","b":"2,3"}]"#, csv_to_json("a,b\n1,\"2,3\"\n", true).unwrap());
+//! ```
+//!
+use crate::parser::{Context, Event, EventKind};
+use crate::schema::{csv, ini};
+use crate::Result;
+
+#[cfg(test)]
+mod test;
+
+/// The destination of the text written by a transformer.
+///
+pub trait Sink {
+  fn write_str(&mut self, text: &str);
+}
+
+impl Sink for String {
+  fn write_str(&mut self, text: &str) {
+    self.push_str(text);
+  }
+}
+
+/// Transform the events of [`csv::schema()`] into a JSON array of records, where each record is an array of fields, or
+/// an object keyed by the fields of the first record if `header` is specified. The fields lacking a header are
+/// dropped. Each record is written as soon as it's parsed.
+///
+/// The events of [`csv::ID::Field`], [`csv::ID::Comma`] and [`csv::ID::Newline`] must not be ignored.
+///
+pub struct CsvToJson<S: Sink> {
+  sink: S,
+  header: Option<Vec<String>>,
+  use_header: bool,
+  records: usize,
+  record: Vec<String>,
+  field: String,
+  in_field: bool,
+}
+
+impl<S: Sink> CsvToJson<S> {
+  pub fn new(sink: S) -> Self {
+    Self {
+      sink,
+      header: None,
+      use_header: false,
+      records: 0,
+      record: Vec::new(),
+      field: String::new(),
+      in_field: false,
+    }
+  }
+
+  pub fn with_header(self, header: bool) -> Self {
+    Self { use_header: header, ..self }
+  }
+
+  pub fn handle(&mut self, e: &Event<csv::ID, char>) {
+    match &e.kind {
+      EventKind::Begin(csv::ID::Field) => self.in_field = true,
+      EventKind::Fragments(chars) if self.in_field => self.field.extend(chars),
+      EventKind::End(csv::ID::Field) => self.in_field = false,
+      EventKind::End(csv::ID::Comma) => self.complete_field(),
+      EventKind::End(csv::ID::Newline) => {
+        self.complete_field();
+        self.complete_record();
+      }
+      _ => (),
+    }
+  }
+
+  /// Write the last record and the end of the array, and return the sink. The empty record following the line break
+  /// at the end of the input is ignored.
+  ///
+  pub fn finish(mut self) -> S {
+    self.complete_field();
+    let trailing = (self.records > 0 || self.header.is_some()) && self.record.len() == 1 && self.record[0].is_empty();
+    if !trailing {
+      self.complete_record();
+    }
+    self.sink.write_str(if self.records == 0 { "[]" } else { "]" });
+    self.sink
+  }
+
+  fn complete_field(&mut self) {
+    let field = std::mem::take(&mut self.field);
+    let field = match field.strip_prefix('"').and_then(|f| f.strip_suffix('"')) {
+      Some(escaped) => escaped.replace("\"\"", "\""),
+      None => field,
+    };
+    self.record.push(field);
+  }
+
+  fn complete_record(&mut self) {
+    let record = std::mem::take(&mut self.record);
+    if self.use_header && self.header.is_none() {
+      self.header = Some(record);
+      return;
+    }
+    self.sink.write_str(if self.records == 0 { "[" } else { "," });
+    self.records += 1;
+    match &self.header {
+      Some(header) => {
+        let members = header.iter().zip(record.iter()).map(|(k, v)| format!("{}:{}", json_string(k), json_string(v)));
+        self.sink.write_str(&format!("{{{}}}", members.collect::<Vec<_>>().join(",")));
+      }
+      None => {
+        let values = record.iter().map(|v| json_string(v)).collect::<Vec<_>>();
+        self.sink.write_str(&format!("[{}]", values.join(",")));
+      }
+    }
+  }
+}
+
+/// Transform the events of [`ini::schema()`] into a JSON object, where the properties before the first section are
+/// its members, and each section is an object of its properties. The trailing whitespace of the values are removed.
+///
+/// The events of [`ini::ID::Section`], [`ini::ID::Name`], [`ini::ID::Property`], [`ini::ID::Key`] and
+/// [`ini::ID::Value`] must not be ignored.
+///
+pub struct IniToJson<S: Sink> {
+  sink: S,
+  members: usize,
+  in_section: bool,
+  text: Option<String>,
+  key: String,
+  value: String,
+}
+
+impl<S: Sink> IniToJson<S> {
+  pub fn new(sink: S) -> Self {
+    Self { sink, members: 0, in_section: false, text: None, key: String::new(), value: String::new() }
+  }
+
+  pub fn handle(&mut self, e: &Event<ini::ID, char>) {
+    match &e.kind {
+      EventKind::Begin(ini::ID::Name | ini::ID::Key | ini::ID::Value) => self.text = Some(String::new()),
+      EventKind::Begin(ini::ID::Property) => self.value.clear(),
+      EventKind::Fragments(chars) => {
+        if let Some(text) = &mut self.text {
+          text.extend(chars);
+        }
+      }
+      EventKind::End(ini::ID::Key) => self.key = self.text.take().unwrap_or_default(),
+      EventKind::End(ini::ID::Value) => self.value = self.text.take().unwrap_or_default().trim_end().to_string(),
+      EventKind::End(ini::ID::Property) => {
+        let member = format!("{}:{}", json_string(&self.key), json_string(&self.value));
+        self.write_member(&member);
+      }
+      EventKind::End(ini::ID::Name) => {
+        let name = self.text.take().unwrap_or_default();
+        if self.in_section {
+          self.sink.write_str("}");
+        }
+        self.write_member(&format!("{}:{{", json_string(&name)));
+        self.in_section = true;
+        self.members = 0;
+      }
+      _ => (),
+    }
+  }
+
+  /// Write the end of the object, and return the sink.
+  ///
+  pub fn finish(mut self) -> S {
+    if self.in_section {
+      self.sink.write_str("}");
+    } else if self.members == 0 {
+      self.sink.write_str("{");
+    }
+    self.sink.write_str("}");
+    self.sink
+  }
+
+  fn write_member(&mut self, member: &str) {
+    let separator = match (self.members, self.in_section) {
+      (0, true) => "",
+      (0, false) => "{",
+      _ => ",",
+    };
+    self.sink.write_str(separator);
+    self.sink.write_str(member);
+    self.members += 1;
+  }
+}
+
+/// Parse the CSV `text` and return it as JSON, see [`CsvToJson`].
+///
+pub fn csv_to_json(text: &str, header: bool) -> Result<char, String> {
+  use csv::ID::*;
+  let schema = csv::schema();
+  let mut transformer = CsvToJson::new(String::new()).with_header(header);
+  let mut parser = Context::new(&schema, File, |e: &Event<_, _>| transformer.handle(e))?
+    .ignore_events_for(&[Escaped, NonEscaped, DQuote, TextData]);
+  parser.push_str(text)?;
+  parser.finish()?;
+  Ok(transformer.finish())
+}
+
+/// Parse the INI `text` and return it as JSON, see [`IniToJson`].
+///
+pub fn ini_to_json(text: &str) -> Result<char, String> {
+  use ini::ID::*;
+  let schema = ini::schema();
+  let mut transformer = IniToJson::new(String::new());
+  let mut parser = Context::new(&schema, File, |e: &Event<_, _>| transformer.handle(e))?
+    .ignore_events_for(&[Line, Comment, WS, Newline]);
+  parser.push_str(text)?;
+  parser.finish()?;
+  Ok(transformer.finish())
+}
+
+pub(crate) fn json_string(text: &str) -> String {
+  let mut json = String::with_capacity(text.len() + 2);
+  json.push('"');
+  for ch in text.chars() {
+    match ch {
+      '"' => json.push_str("\\\""),
+      '\\' => json.push_str("\\\\"),
+      '\n' => json.push_str("\\n"),
+      '\r' => json.push_str("\\r"),
+      '\t' => json.push_str("\\t"),
+      ch if (ch as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", ch as u32)),
+      ch => json.push(ch),
+    }
+  }
+  json.push('"');
+  json
+}
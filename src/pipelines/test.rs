@@ -0,0 +1,60 @@
+use super::{csv_to_json, ini_to_json, CsvToJson, Sink};
+use crate::parser::{Context, Event};
+use crate::schema::csv;
+
+#[test]
+fn csv_records_to_json() {
+  for (text, expected) in [
+    ("", r#"[[""]]"#),
+    ("a", r#"[["a"]]"#),
+    ("a,b\r\n1,2\r\n", r#"[["a","b"],["1","2"]]"#),
+    ("a,,\"x\"\"y\"\n\n\"multi\nline\"", r#"[["a","","x\"y"],[""],["multi\nline"]]"#),
+  ] {
+    assert_eq!(expected, csv_to_json(text, false).unwrap(), "{:?}", text);
+  }
+  assert!(csv_to_json("a,\"b", false).is_err());
+}
+
+#[test]
+fn csv_records_with_header_to_json() {
+  for (text, expected) in
+    [("a,b\n", "[]"), ("a,b\n1,2\n3\n4,5,6\n", r#"[{"a":"1","b":"2"},{"a":"3"},{"a":"4","b":"5"}]"#)]
+  {
+    assert_eq!(expected, csv_to_json(text, true).unwrap(), "{:?}", text);
+  }
+}
+
+#[test]
+fn csv_records_written_while_parsing() {
+  struct Chunks(Vec<String>);
+  impl Sink for Chunks {
+    fn write_str(&mut self, text: &str) {
+      self.0.push(text.to_string());
+    }
+  }
+
+  let schema = csv::schema();
+  let mut transformer = CsvToJson::new(Chunks(Vec::new()));
+  let mut parser = Context::new(&schema, csv::ID::File, |e: &Event<_, _>| transformer.handle(e)).unwrap();
+  parser.push_str("a,b\n1,").unwrap();
+  parser.push_str("2\n").unwrap();
+  parser.finish().unwrap();
+  assert_eq!(vec!["[", "[\"a\",\"b\"]", ",", "[\"1\",\"2\"]", "]"], transformer.finish().0);
+}
+
+#[test]
+fn ini_to_json_objects() {
+  for (text, expected) in [
+    ("", "{}"),
+    ("; nothing\n", "{}"),
+    ("name = terp \n", r#"{"name":"terp"}"#),
+    ("[empty]\n", r#"{"empty":{}}"#),
+    (
+      "top=1\n[server]\nhost = example.com\n# comment\n  port=8080\n[client]\nretry =\n",
+      r#"{"top":"1","server":{"host":"example.com","port":"8080"},"client":{"retry":""}}"#,
+    ),
+  ] {
+    assert_eq!(expected, ini_to_json(text).unwrap(), "{:?}", text);
+  }
+  assert!(ini_to_json("[server\n").is_err());
+}
@@ -0,0 +1,31 @@
+//! The types and functions that most parsers need, so that they're imported at once with `use terp::prelude::*`.
+//! The items exported here are the stable surface of terp: they change only with a major version, while the
+//! engine behind [`Context`] is refactored freely. The symbol-specific matchers such as
+//! [`ch()`](crate::schema::chars::ch) are imported from their own modules.
+//!
+//! ```rust
+//! use terp::prelude::*;
+//! use terp::schema::chars::{ascii_digit, ch};
+//!
+//! let schema = Schema::new("Pair")
+//!   .define("PAIR", id("NUM") & ch(',') & id("NUM"))
+//!   .define("NUM", ascii_digit() * (1..));
+//! let mut nums = Vec::new();
+//! let mut parser = Context::new(&schema, "PAIR", |e: &Event<_, _>| {
+//!   if let EventKind::Begin(id) = e.kind {
+//!     nums.push((id, e.location.position()));
+//!   }
+//! })
+//! .unwrap();
+//! parser.push_str("12,345").unwrap();
+//! parser.finish().unwrap();
+//! assert_eq!(vec![("PAIR", 0), ("NUM", 0), ("NUM", 3)], nums);
+//! ```
+//!
+pub use crate::parser::testing::{assert_events_eq, Events};
+pub use crate::parser::{Context, Engine, Event, EventKind, Ignore, Limits, ParseSummary, Redaction, Sampling};
+pub use crate::schema::Location as _;
+pub use crate::schema::{
+  eof, id, none_of, one_of, one_of_seqs, range, seq, single, MatchCtx, MatchResult, Schema, Symbol, Syntax,
+};
+pub use crate::{Error, ErrorDetail, HandlerError, HandlerPanic, Result};
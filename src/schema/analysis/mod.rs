@@ -0,0 +1,364 @@
+//! Static analysis of a [`Schema`] that can be performed without any input. [`Schema::estimate()`] reports metrics of
+//! the syntaxes reachable from a start ID, and warns of the constructs that are likely to make parsing super-linear.
+//! [`Schema::terminals()`] lists the terms, so that tooling such as syntax highlighting definitions can be generated
+//! from a schema. [`Schema::nullable_rules()`] lists the rules that can match an empty sequence.
+//!
+use crate::schema::{MatchCtx, MatchResult, Primary, Schema, Symbol, Syntax};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::{Debug, Display};
+
+#[cfg(test)]
+mod test;
+
+/// The number of paths that may be forked at a single position, above which [`Warning::HighBranching`] is reported.
+///
+pub const BRANCHING_WARNING_THRESHOLD: usize = 32;
+
+/// The static metrics of the syntaxes reachable from a start ID.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Estimate<ID> {
+  /// The number of rules reachable from the start ID, including itself.
+  pub rules: usize,
+  /// The maximum number of branches of a single `|` choice.
+  pub max_or_fanout: usize,
+  /// The rules that can match an empty sequence.
+  pub nullables: Vec<ID>,
+  /// The worst-case number of paths forked at a single position, that is, the product of the numbers of the branches
+  /// of the choices nested at the beginning of a syntax.
+  pub max_branching: usize,
+  pub warnings: Vec<Warning<ID>>,
+}
+
+impl<ID> Estimate<ID> {
+  /// Whether the parsing is likely to be super-linear, or not to terminate.
+  ///
+  pub fn is_likely_super_linear(&self) -> bool {
+    !self.warnings.is_empty()
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Warning<ID> {
+  /// The rule is referenced but not defined.
+  UndefinedID(ID),
+  /// The rule can reach itself without consuming any symbol, so that its evaluation never reaches a term. The direct
+  /// left recursion supported by [`Schema::define()`] isn't reported.
+  LeftRecursion(ID),
+  /// The rule contains a repetition of a syntax that can match an empty sequence.
+  NullableRepetition(ID),
+  /// The rule contains an unbounded repetition of another unbounded repetition, so that the same input can be split
+  /// into iterations in exponentially many ways.
+  NestedRepetition(ID),
+  /// The rule forks more paths at a single position than [`BRANCHING_WARNING_THRESHOLD`].
+  HighBranching(ID, usize),
+}
+
+impl<ID: Display> Display for Warning<ID> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Warning::UndefinedID(id) => write!(f, "{} is not defined", id),
+      Warning::LeftRecursion(id) => write!(f, "{} is left-recursive", id),
+      Warning::NullableRepetition(id) => write!(f, "{} repeats a syntax that can match empty", id),
+      Warning::NestedRepetition(id) => write!(f, "{} nests unbounded repetitions", id),
+      Warning::HighBranching(id, n) => write!(f, "{} forks {} paths at a single position", id, n),
+    }
+  }
+}
+
+/// A terminal matcher of a [`Schema`], listed by [`Schema::terminals()`].
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TerminalInfo<'s, ID> {
+  /// The label of the term, such as `'x'` or `ASCII_DIGIT`, which is also used in the error messages.
+  pub label: &'s str,
+  /// The rules whose definitions use this term, in the order of their definitions.
+  pub rules: Vec<&'s ID>,
+}
+
+impl<ID: PartialEq, Σ: Symbol> Schema<ID, Σ> {
+  /// The terminal matchers used in the definitions, in the order of their first appearances. The identical terms that
+  /// share a matcher, such as the same [`ch()`](crate::schema::chars::ch) in several rules, are listed once.
+  ///
+  /// ```rust
+  /// use terp::schema::{Schema, id};
+  /// use terp::schema::chars::{ch, ascii_digit};
+  /// let schema = Schema::new("List")
+  ///   .define("List", ch('[') & id("Item") & ((ch(',') & id("Item")) * (0..)) & ch(']'))
+  ///   .define("Item", ascii_digit() * (1..));
+  /// let labels = schema.terminals().map(|t| t.label).collect::<Vec<_>>();
+  /// assert_eq!(vec!["'['", "','", "']'", "ASCII_DIGIT"], labels);
+  /// ```
+  ///
+  pub fn terminals(&self) -> impl Iterator<Item = TerminalInfo<'_, ID>> {
+    let mut terminals: Vec<TerminalInfo<'_, ID>> = Vec::new();
+    let mut indices = HashMap::new();
+    for (id, syntax) in self.definitions() {
+      visit(syntax, &mut |s| {
+        if let Primary::Term(term) = &s.primary {
+          let matcher = std::sync::Arc::as_ptr(&term.matcher) as *const ();
+          let i = *indices.entry(matcher).or_insert_with(|| {
+            terminals.push(TerminalInfo { label: &term.label, rules: Vec::new() });
+            terminals.len() - 1
+          });
+          if terminals[i].rules.last() != Some(&id) {
+            terminals[i].rules.push(id);
+          }
+        }
+      });
+    }
+    terminals.into_iter()
+  }
+}
+
+impl<ID: Clone + Ord + Display + Debug, Σ: 'static + Symbol> Schema<ID, Σ> {
+  /// Estimate the complexity of parsing with the rule `start`.
+  ///
+  pub fn estimate(&self, start: &ID) -> Estimate<ID> {
+    let mut warnings = Vec::new();
+
+    // the rules reachable from the start
+    let mut rules = BTreeSet::new();
+    let mut undefined = BTreeSet::new();
+    let mut queue = vec![start.clone()];
+    while let Some(id) = queue.pop() {
+      if rules.contains(&id) || undefined.contains(&id) {
+        continue;
+      }
+      if let Some(syntax) = self.defs.get(&id) {
+        visit_aliases(syntax, &mut |id| queue.push(id.clone()));
+        rules.insert(id);
+      } else {
+        undefined.insert(id);
+      }
+    }
+    warnings.extend(undefined.into_iter().map(Warning::UndefinedID));
+
+    let nullables = self.nullables(&rules);
+
+    let mut max_or_fanout = 0;
+    let mut max_branching = 0;
+    let mut branchings = BTreeMap::new();
+    for id in &rules {
+      let syntax = &self.defs[id];
+      visit(syntax, &mut |s| {
+        if let Primary::Or(branches) = &s.primary {
+          max_or_fanout = std::cmp::max(max_or_fanout, branches.len());
+        }
+      });
+
+      let mut leftmosts = BTreeSet::new();
+      let mut queue = vec![id];
+      while let Some(id) = queue.pop() {
+        if let Some(syntax) = self.defs.get(id) {
+          for id in self.leftmost_aliases(syntax, &nullables) {
+            if leftmosts.insert(id.clone()) {
+              queue.push(id);
+            }
+          }
+        }
+      }
+      if leftmosts.contains(id) {
+        warnings.push(Warning::LeftRecursion(id.clone()));
+      }
+
+      let mut nullable_repetition = false;
+      let mut nested_repetition = false;
+      visit(syntax, &mut |s| {
+        if *s.repetition.end() > 1 && !matches!(s.primary, Primary::Term(..)) {
+          nullable_repetition |= self.is_primary_nullable(&s.primary, &nullables);
+          nested_repetition |= *s.repetition.end() == usize::MAX
+            && self.is_unbounded_repetition_only(&s.primary, &nullables, &mut BTreeSet::new());
+        }
+      });
+      if nullable_repetition {
+        warnings.push(Warning::NullableRepetition(id.clone()));
+      }
+      if nested_repetition {
+        warnings.push(Warning::NestedRepetition(id.clone()));
+      }
+
+      let branching = self.branching(syntax, &nullables, &mut branchings, &mut BTreeSet::new());
+      max_branching = std::cmp::max(max_branching, branching);
+      if branching > BRANCHING_WARNING_THRESHOLD {
+        warnings.push(Warning::HighBranching(id.clone(), branching));
+      }
+    }
+
+    Estimate { rules: rules.len(), max_or_fanout, nullables: nullables.into_iter().collect(), max_branching, warnings }
+  }
+
+  /// The defined rules that can match an empty sequence, in the order of their definitions. A rule is nullable if
+  /// its definition is a repetition with the minimum of zero such as `x * (0..)`, a sequence of nullable syntaxes, a
+  /// choice with a nullable branch, a reference to a nullable rule, or a term that matches without any symbol.
+  ///
+  /// A [`Context`](crate::parser::Context) for a nullable rule accepts an empty input with only the Begin and End
+  /// events of the rule, unless [`accept_empty(false)`](crate::parser::Context::accept_empty) is specified.
+  ///
+  /// ```rust
+  /// use terp::schema::{Schema, id};
+  /// use terp::schema::chars::{ch, ascii_digit};
+  /// let schema = Schema::new("List")
+  ///   .define("List", id("Item") * (0..))
+  ///   .define("Item", (ascii_digit() * (1..)) & id("Sep"))
+  ///   .define("Sep", ch(',') * (0..=1));
+  /// assert_eq!(vec!["List", "Sep"], schema.nullable_rules());
+  /// assert!(!schema.is_nullable_rule(&"Item"));
+  /// ```
+  ///
+  pub fn nullable_rules(&self) -> Vec<ID> {
+    let nullables = self.nullables(&self.defs.keys().cloned().collect());
+    self.ids().filter(|id| nullables.contains(*id)).cloned().collect()
+  }
+
+  /// Whether the rule `id` is defined and can match an empty sequence, see [`nullable_rules()`](Self::nullable_rules).
+  ///
+  pub fn is_nullable_rule(&self, id: &ID) -> bool {
+    self.defs.contains_key(id) && self.nullables(&self.defs.keys().cloned().collect()).contains(id)
+  }
+
+  /// The rules in `rules` that can match an empty sequence.
+  ///
+  pub(crate) fn nullables(&self, rules: &BTreeSet<ID>) -> BTreeSet<ID> {
+    let mut nullables = BTreeSet::new();
+    loop {
+      let found = rules
+        .iter()
+        .filter(|id| !nullables.contains(*id) && self.is_nullable(&self.defs[*id], &nullables))
+        .cloned()
+        .collect::<Vec<_>>();
+      if found.is_empty() {
+        return nullables;
+      }
+      nullables.extend(found);
+    }
+  }
+
+  fn is_nullable(&self, syntax: &Syntax<ID, Σ>, nullables: &BTreeSet<ID>) -> bool {
+    *syntax.repetition.start() == 0 || self.is_primary_nullable(&syntax.primary, nullables)
+  }
+
+  fn is_primary_nullable(&self, primary: &Primary<ID, Σ>, nullables: &BTreeSet<ID>) -> bool {
+    match primary {
+      // a term matches empty if it can be confirmed without any symbols
+      Primary::Term(term) => {
+        matches!(
+          (term.matcher)(&[], &MatchCtx::default()),
+          Ok(MatchResult::Match(0) | MatchResult::MatchAndCanAcceptMore(0))
+        )
+      }
+      Primary::Alias(id) => nullables.contains(id),
+      Primary::Seq(seq) => seq.iter().all(|s| self.is_nullable(s, nullables)),
+      Primary::Or(branches) => branches.iter().any(|s| self.is_nullable(s, nullables)),
+    }
+  }
+
+  /// The rules that can be evaluated at the beginning of `syntax` without consuming any symbol.
+  ///
+  fn leftmost_aliases<'a>(&self, syntax: &'a Syntax<ID, Σ>, nullables: &BTreeSet<ID>) -> Vec<&'a ID> {
+    match &syntax.primary {
+      Primary::Term(..) => vec![],
+      Primary::Alias(id) => vec![id],
+      Primary::Seq(seq) => {
+        let mut ids = Vec::new();
+        for s in seq {
+          ids.append(&mut self.leftmost_aliases(s, nullables));
+          if !self.is_nullable(s, nullables) {
+            break;
+          }
+        }
+        ids
+      }
+      Primary::Or(branches) => {
+        // the recursive branches of a direct left recursion are evaluated after the others have matched
+        let recursion = self.left_recursion(syntax);
+        branches
+          .iter()
+          .filter(|s| recursion.map(|id| !s.begins_with(id)).unwrap_or(true))
+          .flat_map(|s| self.leftmost_aliases(s, nullables))
+          .collect()
+      }
+    }
+  }
+
+  /// Whether an iteration of `primary` can consist of only an unbounded repetition, i.e., the same input can be
+  /// split into iterations in multiple ways.
+  ///
+  fn is_unbounded_repetition_only(
+    &self, primary: &Primary<ID, Σ>, nullables: &BTreeSet<ID>, visited: &mut BTreeSet<ID>,
+  ) -> bool {
+    match primary {
+      Primary::Term(..) => false,
+      Primary::Alias(id) => {
+        visited.insert(id.clone())
+          && self
+            .defs
+            .get(id)
+            .map(|s| self.is_unbounded_repetition_only(&s.primary, nullables, visited))
+            .unwrap_or(false)
+      }
+      Primary::Seq(seq) => seq.iter().enumerate().any(|(i, s)| {
+        (*s.repetition.end() == usize::MAX || self.is_unbounded_repetition_only(&s.primary, nullables, visited))
+          && seq.iter().enumerate().all(|(j, s)| i == j || self.is_nullable(s, nullables))
+      }),
+      Primary::Or(branches) => branches.iter().any(|s| {
+        *s.repetition.end() == usize::MAX || self.is_unbounded_repetition_only(&s.primary, nullables, visited)
+      }),
+    }
+  }
+
+  /// The worst-case number of paths that reach a term when the evaluation of `syntax` begins. A choice forks a path
+  /// for each of its branches, each of which may fork as many paths as the widest of them, so the nested choices are
+  /// multiplied. The elements that a sequence may begin with because of the nullable ones are added.
+  ///
+  fn branching(
+    &self, syntax: &Syntax<ID, Σ>, nullables: &BTreeSet<ID>, memo: &mut BTreeMap<ID, usize>,
+    evaluating: &mut BTreeSet<ID>,
+  ) -> usize {
+    match &syntax.primary {
+      Primary::Term(..) => 1,
+      Primary::Alias(id) => {
+        if let Some(n) = memo.get(id) {
+          *n
+        } else if let (Some(def), true) = (self.defs.get(id), evaluating.insert(id.clone())) {
+          let n = self.branching(def, nullables, memo, evaluating);
+          evaluating.remove(id);
+          memo.insert(id.clone(), n);
+          n
+        } else {
+          1
+        }
+      }
+      Primary::Seq(seq) => {
+        let mut n: usize = 0;
+        for s in seq {
+          n = n.saturating_add(self.branching(s, nullables, memo, evaluating));
+          if !self.is_nullable(s, nullables) {
+            break;
+          }
+        }
+        std::cmp::max(1, n)
+      }
+      Primary::Or(branches) => {
+        let widest = branches.iter().map(|s| self.branching(s, nullables, memo, evaluating)).max().unwrap_or(1);
+        branches.len().saturating_mul(widest)
+      }
+    }
+  }
+}
+
+fn visit<'a, ID, Σ: Symbol, F: FnMut(&'a Syntax<ID, Σ>)>(syntax: &'a Syntax<ID, Σ>, f: &mut F) {
+  f(syntax);
+  match &syntax.primary {
+    Primary::Seq(seq) | Primary::Or(seq) => seq.iter().for_each(|s| visit(s, f)),
+    Primary::Term(..) | Primary::Alias(_) => (),
+  }
+}
+
+fn visit_aliases<ID, Σ: Symbol, F: FnMut(&ID)>(syntax: &Syntax<ID, Σ>, f: &mut F) {
+  visit(syntax, &mut |s| {
+    if let Primary::Alias(id) = &s.primary {
+      f(id)
+    }
+  })
+}
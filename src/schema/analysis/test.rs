@@ -0,0 +1,140 @@
+use crate::schema::analysis::{Estimate, TerminalInfo, Warning};
+use crate::schema::chars::{ascii_alphabetic, ascii_digit, ch, line};
+use crate::schema::{id, json, Schema};
+
+#[test]
+fn estimate_json() {
+  let schema = json::schema();
+  let estimate = schema.estimate(&json::ID::JsonText);
+  assert!(!estimate.is_likely_super_linear(), "{:?}", estimate.warnings);
+  assert_eq!(schema.ids().count(), estimate.rules);
+  assert!(estimate.nullables.contains(&json::ID::WS));
+  assert!(!estimate.nullables.contains(&json::ID::Value));
+  assert!(estimate.max_or_fanout >= 7);
+}
+
+#[test]
+fn estimate_reachable_rules() {
+  let schema = Schema::new("Foo")
+    .define("A", id("B") & (id("C") * (0..=1)))
+    .define("B", ascii_digit())
+    .define("C", ascii_alphabetic() * (0..))
+    .define("D", ch('x'));
+  let estimate = schema.estimate(&"A");
+  assert_eq!(
+    Estimate { rules: 3, max_or_fanout: 0, nullables: vec!["C"], max_branching: 1, warnings: vec![] },
+    estimate
+  );
+}
+
+#[test]
+fn estimate_warnings() {
+  let schema = Schema::new("Foo")
+    .define("A", id("L") | id("N") | id("R") | id("U"))
+    .define("L", (id("M") & ch('x')) | ch('y'))
+    .define("M", id("L"))
+    .define("N", (line() & ch('\n')) * (1..))
+    .define("R", ((ascii_digit() * (1..)) & (ch(',') * (0..=1))) * (1..))
+    .define("U", id("X"));
+  let estimate = schema.estimate(&"A");
+  assert_eq!(
+    vec![
+      Warning::UndefinedID("X"),
+      Warning::LeftRecursion("L"),
+      Warning::LeftRecursion("M"),
+      Warning::NestedRepetition("R"),
+    ],
+    estimate.warnings
+  );
+  assert!(estimate.is_likely_super_linear());
+  for w in &estimate.warnings {
+    let _ = w.to_string();
+  }
+
+  let schema = Schema::new("Foo").define("B", (id("C") * (0..)) & ch(';')).define("C", ascii_digit() * (0..=3));
+  assert_eq!(vec![Warning::NullableRepetition("B")], schema.estimate(&"B").warnings);
+
+  // the direct left recursion is supported
+  let schema = Schema::new("Foo").define("L", (id("L") & ch('x')) | ch('y'));
+  assert_eq!(Vec::<Warning<&str>>::new(), schema.estimate(&"L").warnings);
+}
+
+#[test]
+fn estimate_high_branching() {
+  let digits = || ch('0') | ch('1') | ch('2') | ch('3') | ch('4') | ch('5') | ch('6') | ch('7');
+  let schema = Schema::new("Foo")
+    .define("A", id("B") | id("C") | id("D") | id("E") | id("F"))
+    .define("B", digits())
+    .define("C", digits())
+    .define("D", digits())
+    .define("E", digits())
+    .define("F", digits());
+  let estimate = schema.estimate(&"A");
+  assert_eq!(40, estimate.max_branching);
+  assert_eq!(vec![Warning::HighBranching("A", 40)], estimate.warnings);
+}
+
+#[test]
+fn estimate_nested_branching() {
+  // the choices nested at the beginning are multiplied, not added
+  let schema = Schema::new("Foo")
+    .define("A", id("B") | ch('x') | ch('y'))
+    .define("B", id("C") | ch('z'))
+    .define("C", ch('0') | ch('1') | ch('2'));
+  assert_eq!(3, schema.estimate(&"C").max_branching);
+  assert_eq!(6, schema.estimate(&"B").max_branching);
+  assert_eq!(18, schema.estimate(&"A").max_branching);
+
+  // the elements that a sequence may begin with are separate paths, which are added
+  let schema = Schema::new("Foo")
+    .define("D", (id("C") * (0..=1)) & id("B"))
+    .define("B", id("C") | ch('z'))
+    .define("C", ch('0') | ch('1') | ch('2'));
+  assert_eq!(9, schema.estimate(&"D").max_branching);
+}
+
+#[test]
+fn terminals_with_rules() {
+  let schema = Schema::new("Foo")
+    .define("A", id("B") & ch(',') & id("C"))
+    .define("B", (ascii_digit() * (1..)) & (ch(',') * (0..=1)))
+    .define("C", ascii_alphabetic() | ascii_digit());
+  assert_eq!(
+    vec![
+      TerminalInfo { label: "','", rules: vec![&"A", &"B"] },
+      TerminalInfo { label: "ASCII_DIGIT", rules: vec![&"B", &"C"] },
+      TerminalInfo { label: "ASCII_ALPHA", rules: vec![&"C"] },
+    ],
+    schema.terminals().collect::<Vec<_>>()
+  );
+}
+
+#[test]
+fn terminals_of_json() {
+  let schema = json::schema();
+  let terminals = schema.terminals().collect::<Vec<_>>();
+  assert!(terminals.iter().any(|t| t.rules.contains(&&json::ID::True)));
+  for (i, t) in terminals.iter().enumerate() {
+    assert!(!t.rules.is_empty());
+    assert_eq!(t.rules.len(), t.rules.iter().collect::<std::collections::BTreeSet<_>>().len(), "{}", t.label);
+    assert_eq!(i, terminals.iter().position(|u| u.label == t.label && u.rules == t.rules).unwrap());
+  }
+  assert_eq!(terminals, schema.terminals().collect::<Vec<_>>());
+}
+
+#[test]
+fn nullable_rules() {
+  let schema = Schema::new("Foo")
+    .define("A", id("B") | id("C"))
+    .define("B", ascii_digit() & (ch(',') * (0..=1)))
+    .define("C", id("D") & id("E"))
+    .define("D", ascii_alphabetic() * (0..))
+    .define("E", id("D") * (1..=2))
+    .define("F", id("X"));
+  assert_eq!(vec!["A", "C", "D", "E"], schema.nullable_rules());
+  assert!(schema.is_nullable_rule(&"A"));
+  assert!(!schema.is_nullable_rule(&"B"));
+  assert!(!schema.is_nullable_rule(&"F"));
+  assert!(!schema.is_nullable_rule(&"X"));
+  assert_eq!(vec![json::ID::WS], json::schema().nullable_rules());
+}
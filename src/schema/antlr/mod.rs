@@ -0,0 +1,624 @@
+//! Import of the lexer rules of [ANTLR](https://www.antlr.org/) grammars, so that the token layer of a protocol
+//! published as a `.g4` file can be parsed by terp. Each lexer rule becomes a syntax of the same name, and the parser
+//! rules of a combined grammar are skipped.
+//!
+//! ```rust
+//! use terp::parser::{Context, Event};
+//! use terp::schema::antlr;
+//!
+//! let lexer = antlr::import_lexer("Version", r#"
+//!   lexer grammar VersionLexer;
+//!   VERSION : NUM '.' NUM ('.' NUM)? ;
+//!   fragment NUM : '0' | [1-9] [0-9]* ;
+//!   WS : [ \t\r\n]+ -> skip ;
+//! "#).unwrap();
+//! assert_eq!(vec!["NUM"], lexer.fragments);
+//!
+//! let mut parser = Context::new(&lexer.schema, "VERSION".to_string(), |_: &Event<_, _>| ()).unwrap();
+//! parser.push_str("1.20.3").unwrap();
+//! parser.finish().unwrap();
+//! ```
+//!
+//! The supported subset is the string literals, the character sets `[...]`, the ranges `'a'..'z'`, the wildcard `.`,
+//! the negation `~` of the sets, the groups, the repetitions `?`, `*` and `+`, and the references to the other lexer
+//! rules and `EOF`. The alternatives `|` are evaluated all at once rather than in order, so the tokens are not
+//! resolved by the longest match but by the rule that the parser is started with. The non-greedy loop is only available
+//! as `.*?` or `.+?` followed by a string literal, which becomes [`until_token()`]. The commands `-> skip` and
+//! `-> channel(...)` list the rule in [`Lexer::skipped`]. The actions, the semantic predicates, the lexical modes and
+//! the other commands are reported as [`ImportError`]s.
+//!
+use crate::schema::chars::{ch, none_of_chars, token, until_token};
+use crate::schema::{any_of_ranges_with_label, eof, id, none_of, one_of, ImportError, MatchResult, Schema, Syntax};
+use std::collections::BTreeSet;
+use std::ops::RangeInclusive;
+
+#[cfg(test)]
+mod test;
+
+/// The schema imported from the lexer rules of an ANTLR grammar.
+///
+pub struct Lexer {
+  pub schema: Schema<String, char>,
+  /// The `fragment` rules, which aren't tokens by themselves.
+  pub fragments: Vec<String>,
+  /// The tokens discarded by `-> skip` or sent to another channel by `-> channel(...)`.
+  pub skipped: Vec<String>,
+}
+
+/// Import the lexer rules of the ANTLR `grammar` as a schema named `name`.
+///
+pub fn import_lexer(name: &str, grammar: &str) -> Result<Lexer, ImportError> {
+  let chars = grammar.chars().collect::<Vec<_>>();
+  let rules = Parser { chars: &chars, pos: 0 }.rules()?;
+  let mut defined = BTreeSet::new();
+  for rule in &rules {
+    if !defined.insert(rule.name.clone()) {
+      return Err(ImportError::at(&chars, rule.pos, &format!("{} is defined twice", rule.name)));
+    }
+  }
+
+  let converter = Converter { chars: &chars, defined: &defined };
+  let mut schema = Schema::new(name);
+  let (mut fragments, mut skipped) = (Vec::new(), Vec::new());
+  for rule in &rules {
+    schema = schema.define(rule.name.clone(), converter.expr(&rule.expr)?);
+    if rule.fragment {
+      fragments.push(rule.name.clone());
+    }
+    if rule.skipped {
+      skipped.push(rule.name.clone());
+    }
+  }
+  Ok(Lexer { schema, fragments, skipped })
+}
+
+// ---------------------------------
+
+struct Rule {
+  /// The position of the rule name in the grammar.
+  pos: usize,
+  name: String,
+  fragment: bool,
+  skipped: bool,
+  expr: Expr,
+}
+
+struct Expr {
+  /// The position of the first character of this expression in the grammar.
+  pos: usize,
+  kind: ExprKind,
+}
+
+enum ExprKind {
+  Str(String),
+  /// The set of characters `[...]`, `'a'..'z'` or `.` with its notation in the grammar, which matches the characters
+  /// out of the ranges if negated.
+  Set(Vec<RangeInclusive<char>>, bool, String),
+  Ref(String),
+  /// The negation `~` with its notation in the grammar.
+  Not(Box<Expr>, String),
+  Seq(Vec<Expr>),
+  Choice(Vec<Expr>),
+  /// The repetition, which is greedy if the flag is true.
+  Repeat(Box<Expr>, usize, usize, bool),
+}
+
+impl Expr {
+  /// The ranges of characters if this expression matches a single character of them.
+  ///
+  fn ranges(&self) -> Option<Vec<RangeInclusive<char>>> {
+    match &self.kind {
+      ExprKind::Str(s) if s.chars().count() == 1 => {
+        let c = s.chars().next().unwrap();
+        Some(vec![c..=c])
+      }
+      ExprKind::Set(ranges, false, _) => Some(ranges.clone()),
+      ExprKind::Choice(branches) => {
+        let mut ranges = Vec::new();
+        for branch in branches {
+          ranges.extend(branch.ranges()?);
+        }
+        Some(ranges)
+      }
+      _ => None,
+    }
+  }
+
+  fn is_wildcard(&self) -> bool {
+    matches!(&self.kind, ExprKind::Set(ranges, true, _) if ranges.is_empty())
+  }
+}
+
+// ---------------------------------
+
+struct Converter<'a> {
+  chars: &'a [char],
+  defined: &'a BTreeSet<String>,
+}
+
+impl Converter<'_> {
+  fn expr(&self, expr: &Expr) -> Result<Syntax<String, char>, ImportError> {
+    let syntax = match &expr.kind {
+      ExprKind::Str(s) => token(s),
+      ExprKind::Set(ranges, negated, label) => set(ranges, *negated, label),
+      ExprKind::Ref(name) if self.defined.contains(name) => id(name.clone()),
+      ExprKind::Ref(name) if name == "EOF" => eof(),
+      ExprKind::Ref(name) => return Err(self.error(expr, &format!("{} is not defined", name))),
+      ExprKind::Not(e, label) => match e.ranges() {
+        Some(ranges) => set(&ranges, true, label),
+        None => return Err(self.error(expr, "the negation `~` is only supported for the sets of characters")),
+      },
+      ExprKind::Seq(exprs) => return self.seq(exprs),
+      ExprKind::Choice(branches) => {
+        let mut syntaxes = Vec::with_capacity(branches.len());
+        for branch in branches {
+          syntaxes.push(self.expr(branch)?);
+        }
+        syntaxes.into_iter().reduce(|a, b| a | b).unwrap()
+      }
+      ExprKind::Repeat(e, min, max, true) => self.expr(e)? * (*min..=*max),
+      ExprKind::Repeat(..) => {
+        let message = "the non-greedy loop is only supported as `.*?` or `.+?` followed by a string literal";
+        return Err(self.error(expr, message));
+      }
+    };
+    Ok(syntax)
+  }
+
+  /// The sequence of `exprs`, where `.*? '...'` becomes [`until_token()`] followed by the terminator.
+  ///
+  fn seq(&self, exprs: &[Expr]) -> Result<Syntax<String, char>, ImportError> {
+    let mut syntaxes = Vec::with_capacity(exprs.len());
+    let mut i = 0;
+    while i < exprs.len() {
+      match (&exprs[i].kind, exprs.get(i + 1).map(|e| &e.kind)) {
+        (ExprKind::Repeat(e, min @ (0 | 1), usize::MAX, false), Some(ExprKind::Str(terminator))) if e.is_wildcard() => {
+          if *min == 1 {
+            syntaxes.push(none_of_chars(""));
+          }
+          syntaxes.push(until_token(terminator) & token(terminator));
+          i += 2;
+        }
+        _ => {
+          syntaxes.push(self.expr(&exprs[i])?);
+          i += 1;
+        }
+      }
+    }
+    Ok(syntaxes.into_iter().reduce(|a, b| a & b).unwrap())
+  }
+
+  fn error(&self, expr: &Expr, message: &str) -> ImportError {
+    ImportError::at(self.chars, expr.pos, message)
+  }
+}
+
+/// The syntax that matches a character in the `ranges`, or out of them if `negated`.
+///
+fn set(ranges: &[RangeInclusive<char>], negated: bool, label: &str) -> Syntax<String, char> {
+  if ranges.iter().all(|r| r.start() == r.end()) {
+    let chars = ranges.iter().map(|r| *r.start()).collect::<Vec<_>>();
+    return match (negated, &chars[..]) {
+      (false, [c]) => ch(*c),
+      (false, _) => one_of(&chars),
+      (true, _) => none_of(&chars),
+    };
+  }
+  if !negated {
+    return any_of_ranges_with_label(label, ranges.to_vec());
+  }
+  let ranges = ranges.to_vec();
+  Syntax::from_fn(label, move |values: &[char]| match values.first() {
+    None => Ok(MatchResult::UnmatchAndCanAcceptMore),
+    Some(c) if ranges.iter().any(|r| r.contains(c)) => Ok(MatchResult::Unmatch),
+    Some(_) => Ok(MatchResult::Match(1)),
+  })
+  .with_window(1)
+}
+
+// ---------------------------------
+
+struct Parser<'a> {
+  chars: &'a [char],
+  pos: usize,
+}
+
+impl Parser<'_> {
+  /// The lexer rules, skipping the header, the options, the actions and the parser rules.
+  ///
+  fn rules(mut self) -> Result<Vec<Rule>, ImportError> {
+    let mut rules = Vec::new();
+    while self.skip_spaces() {
+      let pos = self.pos;
+      if self.peek_raw() == Some('@') {
+        // the named actions such as @header { ... } or @lexer::members { ... }
+        while !matches!(self.peek(), Some('{') | None) {
+          self.pos += 1;
+        }
+        self.block()?;
+        continue;
+      }
+      let word = self.ident()?;
+      match word.as_str() {
+        "lexer" | "parser" | "grammar" => {
+          if word != "grammar" && self.ident()? != "grammar" {
+            return Err(self.error("'grammar' expected"));
+          }
+          self.ident()?;
+          self.expect(';')?;
+        }
+        "options" | "tokens" | "channels" => {
+          self.skip_spaces();
+          self.block()?;
+        }
+        "import" => return Err(ImportError::at(self.chars, pos, "the imported grammars are not supported")),
+        "mode" => return Err(ImportError::at(self.chars, pos, "the lexical modes are not supported")),
+        "fragment" => {
+          self.skip_spaces();
+          let pos = self.pos;
+          let name = self.ident()?;
+          rules.push(self.lexer_rule(pos, name, true)?);
+        }
+        _ if word.starts_with(|c: char| c.is_ascii_uppercase()) => rules.push(self.lexer_rule(pos, word, false)?),
+        _ => self.parser_rule()?,
+      }
+    }
+    Ok(rules)
+  }
+
+  fn lexer_rule(&mut self, pos: usize, name: String, fragment: bool) -> Result<Rule, ImportError> {
+    self.expect(':')?;
+    let expr = self.choice()?;
+    let mut skipped = false;
+    if self.peek() == Some('-') {
+      self.expect_str("->")?;
+      loop {
+        self.skip_spaces();
+        let pos = self.pos;
+        let command = self.ident()?;
+        if self.peek() == Some('(') {
+          self.pos += 1;
+          self.ident()?;
+          self.expect(')')?;
+        }
+        match command.as_str() {
+          "skip" | "channel" => skipped = true,
+          _ => {
+            let message = format!("the lexer command `{}` is not supported", command);
+            return Err(ImportError::at(self.chars, pos, &message));
+          }
+        }
+        if self.peek() != Some(',') {
+          break;
+        }
+        self.pos += 1;
+      }
+    }
+    self.expect(';')?;
+    Ok(Rule { pos, name, fragment, skipped, expr })
+  }
+
+  /// Skip the parser rule up to the terminating `;`.
+  ///
+  fn parser_rule(&mut self) -> Result<(), ImportError> {
+    loop {
+      match self.peek() {
+        None => return Err(self.error("';' expected")),
+        Some(';') => {
+          self.pos += 1;
+          return Ok(());
+        }
+        Some('\'') => {
+          self.string()?;
+        }
+        Some('{') => self.block()?,
+        Some('[') => {
+          // the arguments and the return values
+          while !matches!(self.next(), Some(']') | None) {}
+        }
+        Some(_) => self.pos += 1,
+      }
+    }
+  }
+
+  /// Skip the block `{ ... }` of the options or the action, which may be nested.
+  ///
+  fn block(&mut self) -> Result<(), ImportError> {
+    self.expect_here('{')?;
+    let mut depth = 1;
+    while depth > 0 {
+      match self.next() {
+        None => return Err(self.error("'}' expected")),
+        Some('{') => depth += 1,
+        Some('}') => depth -= 1,
+        Some(_) => (),
+      }
+    }
+    Ok(())
+  }
+
+  fn choice(&mut self) -> Result<Expr, ImportError> {
+    self.skip_spaces();
+    let pos = self.pos;
+    let mut branches = vec![self.seq()?];
+    while self.peek() == Some('|') {
+      self.pos += 1;
+      branches.push(self.seq()?);
+    }
+    Ok(if branches.len() == 1 { branches.pop().unwrap() } else { Expr { pos, kind: ExprKind::Choice(branches) } })
+  }
+
+  fn seq(&mut self) -> Result<Expr, ImportError> {
+    self.skip_spaces();
+    let pos = self.pos;
+    let mut exprs = Vec::new();
+    while !matches!(self.peek(), Some('|' | ')' | ';' | '-') | None) {
+      exprs.push(self.term()?);
+    }
+    match exprs.len() {
+      0 => Err(self.error("an empty alternative is not supported")),
+      1 => Ok(exprs.pop().unwrap()),
+      _ => Ok(Expr { pos, kind: ExprKind::Seq(exprs) }),
+    }
+  }
+
+  fn term(&mut self) -> Result<Expr, ImportError> {
+    self.skip_spaces();
+    let pos = self.pos;
+    let expr = if self.peek_raw() == Some('~') {
+      self.pos += 1;
+      let e = self.primary()?;
+      Expr { pos, kind: ExprKind::Not(Box::new(e), self.notation(pos)) }
+    } else {
+      self.primary()?
+    };
+    let (min, max) = match self.peek() {
+      Some('?') => (0, 1),
+      Some('*') => (0, usize::MAX),
+      Some('+') => (1, usize::MAX),
+      _ => return Ok(expr),
+    };
+    self.pos += 1;
+    let greedy = self.peek_raw() != Some('?');
+    if !greedy {
+      self.pos += 1;
+    }
+    Ok(Expr { pos, kind: ExprKind::Repeat(Box::new(expr), min, max, greedy) })
+  }
+
+  fn primary(&mut self) -> Result<Expr, ImportError> {
+    self.skip_spaces();
+    let pos = self.pos;
+    let kind = match self.peek_raw() {
+      Some('\'') => {
+        let s = self.string()?;
+        if self.peek() != Some('.') || self.chars.get(self.pos + 1) != Some(&'.') {
+          ExprKind::Str(s)
+        } else {
+          self.pos += 2;
+          self.skip_spaces();
+          let end = self.string()?;
+          match (single(&s), single(&end)) {
+            (Some(begin), Some(end)) => ExprKind::Set(vec![begin..=end], false, self.notation(pos)),
+            _ => return Err(ImportError::at(self.chars, pos, "the range must be of single characters")),
+          }
+        }
+      }
+      Some('[') => ExprKind::Set(self.set()?, false, self.notation(pos)),
+      Some('.') => {
+        self.pos += 1;
+        ExprKind::Set(vec![], true, String::from("."))
+      }
+      Some('(') => {
+        self.pos += 1;
+        let expr = self.choice()?;
+        self.expect(')')?;
+        return Ok(expr);
+      }
+      Some('{') => return Err(self.error("the actions and the semantic predicates are not supported")),
+      Some(c) if c.is_ascii_alphabetic() => {
+        let name = self.ident()?;
+        if self.peek() == Some('=') || (self.peek() == Some('+') && self.chars.get(self.pos + 1) == Some(&'=')) {
+          // the label is ignored
+          self.pos += if self.peek_raw() == Some('=') { 1 } else { 2 };
+          return self.primary();
+        }
+        if !name.starts_with(|c: char| c.is_ascii_uppercase()) {
+          let message = format!("the parser rule {} can't be referenced from a lexer rule", name);
+          return Err(ImportError::at(self.chars, pos, &message));
+        }
+        ExprKind::Ref(name)
+      }
+      _ => return Err(self.error("an element expected")),
+    };
+    Ok(Expr { pos, kind })
+  }
+
+  fn ident(&mut self) -> Result<String, ImportError> {
+    self.skip_spaces();
+    let begin = self.pos;
+    while self.peek_raw().map(|c| c.is_ascii_alphanumeric() || c == '_').unwrap_or(false) {
+      self.pos += 1;
+    }
+    if begin == self.pos || self.chars[begin].is_ascii_digit() {
+      return Err(self.error("an identifier expected"));
+    }
+    Ok(self.chars[begin..self.pos].iter().collect())
+  }
+
+  fn string(&mut self) -> Result<String, ImportError> {
+    let pos = self.pos;
+    self.expect_here('\'')?;
+    let mut s = String::new();
+    loop {
+      match self.peek_raw() {
+        None | Some('\n') => return Err(self.error("the string is not closed")),
+        Some('\'') if s.is_empty() => return Err(ImportError::at(self.chars, pos, "an empty string")),
+        Some('\'') => {
+          self.pos += 1;
+          return Ok(s);
+        }
+        Some(_) => s.push(self.escaped(&['\'', '"'])?),
+      }
+    }
+  }
+
+  /// The ranges of the character set `[...]`, where `-` between two characters denotes a range.
+  ///
+  fn set(&mut self) -> Result<Vec<RangeInclusive<char>>, ImportError> {
+    self.expect_here('[')?;
+    let mut ranges = Vec::new();
+    loop {
+      match self.peek_raw() {
+        None => return Err(self.error("the set is not closed")),
+        Some(']') if ranges.is_empty() => return Err(self.error("an empty set")),
+        Some(']') => {
+          self.pos += 1;
+          return Ok(ranges);
+        }
+        Some(_) => {
+          let begin = self.escaped(&[']', '-', '['])?;
+          let end = if self.peek_raw() == Some('-') && !matches!(self.chars.get(self.pos + 1), Some(']') | None) {
+            self.pos += 1;
+            self.escaped(&[']', '-', '['])?
+          } else {
+            begin
+          };
+          if end < begin {
+            return Err(self.error(&format!("the range {:?}-{:?} is reversed", begin, end)));
+          }
+          ranges.push(begin..=end);
+        }
+      }
+    }
+  }
+
+  /// A character of a string or a set, which may be escaped as `\n` or `\uXXXX`, or `\` followed by one of the
+  /// `literals`.
+  ///
+  fn escaped(&mut self, literals: &[char]) -> Result<char, ImportError> {
+    let pos = self.pos;
+    let c = self.next().ok_or_else(|| self.error("a character expected"))?;
+    if c != '\\' {
+      return Ok(c);
+    }
+    let escaped = match self.next() {
+      Some('n') => '\n',
+      Some('r') => '\r',
+      Some('t') => '\t',
+      Some('b') => '\u{8}',
+      Some('f') => '\u{c}',
+      Some('\\') => '\\',
+      Some(c) if literals.contains(&c) => c,
+      Some('u') if self.peek_raw() == Some('{') => {
+        self.pos += 1;
+        let mut hex = String::new();
+        while let Some(c) = self.next() {
+          if c == '}' {
+            break;
+          }
+          hex.push(c);
+        }
+        self.code_point(&hex)?
+      }
+      Some('u') => {
+        let hex = (0..4).filter_map(|_| self.next()).collect::<String>();
+        self.code_point(&hex)?
+      }
+      Some('p' | 'P') => return Err(ImportError::at(self.chars, pos, "the Unicode properties are not supported")),
+      _ => return Err(ImportError::at(self.chars, pos, "an unknown escape sequence")),
+    };
+    Ok(escaped)
+  }
+
+  fn code_point(&self, hex: &str) -> Result<char, ImportError> {
+    u32::from_str_radix(hex, 16)
+      .ok()
+      .and_then(char::from_u32)
+      .ok_or_else(|| self.error(&format!("an invalid code point {:?}", hex)))
+  }
+
+  /// The notation in the grammar from `begin` to the current position.
+  ///
+  fn notation(&self, begin: usize) -> String {
+    self.chars[begin..self.pos].iter().collect()
+  }
+
+  fn expect(&mut self, expected: char) -> Result<(), ImportError> {
+    self.skip_spaces();
+    self.expect_here(expected)
+  }
+
+  fn expect_str(&mut self, expected: &str) -> Result<(), ImportError> {
+    self.skip_spaces();
+    for c in expected.chars() {
+      if self.peek_raw() != Some(c) {
+        return Err(self.error(&format!("{:?} expected", expected)));
+      }
+      self.pos += 1;
+    }
+    Ok(())
+  }
+
+  fn expect_here(&mut self, expected: char) -> Result<(), ImportError> {
+    match self.peek_raw() {
+      Some(c) if c == expected => {
+        self.pos += 1;
+        Ok(())
+      }
+      _ => Err(self.error(&format!("{:?} expected", expected))),
+    }
+  }
+
+  /// Skip the whitespaces and the comments, and return whether any character remains.
+  ///
+  fn skip_spaces(&mut self) -> bool {
+    loop {
+      match (self.peek_raw(), self.chars.get(self.pos + 1).copied()) {
+        (Some(c), _) if c.is_whitespace() => self.pos += 1,
+        (Some('/'), Some('/')) => {
+          while self.peek_raw().map(|c| c != '\n').unwrap_or(false) {
+            self.pos += 1;
+          }
+        }
+        (Some('/'), Some('*')) => {
+          self.pos += 2;
+          while self.pos < self.chars.len() && !self.chars[self.pos..].starts_with(&['*', '/']) {
+            self.pos += 1;
+          }
+          self.pos = std::cmp::min(self.pos + 2, self.chars.len());
+        }
+        (c, _) => return c.is_some(),
+      }
+    }
+  }
+
+  fn peek(&mut self) -> Option<char> {
+    self.skip_spaces();
+    self.peek_raw()
+  }
+
+  fn peek_raw(&self) -> Option<char> {
+    self.chars.get(self.pos).copied()
+  }
+
+  fn next(&mut self) -> Option<char> {
+    let c = self.peek_raw();
+    self.pos += 1;
+    c
+  }
+
+  fn error(&self, message: &str) -> ImportError {
+    ImportError::at(self.chars, self.pos, message)
+  }
+}
+
+/// The character if `s` consists of a single character.
+///
+fn single(s: &str) -> Option<char> {
+  let mut chars = s.chars();
+  match (chars.next(), chars.next()) {
+    (Some(c), None) => Some(c),
+    _ => None,
+  }
+}
@@ -0,0 +1,129 @@
+use super::{import_lexer, Lexer};
+use crate::parser::{test::Events, Context, Event};
+use crate::schema::{ImportError, Location as _};
+
+/// The lexer rules of the JSON grammar in grammars-v4.
+const JSON: &str = r#"
+/** Taken from "The Definitive ANTLR 4 Reference" by Terence Parr */
+grammar JSON;
+
+json : value EOF ;
+obj : '{' pair (',' pair)* '}' | '{' '}' ;
+pair : STRING ':' value ;
+arr : '[' value (',' value)* ']' | '[' ']' ;
+value : STRING | NUMBER | obj | arr | 'true' | 'false' | 'null' ;
+
+STRING : '"' (ESC | SAFECODEPOINT)* '"' ;
+fragment ESC : '\\' (["\\/bfnrt] | UNICODE) ;
+fragment UNICODE : 'u' HEX HEX HEX HEX ;
+fragment HEX : [0-9a-fA-F] ;
+fragment SAFECODEPOINT : ~ ["\\\u0000-\u001F] ;
+NUMBER : '-'? INT ('.' [0-9] +)? EXP? ;
+fragment INT : '0' | [1-9] [0-9]* ;
+// no leading zeros
+fragment EXP : [Ee] [+\-]? [0-9]+ ;
+WS : [ \t\n\r] + -> skip ;
+"#;
+
+#[test]
+fn import_json_lexer() {
+  let lexer = import_lexer("JSON", JSON).unwrap();
+  assert_eq!(vec!["ESC", "UNICODE", "HEX", "SAFECODEPOINT", "INT", "EXP"], lexer.fragments);
+  assert_eq!(vec!["WS"], lexer.skipped);
+  assert_eq!(vec!["STRING", "ESC", "UNICODE", "HEX", "SAFECODEPOINT", "NUMBER", "INT", "EXP", "WS"], {
+    lexer.schema.ids().map(|id| id.as_str()).collect::<Vec<_>>()
+  });
+
+  let events = parse(&lexer, "STRING", &["ESC", "UNICODE", "HEX", "SAFECODEPOINT"], "\"a\\u00e9\\\"\"").unwrap();
+  Events::<String>::new().begin("STRING".into()).fragments("\"a\\u00e9\\\"\"").end().assert_eq(&events);
+
+  let accepts = |id: &str, text: &str| parse(&lexer, id, &[], text).is_ok();
+  for text in ["0", "-12", "3.25", "1e10", "2.5E-3"] {
+    assert!(accepts("NUMBER", text), "{}", text);
+  }
+  for text in ["01", "1.", ".5", "1e", "+1"] {
+    assert!(!accepts("NUMBER", text), "{}", text);
+  }
+  assert!(!accepts("STRING", "\"\\x\""));
+  assert!(!accepts("STRING", "\"\n\""));
+  assert!(accepts("WS", " \t\r\n"));
+}
+
+#[test]
+fn import_literals_and_sets() {
+  let lexer = import_lexer(
+    "Foo",
+    r#"
+    lexer grammar Foo;
+    options { superClass = FooBase; }
+    channels { COMMENTS }
+    @lexer::members { int depth = 0; }
+
+    COMMENT : '/*' .*? '*/' -> channel(COMMENTS) ;
+    LINE : '#' .+? '\n' ;
+    LOWER : 'a'..'z' ;
+    SYMBOL : ~[a-z0-9\]] ;
+    NOT_QUOTE : ~('\'' | '"')+ ;
+    ESCAPED : '\t\u{1F600}\u00e9' | [\u0041-\u{43}\-] ;
+    ENDING : 'end' EOF ;
+    "#,
+  )
+  .unwrap();
+  assert_eq!(vec!["COMMENT"], lexer.skipped);
+  let accepts = |id: &str, text: &str| parse(&lexer, id, &[], text).is_ok();
+  assert!(accepts("COMMENT", "/* a * b / c */"));
+  assert!(accepts("COMMENT", "/**/"));
+  assert!(!accepts("COMMENT", "/* a */ */"));
+  assert!(accepts("LINE", "#a\n"));
+  assert!(!accepts("LINE", "#\n"));
+  assert!(accepts("LOWER", "q"));
+  assert!(!accepts("LOWER", "Q"));
+  assert!(accepts("SYMBOL", "Q"));
+  assert!(!accepts("SYMBOL", "]"));
+  assert!(!accepts("SYMBOL", "5"));
+  assert!(accepts("NOT_QUOTE", "abc"));
+  assert!(!accepts("NOT_QUOTE", "a'c"));
+  assert!(accepts("ESCAPED", "\t😀é"));
+  assert!(accepts("ESCAPED", "B"));
+  assert!(accepts("ESCAPED", "-"));
+  assert!(!accepts("ESCAPED", "D"));
+  assert!(accepts("ENDING", "end"));
+}
+
+#[test]
+fn import_errors() {
+  let error = |grammar: &str| match import_lexer("Foo", grammar) {
+    Err(ImportError { location, message }) => (location.line().unwrap() + 1, location.column().unwrap() + 1, message),
+    Ok(_) => panic!("{}", grammar),
+  };
+  assert_eq!((2, 9, String::from("C is not defined")), error("A : 'a' ;\nB : 'b' C ;"));
+  assert_eq!((2, 1, String::from("A is defined twice")), error("A : 'a' ;\nA : 'b' ;"));
+  assert_eq!((1, 12, String::from("the lexer command `more` is not supported")), error("A : 'a' -> more ;"));
+  assert_eq!((1, 9, String::from("the actions and the semantic predicates are not supported")), {
+    error("A : 'a' {depth++;} ;")
+  });
+  assert_eq!((1, 1, String::from("the lexical modes are not supported")), error("mode INSIDE;"));
+  assert_eq!((1, 5, String::from("the negation `~` is only supported for the sets of characters")), {
+    error("A : ~'ab' ;")
+  });
+  assert_eq!(
+    (1, 5, String::from("the non-greedy loop is only supported as `.*?` or `.+?` followed by a string literal")),
+    { error("A : [a-z]*? 'x' ;") }
+  );
+  assert_eq!((1, 5, String::from("the parser rule b can't be referenced from a lexer rule")), error("A : b ;"));
+  assert_eq!((1, 5, String::from("the range must be of single characters")), error("A : 'ab'..'z' ;"));
+  assert_eq!((1, 6, String::from("the Unicode properties are not supported")), error("A : [\\p{L}] ;"));
+  assert_eq!((1, 8, String::from("the string is not closed")), error("A : 'ab"));
+  assert_eq!((1, 12, String::from("';' expected")), error("A : 'a' | B"));
+  assert_eq!("(1,9) ';' expected", import_lexer("Foo", "a : 'a' ").err().unwrap().to_string());
+}
+
+fn parse(lexer: &Lexer, id: &str, ignore: &[&str], text: &str) -> crate::Result<char, Vec<Event<String, char>>> {
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let ignore = ignore.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+  let mut parser = Context::new(&lexer.schema, id.to_string(), handler)?.ignore_events_for(&ignore);
+  parser.push_str(text)?;
+  parser.finish()?;
+  Ok(Event::normalize(&events))
+}
@@ -1,3 +1,5 @@
+use crate::schema::{MatchResult, Syntax};
+use crate::Result;
 use std::fmt::Display;
 
 #[cfg(test)]
@@ -13,6 +15,9 @@ impl crate::schema::Location<u8> for Location {
   fn increment_with(&mut self, _b: u8) {
     self.0 += 1;
   }
+  fn byte_offset(&self) -> Option<u64> {
+    Some(self.0)
+  }
 }
 
 impl Display for Location {
@@ -20,3 +25,118 @@ impl Display for Location {
     write!(f, "@{}", self.0)
   }
 }
+
+/// The 32-bit checksums that [`checksummed()`] verifies.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Checksum {
+  /// The CRC-32 of ISO-HDLC, used by PNG, gzip and ZIP.
+  Crc32,
+  /// The Adler-32 of zlib.
+  Adler32,
+}
+
+impl Checksum {
+  pub fn compute(&self, data: &[u8]) -> u32 {
+    match self {
+      Checksum::Crc32 => {
+        !data.iter().fold(!0u32, |crc, b| CRC32_TABLE[((crc ^ *b as u32) & 0xFF) as usize] ^ (crc >> 8))
+      }
+      Checksum::Adler32 => {
+        const MOD: u32 = 65521;
+        let (mut a, mut b) = (1u32, 0u32);
+        // 5552 bytes is the longest run that doesn't overflow before the modulo
+        for chunk in data.chunks(5552) {
+          for byte in chunk {
+            a += *byte as u32;
+            b += a;
+          }
+          a %= MOD;
+          b %= MOD;
+        }
+        (b << 16) | a
+      }
+    }
+  }
+}
+
+impl Display for Checksum {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(match self {
+      Checksum::Crc32 => "CRC-32",
+      Checksum::Adler32 => "Adler-32",
+    })
+  }
+}
+
+const CRC32_TABLE: [u32; 256] = {
+  let mut table = [0u32; 256];
+  let mut i = 0;
+  while i < 256 {
+    let mut crc = i as u32;
+    let mut bit = 0;
+    while bit < 8 {
+      crc = if crc & 1 != 0 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+      bit += 1;
+    }
+    table[i] = crc;
+    i += 1;
+  }
+  table
+};
+
+/// The layout of a frame that consists of a big-endian length field, the fixed header fields, the data of that length,
+/// and a big-endian 32-bit checksum.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Frame {
+  /// The size of the length field in bytes, from 1 to 8.
+  pub length_size: usize,
+  /// The size of the fixed fields between the length field and the data, such as the chunk type of PNG.
+  pub header_size: usize,
+  /// Whether the checksum covers the length field in addition to the header fields and the data.
+  pub checksum_length: bool,
+  pub checksum: Checksum,
+}
+
+impl Frame {
+  /// The chunk of PNG, whose CRC-32 covers the chunk type and the data.
+  pub const PNG_CHUNK: Frame =
+    Frame { length_size: 4, header_size: 4, checksum_length: false, checksum: Checksum::Crc32 };
+}
+
+/// `checksummed` matches a whole `frame` only if its checksum agrees with the bytes it covers, so that a binary schema
+/// verifies the integrity of the input as it's parsed. The matcher waits until the length field and then the entire
+/// frame arrive, and computes the checksum once, so pushing the frame in pieces costs no more than pushing it at once.
+///
+/// A frame with a wrong checksum is unmatched like any other unexpected bytes. Note that the checksum must be over the
+/// bytes in the frame: for example, the Adler-32 trailer of a zlib stream covers the inflated data and can't be
+/// verified by this matcher.
+///
+pub fn checksummed<ID>(frame: Frame) -> Syntax<ID, u8> {
+  assert!((1..=8).contains(&frame.length_size), "the length field must be 1 to 8 bytes: {}", frame.length_size);
+  let head = frame.length_size + frame.header_size;
+  let signature = format!("checksummed({:?})", frame);
+  Syntax::from_fn(&format!("{} frame", frame.checksum), move |buffer: &[u8]| -> Result<u8, MatchResult> {
+    if buffer.len() < frame.length_size {
+      return Ok(MatchResult::UnmatchAndCanAcceptMore);
+    }
+    let length = buffer[..frame.length_size].iter().fold(0u64, |length, b| (length << 8) | *b as u64);
+    let data_end = match usize::try_from(length).ok().and_then(|length| length.checked_add(head)) {
+      Some(data_end) if data_end.checked_add(4).is_some() => data_end,
+      _ => return Ok(MatchResult::Unmatch),
+    };
+    if buffer.len() < data_end + 4 {
+      return Ok(MatchResult::UnmatchAndCanAcceptMore);
+    }
+    let covered = &buffer[if frame.checksum_length { 0 } else { frame.length_size }..data_end];
+    let expected =
+      u32::from_be_bytes([buffer[data_end], buffer[data_end + 1], buffer[data_end + 2], buffer[data_end + 3]]);
+    if frame.checksum.compute(covered) == expected {
+      Ok(MatchResult::Match(data_end + 4))
+    } else {
+      Ok(MatchResult::Unmatch)
+    }
+  })
+  .with_signature(signature)
+}
@@ -1,5 +1,7 @@
-use crate::schema::bytes::Location;
-use crate::schema::Location as L;
+use crate::parser::{Context, Event, EventKind};
+use crate::schema::bytes::{checksummed, Checksum, Frame, Location};
+use crate::schema::{id, seq, Location as L, MatchCtx, MatchResult, Primary, Schema, Syntax};
+use crate::Error;
 
 #[test]
 #[allow(clippy::clone_on_copy)]
@@ -23,3 +25,90 @@ fn bytes_location() {
   assert_eq!(l.0, l2.0);
   assert_eq!(&l.0, &l.clone().0);
 }
+
+#[test]
+fn checksums() {
+  assert_eq!(0xCBF4_3926, Checksum::Crc32.compute(b"123456789"));
+  assert_eq!(0, Checksum::Crc32.compute(b""));
+  assert_eq!(0x11E6_0398, Checksum::Adler32.compute(b"Wikipedia"));
+  assert_eq!(1, Checksum::Adler32.compute(b""));
+  // beyond the run that is summed up without the modulo
+  let (a, b) = (0..6000u64).fold((1u64, 0u64), |(a, b), _| ((a + 0xFF) % 65521, (b + a + 0xFF) % 65521));
+  assert_eq!(((b << 16) | a) as u32, Checksum::Adler32.compute(&[0xFF; 6000]));
+  assert_eq!("CRC-32", Checksum::Crc32.to_string());
+}
+
+/// The signature, IHDR of an 1x1 RGBA image and IEND.
+const PNG: &[u8] = &[
+  0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44, 0x52, 0x00, 0x00, 0x00,
+  0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1F, 0x15, 0xC4, 0x89, 0x00, 0x00, 0x00, 0x00, 0x49,
+  0x45, 0x4E, 0x44, 0xAE, 0x42, 0x60, 0x82,
+];
+
+#[test]
+fn checksummed_png_chunks() {
+  let schema = Schema::new("PNG")
+    .define("PNG", id("SIGNATURE") & (id("CHUNK") * (1..)))
+    .define("SIGNATURE", seq(&PNG[..8]))
+    .define("CHUNK", checksummed(Frame::PNG_CHUNK));
+  let parse = |bytes: &[u8], piece: usize| {
+    let mut fragments = 0;
+    let mut parser = Context::new(&schema, "PNG", |e: &Event<_, u8>| {
+      if let EventKind::Fragments(_) = e.kind {
+        fragments += 1;
+      }
+    })?;
+    for bytes in bytes.chunks(piece) {
+      parser.push_seq(bytes)?;
+    }
+    parser.finish()?;
+    Ok::<_, Error<u8>>(fragments)
+  };
+  // the signature and two chunks
+  for piece in [1, 3, PNG.len()] {
+    assert_eq!(3, parse(PNG, piece).unwrap());
+  }
+
+  // a flipped bit in the data or the checksum
+  for i in [20, 31, 44] {
+    let mut png = PNG.to_vec();
+    png[i] ^= 0x10;
+    assert!(parse(&png, 1).is_err(), "{}", i);
+  }
+  // truncated
+  assert!(parse(&PNG[..40], 1).is_err());
+}
+
+#[test]
+fn checksummed_with_length() {
+  let frame = Frame { length_size: 2, header_size: 0, checksum_length: true, checksum: Checksum::Adler32 };
+  let matcher = get_matcher(checksummed::<String>(frame));
+  let mut bytes = vec![0x00, 0x03, b'a', b'b', b'c'];
+  bytes.extend_from_slice(&Checksum::Adler32.compute(&bytes).to_be_bytes());
+  for len in 0..bytes.len() {
+    assert_eq!(MatchResult::UnmatchAndCanAcceptMore, matcher(&bytes[..len]).unwrap());
+  }
+  assert_eq!(MatchResult::Match(9), matcher(&bytes).unwrap());
+  bytes.push(0x00);
+  assert_eq!(MatchResult::Match(9), matcher(&bytes).unwrap());
+  bytes[2] = b'x';
+  assert_eq!(MatchResult::Unmatch, matcher(&bytes).unwrap());
+
+  // the length exceeding the address space
+  let frame = Frame { length_size: 8, ..frame };
+  assert_eq!(MatchResult::Unmatch, get_matcher(checksummed::<String>(frame))(&[0xFF; 8]).unwrap());
+  assert_eq!("Adler-32 frame", checksummed::<String>(frame).to_string());
+}
+
+#[test]
+#[should_panic]
+fn checksummed_with_too_long_length_field() {
+  checksummed::<String>(Frame { length_size: 9, ..Frame::PNG_CHUNK });
+}
+
+fn get_matcher<ID>(s: Syntax<ID, u8>) -> impl Fn(&[u8]) -> crate::Result<u8, MatchResult> {
+  match s {
+    Syntax { primary: Primary::Term(term), .. } => move |values: &[u8]| (term.matcher)(values, &MatchCtx::default()),
+    _ => panic!(),
+  }
+}
@@ -1,9 +1,15 @@
-use crate::schema::{any_of_ranges_with_label, one_of, one_of_seqs, range_with_label, seq, single, Syntax};
+use crate::schema::{
+  any_of_ranges_with_label, none_of, one_of, one_of_seqs, range_with_label, seq, single, MatchResult, Syntax,
+};
+use crate::Result;
 use std::fmt::{Debug, Display};
+use std::ops::{BitAnd, BitOr};
 
 #[cfg(test)]
 mod test;
 
+mod width;
+
 #[inline]
 pub fn ch<ID>(ch: char) -> Syntax<ID, char> {
   single(ch)
@@ -20,11 +26,162 @@ pub fn one_of_tokens<ID>(tokens: &[&str]) -> Syntax<ID, char> {
   one_of_seqs(&tokens)
 }
 
-#[derive(Default, Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
+/// The conversion of a literal into a term, so that an inline grammar can be written as `'u' & "abc".t()`. A `char`
+/// becomes [`ch()`] and a `&str` becomes [`token()`].
+///
+pub trait Terminal {
+  fn t<ID>(self) -> Syntax<ID, char>;
+}
+
+impl Terminal for char {
+  fn t<ID>(self) -> Syntax<ID, char> {
+    ch(self)
+  }
+}
+
+impl Terminal for &str {
+  fn t<ID>(self) -> Syntax<ID, char> {
+    token(self)
+  }
+}
+
+impl<ID> From<char> for Syntax<ID, char> {
+  fn from(value: char) -> Self {
+    ch(value)
+  }
+}
+
+impl<ID> From<&str> for Syntax<ID, char> {
+  fn from(value: &str) -> Self {
+    token(value)
+  }
+}
+
+/// The `&` and `|` operators between a syntax and a literal on either side.
+///
+macro_rules! literal_operators {
+  ($literal:ty) => {
+    impl<ID: Debug> BitAnd<$literal> for Syntax<ID, char> {
+      type Output = Self;
+
+      fn bitand(self, rhs: $literal) -> Self::Output {
+        self.and(rhs.t())
+      }
+    }
+
+    impl<ID: Debug> BitOr<$literal> for Syntax<ID, char> {
+      type Output = Self;
+
+      fn bitor(self, rhs: $literal) -> Self::Output {
+        self.or(rhs.t())
+      }
+    }
+
+    impl<ID: Debug> BitAnd<Syntax<ID, char>> for $literal {
+      type Output = Syntax<ID, char>;
+
+      fn bitand(self, rhs: Syntax<ID, char>) -> Self::Output {
+        self.t().and(rhs)
+      }
+    }
+
+    impl<ID: Debug> BitOr<Syntax<ID, char>> for $literal {
+      type Output = Syntax<ID, char>;
+
+      fn bitor(self, rhs: Syntax<ID, char>) -> Self::Output {
+        self.t().or(rhs)
+      }
+    }
+  };
+}
+
+literal_operators!(char);
+literal_operators!(&str);
+
+/// The location of a character in a text, where `lines` and `columns` start from 0. The line breaks and the widths of
+/// the characters are recognized according to the conventions of the location where parsing starts, see
+/// [`Context::new_at()`](crate::parser::Context::new_at).
+///
+/// Locations are compared by their positions, regardless of the conventions.
+///
+#[derive(Default, Copy, Clone, Debug)]
 pub struct Location {
   pub chars: u64,
   pub lines: u64,
   pub columns: u64,
+  /// The number of bytes of the characters in UTF-8.
+  bytes: u64,
+  newline: Newline,
+  tab_width: u8,
+  east_asian_width: bool,
+  /// Whether the last character was a CR that may be followed by an LF.
+  after_cr: bool,
+}
+
+/// The characters recognized as a line break by [`Location`].
+///
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Newline {
+  /// Only LF, where CR moves to the beginning of the line as a terminal does.
+  Lf,
+  /// Only CR.
+  Cr,
+  /// Only CRLF, where neither CR nor LF alone breaks the line.
+  Crlf,
+  /// Any of LF, CR and CRLF, where CRLF is a single line break.
+  #[default]
+  Any,
+  /// Any of LF, CR, CRLF, NEL (U+0085), LS (U+2028) and PS (U+2029), where CRLF is a single line break.
+  Unicode,
+}
+
+impl Location {
+  pub fn new(chars: u64, lines: u64, columns: u64) -> Self {
+    Self { chars, lines, columns, ..Default::default() }
+  }
+
+  pub fn with_newline(self, newline: Newline) -> Self {
+    Self { newline, ..self }
+  }
+
+  pub fn newline(&self) -> Newline {
+    self.newline
+  }
+
+  /// Count a tab as the columns up to the next tab stop of `width`, like editors display. A tab is a single column by
+  /// default.
+  ///
+  pub fn with_tab_width(self, width: u8) -> Self {
+    Self { tab_width: width, ..self }
+  }
+
+  pub fn tab_width(&self) -> u8 {
+    std::cmp::max(1, self.tab_width)
+  }
+
+  /// Count the Wide and Fullwidth characters of the Unicode East Asian Width, such as CJK ideographs, as two columns.
+  ///
+  pub fn with_east_asian_width(self, enabled: bool) -> Self {
+    Self { east_asian_width: enabled, ..self }
+  }
+
+  pub fn east_asian_width(&self) -> bool {
+    self.east_asian_width
+  }
+
+  fn advance(&mut self, ch: char) {
+    let tab_width = self.tab_width() as u64;
+    self.columns = match ch {
+      '\t' => (self.columns / tab_width + 1) * tab_width,
+      _ if self.east_asian_width && width::is_wide(ch) => self.columns + 2,
+      _ => self.columns + 1,
+    };
+  }
+
+  fn break_line(&mut self) {
+    self.lines += 1;
+    self.columns = 0;
+  }
 }
 
 impl crate::schema::Location<char> for Location {
@@ -33,15 +190,54 @@ impl crate::schema::Location<char> for Location {
   }
   fn increment_with(&mut self, ch: char) {
     self.chars += 1;
-    if ch == '\n' {
-      self.lines += 1;
-      self.columns = 0;
-    } else if ch == '\r' {
-      self.columns = 0;
-    } else {
-      self.columns += 1;
+    self.bytes += ch.len_utf8() as u64;
+    let after_cr = std::mem::take(&mut self.after_cr);
+    match (self.newline, ch) {
+      (Newline::Any | Newline::Unicode, '\n') if after_cr => (),
+      (Newline::Lf | Newline::Any | Newline::Unicode, '\n') => self.break_line(),
+      (Newline::Crlf, '\n') if after_cr => self.break_line(),
+      (Newline::Lf, '\r') => self.columns = 0,
+      (Newline::Cr | Newline::Any | Newline::Unicode, '\r') => {
+        self.break_line();
+        self.after_cr = true;
+      }
+      (Newline::Crlf, '\r') => {
+        self.advance(ch);
+        self.after_cr = true;
+      }
+      (Newline::Unicode, '\u{85}' | '\u{2028}' | '\u{2029}') => self.break_line(),
+      _ => self.advance(ch),
     }
   }
+  fn byte_offset(&self) -> Option<u64> {
+    Some(self.bytes)
+  }
+  fn line(&self) -> Option<u64> {
+    Some(self.lines)
+  }
+  fn column(&self) -> Option<u64> {
+    Some(self.columns)
+  }
+}
+
+impl PartialEq for Location {
+  fn eq(&self, other: &Self) -> bool {
+    (self.chars, self.lines, self.columns) == (other.chars, other.lines, other.columns)
+  }
+}
+
+impl Eq for Location {}
+
+impl PartialOrd for Location {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for Location {
+  fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    (self.chars, self.lines, self.columns).cmp(&(other.chars, other.lines, other.columns))
+  }
 }
 
 impl Display for Location {
@@ -55,6 +251,11 @@ pub fn one_of_chars<ID: Debug>(chars: &str) -> Syntax<ID, char> {
   one_of(&chars.chars().collect::<Vec<_>>())
 }
 
+#[inline]
+pub fn none_of_chars<ID: Debug>(chars: &str) -> Syntax<ID, char> {
+  none_of(&chars.chars().collect::<Vec<_>>())
+}
+
 #[inline]
 pub fn ascii_digit<ID: Debug>() -> Syntax<ID, char> {
   range_with_label("ASCII_DIGIT", '0'..='9')
@@ -74,3 +275,58 @@ pub fn ascii_upper_alphabetic<ID>() -> Syntax<ID, char> {
 pub fn ascii_alphabetic<ID>() -> Syntax<ID, char> {
   any_of_ranges_with_label("ASCII_ALPHA", vec!['A'..='Z', 'a'..='z'])
 }
+
+/// `newline` matches a single line break, either of LF, CRLF or CR.
+///
+#[inline]
+pub fn newline<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("NEWLINE", |values: &[char]| -> Result<char, MatchResult> {
+    match values {
+      [] => Ok(MatchResult::UnmatchAndCanAcceptMore),
+      ['\n', ..] => Ok(MatchResult::Match(1)),
+      ['\r', '\n', ..] => Ok(MatchResult::Match(2)),
+      ['\r'] => Ok(MatchResult::MatchAndCanAcceptMore(1)),
+      ['\r', ..] => Ok(MatchResult::Match(1)),
+      _ => Ok(MatchResult::Unmatch),
+    }
+  })
+  .with_window(2)
+  .with_signature(String::from("newline"))
+}
+
+/// `until_token` matches zero or more characters up to (but not including) the first occurrence of `terminator`, or
+/// up to the end of input if it doesn't appear. Like [`line`], it shouldn't be repeated by itself; use it as
+/// `until_token("*/") & token("*/")`.
+///
+/// # Panics
+///
+/// If `terminator` is empty.
+///
+pub fn until_token<ID>(terminator: &str) -> Syntax<ID, char> {
+  assert!(!terminator.is_empty(), "the terminator of until_token() is empty");
+  let signature = format!("until_token({:?})", terminator);
+  let terminator = terminator.chars().collect::<Vec<_>>();
+  let label = format!("UNTIL({})", terminator.iter().collect::<String>());
+  Syntax::from_fn(&label, move |values: &[char]| -> Result<char, MatchResult> {
+    match values.windows(terminator.len()).position(|w| w == terminator.as_slice()) {
+      Some(length) => Ok(MatchResult::Match(length)),
+      None => Ok(MatchResult::MatchAndCanAcceptMore(values.len())),
+    }
+  })
+  .with_signature(signature)
+}
+
+/// `line` matches the content of a line, that is, zero or more characters up to (but not including) the next
+/// [`newline`] or the end of input. Since it may match zero characters, it shouldn't be repeated by itself; use it
+/// as `line() & newline()`.
+///
+#[inline]
+pub fn line<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("LINE", |values: &[char]| -> Result<char, MatchResult> {
+    match values.iter().position(|ch| *ch == '\n' || *ch == '\r') {
+      Some(length) => Ok(MatchResult::Match(length)),
+      None => Ok(MatchResult::MatchAndCanAcceptMore(values.len())),
+    }
+  })
+  .with_signature(String::from("line"))
+}
@@ -1,24 +1,25 @@
-use crate::schema::chars::Location;
-use crate::schema::{Location as L, MatchResult, Matcher, Primary, Symbol, Syntax};
+use crate::schema::chars::{Location, Newline};
+use crate::schema::{Location as L, MatchCtx, MatchResult, Primary, Symbol, Syntax};
+use crate::Result;
 
 #[test]
 fn char_location() {
   let mut l = Location::default();
-  assert!(matches!(l, Location { chars: 0, lines: 0, columns: 0 }));
+  assert_eq!(Location::new(0, 0, 0), l);
   l.increment_with('A');
-  assert!(matches!(l, Location { chars: 1, lines: 0, columns: 1 }));
+  assert_eq!(Location::new(1, 0, 1), l);
   l.increment_with('あ');
-  assert!(matches!(l, Location { chars: 2, lines: 0, columns: 2 }));
+  assert_eq!(Location::new(2, 0, 2), l);
   l.increment_with('\n');
-  assert!(matches!(l, Location { chars: 3, lines: 1, columns: 0 }));
+  assert_eq!(Location::new(3, 1, 0), l);
   l.increment_with('😊');
-  assert!(matches!(l, Location { chars: 4, lines: 1, columns: 1 }));
+  assert_eq!(Location::new(4, 1, 1), l);
   l.increment_with('\r');
-  assert!(matches!(l, Location { chars: 5, lines: 1, columns: 0 }));
-  l.increment_with('\n');
-  assert!(matches!(l, Location { chars: 6, lines: 2, columns: 0 }));
+  assert_eq!(Location::new(5, 2, 0), l);
+  l.increment_with('\n'); // CRLF is a single line break
+  assert_eq!(Location::new(6, 2, 0), l);
   l.increment_with('🗿'); // surrogate pairs
-  assert!(matches!(l, Location { chars: 7, lines: 2, columns: 1 }));
+  assert_eq!(Location::new(7, 2, 1), l);
   assert_eq!("(3,2)", l.to_string());
 
   fn assert_equals(l1: &Location, l2: &Location) {
@@ -32,12 +33,50 @@ fn char_location() {
   assert_equals(&l, &l.clone());
 }
 
+#[test]
+fn char_location_newlines() {
+  let text = "a\nb\rc\r\nd\u{2028}e\n\rf";
+  for (newline, lines, columns) in
+    [(Newline::Lf, 3, 1), (Newline::Cr, 3, 1), (Newline::Crlf, 1, 6), (Newline::Any, 5, 1), (Newline::Unicode, 6, 1)]
+  {
+    let mut l = Location::default().with_newline(newline);
+    l.increment_with_seq(&text.chars().collect::<Vec<_>>());
+    assert_eq!(Location::new(13, lines, columns), l, "{:?}", newline);
+    assert_eq!(newline, l.newline());
+  }
+  assert_eq!(Newline::Any, Location::default().newline());
+}
+
+#[test]
+fn char_location_widths() {
+  for (tab_width, east_asian_width, text, columns) in [
+    (0, false, "\tab\tc", 5),
+    (1, false, "\tab\tc", 5),
+    (4, false, "\tab\tc", 9),
+    (4, false, "abcd\t", 8),
+    (8, false, "a\t\tb", 17),
+    (1, true, "aあ漢ｱＡ😊\t", 11),
+    (4, true, "あ\tb", 5),
+  ] {
+    let mut l = Location::default().with_tab_width(tab_width).with_east_asian_width(east_asian_width);
+    l.increment_with_seq(&text.chars().collect::<Vec<_>>());
+    assert_eq!(columns, l.columns, "{:?}", text);
+    assert_eq!(std::cmp::max(1, tab_width), l.tab_width());
+    assert_eq!(east_asian_width, l.east_asian_width());
+  }
+}
+
 #[test]
 fn one_of_chars() {
   test_all(super::one_of_chars("0123"), "'0'|'1'|'2'|'3'", '\0', '\x7F', &|ch: char| ('0'..='3').contains(&ch));
   test_all(super::one_of_chars(""), "", '\0', '\x7F', &|_: char| false);
 }
 
+#[test]
+fn none_of_chars() {
+  test_all(super::none_of_chars("0123"), "^'0'|'1'|'2'|'3'", '\0', '\x7F', &|ch: char| !('0'..='3').contains(&ch));
+}
+
 #[test]
 fn ascii_digit() {
   test_all(super::ascii_digit(), "ASCII_DIGIT", '\0', '\x7F', &|ch: char| ch.is_ascii_digit());
@@ -72,9 +111,141 @@ fn test_all(syntax: Syntax<String, char>, label: &str, t0: char, t1: char, pred:
   }
 }
 
-fn get_matcher<ID, Σ: Symbol>(s: Syntax<ID, Σ>) -> Box<Matcher<Σ>> {
+fn get_matcher<ID, Σ: Symbol>(s: Syntax<ID, Σ>) -> impl Fn(&[Σ]) -> Result<Σ, MatchResult> {
   match s {
-    Syntax { primary: Primary::Term(_, matcher), .. } => matcher,
+    Syntax { primary: Primary::Term(term), .. } => move |values: &[Σ]| (term.matcher)(values, &MatchCtx::default()),
     _ => panic!(),
   }
 }
+
+#[test]
+fn newline() {
+  let matcher = get_matcher(super::newline::<String>());
+  for (sample, expected) in [
+    ("", MatchResult::UnmatchAndCanAcceptMore),
+    ("\n", MatchResult::Match(1)),
+    ("\nA", MatchResult::Match(1)),
+    ("\r", MatchResult::MatchAndCanAcceptMore(1)),
+    ("\r\n", MatchResult::Match(2)),
+    ("\r\r", MatchResult::Match(1)),
+    ("\rA", MatchResult::Match(1)),
+    ("A", MatchResult::Unmatch),
+    ("A\n", MatchResult::Unmatch),
+  ] {
+    let sample = sample.chars().collect::<Vec<_>>();
+    assert_eq!(Ok(expected), matcher(&sample), "{:?}", sample);
+  }
+}
+
+#[test]
+fn line() {
+  let matcher = get_matcher(super::line::<String>());
+  for (sample, expected) in [
+    ("", MatchResult::MatchAndCanAcceptMore(0)),
+    ("ABC", MatchResult::MatchAndCanAcceptMore(3)),
+    ("\n", MatchResult::Match(0)),
+    ("ABC\nDEF", MatchResult::Match(3)),
+    ("ABC\r\nDEF", MatchResult::Match(3)),
+    ("ABC\rDEF", MatchResult::Match(3)),
+  ] {
+    let sample = sample.chars().collect::<Vec<_>>();
+    assert_eq!(Ok(expected), matcher(&sample), "{:?}", sample);
+  }
+}
+
+#[test]
+fn until_token() {
+  let matcher = get_matcher(super::until_token::<String>("-->"));
+  for (sample, expected) in [
+    ("", MatchResult::MatchAndCanAcceptMore(0)),
+    ("ABC", MatchResult::MatchAndCanAcceptMore(3)),
+    ("ABC--", MatchResult::MatchAndCanAcceptMore(5)),
+    ("-->", MatchResult::Match(0)),
+    ("AB-->C", MatchResult::Match(2)),
+    ("A->B-->C-->", MatchResult::Match(4)),
+  ] {
+    let sample = sample.chars().collect::<Vec<_>>();
+    assert_eq!(Ok(expected), matcher(&sample), "{:?}", sample);
+  }
+}
+
+#[test]
+#[should_panic(expected = "the terminator of until_token() is empty")]
+fn until_token_empty() {
+  let _ = super::until_token::<String>("");
+}
+
+#[test]
+fn literal_sugar() {
+  use crate::parser::{Context, Event};
+  use crate::schema::chars::{ascii_digit, ch, token, Terminal};
+  use crate::schema::{id, Schema};
+
+  let sugar: Syntax<&str, char> = 'u' & (id("HEX") * 4) | "\\x" & id("HEX").plus() & ';';
+  let plain: Syntax<&str, char> = ch('u') & (id("HEX") * 4) | token("\\x") & (id("HEX") * (1..)) & ch(';');
+  assert_eq!(plain.to_string(), sugar.to_string());
+  assert_eq!((ch::<&str>('a') * (0..=1)).to_string(), 'a'.t::<&str>().opt().to_string());
+  assert_eq!((token::<&str>("ab") * (0..)).to_string(), "ab".t::<&str>().star().to_string());
+  assert_eq!(ch::<&str>('a').to_string(), Syntax::<&str, char>::from('a').to_string());
+  assert_eq!(token::<&str>("ab").to_string(), Syntax::<&str, char>::from("ab").to_string());
+
+  let schema = Schema::new("Foo")
+    .define("A", (id("ESCAPE") & ('-' | id("ESCAPE")).star()) | ("null".t() & '!'.t().opt()))
+    .define("ESCAPE", 'u' & (id("HEX") * 4) | "\\x" & id("HEX").plus() & ';')
+    .define("HEX", ascii_digit() | 'a' | 'b' | 'c' | 'd' | 'e' | 'f');
+  for (text, expected) in
+    [("u00ff", true), ("\\xa;-u1234", true), ("null", true), ("null!", true), ("u0f", false), ("\\x;", false)]
+  {
+    let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+    let result = parser.push_str(text).and_then(|_| parser.finish());
+    assert_eq!(expected, result.is_ok(), "{:?}", text);
+  }
+}
+
+#[test]
+fn nested_unbounded_repetition() {
+  use crate::parser::{Context, Event};
+  use crate::schema::chars::ch;
+  use crate::schema::Schema;
+
+  // the bounds saturate rather than overflow
+  let x = || ch::<&str>('x');
+  assert_eq!((x() * (1..)).to_string(), (x() * (1..)).plus().to_string());
+  assert_eq!((x() * (0..)).to_string(), (x() * (1..)).star().to_string());
+  assert_eq!((x() * (3..)).to_string(), (x() * 3).plus().to_string());
+
+  let schema = Schema::new("Foo").define("A", (x() * (1..)).plus());
+  for (text, expected) in [("x", true), ("xxxx", true), ("", false), ("xy", false)] {
+    let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+    let result = parser.push_str(text).and_then(|_| parser.finish());
+    assert_eq!(expected, result.is_ok(), "{:?}", text);
+  }
+}
+
+#[test]
+fn literal_operators() {
+  use crate::parser::test::Events;
+  use crate::parser::{Context, Event};
+  use crate::schema::chars::ascii_alphabetic;
+  use crate::schema::{id, Schema};
+
+  let schema = Schema::new("Foo")
+    .define("PATH", id("NAME") & "::" & id("NAME") & (id("NAME") | '+' | "++").opt())
+    .define("NAME", ascii_alphabetic().plus());
+  let mut events = Vec::new();
+  let mut parser = Context::new(&schema, "PATH", |e: &Event<_, _>| events.push(e.clone())).unwrap();
+  parser.push_str("std::io++").unwrap();
+  parser.finish().unwrap();
+  Events::new()
+    .begin("PATH")
+    .begin("NAME")
+    .fragments("std")
+    .end()
+    .fragments("::")
+    .begin("NAME")
+    .fragments("io")
+    .end()
+    .fragments("++")
+    .end()
+    .assert_eq(&events);
+}
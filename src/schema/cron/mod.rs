@@ -0,0 +1,112 @@
+use crate::schema::chars::{ch, one_of_chars, one_of_tokens};
+use crate::schema::{id, MatchResult, Schema, Syntax};
+use crate::Result;
+use std::fmt::Display;
+
+#[cfg(test)]
+mod test;
+
+/// The fields of a cron expression.
+///
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum Field {
+  Second,
+  Minute,
+  Hour,
+  DayOfMonth,
+  Month,
+  DayOfWeek,
+}
+
+impl Field {
+  /// The range of the numeric values of this field.
+  ///
+  pub fn range(&self) -> (u32, u32) {
+    match self {
+      Field::Second | Field::Minute => (0, 59),
+      Field::Hour => (0, 23),
+      Field::DayOfMonth => (1, 31),
+      Field::Month => (1, 12),
+      Field::DayOfWeek => (0, 7),
+    }
+  }
+
+  /// The names of the values of this field, which correspond to the values from the start of the range.
+  ///
+  pub fn names(&self) -> &'static [&'static str] {
+    match self {
+      Field::Month => &["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"],
+      Field::DayOfWeek => &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"],
+      _ => &[],
+    }
+  }
+}
+
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ID {
+  Expression,
+  Macro,
+  Field(Field),
+  Range(Field),
+  Step(Field),
+  Value(Field),
+  WS,
+}
+
+impl Display for ID {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// The cron expressions of 5 fields, `minute hour day-of-month month day-of-week`, or of 6 fields with the leading
+/// `second`, as well as the macros such as `@daily`. Each field is a comma-separated list of `*`, a value or a range
+/// `a-b`, optionally followed by a step `/n`. The values are validated against [`Field::range()`], and the months and
+/// the days of the week may also be the case-insensitive [`Field::names()`].
+/// <https://pubs.opengroup.org/onlinepubs/9699919799/utilities/crontab.html>
+///
+pub fn schema() -> Schema<ID, char> {
+  use Field::*;
+  let fields = |fields: &[Field]| {
+    fields.iter().skip(1).fold(id(ID::Field(fields[0])), |syntax, field| syntax & id(ID::WS) & id(ID::Field(*field)))
+  };
+  let expression = fields(&[Minute, Hour, DayOfMonth, Month, DayOfWeek])
+    | fields(&[Second, Minute, Hour, DayOfMonth, Month, DayOfWeek])
+    | id(ID::Macro);
+  let schema = Schema::new("CRON")
+    .define(ID::Expression, (id(ID::WS) * (0..=1)) & expression & (id(ID::WS) * (0..=1)))
+    .define(
+      ID::Macro,
+      one_of_tokens(&["@yearly", "@annually", "@monthly", "@weekly", "@daily", "@midnight", "@hourly", "@reboot"]),
+    )
+    .define(ID::WS, one_of_chars(" \t") * (1..));
+  [Second, Minute, Hour, DayOfMonth, Month, DayOfWeek].into_iter().fold(schema, |schema, field| {
+    let item = || (ch('*') | id(ID::Range(field)) | id(ID::Value(field))) & ((ch('/') & id(ID::Step(field))) * (0..=1));
+    let (min, max) = field.range();
+    schema
+      .define(ID::Field(field), item() & ((ch(',') & item()) * (0..)))
+      .define(ID::Range(field), id(ID::Value(field)) & ch('-') & id(ID::Value(field)))
+      .define(ID::Step(field), value(1, max, &[]))
+      .define(ID::Value(field), value(min, max, field.names()))
+  })
+}
+
+/// A decimal number within `min..=max`, or one of the case-insensitive `names`.
+///
+fn value<ID>(min: u32, max: u32, names: &'static [&'static str]) -> Syntax<ID, char> {
+  let label = format!("{}..={}", min, max);
+  Syntax::from_fn(&label, move |values: &[char]| -> Result<char, MatchResult> {
+    let length = values.iter().position(|ch| !ch.is_ascii_alphanumeric());
+    let token = values[..length.unwrap_or(values.len())].iter().collect::<String>();
+    let valid = match token.parse::<u32>() {
+      Ok(number) => (min..=max).contains(&number),
+      Err(_) => names.iter().any(|name| name.eq_ignore_ascii_case(&token)),
+    };
+    Ok(match length {
+      None if valid => MatchResult::MatchAndCanAcceptMore(values.len()),
+      None => MatchResult::UnmatchAndCanAcceptMore,
+      Some(length) if length > 0 && valid => MatchResult::Match(length),
+      Some(_) => MatchResult::Unmatch,
+    })
+  })
+}
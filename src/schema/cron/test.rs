@@ -0,0 +1,301 @@
+use super::{schema, Field, ID};
+use crate::parser::{test::Events, Context, Event};
+use crate::Error;
+
+#[test]
+fn fields() {
+  use Field::*;
+  let events = parse("*/15 0-6,22 1 jan-MAR mon").unwrap();
+  Events::new()
+    .begin(ID::Expression)
+    .begin(ID::Field(Minute))
+    .fragments("*/")
+    .begin(ID::Step(Minute))
+    .fragments("15")
+    .end()
+    .end()
+    .fragments(" ")
+    .begin(ID::Field(Hour))
+    .begin(ID::Range(Hour))
+    .begin(ID::Value(Hour))
+    .fragments("0")
+    .end()
+    .fragments("-")
+    .begin(ID::Value(Hour))
+    .fragments("6")
+    .end()
+    .end()
+    .fragments(",")
+    .begin(ID::Value(Hour))
+    .fragments("22")
+    .end()
+    .end()
+    .fragments(" ")
+    .begin(ID::Field(DayOfMonth))
+    .begin(ID::Value(DayOfMonth))
+    .fragments("1")
+    .end()
+    .end()
+    .fragments(" ")
+    .begin(ID::Field(Month))
+    .begin(ID::Range(Month))
+    .begin(ID::Value(Month))
+    .fragments("jan")
+    .end()
+    .fragments("-")
+    .begin(ID::Value(Month))
+    .fragments("MAR")
+    .end()
+    .end()
+    .end()
+    .fragments(" ")
+    .begin(ID::Field(DayOfWeek))
+    .begin(ID::Value(DayOfWeek))
+    .fragments("mon")
+    .end()
+    .end()
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn valid_expressions() {
+  use Field::*;
+  for (text, expected) in [
+    (
+      "* * * * *",
+      Events::new()
+        .begin(ID::Expression)
+        .begin(ID::Field(Minute))
+        .fragments("*")
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(Hour))
+        .fragments("*")
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(DayOfMonth))
+        .fragments("*")
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(Month))
+        .fragments("*")
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(DayOfWeek))
+        .fragments("*")
+        .end()
+        .end(),
+    ),
+    (
+      " 0 0 1 1 0 ",
+      Events::new()
+        .begin(ID::Expression)
+        .fragments(" ")
+        .begin(ID::Field(Minute))
+        .begin(ID::Value(Minute))
+        .fragments("0")
+        .end()
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(Hour))
+        .begin(ID::Value(Hour))
+        .fragments("0")
+        .end()
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(DayOfMonth))
+        .begin(ID::Value(DayOfMonth))
+        .fragments("1")
+        .end()
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(Month))
+        .begin(ID::Value(Month))
+        .fragments("1")
+        .end()
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(DayOfWeek))
+        .begin(ID::Value(DayOfWeek))
+        .fragments("0")
+        .end()
+        .end()
+        .fragments(" ")
+        .end(),
+    ),
+    (
+      "0 0 * * 7",
+      Events::new()
+        .begin(ID::Expression)
+        .begin(ID::Field(Minute))
+        .begin(ID::Value(Minute))
+        .fragments("0")
+        .end()
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(Hour))
+        .begin(ID::Value(Hour))
+        .fragments("0")
+        .end()
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(DayOfMonth))
+        .fragments("*")
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(Month))
+        .fragments("*")
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(DayOfWeek))
+        .begin(ID::Value(DayOfWeek))
+        .fragments("7")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "59 23 31 12 SAT",
+      Events::new()
+        .begin(ID::Expression)
+        .begin(ID::Field(Minute))
+        .begin(ID::Value(Minute))
+        .fragments("59")
+        .end()
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(Hour))
+        .begin(ID::Value(Hour))
+        .fragments("23")
+        .end()
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(DayOfMonth))
+        .begin(ID::Value(DayOfMonth))
+        .fragments("31")
+        .end()
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(Month))
+        .begin(ID::Value(Month))
+        .fragments("12")
+        .end()
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(DayOfWeek))
+        .begin(ID::Value(DayOfWeek))
+        .fragments("SAT")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "30 */5 * * * *",
+      Events::new()
+        .begin(ID::Expression)
+        .begin(ID::Field(Second))
+        .begin(ID::Value(Second))
+        .fragments("30")
+        .end()
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(Minute))
+        .fragments("*/")
+        .begin(ID::Step(Minute))
+        .fragments("5")
+        .end()
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(Hour))
+        .fragments("*")
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(DayOfMonth))
+        .fragments("*")
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(Month))
+        .fragments("*")
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(DayOfWeek))
+        .fragments("*")
+        .end()
+        .end(),
+    ),
+    (
+      "0-59/59 * * * *",
+      Events::new()
+        .begin(ID::Expression)
+        .begin(ID::Field(Minute))
+        .begin(ID::Range(Minute))
+        .begin(ID::Value(Minute))
+        .fragments("0")
+        .end()
+        .fragments("-")
+        .begin(ID::Value(Minute))
+        .fragments("59")
+        .end()
+        .end()
+        .fragments("/")
+        .begin(ID::Step(Minute))
+        .fragments("59")
+        .end()
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(Hour))
+        .fragments("*")
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(DayOfMonth))
+        .fragments("*")
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(Month))
+        .fragments("*")
+        .end()
+        .fragments(" ")
+        .begin(ID::Field(DayOfWeek))
+        .fragments("*")
+        .end()
+        .end(),
+    ),
+    ("@daily", Events::new().begin(ID::Expression).begin(ID::Macro).fragments("@daily").end().end()),
+  ] {
+    expected.assert_eq(&parse(text).unwrap());
+  }
+}
+
+#[test]
+fn invalid_expressions() {
+  for (text, position) in [
+    ("", 0),
+    ("* * * *", 7),
+    ("60 * * * *", 0),
+    ("* 24 * * *", 10),
+    ("* * 0 * *", 9),
+    ("* * * 13 *", 10),
+    ("* * * * 8", 9),
+    ("* * * * JAN", 11),
+    ("*/0 * * * *", 2),
+    ("1-", 2),
+    ("* * * * * * *", 12),
+    ("@never", 0),
+  ] {
+    match parse(text) {
+      Err(Error::Unmatched(detail)) => assert_eq!(position, detail.location.chars, "{:?}", text),
+      unexpected => panic!("{:?}: {:?}", text, unexpected),
+    }
+  }
+}
+
+fn parse(text: &str) -> crate::Result<char, Vec<Event<ID, char>>> {
+  let schema = schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, ID::Expression, handler)?.ignore_events_for(&[ID::WS]);
+  parser.push_str(text)?;
+  parser.finish()?;
+  Ok(Event::normalize(&events))
+}
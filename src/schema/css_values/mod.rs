@@ -0,0 +1,193 @@
+use crate::schema::chars::{ch, one_of_chars, one_of_tokens};
+use crate::schema::{id, MatchResult, Schema, Syntax};
+use crate::Result;
+use std::fmt::{Debug, Display};
+
+#[cfg(test)]
+mod test;
+
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ID {
+  Color,
+  HexColor,
+  NamedColor,
+  Rgb,
+  Hsl,
+  Angle,
+  Length,
+  Percentage,
+  Number,
+  WS,
+}
+
+impl Display for ID {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// The units of [`ID::Length`], which are case-insensitive.
+///
+pub const LENGTH_UNITS: [&str; 15] =
+  ["px", "em", "rem", "ex", "ch", "vw", "vh", "vmin", "vmax", "cm", "mm", "q", "in", "pt", "pc"];
+
+/// The units of [`ID::Angle`], which are case-insensitive.
+///
+pub const ANGLE_UNITS: [&str; 4] = ["deg", "rad", "grad", "turn"];
+
+/// The keywords of [`ID::NamedColor`], which are case-insensitive.
+///
+pub const NAMED_COLORS: [&str; 18] = [
+  "black",
+  "silver",
+  "gray",
+  "white",
+  "maroon",
+  "red",
+  "purple",
+  "fuchsia",
+  "green",
+  "lime",
+  "olive",
+  "yellow",
+  "navy",
+  "blue",
+  "teal",
+  "aqua",
+  "transparent",
+  "currentcolor",
+];
+
+/// The micro-grammars of the CSS values as a schema of their own, see [`define()`] to compose them into a larger
+/// schema.
+///
+pub fn schema() -> Schema<ID, char> {
+  define(Schema::new("CSS_VALUES"))
+}
+
+/// Define the CSS values in `schema` whose IDs can be converted from [`ID`], so that its rules can refer to them such
+/// as `id(MyID::Css(css_values::ID::Color))`.
+///
+/// - [`ID::Color`]: a hex color `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`, `rgb()`, `rgba()`, `hsl()` or `hsla()`
+///   with either comma- or space-separated arguments, or one of the [`NAMED_COLORS`].
+/// - [`ID::Length`]: a number with one of the [`LENGTH_UNITS`], or `0` without a unit.
+/// - [`ID::Percentage`]: a number followed by `%`.
+/// - [`ID::Angle`]: a number with one of the [`ANGLE_UNITS`].
+/// - [`ID::Number`]: an integer or a real number with an optional sign and exponent.
+///
+/// <https://www.w3.org/TR/css-color-4/> <https://www.w3.org/TR/css-values-4/>
+///
+pub fn define<T>(schema: Schema<T, char>) -> Schema<T, char>
+where
+  T: From<ID> + Ord + Debug,
+{
+  let i = |i: ID| id(T::from(i));
+  let sep = || (i(ID::WS) & ch(',') & i(ID::WS)) | (one_of_chars(" \t\n") * (1..));
+  let alpha = || (i(ID::WS) & one_of_chars(",/") & i(ID::WS) & (i(ID::Percentage) | i(ID::Number))) * (0..=1);
+  let component = || i(ID::Percentage) | i(ID::Number);
+  schema
+    .define(T::from(ID::Color), i(ID::HexColor) | i(ID::Rgb) | i(ID::Hsl) | i(ID::NamedColor))
+    .define(T::from(ID::HexColor), ch('#') & hex_digits())
+    .define(T::from(ID::NamedColor), keyword("NAMED_COLOR", &NAMED_COLORS))
+    .define(
+      T::from(ID::Rgb),
+      one_of_tokens(&["rgb(", "rgba("])
+        & i(ID::WS)
+        & component()
+        & sep()
+        & component()
+        & sep()
+        & component()
+        & alpha()
+        & i(ID::WS)
+        & ch(')'),
+    )
+    .define(
+      T::from(ID::Hsl),
+      one_of_tokens(&["hsl(", "hsla("])
+        & i(ID::WS)
+        & (i(ID::Angle) | i(ID::Number))
+        & sep()
+        & i(ID::Percentage)
+        & sep()
+        & i(ID::Percentage)
+        & alpha()
+        & i(ID::WS)
+        & ch(')'),
+    )
+    .define(T::from(ID::Angle), i(ID::Number) & keyword("ANGLE_UNIT", &ANGLE_UNITS))
+    .define(T::from(ID::Length), (i(ID::Number) & keyword("LENGTH_UNIT", &LENGTH_UNITS)) | ch('0'))
+    .define(T::from(ID::Percentage), i(ID::Number) & ch('%'))
+    .define(T::from(ID::Number), number())
+    .define(T::from(ID::WS), one_of_chars(" \t\n") * (0..))
+}
+
+/// `#` followed by 3, 4, 6 or 8 hexadecimal digits.
+///
+fn hex_digits<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("HEX_DIGITS", |values: &[char]| -> Result<char, MatchResult> {
+    let length = values.iter().position(|ch| !ch.is_ascii_hexdigit());
+    let valid = |length: usize| matches!(length, 3 | 4 | 6 | 8);
+    Ok(match length {
+      None if values.len() > 8 => MatchResult::Unmatch,
+      None if valid(values.len()) => MatchResult::MatchAndCanAcceptMore(values.len()),
+      None => MatchResult::UnmatchAndCanAcceptMore,
+      Some(length) if valid(length) => MatchResult::Match(length),
+      Some(_) => MatchResult::Unmatch,
+    })
+  })
+}
+
+/// One of the case-insensitive ASCII `keywords` that isn't followed by another letter.
+///
+fn keyword<ID>(label: &str, keywords: &'static [&'static str]) -> Syntax<ID, char> {
+  Syntax::from_fn(label, move |values: &[char]| -> Result<char, MatchResult> {
+    let length = values.iter().position(|ch| !ch.is_ascii_alphabetic());
+    let word = values[..length.unwrap_or(values.len())].iter().collect::<String>();
+    let matches = keywords.iter().any(|k| k.eq_ignore_ascii_case(&word));
+    let prefix = keywords.iter().any(|k| k.len() > word.len() && k[..word.len()].eq_ignore_ascii_case(&word));
+    Ok(match length {
+      None if matches => MatchResult::MatchAndCanAcceptMore(values.len()),
+      None if prefix || values.is_empty() => MatchResult::UnmatchAndCanAcceptMore,
+      Some(length) if length > 0 && matches => MatchResult::Match(length),
+      _ => MatchResult::Unmatch,
+    })
+  })
+}
+
+/// `[+-]? (digits ("." digits)? | "." digits) ([eE] [+-]? digits)?`
+/// <https://www.w3.org/TR/css-syntax-3/#consume-number>
+///
+fn number<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("NUMBER", |values: &[char]| -> Result<char, MatchResult> {
+    let digits = |from: usize| values[from..].iter().take_while(|ch| ch.is_ascii_digit()).count();
+    let mut i = if matches!(values.first(), Some('+' | '-')) { 1 } else { 0 };
+    let integer = digits(i);
+    i += integer;
+    let mut fraction = 0;
+    if values.get(i) == Some(&'.') {
+      fraction = digits(i + 1);
+      if fraction > 0 {
+        i += 1 + fraction;
+      }
+    }
+    if integer == 0 && fraction == 0 {
+      let partial = values.len() <= i + 1;
+      return Ok(if partial { MatchResult::UnmatchAndCanAcceptMore } else { MatchResult::Unmatch });
+    }
+    if matches!(values.get(i), Some('e' | 'E')) {
+      let sign = if matches!(values.get(i + 1), Some('+' | '-')) { 1 } else { 0 };
+      let exponent = digits(std::cmp::min(values.len(), i + 1 + sign));
+      if exponent > 0 {
+        i += 1 + sign + exponent;
+      } else if values.len() <= i + 1 + sign {
+        return Ok(MatchResult::MatchAndCanAcceptMore(i));
+      }
+    }
+    Ok(if i == values.len() || (values.get(i) == Some(&'.') && i + 1 == values.len()) {
+      MatchResult::MatchAndCanAcceptMore(i)
+    } else {
+      MatchResult::Match(i)
+    })
+  })
+}
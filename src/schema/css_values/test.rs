@@ -0,0 +1,112 @@
+use super::{define, schema, ID};
+use crate::parser::{test::Events, Context, Event};
+use crate::schema::chars::{ch, token};
+use crate::schema::{id, Schema};
+
+#[test]
+fn colors() {
+  for color in [
+    "#fff",
+    "#FFFA",
+    "#00ff00",
+    "#00ff0080",
+    "red",
+    "CurrentColor",
+    "rgb(255,0,0)",
+    "rgba( 255 , 0 , 0 , .5 )",
+    "rgb(100% 0% 0% / 50%)",
+    "hsl(120, 100%, 50%)",
+    "hsla(0.5turn 100% 50% / 0.25)",
+  ] {
+    assert!(parse(ID::Color, color).is_ok(), "{:?}", color);
+  }
+  for color in ["#ff", "#fffff", "#ggg", "reddish", "rgb(1,2)", "rgb(1,2,3", "hsl(1, 2, 3)"] {
+    assert!(parse(ID::Color, color).is_err(), "{:?}", color);
+  }
+}
+
+#[test]
+fn numbers_and_dimensions() {
+  for (id, text) in [
+    (ID::Number, "0"),
+    (ID::Number, "-1.5"),
+    (ID::Number, "+.5e-3"),
+    (ID::Length, "0"),
+    (ID::Length, "12px"),
+    (ID::Length, "1.5EM"),
+    (ID::Length, "1e1vmin"),
+    (ID::Percentage, "50%"),
+    (ID::Angle, "-90deg"),
+  ] {
+    assert!(parse(id, text).is_ok(), "{:?}: {:?}", id, text);
+  }
+  for (id, text) in [(ID::Number, "."), (ID::Number, "1."), (ID::Length, "12"), (ID::Length, "1xx"), (ID::Angle, "90")]
+  {
+    assert!(parse(id, text).is_err(), "{:?}: {:?}", id, text);
+  }
+}
+
+#[test]
+fn events() {
+  let events = parse(ID::Color, "rgb(1, 50%, 3)").unwrap();
+  Events::new()
+    .begin(ID::Color)
+    .begin(ID::Rgb)
+    .fragments("rgb(")
+    .begin(ID::Number)
+    .fragments("1")
+    .end()
+    .fragments(", ")
+    .begin(ID::Percentage)
+    .begin(ID::Number)
+    .fragments("50")
+    .end()
+    .fragments("%")
+    .end()
+    .fragments(", ")
+    .begin(ID::Number)
+    .fragments("3")
+    .end()
+    .fragments(")")
+    .end()
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn composed_into_another_schema() {
+  #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+  enum Css {
+    Declaration,
+    Value(ID),
+  }
+  impl From<ID> for Css {
+    fn from(id: ID) -> Self {
+      Css::Value(id)
+    }
+  }
+  impl std::fmt::Display for Css {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      write!(f, "{:?}", self)
+    }
+  }
+
+  let schema = define(Schema::new("CSS").define(
+    Css::Declaration,
+    (token("color:") & id(Css::Value(ID::Color)) | token("width:") & id(Css::Value(ID::Length))) & ch(';'),
+  ));
+  for (text, ok) in [("color:#abc;", true), ("width:10px;", true), ("width:red;", false)] {
+    let mut parser = Context::new(&schema, Css::Declaration, |_: &Event<_, _>| ()).unwrap();
+    assert_eq!(ok, parser.push_str(text).and_then(|_| parser.finish_unit()).is_ok(), "{:?}", text);
+  }
+}
+
+fn parse(id: ID, text: &str) -> crate::Result<char, Vec<Event<ID, char>>> {
+  let schema = schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, id, handler)?.ignore_events_for(&[ID::WS]);
+  parser.push_str(text)?;
+  parser.finish()?;
+  Ok(Event::normalize(&events))
+}
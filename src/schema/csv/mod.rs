@@ -0,0 +1,45 @@
+use crate::schema::chars::{ch, newline, none_of_chars, token};
+use crate::schema::{id, Schema};
+use std::fmt::Display;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ID {
+  File,
+  Record,
+  Field,
+  Escaped,
+  NonEscaped,
+  Comma,
+  Newline,
+  DQuote,
+  TextData,
+}
+
+impl Display for ID {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// Common Format and MIME Type for Comma-Separated Values (CSV) Files
+/// <https://datatracker.ietf.org/doc/html/rfc4180>
+///
+/// The records may be separated by LF or CR as well as CRLF. Since a record may consist of a single empty field, the
+/// line break at the end of the file is parsed as the separator of an empty record.
+///
+pub fn schema() -> Schema<ID, char> {
+  use ID::*;
+  Schema::new("CSV")
+    .define(File, id(Record) & ((id(Newline) & id(Record)) * (0..)))
+    .define(Record, id(Field) & ((id(Comma) & id(Field)) * (0..)))
+    .define(Field, id(Escaped) | id(NonEscaped))
+    .define(Escaped, id(DQuote) & ((none_of_chars("\"") | token("\"\"")) * (0..)) & id(DQuote))
+    .define(NonEscaped, id(TextData) * (0..))
+    .define(Comma, ch(','))
+    .define(Newline, newline())
+    .define(DQuote, ch('\"'))
+    .define(TextData, none_of_chars(",\"\r\n"))
+}
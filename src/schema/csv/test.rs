@@ -0,0 +1,59 @@
+use super::{schema, ID};
+use crate::parser::{test::Events, Context, Event};
+
+#[test]
+fn records() {
+  let events = parse("a,\"b,\"\"c\"\"\"\r\n,d\n");
+  Events::new()
+    .begin(ID::File)
+    .begin(ID::Record)
+    .begin(ID::Field)
+    .fragments("a")
+    .end()
+    .fragments(",")
+    .begin(ID::Field)
+    .fragments("\"b,\"\"c\"\"\"")
+    .end()
+    .end()
+    .fragments("\r\n")
+    .begin(ID::Record)
+    .begin(ID::Field)
+    .end()
+    .fragments(",")
+    .begin(ID::Field)
+    .fragments("d")
+    .end()
+    .end()
+    .fragments("\n")
+    .begin(ID::Record)
+    .begin(ID::Field)
+    .end()
+    .end()
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn unterminated_quote() {
+  let schema = schema();
+  let mut parser = Context::new(&schema, ID::File, |_: &Event<_, _>| ()).unwrap();
+  parser.push_str("a,\"b").unwrap();
+  assert!(parser.finish().is_err());
+}
+
+fn parse(text: &str) -> Vec<Event<ID, char>> {
+  let schema = schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, ID::File, handler).unwrap().ignore_events_for(&[
+    ID::Escaped,
+    ID::NonEscaped,
+    ID::Comma,
+    ID::Newline,
+    ID::DQuote,
+    ID::TextData,
+  ]);
+  parser.push_str(text).unwrap();
+  parser.finish().unwrap();
+  Event::normalize(&events)
+}
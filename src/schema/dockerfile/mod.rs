@@ -0,0 +1,211 @@
+use crate::schema::chars::{ch, line, newline, one_of_chars};
+use crate::schema::{eof, id, MatchResult, Schema, Syntax};
+use crate::Result;
+use std::fmt::Display;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ID {
+  Dockerfile,
+  Instruction,
+  Keyword,
+  Arguments,
+  Continuation,
+  Heredoc,
+  Comment,
+  WS,
+}
+
+impl Display for ID {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// The instructions of [`ID::Keyword`], which are case-insensitive.
+///
+pub const INSTRUCTIONS: [&str; 18] = [
+  "ADD",
+  "ARG",
+  "CMD",
+  "COPY",
+  "ENTRYPOINT",
+  "ENV",
+  "EXPOSE",
+  "FROM",
+  "HEALTHCHECK",
+  "LABEL",
+  "MAINTAINER",
+  "ONBUILD",
+  "RUN",
+  "SHELL",
+  "STOPSIGNAL",
+  "USER",
+  "VOLUME",
+  "WORKDIR",
+];
+
+/// The Dockerfile, a sequence of lines each of which is blank, a [`ID::Comment`] or an [`ID::Instruction`].
+///
+/// - An instruction begins at the start of a line with one of the [`INSTRUCTIONS`] followed by the
+///   [`ID::Arguments`], which may span multiple lines by the line [`ID::Continuation`] `\`. The comment lines within
+///   the continuation are skipped.
+/// - An [`ID::Heredoc`] such as `<<EOF` in the arguments extends to the line consisting only of its delimiter. The
+///   heredoc includes the rest of the line where it appears, and the bodies of the subsequent heredocs on that line.
+/// - The parser directives such as `# syntax=...` are treated as comments, so the `escape` directive isn't
+///   supported.
+///
+/// <https://docs.docker.com/reference/dockerfile/>
+///
+pub fn schema() -> Schema<ID, char> {
+  let ws = || id(ID::WS) * (0..=1);
+  let end_of_line = || newline() | eof();
+  let item = |first: bool| arguments_text(first) | id(ID::Heredoc);
+  Schema::new("DOCKERFILE")
+    .define(ID::Dockerfile, ((ws() & newline()) | id(ID::Comment) | id(ID::Instruction)) * (0..))
+    .define(ID::Comment, ws() & ch('#') & line() & end_of_line())
+    .define(
+      ID::Instruction,
+      ws() & id(ID::Keyword) & ((id(ID::WS) & id(ID::Arguments)) * (0..=1)) & ws() & end_of_line(),
+    )
+    .define(ID::Keyword, keyword())
+    .define(ID::Arguments, item(true) & ((id(ID::Continuation) | (ws() & item(false))) * (0..)))
+    .define(ID::Continuation, ch('\\') & ws() & newline() & ((ws() & ch('#') & line() & newline()) * (0..)))
+    .define(ID::Heredoc, heredoc())
+    .define(ID::WS, one_of_chars(" \t") * (1..))
+}
+
+/// One of the case-insensitive [`INSTRUCTIONS`] that isn't followed by another letter.
+///
+fn keyword<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("INSTRUCTION", |values: &[char]| -> Result<char, MatchResult> {
+    let length = values.iter().position(|ch| !ch.is_ascii_alphabetic());
+    let word = values[..length.unwrap_or(values.len())].iter().collect::<String>();
+    let matches = INSTRUCTIONS.iter().any(|k| k.eq_ignore_ascii_case(&word));
+    let prefix = INSTRUCTIONS.iter().any(|k| k.len() > word.len() && k[..word.len()].eq_ignore_ascii_case(&word));
+    Ok(match length {
+      None if matches => MatchResult::MatchAndCanAcceptMore(values.len()),
+      None if prefix || values.is_empty() => MatchResult::UnmatchAndCanAcceptMore,
+      Some(length) if length > 0 && matches => MatchResult::Match(length),
+      _ => MatchResult::Unmatch,
+    })
+  })
+}
+
+/// The text of the arguments up to the end of the line, a line continuation `\` or a heredoc. It doesn't begin with a
+/// whitespace, nor with `#` unless it's the `first` of the arguments, so that the comment lines within the
+/// continuation aren't taken as the arguments.
+///
+fn arguments_text<ID>(first: bool) -> Syntax<ID, char> {
+  Syntax::from_fn("ARGUMENTS", move |values: &[char]| -> Result<char, MatchResult> {
+    match values.first() {
+      None => return Ok(MatchResult::UnmatchAndCanAcceptMore),
+      Some(' ' | '\t' | '\n' | '\r') => return Ok(MatchResult::Unmatch),
+      Some('#') if !first => return Ok(MatchResult::Unmatch),
+      Some(_) => (),
+    }
+    for i in 0..values.len() {
+      let length = match values[i] {
+        '\n' | '\r' => {
+          let content = values[..i].iter().rposition(|ch| *ch != ' ' && *ch != '\t').map(|j| j + 1).unwrap_or(0);
+          if content > 0 && values[content - 1] == '\\' {
+            content - 1
+          } else {
+            i
+          }
+        }
+        '<' if heredoc_marker(&values[i..]).is_some() => i,
+        _ => continue,
+      };
+      return Ok(if length > 0 { MatchResult::Match(length) } else { MatchResult::Unmatch });
+    }
+    Ok(MatchResult::MatchAndCanAcceptMore(values.len()))
+  })
+}
+
+/// The heredoc from its marker `<<[-]DELIMITER` to the end of the line consisting only of the delimiter. If the line
+/// of the marker contains other markers, it extends to the end of the last one.
+///
+fn heredoc<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("HEREDOC", |values: &[char]| -> Result<char, MatchResult> {
+    match values {
+      [] | ['<'] => return Ok(MatchResult::UnmatchAndCanAcceptMore),
+      ['<', '<', ..] => (),
+      _ => return Ok(MatchResult::Unmatch),
+    }
+    let eol = match values.iter().position(|ch| *ch == '\n') {
+      Some(eol) => eol,
+      None => return Ok(MatchResult::UnmatchAndCanAcceptMore),
+    };
+    let mut markers = Vec::new();
+    let mut i = 0;
+    while i < eol {
+      match heredoc_marker(&values[i..eol]) {
+        Some((delimiter, strip_tabs, length)) => {
+          markers.push((delimiter, strip_tabs));
+          i += length;
+        }
+        None if i == 0 => return Ok(MatchResult::Unmatch),
+        None => i += 1,
+      }
+    }
+
+    let mut begin = eol + 1;
+    for (n, (delimiter, strip_tabs)) in markers.iter().enumerate() {
+      let last = n + 1 == markers.len();
+      loop {
+        let end = values[begin..].iter().position(|ch| *ch == '\n').map(|i| begin + i);
+        let content = &values[begin..end.unwrap_or(values.len())];
+        let content = content.strip_suffix(&['\r']).unwrap_or(content);
+        let leading_tabs = if *strip_tabs { content.iter().take_while(|ch| **ch == '\t').count() } else { 0 };
+        let terminated = content[leading_tabs..] == delimiter[..];
+        match end {
+          None if terminated && last => return Ok(MatchResult::MatchAndCanAcceptMore(begin + content.len())),
+          None => return Ok(MatchResult::UnmatchAndCanAcceptMore),
+          Some(_) if terminated && last => return Ok(MatchResult::Match(begin + content.len())),
+          Some(end) => {
+            begin = end + 1;
+            if terminated {
+              break;
+            }
+          }
+        }
+      }
+    }
+    unreachable!()
+  })
+}
+
+/// Parse the heredoc marker `<<[-]DELIMITER` at the head of `values`, where the delimiter is a word that doesn't begin
+/// with a digit, such as `<<2` of a shift operator, and may be quoted with `"` or `'`. Returns the delimiter, whether
+/// the leading tabs of the body are stripped by `-`, and the length of the marker.
+///
+fn heredoc_marker(values: &[char]) -> Option<(Vec<char>, bool, usize)> {
+  let mut i = 2;
+  if values.len() < i || values[..i] != ['<', '<'] {
+    return None;
+  }
+  let strip_tabs = values.get(i) == Some(&'-');
+  if strip_tabs {
+    i += 1;
+  }
+  let quote = values.get(i).filter(|ch| **ch == '"' || **ch == '\'').copied();
+  if quote.is_some() {
+    i += 1;
+  }
+  let length = values[i..].iter().take_while(|ch| ch.is_ascii_alphanumeric() || **ch == '_').count();
+  if length == 0 || values[i].is_ascii_digit() {
+    return None;
+  }
+  let delimiter = values[i..][..length].to_vec();
+  i += length;
+  if let Some(quote) = quote {
+    if values.get(i) != Some(&quote) {
+      return None;
+    }
+    i += 1;
+  }
+  Some((delimiter, strip_tabs, i))
+}
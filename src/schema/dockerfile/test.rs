@@ -0,0 +1,338 @@
+use super::{schema, ID};
+use crate::parser::{test::Events, Context, Event};
+use crate::Error;
+
+#[test]
+fn instructions() {
+  let text = concat!(
+    "# syntax=docker/dockerfile:1\n\n",
+    "FROM rust:1 AS build\n",
+    "  run cargo build \\\n",
+    "  # comment\n",
+    "    --release\n",
+    "CMD [\"app\"]",
+  );
+  let events = parse(text).unwrap();
+  Events::new()
+    .begin(ID::Dockerfile)
+    .begin(ID::Comment)
+    .fragments("# syntax=docker/dockerfile:1\n")
+    .end()
+    .fragments("\n")
+    .begin(ID::Instruction)
+    .begin(ID::Keyword)
+    .fragments("FROM")
+    .end()
+    .fragments(" ")
+    .begin(ID::Arguments)
+    .fragments("rust:1 AS build")
+    .end()
+    .fragments("\n")
+    .end()
+    .begin(ID::Instruction)
+    .fragments("  ")
+    .begin(ID::Keyword)
+    .fragments("run")
+    .end()
+    .fragments(" ")
+    .begin(ID::Arguments)
+    .fragments("cargo build ")
+    .begin(ID::Continuation)
+    .fragments("\\\n  # comment\n")
+    .end()
+    .fragments("    --release")
+    .end()
+    .fragments("\n")
+    .end()
+    .begin(ID::Instruction)
+    .begin(ID::Keyword)
+    .fragments("CMD")
+    .end()
+    .fragments(" ")
+    .begin(ID::Arguments)
+    .fragments("[\"app\"]")
+    .end()
+    .end()
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn heredocs() {
+  let events = parse("RUN <<EOF\necho a\nEOF\nCOPY <<-\"A\" /a <<B /b\n\tx\n\tA\ny\nB\n").unwrap();
+  Events::new()
+    .begin(ID::Dockerfile)
+    .begin(ID::Instruction)
+    .begin(ID::Keyword)
+    .fragments("RUN")
+    .end()
+    .fragments(" ")
+    .begin(ID::Arguments)
+    .begin(ID::Heredoc)
+    .fragments("<<EOF\necho a\nEOF")
+    .end()
+    .end()
+    .fragments("\n")
+    .end()
+    .begin(ID::Instruction)
+    .begin(ID::Keyword)
+    .fragments("COPY")
+    .end()
+    .fragments(" ")
+    .begin(ID::Arguments)
+    .begin(ID::Heredoc)
+    .fragments("<<-\"A\" /a <<B /b\n\tx\n\tA\ny\nB")
+    .end()
+    .end()
+    .fragments("\n")
+    .end()
+    .end()
+    .assert_eq(&events);
+
+  let events = parse("RUN echo $((1<<2)) > /a <<EOF\nEOF").unwrap();
+  Events::new()
+    .begin(ID::Dockerfile)
+    .begin(ID::Instruction)
+    .begin(ID::Keyword)
+    .fragments("RUN")
+    .end()
+    .fragments(" ")
+    .begin(ID::Arguments)
+    .fragments("echo $((1<<2)) > /a ")
+    .begin(ID::Heredoc)
+    .fragments("<<EOF\nEOF")
+    .end()
+    .end()
+    .end()
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn valid_dockerfiles() {
+  for (text, expected) in [
+    ("", Events::new()),
+    ("\n\n", Events::new().begin(ID::Dockerfile).fragments("\n\n").end()),
+    (
+      "# only a comment",
+      Events::new().begin(ID::Dockerfile).begin(ID::Comment).fragments("# only a comment").end().end(),
+    ),
+    (
+      "FROM scratch",
+      Events::new()
+        .begin(ID::Dockerfile)
+        .begin(ID::Instruction)
+        .begin(ID::Keyword)
+        .fragments("FROM")
+        .end()
+        .fragments(" ")
+        .begin(ID::Arguments)
+        .fragments("scratch")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "FROM scratch\n",
+      Events::new()
+        .begin(ID::Dockerfile)
+        .begin(ID::Instruction)
+        .begin(ID::Keyword)
+        .fragments("FROM")
+        .end()
+        .fragments(" ")
+        .begin(ID::Arguments)
+        .fragments("scratch")
+        .end()
+        .fragments("\n")
+        .end()
+        .end(),
+    ),
+    (
+      "from scratch\r\nrun a\r\n",
+      Events::new()
+        .begin(ID::Dockerfile)
+        .begin(ID::Instruction)
+        .begin(ID::Keyword)
+        .fragments("from")
+        .end()
+        .fragments(" ")
+        .begin(ID::Arguments)
+        .fragments("scratch")
+        .end()
+        .fragments("\r\n")
+        .end()
+        .begin(ID::Instruction)
+        .begin(ID::Keyword)
+        .fragments("run")
+        .end()
+        .fragments(" ")
+        .begin(ID::Arguments)
+        .fragments("a")
+        .end()
+        .fragments("\r\n")
+        .end()
+        .end(),
+    ),
+    (
+      "\tFROM scratch  \n",
+      Events::new()
+        .begin(ID::Dockerfile)
+        .begin(ID::Instruction)
+        .fragments("\t")
+        .begin(ID::Keyword)
+        .fragments("FROM")
+        .end()
+        .fragments(" ")
+        .begin(ID::Arguments)
+        .fragments("scratch  ")
+        .end()
+        .fragments("\n")
+        .end()
+        .end(),
+    ),
+    (
+      "EXPOSE\n",
+      Events::new()
+        .begin(ID::Dockerfile)
+        .begin(ID::Instruction)
+        .begin(ID::Keyword)
+        .fragments("EXPOSE")
+        .end()
+        .fragments("\n")
+        .end()
+        .end(),
+    ),
+    (
+      "RUN a \\\n",
+      Events::new()
+        .begin(ID::Dockerfile)
+        .begin(ID::Instruction)
+        .begin(ID::Keyword)
+        .fragments("RUN")
+        .end()
+        .fragments(" ")
+        .begin(ID::Arguments)
+        .fragments("a ")
+        .begin(ID::Continuation)
+        .fragments("\\\n")
+        .end()
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "RUN a \\\n\nRUN b",
+      Events::new()
+        .begin(ID::Dockerfile)
+        .begin(ID::Instruction)
+        .begin(ID::Keyword)
+        .fragments("RUN")
+        .end()
+        .fragments(" ")
+        .begin(ID::Arguments)
+        .fragments("a ")
+        .begin(ID::Continuation)
+        .fragments("\\\n")
+        .end()
+        .end()
+        .fragments("\n")
+        .end()
+        .begin(ID::Instruction)
+        .begin(ID::Keyword)
+        .fragments("RUN")
+        .end()
+        .fragments(" ")
+        .begin(ID::Arguments)
+        .fragments("b")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "RUN a \\  \n  b \\\n  c",
+      Events::new()
+        .begin(ID::Dockerfile)
+        .begin(ID::Instruction)
+        .begin(ID::Keyword)
+        .fragments("RUN")
+        .end()
+        .fragments(" ")
+        .begin(ID::Arguments)
+        .fragments("a ")
+        .begin(ID::Continuation)
+        .fragments("\\  \n")
+        .end()
+        .fragments("  b ")
+        .begin(ID::Continuation)
+        .fragments("\\\n")
+        .end()
+        .fragments("  c")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "RUN #not a comment\n",
+      Events::new()
+        .begin(ID::Dockerfile)
+        .begin(ID::Instruction)
+        .begin(ID::Keyword)
+        .fragments("RUN")
+        .end()
+        .fragments(" ")
+        .begin(ID::Arguments)
+        .fragments("#not a comment")
+        .end()
+        .fragments("\n")
+        .end()
+        .end(),
+    ),
+    (
+      "RUN <<EOF\n  EOF\nEOF",
+      Events::new()
+        .begin(ID::Dockerfile)
+        .begin(ID::Instruction)
+        .begin(ID::Keyword)
+        .fragments("RUN")
+        .end()
+        .fragments(" ")
+        .begin(ID::Arguments)
+        .begin(ID::Heredoc)
+        .fragments("<<EOF\n  EOF\nEOF")
+        .end()
+        .end()
+        .end()
+        .end(),
+    ),
+  ] {
+    expected.assert_eq(&parse(text).unwrap());
+  }
+}
+
+#[test]
+fn invalid_dockerfiles() {
+  for (text, position) in [
+    ("FRM scratch", 0),
+    ("FROMscratch", 0),
+    ("  # comment\n  x", 14),
+    ("RUN <<EOF\necho a\n", 4),
+    ("RUN <<EOF\n\tEOF\n", 4),
+    ("RUN a\n b", 7),
+  ] {
+    match parse(text) {
+      Err(Error::Unmatched(detail)) => assert_eq!(position, detail.location.chars, "{:?}", text),
+      unexpected => panic!("{:?}: {:?}", text, unexpected),
+    }
+  }
+}
+
+fn parse(text: &str) -> crate::Result<char, Vec<Event<ID, char>>> {
+  let schema = schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, ID::Dockerfile, handler)?.ignore_events_for(&[ID::WS]);
+  parser.push_str(text)?;
+  parser.finish()?;
+  Ok(Event::normalize(&events))
+}
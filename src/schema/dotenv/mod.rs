@@ -0,0 +1,173 @@
+use crate::schema::chars::{ch, line, newline, none_of_chars, one_of_chars, one_of_tokens, token, until_token};
+use crate::schema::{eof, id, MatchResult, Schema, Syntax};
+use crate::Result;
+use std::fmt::Display;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ID {
+  Dotenv,
+  Entry,
+  Key,
+  Value,
+  SingleQuoted,
+  DoubleQuoted,
+  Escape,
+  Interpolation,
+  Variable,
+  Default,
+  Comment,
+  WS,
+}
+
+impl Display for ID {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// The environment file `.env`, a sequence of lines each of which is blank, a [`ID::Comment`] or an [`ID::Entry`]
+/// `KEY=value` optionally preceded by `export`. The [`ID::Value`] is one of:
+///
+/// - an unquoted text up to the end of the line or a comment `#` that begins the value or follows a whitespace,
+///   without the trailing whitespaces,
+/// - an [`ID::SingleQuoted`] literal text without escapes and interpolations, or
+/// - an [`ID::DoubleQuoted`] text with the [`ID::Escape`]s such as `\n` or `\"`.
+///
+/// Both the unquoted and the double-quoted values may contain the [`ID::Interpolation`]s `$VAR`, `${VAR}`,
+/// `${VAR:-default}` or `${VAR-default}`, so that the [`ID::Variable`]s and the [`ID::Default`]s can be resolved by
+/// the application. The quoted values may span multiple lines.
+///
+pub fn schema() -> Schema<ID, char> {
+  let ws = || id(ID::WS) * (0..=1);
+  let end_of_line = || newline() | eof();
+  let comment = || id(ID::Comment) * (0..=1);
+  let interpolation = || id(ID::Interpolation);
+  Schema::new("DOTENV")
+    .define(
+      ID::Dotenv,
+      ((ws() & comment() & newline()) | (id(ID::WS) & comment() & eof()) | (id(ID::Comment) & eof()) | id(ID::Entry))
+        * (0..),
+    )
+    .define(
+      ID::Entry,
+      ws()
+        & ((token("export") & id(ID::WS)) * (0..=1))
+        & id(ID::Key)
+        & ws()
+        & ch('=')
+        & ((ws() & id(ID::Value)) * (0..=1))
+        & ws()
+        & comment()
+        & end_of_line(),
+    )
+    .define(ID::Key, name())
+    .define(
+      ID::Value,
+      id(ID::SingleQuoted)
+        | id(ID::DoubleQuoted)
+        | ((unquoted_text(true) | interpolation()) & ((unquoted_text(false) | interpolation()) * (0..))),
+    )
+    .define(ID::SingleQuoted, ch('\'') & until_token("'") & ch('\''))
+    .define(ID::DoubleQuoted, ch('"') & ((double_quoted_text() | id(ID::Escape) | interpolation()) * (0..)) & ch('"'))
+    .define(ID::Escape, ch('\\') & none_of_chars(""))
+    .define(
+      ID::Interpolation,
+      (token("${") & id(ID::Variable) & ((one_of_tokens(&[":-", "-"]) & id(ID::Default)) * (0..=1)) & ch('}'))
+        | (ch('$') & id(ID::Variable)),
+    )
+    .define(ID::Variable, name())
+    .define(ID::Default, none_of_chars("}") * (0..))
+    .define(ID::Comment, ch('#') & line())
+    .define(ID::WS, one_of_chars(" \t") * (1..))
+}
+
+/// A name that begins with an ASCII letter or `_` followed by ASCII letters, digits or `_`, as long as possible.
+///
+fn name<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("NAME", |values: &[char]| -> Result<char, MatchResult> {
+    let length = values.iter().enumerate().position(|(i, ch)| !is_name_char(*ch, i == 0));
+    Ok(match length {
+      None if values.is_empty() => MatchResult::UnmatchAndCanAcceptMore,
+      None => MatchResult::MatchAndCanAcceptMore(values.len()),
+      Some(0) => MatchResult::Unmatch,
+      Some(length) => MatchResult::Match(length),
+    })
+  })
+}
+
+fn is_name_char(ch: char, first: bool) -> bool {
+  ch.is_ascii_alphabetic() || ch == '_' || (!first && ch.is_ascii_digit())
+}
+
+/// Whether an interpolation begins at the head of `values`, or `None` if it can't be determined yet.
+///
+fn begins_interpolation(values: &[char]) -> Option<bool> {
+  match values {
+    ['$'] => None,
+    ['$', ch, ..] => Some(*ch == '{' || is_name_char(*ch, true)),
+    _ => Some(false),
+  }
+}
+
+/// The text of an unquoted value up to the end of the line, an interpolation or a comment, without the trailing
+/// whitespaces. It doesn't begin with a whitespace or `#` if it's the `first` of the value.
+///
+fn unquoted_text<ID>(first: bool) -> Syntax<ID, char> {
+  Syntax::from_fn("UNQUOTED", move |values: &[char]| -> Result<char, MatchResult> {
+    match values.first() {
+      None => return Ok(MatchResult::UnmatchAndCanAcceptMore),
+      Some(' ' | '\t' | '#' | '"' | '\'') if first => return Ok(MatchResult::Unmatch),
+      Some(_) => (),
+    }
+    let mut end = None;
+    for i in 0..values.len() {
+      let stop = match values[i] {
+        '\n' | '\r' => true,
+        '#' => i == 0 || values[i - 1] == ' ' || values[i - 1] == '\t',
+        // a trailing `$` is a part of the text if the input ends there
+        '$' => match begins_interpolation(&values[i..]) {
+          Some(begins) => begins,
+          None => break,
+        },
+        _ => false,
+      };
+      if stop {
+        end = Some(i);
+        break;
+      }
+    }
+    let trim = |i: usize| values[..i].iter().rposition(|ch| *ch != ' ' && *ch != '\t').map(|j| j + 1).unwrap_or(0);
+    Ok(match end {
+      // the whitespaces before an interpolation belong to the value
+      Some(i) if values[i] == '$' && i > 0 => MatchResult::Match(i),
+      Some(i) if trim(i) > 0 => MatchResult::Match(trim(i)),
+      Some(_) => MatchResult::Unmatch,
+      None if trim(values.len()) > 0 => MatchResult::MatchAndCanAcceptMore(trim(values.len())),
+      None => MatchResult::UnmatchAndCanAcceptMore,
+    })
+  })
+}
+
+/// The text of a double-quoted value up to the closing `"`, an escape or an interpolation.
+///
+fn double_quoted_text<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("DOUBLE_QUOTED", |values: &[char]| -> Result<char, MatchResult> {
+    for i in 0..values.len() {
+      let stop = match values[i] {
+        '"' | '\\' => true,
+        '$' => match begins_interpolation(&values[i..]) {
+          Some(begins) => begins,
+          None => return Ok(MatchResult::UnmatchAndCanAcceptMore),
+        },
+        _ => false,
+      };
+      if stop {
+        return Ok(if i > 0 { MatchResult::Match(i) } else { MatchResult::Unmatch });
+      }
+    }
+    Ok(MatchResult::UnmatchAndCanAcceptMore)
+  })
+}
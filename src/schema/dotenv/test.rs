@@ -0,0 +1,402 @@
+use super::{schema, ID};
+use crate::parser::{test::Events, Context, Event};
+use crate::Error;
+
+#[test]
+fn entries() {
+  let events =
+    parse("# database\nexport HOST=localhost # local\nURL=\"http://${HOST:-db}:$PORT/\\n\"\nPASS='p$a\"ss'\nEMPTY=\n")
+      .unwrap();
+  Events::new()
+    .begin(ID::Dotenv)
+    .begin(ID::Comment)
+    .fragments("# database")
+    .end()
+    .fragments("\n")
+    .begin(ID::Entry)
+    .fragments("export ")
+    .begin(ID::Key)
+    .fragments("HOST")
+    .end()
+    .fragments("=")
+    .begin(ID::Value)
+    .fragments("localhost")
+    .end()
+    .fragments(" ")
+    .begin(ID::Comment)
+    .fragments("# local")
+    .end()
+    .fragments("\n")
+    .end()
+    .begin(ID::Entry)
+    .begin(ID::Key)
+    .fragments("URL")
+    .end()
+    .fragments("=")
+    .begin(ID::Value)
+    .begin(ID::DoubleQuoted)
+    .fragments("\"http://")
+    .begin(ID::Interpolation)
+    .fragments("${")
+    .begin(ID::Variable)
+    .fragments("HOST")
+    .end()
+    .fragments(":-")
+    .begin(ID::Default)
+    .fragments("db")
+    .end()
+    .fragments("}")
+    .end()
+    .fragments(":")
+    .begin(ID::Interpolation)
+    .fragments("$")
+    .begin(ID::Variable)
+    .fragments("PORT")
+    .end()
+    .end()
+    .fragments("/")
+    .begin(ID::Escape)
+    .fragments("\\n")
+    .end()
+    .fragments("\"")
+    .end()
+    .end()
+    .fragments("\n")
+    .end()
+    .begin(ID::Entry)
+    .begin(ID::Key)
+    .fragments("PASS")
+    .end()
+    .fragments("=")
+    .begin(ID::Value)
+    .begin(ID::SingleQuoted)
+    .fragments("'p$a\"ss'")
+    .end()
+    .end()
+    .fragments("\n")
+    .end()
+    .begin(ID::Entry)
+    .begin(ID::Key)
+    .fragments("EMPTY")
+    .end()
+    .fragments("=\n")
+    .end()
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn unquoted_interpolation() {
+  let events = parse("A = x ${B}y $C  # c").unwrap();
+  Events::new()
+    .begin(ID::Dotenv)
+    .begin(ID::Entry)
+    .begin(ID::Key)
+    .fragments("A")
+    .end()
+    .fragments(" = ")
+    .begin(ID::Value)
+    .fragments("x ")
+    .begin(ID::Interpolation)
+    .fragments("${")
+    .begin(ID::Variable)
+    .fragments("B")
+    .end()
+    .fragments("}")
+    .end()
+    .fragments("y ")
+    .begin(ID::Interpolation)
+    .fragments("$")
+    .begin(ID::Variable)
+    .fragments("C")
+    .end()
+    .end()
+    .end()
+    .fragments("  ")
+    .begin(ID::Comment)
+    .fragments("# c")
+    .end()
+    .end()
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn valid_files() {
+  for (text, expected) in [
+    ("", Events::new()),
+    ("\n", Events::new().begin(ID::Dotenv).fragments("\n").end()),
+    ("   ", Events::new().begin(ID::Dotenv).fragments("   ").end()),
+    ("# comment", Events::new().begin(ID::Dotenv).begin(ID::Comment).fragments("# comment").end().end()),
+    (
+      "  # comment",
+      Events::new().begin(ID::Dotenv).fragments("  ").begin(ID::Comment).fragments("# comment").end().end(),
+    ),
+    (
+      "A=1",
+      Events::new()
+        .begin(ID::Dotenv)
+        .begin(ID::Entry)
+        .begin(ID::Key)
+        .fragments("A")
+        .end()
+        .fragments("=")
+        .begin(ID::Value)
+        .fragments("1")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "A=1\r\nB=2\r\n",
+      Events::new()
+        .begin(ID::Dotenv)
+        .begin(ID::Entry)
+        .begin(ID::Key)
+        .fragments("A")
+        .end()
+        .fragments("=")
+        .begin(ID::Value)
+        .fragments("1")
+        .end()
+        .fragments("\r\n")
+        .end()
+        .begin(ID::Entry)
+        .begin(ID::Key)
+        .fragments("B")
+        .end()
+        .fragments("=")
+        .begin(ID::Value)
+        .fragments("2")
+        .end()
+        .fragments("\r\n")
+        .end()
+        .end(),
+    ),
+    (
+      "export=1",
+      Events::new()
+        .begin(ID::Dotenv)
+        .begin(ID::Entry)
+        .begin(ID::Key)
+        .fragments("export")
+        .end()
+        .fragments("=")
+        .begin(ID::Value)
+        .fragments("1")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "A=a#b",
+      Events::new()
+        .begin(ID::Dotenv)
+        .begin(ID::Entry)
+        .begin(ID::Key)
+        .fragments("A")
+        .end()
+        .fragments("=")
+        .begin(ID::Value)
+        .fragments("a#b")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "A=#b",
+      Events::new()
+        .begin(ID::Dotenv)
+        .begin(ID::Entry)
+        .begin(ID::Key)
+        .fragments("A")
+        .end()
+        .fragments("=")
+        .begin(ID::Comment)
+        .fragments("#b")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "A=$",
+      Events::new()
+        .begin(ID::Dotenv)
+        .begin(ID::Entry)
+        .begin(ID::Key)
+        .fragments("A")
+        .end()
+        .fragments("=")
+        .begin(ID::Value)
+        .fragments("$")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "A=a $ b",
+      Events::new()
+        .begin(ID::Dotenv)
+        .begin(ID::Entry)
+        .begin(ID::Key)
+        .fragments("A")
+        .end()
+        .fragments("=")
+        .begin(ID::Value)
+        .fragments("a $ b")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "A=${B-}",
+      Events::new()
+        .begin(ID::Dotenv)
+        .begin(ID::Entry)
+        .begin(ID::Key)
+        .fragments("A")
+        .end()
+        .fragments("=")
+        .begin(ID::Value)
+        .begin(ID::Interpolation)
+        .fragments("${")
+        .begin(ID::Variable)
+        .fragments("B")
+        .end()
+        .fragments("-}")
+        .end()
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "A=\"multi\nline\"",
+      Events::new()
+        .begin(ID::Dotenv)
+        .begin(ID::Entry)
+        .begin(ID::Key)
+        .fragments("A")
+        .end()
+        .fragments("=")
+        .begin(ID::Value)
+        .begin(ID::DoubleQuoted)
+        .fragments("\"multi\nline\"")
+        .end()
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "A='multi\nline'",
+      Events::new()
+        .begin(ID::Dotenv)
+        .begin(ID::Entry)
+        .begin(ID::Key)
+        .fragments("A")
+        .end()
+        .fragments("=")
+        .begin(ID::Value)
+        .begin(ID::SingleQuoted)
+        .fragments("'multi\nline'")
+        .end()
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "A=\"\"",
+      Events::new()
+        .begin(ID::Dotenv)
+        .begin(ID::Entry)
+        .begin(ID::Key)
+        .fragments("A")
+        .end()
+        .fragments("=")
+        .begin(ID::Value)
+        .begin(ID::DoubleQuoted)
+        .fragments("\"\"")
+        .end()
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "A=''",
+      Events::new()
+        .begin(ID::Dotenv)
+        .begin(ID::Entry)
+        .begin(ID::Key)
+        .fragments("A")
+        .end()
+        .fragments("=")
+        .begin(ID::Value)
+        .begin(ID::SingleQuoted)
+        .fragments("''")
+        .end()
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "_A1=1\n\n  B=2  \n",
+      Events::new()
+        .begin(ID::Dotenv)
+        .begin(ID::Entry)
+        .begin(ID::Key)
+        .fragments("_A1")
+        .end()
+        .fragments("=")
+        .begin(ID::Value)
+        .fragments("1")
+        .end()
+        .fragments("\n")
+        .end()
+        .fragments("\n")
+        .begin(ID::Entry)
+        .fragments("  ")
+        .begin(ID::Key)
+        .fragments("B")
+        .end()
+        .fragments("=")
+        .begin(ID::Value)
+        .fragments("2")
+        .end()
+        .fragments("  \n")
+        .end()
+        .end(),
+    ),
+  ] {
+    expected.assert_eq(&parse(text).unwrap());
+  }
+}
+
+#[test]
+fn invalid_files() {
+  for (text, position) in [
+    ("A", 1),
+    ("=1", 0),
+    ("1A=1", 0),
+    ("A B=1", 2),
+    ("A=\"unterminated", 3),
+    ("A='unterminated", 15),
+    ("A=\"a\"b", 5),
+    ("A=${B", 5),
+    ("A=${1}", 4),
+    ("export A", 8),
+  ] {
+    match parse(text) {
+      Err(Error::Unmatched(detail)) => assert_eq!(position, detail.location.chars, "{:?}", text),
+      unexpected => panic!("{:?}: {:?}", text, unexpected),
+    }
+  }
+}
+
+fn parse(text: &str) -> crate::Result<char, Vec<Event<ID, char>>> {
+  let schema = schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, ID::Dotenv, handler)?.ignore_events_for(&[ID::WS]);
+  parser.push_str(text)?;
+  parser.finish()?;
+  Ok(Event::normalize(&events))
+}
@@ -0,0 +1,211 @@
+use crate::schema::chars::{ch, none_of_chars, one_of_chars, token, until_token};
+use crate::schema::{eof, id, MatchResult, Schema, Syntax};
+use crate::Result;
+use std::fmt::Display;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ID {
+  Document,
+  Node,
+  Text,
+  Comment,
+  Declaration,
+  StartTag,
+  EndTag,
+  TagName,
+  Attributes,
+  Attribute,
+  AttrName,
+  AttrValue,
+  RawTextElement,
+  RawText,
+  WS,
+}
+
+impl Display for ID {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// The elements that have no end tag.
+///
+pub const VOID_ELEMENTS: [&str; 14] =
+  ["area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr"];
+
+/// The elements whose contents are raw text rather than markup.
+///
+pub const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// Whether the element `name` has no end tag, such as `br`, regardless of the case.
+///
+pub fn is_void_element(name: &str) -> bool {
+  VOID_ELEMENTS.iter().any(|e| e.eq_ignore_ascii_case(name))
+}
+
+/// A permissive HTML schema for tag soup, which doesn't require the tags to be balanced nor the void elements to be
+/// closed. A document is a flat sequence of text, comments, declarations such as `<!DOCTYPE html>`, start tags and
+/// end tags, so that the caller builds the tree as tolerantly as it needs, see [`is_void_element()`].
+///
+/// The names of the tags and the attributes are case-insensitive and the attribute values may be double-quoted,
+/// single-quoted, unquoted or omitted. The contents of the [`RAW_TEXT_ELEMENTS`] are [`ID::RawText`] instead of
+/// markup, and a `<` that doesn't begin a tag is a part of the text. An unclosed comment or raw text element extends
+/// to the end of the input.
+///
+pub fn schema() -> Schema<ID, char> {
+  use ID::*;
+  Schema::new("HTML")
+    .define(Document, id(Node) * (0..))
+    .define(Node, id(Text) | id(Comment) | id(Declaration) | id(StartTag) | id(EndTag) | id(RawTextElement))
+    .define(Text, text())
+    .define(Comment, token("<!--") & until_token("-->") & (token("-->") | eof()))
+    .define(Declaration, ch('<') & one_of_chars("!?") & declaration())
+    .define(StartTag, ch('<') & raw_text_element(false) & id(TagName) & id(Attributes) & ch('>'))
+    .define(EndTag, token("</") & id(TagName) & id(WS) & ch('>'))
+    .define(TagName, name())
+    .define(Attributes, ((id(WS) & id(Attribute)) * (0..)) & id(WS) & (ch('/') * (0..=1)))
+    .define(Attribute, id(AttrName) & ((id(WS) & ch('=') & id(WS) & id(AttrValue)) * (0..=1)))
+    .define(AttrName, attribute_name())
+    .define(
+      AttrValue,
+      (ch('"') & (none_of_chars("\"") * (0..)) & ch('"'))
+        | (ch('\'') & (none_of_chars("'") * (0..)) & ch('\''))
+        | unquoted_attribute_value(),
+    )
+    .define(
+      RawTextElement,
+      ch('<') & raw_text_element(true) & id(TagName) & id(Attributes) & ch('>') & id(RawText) & (id(EndTag) | eof()),
+    )
+    .define(RawText, raw_text())
+    .define(WS, one_of_chars(" \t\r\n\x0C") * (0..))
+}
+
+fn is_name_char(ch: char) -> bool {
+  !(ch.is_whitespace() || matches!(ch, '/' | '>' | '<' | '=' | '"' | '\''))
+}
+
+/// The name of a tag beginning with an ASCII letter, which is confirmed when a delimiter follows.
+///
+fn name<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("TAG_NAME", |values: &[char]| -> Result<char, MatchResult> {
+    match values.first() {
+      None => Ok(MatchResult::UnmatchAndCanAcceptMore),
+      Some(ch) if !ch.is_ascii_alphabetic() => Ok(MatchResult::Unmatch),
+      _ => match values.iter().position(|ch| !is_name_char(*ch)) {
+        Some(length) => Ok(MatchResult::Match(length)),
+        None => Ok(MatchResult::MatchAndCanAcceptMore(values.len())),
+      },
+    }
+  })
+}
+
+fn attribute_name<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("ATTR_NAME", |values: &[char]| -> Result<char, MatchResult> {
+    match values.iter().position(|ch| !is_name_char(*ch)) {
+      Some(0) => Ok(MatchResult::Unmatch),
+      Some(length) => Ok(MatchResult::Match(length)),
+      None if values.is_empty() => Ok(MatchResult::UnmatchAndCanAcceptMore),
+      None => Ok(MatchResult::MatchAndCanAcceptMore(values.len())),
+    }
+  })
+}
+
+fn unquoted_attribute_value<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("UNQUOTED", |values: &[char]| -> Result<char, MatchResult> {
+    if values.first().map(|ch| *ch == '"' || *ch == '\'').unwrap_or(false) {
+      return Ok(MatchResult::Unmatch);
+    }
+    match values.iter().position(|ch| ch.is_whitespace() || *ch == '>') {
+      Some(0) => Ok(MatchResult::Unmatch),
+      Some(length) => Ok(MatchResult::Match(length)),
+      None if values.is_empty() => Ok(MatchResult::UnmatchAndCanAcceptMore),
+      None => Ok(MatchResult::MatchAndCanAcceptMore(values.len())),
+    }
+  })
+}
+
+/// Look ahead whether the tag name is (`expected`) or isn't one of the [`RAW_TEXT_ELEMENTS`] without consuming it.
+///
+fn raw_text_element<ID>(expected: bool) -> Syntax<ID, char> {
+  let label = if expected { "&RAW_TEXT_ELEMENT" } else { "!RAW_TEXT_ELEMENT" };
+  Syntax::from_fn(label, move |values: &[char]| -> Result<char, MatchResult> {
+    let length = match values.iter().position(|ch| !is_name_char(*ch)) {
+      Some(length) => length,
+      None => return Ok(MatchResult::UnmatchAndCanAcceptMore),
+    };
+    let name = values[..length].iter().collect::<String>();
+    if RAW_TEXT_ELEMENTS.iter().any(|e| e.eq_ignore_ascii_case(&name)) == expected {
+      Ok(MatchResult::Match(0))
+    } else {
+      Ok(MatchResult::Unmatch)
+    }
+  })
+}
+
+/// The text up to a `<` that begins a tag, a comment or a declaration.
+///
+fn text<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("TEXT", |values: &[char]| -> Result<char, MatchResult> {
+    for i in 0..values.len() {
+      if values[i] != '<' {
+        continue;
+      }
+      let markup = match (values.get(i + 1), values.get(i + 2)) {
+        (None, _) | (Some('/'), None) => return Ok(MatchResult::MatchAndCanAcceptMore(values.len())),
+        (Some('/'), Some(ch)) => ch.is_ascii_alphabetic(),
+        (Some(ch), _) => ch.is_ascii_alphabetic() || *ch == '!' || *ch == '?',
+      };
+      if markup {
+        return Ok(if i == 0 { MatchResult::Unmatch } else { MatchResult::Match(i) });
+      }
+    }
+    Ok(if values.is_empty() {
+      MatchResult::UnmatchAndCanAcceptMore
+    } else {
+      MatchResult::MatchAndCanAcceptMore(values.len())
+    })
+  })
+}
+
+/// The body of a declaration up to and including `>`, which isn't a comment.
+///
+fn declaration<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("DECLARATION", |values: &[char]| -> Result<char, MatchResult> {
+    match values {
+      ['-', '-', ..] => Ok(MatchResult::Unmatch),
+      [] | ['-'] => Ok(MatchResult::UnmatchAndCanAcceptMore),
+      _ => match values.iter().position(|ch| *ch == '>') {
+        Some(length) => Ok(MatchResult::Match(length + 1)),
+        None => Ok(MatchResult::MatchAndCanAcceptMore(values.len())),
+      },
+    }
+  })
+}
+
+/// The raw text up to the end tag of any of the [`RAW_TEXT_ELEMENTS`], or to the end of the input.
+///
+fn raw_text<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("RAW_TEXT", |values: &[char]| -> Result<char, MatchResult> {
+    let max = RAW_TEXT_ELEMENTS.iter().map(|e| e.len()).max().unwrap_or(0) + 2;
+    for i in 0..values.len() {
+      if values[i] != '<' {
+        continue;
+      }
+      let rest = &values[i..std::cmp::min(values.len(), i + max)];
+      let rest = rest.iter().collect::<String>().to_ascii_lowercase();
+      for e in RAW_TEXT_ELEMENTS {
+        let end = format!("</{}", e);
+        if rest.starts_with(&end) {
+          return Ok(MatchResult::Match(i));
+        } else if end.starts_with(&rest) {
+          // the end tag may follow
+          return Ok(MatchResult::MatchAndCanAcceptMore(values.len()));
+        }
+      }
+    }
+    Ok(MatchResult::MatchAndCanAcceptMore(values.len()))
+  })
+}
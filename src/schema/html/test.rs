@@ -0,0 +1,171 @@
+use super::{is_void_element, schema, ID};
+use crate::parser::{test::Events, Context, Event};
+
+#[test]
+fn tag_soup() {
+  let events = parse("<!DOCTYPE html><P class=x ID='a b' hidden>1 < 2<br/><IMG src=\"/i.png\"></p></div>");
+  Events::new()
+    .begin(ID::Document)
+    .begin(ID::Declaration)
+    .fragments("<!DOCTYPE html>")
+    .end()
+    .begin(ID::StartTag)
+    .fragments("<")
+    .begin(ID::TagName)
+    .fragments("P")
+    .end()
+    .fragments(" ")
+    .begin(ID::Attribute)
+    .begin(ID::AttrName)
+    .fragments("class")
+    .end()
+    .fragments("=")
+    .begin(ID::AttrValue)
+    .fragments("x")
+    .end()
+    .end()
+    .fragments(" ")
+    .begin(ID::Attribute)
+    .begin(ID::AttrName)
+    .fragments("ID")
+    .end()
+    .fragments("=")
+    .begin(ID::AttrValue)
+    .fragments("'a b'")
+    .end()
+    .end()
+    .fragments(" ")
+    .begin(ID::Attribute)
+    .begin(ID::AttrName)
+    .fragments("hidden")
+    .end()
+    .end()
+    .fragments(">")
+    .end()
+    .begin(ID::Text)
+    .fragments("1 < 2")
+    .end()
+    .begin(ID::StartTag)
+    .fragments("<")
+    .begin(ID::TagName)
+    .fragments("br")
+    .end()
+    .fragments("/>")
+    .end()
+    .begin(ID::StartTag)
+    .fragments("<")
+    .begin(ID::TagName)
+    .fragments("IMG")
+    .end()
+    .fragments(" ")
+    .begin(ID::Attribute)
+    .begin(ID::AttrName)
+    .fragments("src")
+    .end()
+    .fragments("=")
+    .begin(ID::AttrValue)
+    .fragments("\"/i.png\"")
+    .end()
+    .end()
+    .fragments(">")
+    .end()
+    .begin(ID::EndTag)
+    .fragments("</")
+    .begin(ID::TagName)
+    .fragments("p")
+    .end()
+    .fragments(">")
+    .end()
+    .begin(ID::EndTag)
+    .fragments("</")
+    .begin(ID::TagName)
+    .fragments("div")
+    .end()
+    .fragments(">")
+    .end()
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn comments_and_raw_text() {
+  let events = parse("<!-- <b> --><script type=a>if (a<b) x='</p>';</SCRIPT ><style>p{}");
+  Events::new()
+    .begin(ID::Document)
+    .begin(ID::Comment)
+    .fragments("<!-- <b> -->")
+    .end()
+    .begin(ID::RawTextElement)
+    .fragments("<")
+    .begin(ID::TagName)
+    .fragments("script")
+    .end()
+    .fragments(" ")
+    .begin(ID::Attribute)
+    .begin(ID::AttrName)
+    .fragments("type")
+    .end()
+    .fragments("=")
+    .begin(ID::AttrValue)
+    .fragments("a")
+    .end()
+    .end()
+    .fragments(">")
+    .begin(ID::RawText)
+    .fragments("if (a<b) x='</p>';")
+    .end()
+    .begin(ID::EndTag)
+    .fragments("</")
+    .begin(ID::TagName)
+    .fragments("SCRIPT")
+    .end()
+    .fragments(" >")
+    .end()
+    .end()
+    .begin(ID::RawTextElement)
+    .fragments("<")
+    .begin(ID::TagName)
+    .fragments("style")
+    .end()
+    .fragments(">")
+    .begin(ID::RawText)
+    .fragments("p{}")
+    .end()
+    .end()
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn tolerant_inputs() {
+  for html in ["", "text only", "a<", "a</", "<!-- unclosed", "<p>x</p", "x <3 y", "<?xml version=\"1.0\"?><a>"] {
+    let schema = schema();
+    let mut parser = Context::new(&schema, ID::Document, |_: &Event<_, _>| ()).unwrap();
+    let result = parser.push_str(html).and_then(|_| parser.finish_unit());
+    if html == "<p>x</p" {
+      assert!(result.is_err(), "{:?}", html);
+    } else {
+      assert!(result.is_ok(), "{:?}: {:?}", html, result);
+    }
+  }
+}
+
+#[test]
+fn void_elements() {
+  assert!(is_void_element("br"));
+  assert!(is_void_element("IMG"));
+  assert!(!is_void_element("div"));
+}
+
+fn parse(text: &str) -> Vec<Event<ID, char>> {
+  let schema = schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser =
+    Context::new(&schema, ID::Document, handler).unwrap().ignore_events_for(&[ID::Node, ID::Attributes, ID::WS]);
+  for ch in text.chars() {
+    parser.push(ch).unwrap();
+  }
+  parser.finish().unwrap();
+  Event::normalize(&events)
+}
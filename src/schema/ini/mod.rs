@@ -0,0 +1,45 @@
+use crate::schema::chars::{ch, line, newline, none_of_chars, one_of_chars};
+use crate::schema::{id, Schema};
+use std::fmt::Display;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ID {
+  File,
+  Line,
+  Section,
+  Name,
+  Property,
+  Key,
+  Value,
+  Comment,
+  WS,
+  Newline,
+}
+
+impl Display for ID {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// The INI file format, which has no formal specification. A line is either a `[section]`, a `key = value` property,
+/// a comment starting with `;` or `#`, or blank. A key contains no whitespace, and a value is the rest of the line
+/// including its trailing whitespace.
+///
+pub fn schema() -> Schema<ID, char> {
+  use ID::*;
+  Schema::new("INI")
+    .define(File, id(Line) & ((id(Newline) & id(Line)) * (0..)))
+    .define(Line, id(WS) & (((id(Section) & id(WS)) | id(Property) | id(Comment)) * (0..=1)))
+    .define(Section, ch('[') & id(Name) & ch(']'))
+    .define(Name, none_of_chars("]\r\n") * (1..))
+    .define(Property, id(Key) & id(WS) & ch('=') & id(WS) & (id(Value) * (0..=1)))
+    .define(Key, none_of_chars(" \t=[;#\r\n") * (1..))
+    .define(Value, none_of_chars(" \t\r\n") & line())
+    .define(Comment, one_of_chars(";#") & line())
+    .define(WS, one_of_chars(" \t") * (0..))
+    .define(Newline, newline())
+}
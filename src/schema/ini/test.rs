@@ -0,0 +1,65 @@
+use super::{schema, ID};
+use crate::parser::{test::Events, Context, Event};
+
+#[test]
+fn lines() {
+  let events = parse("; comment\n[server]\nhost = example.com \n\n  port=8080\nempty =\n");
+  Events::new()
+    .begin(ID::File)
+    .fragments("; comment\n")
+    .begin(ID::Section)
+    .fragments("[")
+    .begin(ID::Name)
+    .fragments("server")
+    .end()
+    .fragments("]")
+    .end()
+    .fragments("\n")
+    .begin(ID::Property)
+    .begin(ID::Key)
+    .fragments("host")
+    .end()
+    .fragments(" = ")
+    .begin(ID::Value)
+    .fragments("example.com ")
+    .end()
+    .end()
+    .fragments("\n\n  ")
+    .begin(ID::Property)
+    .begin(ID::Key)
+    .fragments("port")
+    .end()
+    .fragments("=")
+    .begin(ID::Value)
+    .fragments("8080")
+    .end()
+    .end()
+    .fragments("\n")
+    .begin(ID::Property)
+    .begin(ID::Key)
+    .fragments("empty")
+    .end()
+    .fragments(" =")
+    .end()
+    .fragments("\n")
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn unclosed_section() {
+  let schema = schema();
+  let mut parser = Context::new(&schema, ID::File, |_: &Event<_, _>| ()).unwrap();
+  assert!(parser.push_str("[server\nhost=x").and_then(|_| parser.finish_unit()).is_err());
+}
+
+fn parse(text: &str) -> Vec<Event<ID, char>> {
+  let schema = schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser =
+    Context::new(&schema, ID::File, handler).unwrap().ignore_events_for(&[ID::Line, ID::Comment, ID::WS, ID::Newline]);
+  parser.push_str(text).unwrap();
+  parser.finish().unwrap();
+  Event::normalize(&events)
+}
@@ -0,0 +1,199 @@
+//! Interned rule IDs. The parser clones and compares the IDs constantly while evaluating the paths, which is costly
+//! for the IDs such as `String`. [`Schema::interned()`] translates a schema into the one whose IDs are [`RuleId`]s,
+//! which are compared and hashed as integers, and the contexts created by [`Interned::context()`] parse with it while
+//! delivering the events with references to the original IDs.
+//!
+//! ```rust
+//! use terp::parser::{Event, EventKind};
+//! use terp::schema::{Schema, id};
+//! use terp::schema::chars::{ch, ascii_digit};
+//!
+//! let schema = Schema::new("List")
+//!   .define(String::from("List"), ch('[') & id(String::from("Item")) & ch(']'))
+//!   .define(String::from("Item"), ascii_digit() * (1..));
+//! let interned = schema.interned();
+//!
+//! let mut items = Vec::new();
+//! let mut parser = interned.context(&String::from("List"), |e: &Event<&String, char>| {
+//!   if let EventKind::Begin(id) = &e.kind {
+//!     items.push(id.to_string());
+//!   }
+//! }).unwrap();
+//! parser.push_str("[42]").unwrap();
+//! parser.finish().unwrap();
+//! assert_eq!(vec![String::from("List"), String::from("Item")], items);
+//! ```
+//!
+use crate::parser::{Context, Event, EventKind};
+use crate::schema::{Schema, Symbol};
+use crate::{Error, Result};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+#[cfg(test)]
+mod test;
+
+/// The ID of a rule interned by [`Schema::interned()`], which is the index of the rule in the symbol table of the
+/// [`Interned`] schema. It's compared and hashed by the index, and carries the name of the rule to be displayed
+/// without the table.
+///
+/// Since the index is only meaningful in the table it's taken from, the `RuleId`s are comparable only within the same
+/// [`Interned`] schema. Those of two schemas, even two interned from the same one, may be equal for different rules or
+/// be ordered differently from the original IDs; use [`Interned::id()`] to compare them across the schemas.
+///
+#[derive(Clone)]
+pub struct RuleId {
+  index: u32,
+  name: Arc<str>,
+}
+
+impl RuleId {
+  pub fn index(&self) -> u32 {
+    self.index
+  }
+
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+}
+
+impl PartialEq for RuleId {
+  fn eq(&self, other: &Self) -> bool {
+    self.index == other.index
+  }
+}
+
+impl Eq for RuleId {}
+
+impl PartialOrd for RuleId {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for RuleId {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.index.cmp(&other.index)
+  }
+}
+
+impl Hash for RuleId {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.index.hash(state)
+  }
+}
+
+impl Display for RuleId {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&self.name)
+  }
+}
+
+/// The name is written as it is, as the variants of an enum ID, so that the schema is written as the original one.
+///
+impl Debug for RuleId {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&self.name)
+  }
+}
+
+/// The context created by [`Interned::context()`], whose handler translates the events into the original IDs.
+///
+pub type InternedContext<'s, Σ> = Context<'s, RuleId, Σ, Box<dyn FnMut(&Event<RuleId, Σ>) + 's>>;
+
+/// A schema whose IDs are interned, with the symbol table between the original IDs and the [`RuleId`]s.
+///
+pub struct Interned<ID, Σ: Symbol> {
+  schema: Schema<RuleId, Σ>,
+  rules: HashMap<ID, RuleId>,
+  /// The original IDs indexed by [`RuleId::index()`].
+  ids: Vec<ID>,
+}
+
+impl<ID: Clone + Hash + Eq, Σ: Symbol> Interned<ID, Σ> {
+  /// The translated schema, which can also be parsed by a [`Context`] directly to receive the events with the
+  /// [`RuleId`]s.
+  ///
+  pub fn schema(&self) -> &Schema<RuleId, Σ> {
+    &self.schema
+  }
+
+  pub fn rule_id(&self, id: &ID) -> Option<&RuleId> {
+    self.rules.get(id)
+  }
+
+  pub fn id(&self, rule: &RuleId) -> Option<&ID> {
+    self.ids.get(rule.index as usize)
+  }
+}
+
+impl<ID, Σ> Interned<ID, Σ>
+where
+  ID: Clone + Hash + Eq + Ord + Display + Debug + Send + Sync,
+  Σ: 'static + Symbol,
+{
+  /// Create a context for the syntax `id` that parses with the interned IDs, and delivers the events with the
+  /// references to the original IDs to the `handler`, as a context parsing with
+  /// [`Schema::by_ref()`](crate::schema::Schema::by_ref) does.
+  ///
+  pub fn context<'s, H>(&'s self, id: &ID, mut handler: H) -> Result<Σ, InternedContext<'s, Σ>>
+  where
+    H: FnMut(&Event<&'s ID, Σ>) + 's,
+  {
+    let rule = self.rule_id(id).ok_or_else(|| Error::UndefinedID(id.to_string()))?;
+    // the buffer of the fragments is reused for all the events
+    let mut fragments = Vec::new();
+    let translate = move |e: &Event<RuleId, Σ>| {
+      let kind = match &e.kind {
+        EventKind::Begin(rule) => EventKind::Begin(&self.ids[rule.index as usize]),
+        EventKind::End(rule) => EventKind::End(&self.ids[rule.index as usize]),
+        EventKind::Fragments(values) => {
+          fragments.extend_from_slice(values);
+          EventKind::Fragments(std::mem::take(&mut fragments))
+        }
+      };
+      let e = Event { location: e.location, kind, end: e.end };
+      handler(&e);
+      if let EventKind::Fragments(mut values) = e.kind {
+        values.clear();
+        fragments = values;
+      }
+    };
+    Context::new(&self.schema, rule.clone(), Box::new(translate))
+  }
+}
+
+impl<ID, Σ> Schema<ID, Σ>
+where
+  ID: Clone + Hash + Eq + Ord + Display,
+  Σ: 'static + Symbol,
+{
+  /// Translate this schema into the one whose IDs are interned, see [`Interned`]. The distinct IDs are interned to
+  /// the distinct [`RuleId`]s even if they're displayed as the same name.
+  ///
+  pub fn interned(&self) -> Interned<ID, Σ> {
+    let mut rules = HashMap::new();
+    let mut ids = Vec::new();
+    let mut intern = |id: &ID| -> RuleId {
+      rules
+        .entry(id.clone())
+        .or_insert_with(|| {
+          let index = u32::try_from(ids.len()).expect("the number of rules never exceeds u32");
+          ids.push(id.clone());
+          RuleId { index, name: Arc::from(id.to_string()) }
+        })
+        .clone()
+    };
+    // the defined rules are interned in the order of their IDs rather than of the definitions, so that the RuleIds are
+    // ordered as the original IDs and the translated schema iterates the rules in the same order
+    for id in self.defs.keys() {
+      intern(id);
+    }
+
+    let schema = self.map_ids(&mut intern);
+    Interned { schema, rules, ids }
+  }
+}
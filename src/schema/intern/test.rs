@@ -0,0 +1,89 @@
+use crate::parser::{Context, Event, EventKind};
+use crate::schema::chars::ch;
+use crate::schema::json::{schema, ID};
+use crate::schema::{id, Schema};
+use crate::Error;
+
+#[test]
+fn rule_ids_interned_in_schema() {
+  let schema = schema();
+  let interned = schema.interned();
+  let (object, array) = (interned.rule_id(&ID::Object).unwrap(), interned.rule_id(&ID::Array).unwrap());
+  assert_ne!(object, array);
+  assert_eq!(object.cmp(array), ID::Object.cmp(&ID::Array));
+  assert_eq!(ID::Object.to_string(), object.to_string());
+  assert_eq!(format!("{:?}", ID::Array), format!("{:?}", array));
+  assert_eq!(Some(&ID::Array), interned.id(array));
+}
+
+#[test]
+fn rule_ids_ordered_as_ids() {
+  // the order of the IDs, not of the definitions
+  let schema = Schema::new("Foo").define("B", id("A") * (0..)).define("A", ch('a'));
+  let interned = schema.interned();
+  let (a, b) = (interned.rule_id(&"A").unwrap(), interned.rule_id(&"B").unwrap());
+  assert!(a < b);
+  assert_eq!("Foo\n  B := A*\n  A := 'a'\n", interned.schema().to_string());
+}
+
+#[test]
+fn rule_ids_with_same_name() {
+  #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+  enum Id {
+    A(u8),
+  }
+  impl std::fmt::Display for Id {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      f.write_str("A")
+    }
+  }
+  let schema = Schema::new("Same").define(Id::A(0), id(Id::A(1)) * 2).define(Id::A(1), ch('a'));
+  let interned = schema.interned();
+  let (a0, a1) = (interned.rule_id(&Id::A(0)).unwrap(), interned.rule_id(&Id::A(1)).unwrap());
+  assert_ne!(a0, a1);
+  assert_eq!(a0.to_string(), a1.to_string());
+
+  let mut events = Vec::new();
+  let mut parser = interned.context(&Id::A(0), |e: &Event<&Id, char>| events.push(e.to_owned_ids())).unwrap();
+  parser.push_str("aa").unwrap();
+  parser.finish().unwrap();
+  let ids = events
+    .iter()
+    .filter_map(|e| match &e.kind {
+      EventKind::Begin(id) | EventKind::End(id) => Some(id.clone()),
+      EventKind::Fragments(_) => None,
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(vec![Id::A(0), Id::A(1), Id::A(1), Id::A(1), Id::A(1), Id::A(0)], ids);
+
+  assert!(matches!(interned.context(&Id::A(2), |_: &Event<_, _>| ()), Err(Error::UndefinedID(_))));
+}
+
+#[test]
+fn interned_schema_delivers_the_same_events() {
+  let schema = schema();
+  let interned = schema.interned();
+  assert_eq!(Some(&ID::Object), interned.rule_id(&ID::Object).and_then(|rule| interned.id(rule)));
+  assert_eq!(schema.to_string(), interned.schema().to_string());
+
+  let parse = |text: &str, interning: bool| {
+    let mut events = Vec::new();
+    let result = if interning {
+      let handler = |e: &Event<&ID, char>| events.push(e.to_owned_ids());
+      let mut parser = interned.context(&ID::JsonText, handler).unwrap();
+      parser.push_str(text).and_then(|_| parser.finish().map(|_| ()))
+    } else {
+      let handler = |e: &Event<ID, char>| events.push(e.clone());
+      let mut parser = Context::new(&schema, ID::JsonText, handler).unwrap();
+      parser.push_str(text).and_then(|_| parser.finish().map(|_| ()))
+    };
+    (events, result.map_err(|err| err.to_string()))
+  };
+  for text in [
+    r#"{"name": "terp", "tags": ["parser", "schema"], "version": [0, 1.5e-3], "stable": false}"#,
+    "[1, 2, {\"a\": null}]",
+    "{\"a\": tru}",
+  ] {
+    assert_eq!(parse(text, false), parse(text, true), "{:?}", text);
+  }
+}
@@ -11,7 +11,7 @@ fn char() {
     .chain('\u{5D}'..='\u{7F}')
     .map(|c| (c.to_string(), e(|eb| eb.begin(ID::Unescaped).fragments(&c.to_string()).end())))
     .chain(
-      vec!['\"', '\\', '/', 'b', 'f', 'n', 'r', 't']
+      ['\"', '\\', '/', 'b', 'f', 'n', 'r', 't']
         .iter()
         .map(|c| (format!("\\{}", c), e(|eb| eb.begin(ID::Escape).fragments("\\").end().fragments(&c.to_string())))),
     )
@@ -1,4 +1,4 @@
-use crate::schema::{MatchResult, Symbol, Syntax};
+use crate::schema::{MatchCtx, MatchResult, Symbol, Syntax};
 use crate::Result;
 use std::collections::HashSet;
 use std::hash::Hash;
@@ -16,6 +16,7 @@ pub fn id_str<S: Into<String>>(id: S) -> Syntax<String, char> {
 }
 
 pub fn single<ID, Σ: Symbol>(item: Σ) -> Syntax<ID, Σ> {
+  let signature = format!("single({:?})", item);
   Syntax::from_fn(&Σ::debug_symbol(item), move |values: &[Σ]| -> Result<Σ, MatchResult> {
     if values.is_empty() {
       Ok(MatchResult::UnmatchAndCanAcceptMore)
@@ -25,6 +26,26 @@ pub fn single<ID, Σ: Symbol>(item: Σ) -> Syntax<ID, Σ> {
       Ok(MatchResult::Unmatch)
     }
   })
+  .with_window(1)
+  .with_signature(signature)
+}
+
+/// `eof` matches only at the end of the input, without consuming any symbols. This allows a grammar to require the
+/// end-of-input within a particular branch rather than only at the top level by [`Context::finish()`].
+///
+/// [`Context::finish()`]: crate::parser::Context::finish
+///
+pub fn eof<ID, Σ: Symbol>() -> Syntax<ID, Σ> {
+  Syntax::from_fn("EOF", move |values: &[Σ]| -> Result<Σ, MatchResult> {
+    if values.is_empty() {
+      // confirmed as `Match(0)` when the parser reaches the end of input
+      Ok(MatchResult::MatchAndCanAcceptMore(0))
+    } else {
+      Ok(MatchResult::Unmatch)
+    }
+  })
+  .with_window(1)
+  .with_signature(String::from("eof"))
 }
 
 pub fn range<ID, Σ: Symbol + PartialOrd>(r: RangeInclusive<Σ>) -> Syntax<ID, Σ> {
@@ -39,6 +60,7 @@ pub fn range_with_label<ID, Σ: Symbol + PartialOrd>(label: &str, r: RangeInclus
 pub fn any_of_ranges_with_label<ID, Σ: Symbol + PartialOrd>(
   label: &str, rs: Vec<RangeInclusive<Σ>>,
 ) -> Syntax<ID, Σ> {
+  let signature = format!("ranges({:?})", rs);
   Syntax::from_fn(label, move |values: &[Σ]| -> Result<Σ, MatchResult> {
     if values.is_empty() {
       Ok(MatchResult::UnmatchAndCanAcceptMore)
@@ -48,23 +70,32 @@ pub fn any_of_ranges_with_label<ID, Σ: Symbol + PartialOrd>(
       Ok(MatchResult::Unmatch)
     }
   })
+  .with_window(1)
+  .with_signature(signature)
 }
 
+/// `seq` matches the `items` in order. While the input is shorter than them, the prefix that has matched is reported
+/// as [`MatchResult::Partial`], so that the comparison resumes after it.
+///
 pub fn seq<ID, Σ: Symbol>(items: &[Σ]) -> Syntax<ID, Σ> {
+  let signature = format!("seq({:?})", items);
   let items = items.to_vec();
-  Syntax::from_fn(&Σ::debug_symbols(&items), move |buffer: &[Σ]| -> Result<Σ, MatchResult> {
+  let window = items.len();
+  Syntax::from_fn_ctx(&Σ::debug_symbols(&items), move |buffer: &[Σ], ctx: &MatchCtx<Σ>| -> Result<Σ, MatchResult> {
     let min = std::cmp::min(items.len(), buffer.len());
-    for (i, value) in buffer.iter().take(min).enumerate() {
-      if *value != items[i] {
-        return Ok(MatchResult::Unmatch);
-      }
+    let matched = std::cmp::min(ctx.matched, min);
+    if buffer[matched..min] != items[matched..min] {
+      return Ok(MatchResult::Unmatch);
     }
-    Ok(if min < items.len() { MatchResult::UnmatchAndCanAcceptMore } else { MatchResult::Match(min) })
+    Ok(if min < items.len() { MatchResult::Partial(min) } else { MatchResult::Match(min) })
   })
+  .with_window(window)
+  .with_signature(signature)
 }
 
 pub fn one_of<ID, Σ: Symbol + Hash>(items: &[Σ]) -> Syntax<ID, Σ> {
   let label = items.iter().map(|i| Σ::debug_symbol(*i)).collect::<Vec<_>>().join("|");
+  let signature = format!("one_of({:?})", items);
   let items = items.iter().fold(HashSet::with_capacity(items.len()), |mut items, item| {
     items.insert(*item);
     items
@@ -78,11 +109,34 @@ pub fn one_of<ID, Σ: Symbol + Hash>(items: &[Σ]) -> Syntax<ID, Σ> {
       Ok(MatchResult::Unmatch)
     }
   })
+  .with_window(1)
+  .with_signature(signature)
+}
+
+/// `none_of` matches a single symbol other than the `items`.
+///
+pub fn none_of<ID, Σ: Symbol + Hash>(items: &[Σ]) -> Syntax<ID, Σ> {
+  let label = format!("^{}", items.iter().map(|i| Σ::debug_symbol(*i)).collect::<Vec<_>>().join("|"));
+  let signature = format!("none_of({:?})", items);
+  let items = items.iter().copied().collect::<HashSet<_>>();
+  Syntax::from_fn(&label, move |buffer: &[Σ]| -> Result<Σ, MatchResult> {
+    if buffer.is_empty() {
+      Ok(MatchResult::UnmatchAndCanAcceptMore)
+    } else if items.contains(&buffer[0]) {
+      Ok(MatchResult::Unmatch)
+    } else {
+      Ok(MatchResult::Match(1))
+    }
+  })
+  .with_window(1)
+  .with_signature(signature)
 }
 
 pub fn one_of_seqs<ID, Σ: Symbol + PartialEq>(items: &[Vec<Σ>]) -> Syntax<ID, Σ> {
   let label = items.iter().map(|i| Σ::debug_symbols(i)).collect::<Vec<_>>().join("|");
+  let signature = format!("one_of_seqs({:?})", items);
   let items = items.iter().map(|i| i.to_vec()).collect::<Vec<_>>();
+  let window = items.iter().map(|i| i.len()).max().unwrap_or(0);
   Syntax::from_fn(&label, move |buffer: &[Σ]| -> Result<Σ, MatchResult> {
     use MatchResult::*;
     let result = items
@@ -119,8 +173,11 @@ pub fn one_of_seqs<ID, Σ: Symbol + PartialEq>(items: &[Vec<Σ>]) -> Syntax<ID,
           debug_assert!(!matches!(result, MatchAndCanAcceptMore(_)));
           b
         }
+        (Partial(_), _) => unreachable!(),
       })
       .unwrap_or(Unmatch);
     Ok(result)
   })
+  .with_window(window)
+  .with_signature(signature)
 }
@@ -1,4 +1,4 @@
-use crate::schema::{MatchResult, Primary, Symbol, Syntax};
+use crate::schema::{MatchCtx, MatchResult, Primary, Symbol, Syntax};
 use crate::Result;
 
 #[test]
@@ -55,12 +55,44 @@ fn one_of_seqs() {
   }
 }
 
+#[test]
+fn seq() {
+  use MatchResult::*;
+  let syntax = super::seq::<String, _>(&['a', 'b', 'c']);
+  assert_match_str(&syntax, "", Ok(Partial(0)));
+  assert_match_str(&syntax, "a", Ok(Partial(1)));
+  assert_match_str(&syntax, "ab", Ok(Partial(2)));
+  assert_match_str(&syntax, "abc", Ok(Match(3)));
+  assert_match_str(&syntax, "abcd", Ok(Match(3)));
+  assert_match_str(&syntax, "x", Ok(Unmatch));
+  assert_match_str(&syntax, "abx", Ok(Unmatch));
+
+  // the symbols reported as matched aren't compared again
+  let matcher = if let Syntax { primary: Primary::Term(term), .. } = &syntax { &term.matcher } else { panic!() };
+  let ctx = MatchCtx { matched: 2, ..MatchCtx::default() };
+  assert_eq!(Ok(Match(3)), matcher(&['x', 'x', 'c'], &ctx));
+  assert_eq!(Ok(Unmatch), matcher(&['a', 'b', 'x'], &ctx));
+}
+
 fn assert_match_str<ID>(syntax: &Syntax<ID, char>, values: &str, expected: Result<char, MatchResult>) {
   let values = values.chars().collect::<Vec<_>>();
   assert_match(syntax, &values, expected);
 }
 
 fn assert_match<ID, Σ: Symbol>(syntax: &Syntax<ID, Σ>, values: &[Σ], expected: Result<Σ, MatchResult>) {
-  let result = if let Syntax { primary: Primary::Term(_, matcher), .. } = syntax { matcher(values) } else { panic!() };
+  let result = if let Syntax { primary: Primary::Term(term), .. } = syntax {
+    (term.matcher)(values, &MatchCtx::default())
+  } else {
+    panic!()
+  };
   assert_eq!(expected, result);
 }
+
+#[test]
+fn eof() {
+  let syntax = super::eof::<String, char>();
+  assert_match_str(&syntax, "", Ok(MatchResult::MatchAndCanAcceptMore(0)));
+  assert_match_str(&syntax, "X", Ok(MatchResult::Unmatch));
+  assert_match_str(&syntax, "XY", Ok(MatchResult::Unmatch));
+  assert_eq!("EOF", syntax.to_string());
+}
@@ -0,0 +1,143 @@
+use crate::parser::{Context, Event, EventKind};
+use crate::schema::chars::{ch, one_of_chars};
+use crate::schema::{any_of_ranges_with_label, id, MatchResult, Schema, Syntax};
+use crate::Result;
+use std::fmt::Display;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ID {
+  MediaType,
+  Type,
+  Subtype,
+  Parameter,
+  Name,
+  Value,
+  QuotedString,
+  QuotedPair,
+  WS,
+}
+
+impl Display for ID {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// The media type with parameters such as `text/html; charset="utf-8"` of the `Content-Type` header field.
+///
+/// - The [`ID::Type`] and the [`ID::Subtype`] are the restricted names of
+///   [RFC 6838 Section 4.2](https://www.rfc-editor.org/rfc/rfc6838#section-4.2), which are up to 127 characters.
+/// - Each [`ID::Parameter`] follows `;` with optional whitespaces according to
+///   [RFC 9110 Section 8.3.1](https://www.rfc-editor.org/rfc/rfc9110#section-8.3.1), and may be empty such as
+///   `text/plain;;charset=utf-8`. The [`ID::Value`] is either a token or an [`ID::QuotedString`] that may contain the
+///   [`ID::QuotedPair`]s such as `\"`, which are unescaped by [`unquote()`].
+///
+pub fn schema() -> Schema<ID, char> {
+  let ws = || id(ID::WS) * (0..=1);
+  let parameter = || ws() & ch(';') & ((ws() & id(ID::Parameter)) * (0..=1));
+  let qdtext =
+    any_of_ranges_with_label("QDTEXT", vec!['\t'..='\t', ' '..='!', '#'..='[', ']'..='~', '\u{80}'..='\u{FF}']);
+  let quoted = any_of_ranges_with_label("QUOTED", vec!['\t'..='\t', ' '..='~', '\u{80}'..='\u{FF}']);
+  Schema::new("MEDIA_TYPE")
+    .define(ID::MediaType, id(ID::Type) & ch('/') & id(ID::Subtype) & (parameter() * (0..)) & ws())
+    .define(ID::Type, restricted_name())
+    .define(ID::Subtype, restricted_name())
+    .define(ID::Parameter, id(ID::Name) & ch('=') & id(ID::Value))
+    .define(ID::Name, token())
+    .define(ID::Value, token() | id(ID::QuotedString))
+    .define(ID::QuotedString, ch('"') & ((qdtext | id(ID::QuotedPair)) * (0..)) & ch('"'))
+    .define(ID::QuotedPair, ch('\\') & quoted)
+    .define(ID::WS, one_of_chars(" \t") * (1..))
+}
+
+/// A media type parsed by [`parse()`].
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MediaType {
+  /// The type in lowercase, such as `text`.
+  pub r#type: String,
+  /// The subtype in lowercase, such as `html`.
+  pub subtype: String,
+  /// The parameters in the order of appearance, whose names are in lowercase and whose values are unquoted.
+  pub parameters: Vec<(String, String)>,
+}
+
+impl MediaType {
+  /// The value of the parameter `name`, which is case-insensitive.
+  ///
+  pub fn parameter(&self, name: &str) -> Option<&str> {
+    self.parameters.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+  }
+}
+
+/// Parse `text` as a media type, where the type, the subtype and the parameter names are case-insensitive.
+///
+pub fn parse(text: &str) -> Result<char, MediaType> {
+  let schema = schema();
+  let mut media_type = MediaType { r#type: String::new(), subtype: String::new(), parameters: Vec::new() };
+  let mut current = None;
+  let handler = |e: &Event<ID, char>| match &e.kind {
+    EventKind::Begin(ID::Parameter) => media_type.parameters.push((String::new(), String::new())),
+    EventKind::Begin(id @ (ID::Type | ID::Subtype | ID::Name | ID::Value)) => current = Some(*id),
+    EventKind::End(ID::Type | ID::Subtype | ID::Name | ID::Value) => current = None,
+    EventKind::Fragments(chars) => match (current, media_type.parameters.last_mut()) {
+      (Some(ID::Type), _) => media_type.r#type.extend(chars.iter().map(|c| c.to_ascii_lowercase())),
+      (Some(ID::Subtype), _) => media_type.subtype.extend(chars.iter().map(|c| c.to_ascii_lowercase())),
+      (Some(ID::Name), Some((name, _))) => name.extend(chars.iter().map(|c| c.to_ascii_lowercase())),
+      (Some(ID::Value), Some((_, value))) => value.extend(chars),
+      _ => (),
+    },
+    _ => (),
+  };
+  let mut parser =
+    Context::new(&schema, ID::MediaType, handler)?.ignore_events_for(&[ID::QuotedString, ID::QuotedPair, ID::WS]);
+  parser.push_str(text)?;
+  parser.finish()?;
+  for (_, value) in &mut media_type.parameters {
+    *value = unquote(value);
+  }
+  Ok(media_type)
+}
+
+/// Remove the enclosing `"` of a quoted string and unescape its quoted pairs. A token is returned as it is.
+///
+pub fn unquote(value: &str) -> String {
+  let inner = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+    Some(inner) => inner,
+    None => return value.to_string(),
+  };
+  let mut unquoted = String::with_capacity(inner.len());
+  let mut chars = inner.chars();
+  while let Some(ch) = chars.next() {
+    unquoted.push(if ch == '\\' { chars.next().unwrap_or(ch) } else { ch });
+  }
+  unquoted
+}
+
+/// The token of RFC 9110, one or more characters other than the delimiters, as long as possible.
+///
+fn token<ID>() -> Syntax<ID, char> {
+  name("TOKEN", usize::MAX, |ch, _| ch.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(ch))
+}
+
+/// The restricted name of RFC 6838 that begins with an alphanumeric character, as long as possible.
+///
+fn restricted_name<ID>() -> Syntax<ID, char> {
+  name("RESTRICTED_NAME", 127, |ch, first| ch.is_ascii_alphanumeric() || (!first && "!#$&-^_.+".contains(ch)))
+}
+
+fn name<ID>(label: &str, max: usize, is_name_char: fn(char, bool) -> bool) -> Syntax<ID, char> {
+  Syntax::from_fn(label, move |values: &[char]| -> Result<char, MatchResult> {
+    let length = values.iter().enumerate().position(|(i, ch)| !is_name_char(*ch, i == 0));
+    Ok(match length {
+      _ if length.unwrap_or(values.len()) > max => MatchResult::Unmatch,
+      None if values.is_empty() => MatchResult::UnmatchAndCanAcceptMore,
+      None => MatchResult::MatchAndCanAcceptMore(values.len()),
+      Some(0) => MatchResult::Unmatch,
+      Some(length) => MatchResult::Match(length),
+    })
+  })
+}
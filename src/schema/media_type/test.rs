@@ -0,0 +1,120 @@
+use super::{parse, schema, unquote, MediaType, ID};
+use crate::parser::{test::Events, Context, Event};
+
+#[test]
+fn media_type_events() {
+  let events = events("text/html; charset=UTF-8 ;format=\"a \\\"b\\\"\"").unwrap();
+  Events::new()
+    .begin(ID::MediaType)
+    .begin(ID::Type)
+    .fragments("text")
+    .end()
+    .fragments("/")
+    .begin(ID::Subtype)
+    .fragments("html")
+    .end()
+    .fragments("; ")
+    .begin(ID::Parameter)
+    .begin(ID::Name)
+    .fragments("charset")
+    .end()
+    .fragments("=")
+    .begin(ID::Value)
+    .fragments("UTF-8")
+    .end()
+    .end()
+    .fragments(" ;")
+    .begin(ID::Parameter)
+    .begin(ID::Name)
+    .fragments("format")
+    .end()
+    .fragments("=")
+    .begin(ID::Value)
+    .begin(ID::QuotedString)
+    .fragments("\"a ")
+    .begin(ID::QuotedPair)
+    .fragments("\\\"")
+    .end()
+    .fragments("b")
+    .begin(ID::QuotedPair)
+    .fragments("\\\"")
+    .end()
+    .fragments("\"")
+    .end()
+    .end()
+    .end()
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn valid_media_types() {
+  for text in [
+    "text/plain",
+    "TEXT/Plain",
+    "application/vnd.api+json",
+    "application/x-www-form-urlencoded",
+    "multipart/form-data; boundary=----WebKitFormBoundary7MA4YWxkTrZu0gW",
+    "text/plain;charset=utf-8",
+    "text/plain;;charset=utf-8;",
+    "text/plain; a=\"\"; b=\"x;y=z\"",
+    "text/plain ",
+  ] {
+    assert!(events(text).is_ok(), "{:?}", text);
+  }
+  for text in [
+    "",
+    "text",
+    "text/",
+    "/plain",
+    "-text/plain",
+    "text/pl ain",
+    "text/plain; charset",
+    "text/plain; a=\"b",
+    "text/plain; a=b c",
+    "text/plain; a = b",
+    " text/plain",
+  ] {
+    assert!(events(text).is_err(), "{:?}", text);
+  }
+  assert!(events(&format!("text/{}", "a".repeat(127))).is_ok());
+  assert!(events(&format!("text/{}", "a".repeat(128))).is_err());
+}
+
+#[test]
+fn parse_media_type() {
+  let media_type = parse("Multipart/Form-Data; Boundary=\"a\\\\b\\\"c\"; charset=UTF-8").unwrap();
+  assert_eq!(
+    MediaType {
+      r#type: String::from("multipart"),
+      subtype: String::from("form-data"),
+      parameters: vec![
+        (String::from("boundary"), String::from("a\\b\"c")),
+        (String::from("charset"), String::from("UTF-8"))
+      ],
+    },
+    media_type
+  );
+  assert_eq!(Some("UTF-8"), media_type.parameter("CharSet"));
+  assert_eq!(None, media_type.parameter("format"));
+  assert!(parse("text/plain; charset").is_err());
+}
+
+#[test]
+fn unquote_values() {
+  for (value, expected) in
+    [("utf-8", "utf-8"), ("\"utf-8\"", "utf-8"), ("\"\"", ""), ("\"a\\\"b\\\\\"", "a\"b\\"), ("\"", "\"")]
+  {
+    assert_eq!(expected, unquote(value), "{:?}", value);
+  }
+}
+
+fn events(text: &str) -> crate::Result<char, Vec<Event<ID, char>>> {
+  let schema = schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, ID::MediaType, handler)?.ignore_events_for(&[ID::WS]);
+  parser.push_str(text)?;
+  parser.finish()?;
+  Ok(Event::normalize(&events))
+}
@@ -0,0 +1,110 @@
+use crate::parser::{Context, Event, EventKind};
+use crate::schema::chars::{ch, newline, none_of_chars, one_of_chars};
+use crate::schema::{eof, id, range, Schema, Syntax};
+use crate::Result;
+use std::fmt::{Debug, Display};
+
+#[cfg(test)]
+mod test;
+
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ID {
+  Headers,
+  Field,
+  Name,
+  Body,
+  Fold,
+  Comment,
+  QuotedString,
+  QuotedPair,
+  Newline,
+}
+
+impl Display for ID {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// The header fields of Internet Message Format, which are also used by MIME and HTTP/1.x.
+/// <https://datatracker.ietf.org/doc/html/rfc5322#section-2.2>
+///
+/// A field body may be folded into multiple lines, where a line beginning with a whitespace continues the previous
+/// one as [`ID::Fold`]. The comments in parentheses, which can be nested, and the quoted strings are recognized so
+/// that their parentheses and quotes don't end the field, see [`unfold()`] and [`fields()`] for the logical values.
+/// The headers end at an empty line or at the end of the input, and LF or CR alone is also accepted as a line break.
+///
+pub fn schema() -> Schema<ID, char> {
+  use ID::*;
+  Schema::new("MIME_HEADERS")
+    .define(Headers, (id(Field) * (0..)) & (id(Newline) * (0..=1)))
+    .define(Field, id(Name) & ch(':') & id(Body) & (id(Newline) | eof()))
+    .define(Name, (range('\x21'..='\x39') | range('\x3B'..='\x7E')) * (1..))
+    .define(Body, (id(Fold) | id(Comment) | id(QuotedString) | none_of_chars("\r\n(\"")) * (0..))
+    .define(Fold, id(Newline) & (wsp() * (1..)))
+    .define(
+      Comment,
+      ch('(') & ((id(Fold) | id(QuotedPair) | id(Comment) | none_of_chars("()\\\r\n")) * (0..)) & ch(')'),
+    )
+    .define(QuotedString, ch('"') & ((id(Fold) | id(QuotedPair) | none_of_chars("\"\\\r\n")) * (0..)) & ch('"'))
+    .define(QuotedPair, ch('\\') & none_of_chars("\r\n"))
+    .define(Newline, newline())
+}
+
+fn wsp<ID: Debug>() -> Syntax<ID, char> {
+  one_of_chars(" \t")
+}
+
+/// Remove the line breaks followed by a whitespace, which fold a field body into multiple lines.
+///
+pub fn unfold(body: &str) -> String {
+  let chars = body.chars().collect::<Vec<_>>();
+  let mut unfolded = String::with_capacity(body.len());
+  let mut i = 0;
+  while i < chars.len() {
+    let newline = match chars[i..] {
+      ['\r', '\n', ..] => 2,
+      ['\r', ..] | ['\n', ..] => 1,
+      _ => 0,
+    };
+    if newline > 0 && matches!(chars.get(i + newline), Some(' ' | '\t')) {
+      i += newline;
+    } else {
+      unfolded.push(chars[i]);
+      i += 1;
+    }
+  }
+  unfolded
+}
+
+/// Parse `text` and return the names and the unfolded bodies of the header fields in the order of appearance, where
+/// the leading and trailing whitespaces of the bodies are removed. The rest of `text` following the empty line isn't
+/// parsed.
+///
+pub fn fields(text: &str) -> Result<char, Vec<(String, String)>> {
+  let end = text.find("\r\n\r\n").map(|i| i + 2).or_else(|| text.find("\n\n").map(|i| i + 1)).unwrap_or(text.len());
+  let schema = schema();
+  let mut fields = Vec::new();
+  let mut current = None;
+  let handler = |e: &Event<ID, char>| match &e.kind {
+    EventKind::Begin(ID::Field) => fields.push((String::new(), String::new())),
+    EventKind::Begin(id @ (ID::Name | ID::Body)) => current = Some(*id),
+    EventKind::End(ID::Name | ID::Body) => current = None,
+    EventKind::Fragments(chars) => match (current, fields.last_mut()) {
+      (Some(ID::Name), Some((name, _))) => name.extend(chars),
+      (Some(ID::Body), Some((_, body))) => body.extend(chars),
+      _ => (),
+    },
+    _ => (),
+  };
+  let mut parser = Context::new(&schema, ID::Headers, handler)?.ignore_events_for(&[
+    ID::Fold,
+    ID::Comment,
+    ID::QuotedString,
+    ID::QuotedPair,
+    ID::Newline,
+  ]);
+  parser.push_str(&text[..end])?;
+  parser.finish()?;
+  Ok(fields.into_iter().map(|(name, body)| (name, unfold(&body).trim().to_string())).collect())
+}
@@ -0,0 +1,67 @@
+use super::{fields, schema, unfold, ID};
+use crate::parser::{test::Events, Context, Event};
+
+#[test]
+fn folded_field() {
+  let schema = schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, ID::Headers, handler).unwrap().ignore_events_for(&[
+    ID::Comment,
+    ID::QuotedString,
+    ID::QuotedPair,
+    ID::Newline,
+  ]);
+  parser.push_str("Subject: a\r\n b (c:\r\n d)\r\n\r\n").unwrap();
+  parser.finish().unwrap();
+  Events::new()
+    .begin(ID::Headers)
+    .begin(ID::Field)
+    .begin(ID::Name)
+    .fragments("Subject")
+    .end()
+    .fragments(":")
+    .begin(ID::Body)
+    .fragments(" a")
+    .begin(ID::Fold)
+    .fragments("\r\n ")
+    .end()
+    .fragments("b (c:")
+    .begin(ID::Fold)
+    .fragments("\r\n ")
+    .end()
+    .fragments("d)")
+    .end()
+    .fragments("\r\n")
+    .end()
+    .fragments("\r\n")
+    .end()
+    .assert_eq(&Event::normalize(&events));
+}
+
+#[test]
+fn unfolded_fields() {
+  let f = |n: &str, b: &str| (n.to_string(), b.to_string());
+  assert_eq!(
+    vec![
+      f("From", "John Doe <jdoe@example.com> (a (nested) comment)"),
+      f("Subject", "Saying\tHello"),
+      f("X-Quoted", "\"a \\\" (not a comment\""),
+      f("X-Empty", ""),
+    ],
+    fields(
+      "From: John Doe <jdoe@example.com>\r\n (a (nested)\r\n comment)\r\nSubject: Saying\r\n\tHello\r\n\
+       X-Quoted: \"a \\\" (not a comment\"\r\nX-Empty:\r\n\r\nbody: not a header\r\n"
+    )
+    .unwrap()
+  );
+  assert_eq!(vec![f("A", "1"), f("B", "2")], fields("A: 1\nB: 2").unwrap());
+  assert!(fields("A: (unclosed\r\n").is_err());
+  assert!(fields("No colon\r\n").is_err());
+}
+
+#[test]
+fn unfold_only_folds() {
+  assert_eq!("a b\tc", unfold("a\r\n b\n\tc"));
+  assert_eq!("a\r\nb", unfold("a\r\nb"));
+}
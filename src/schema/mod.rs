@@ -1,13 +1,36 @@
 use crate::Result;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 use std::marker::Send;
 use std::ops::{BitAnd, BitOr, Mul, RangeFrom, RangeInclusive, RangeTo, RangeToInclusive};
+use std::sync::Arc;
 
+pub mod analysis;
+pub mod antlr;
 pub mod bytes;
 pub mod chars;
+pub mod cron;
+pub mod css_values;
+pub mod csv;
+pub mod dockerfile;
+pub mod dotenv;
+pub mod html;
+pub mod ini;
+pub mod intern;
 pub mod json;
+pub mod media_type;
+pub mod mime_headers;
+pub mod net;
+pub mod pest;
+pub mod phone;
+pub mod pratt;
+pub mod robots;
+pub mod sexpr;
+pub mod shellwords;
+pub mod spanned;
+pub mod tree_sitter;
+pub mod urlencoded;
 
 mod matcher;
 pub use matcher::*;
@@ -20,40 +43,392 @@ pub struct Schema<ID, Σ: Symbol> {
   syntax_id_seq: usize,
   /// The top-level [`Syntax`] stored with the `ID` must be [`Primary::Seq`].
   defs: BTreeMap<ID, Syntax<ID, Σ>>,
+  /// The sequences that skip the input to resynchronize when the syntax of `ID` fails.
+  recoveries: BTreeMap<ID, Vec<Syntax<ID, Σ>>>,
+  /// The maximum number of symbols that the syntax of `ID` may match.
+  max_lengths: BTreeMap<ID, usize>,
+  /// The syntaxes whose symbols are redacted from the errors and the debug output.
+  sensitives: BTreeSet<ID>,
+  /// The mappings of the symbols evaluated by the terms in the definitions of the syntaxes.
+  reinterpretations: BTreeMap<ID, Arc<Reinterpretation<Σ>>>,
+  /// The syntax IDs of the choices that make their rules directly left-recursive.
+  left_recursions: BTreeSet<usize>,
+  bindings: pratt::Bindings,
+  /// The profile whose syntaxes are defined, or all syntaxes if `None`.
+  profile: Option<String>,
+  /// The matchers of the terms by their signatures, which are shared by the identical terms.
+  terms: HashMap<String, Arc<Matcher<Σ>>>,
 }
 
 impl<ID, Σ: 'static + Symbol> Schema<ID, Σ> {
   pub fn new(name: &str) -> Self {
-    Self { name: name.to_string(), syntax_id_seq: 1, defs: BTreeMap::default() }
+    Self {
+      name: name.to_string(),
+      syntax_id_seq: 1,
+      defs: BTreeMap::default(),
+      recoveries: BTreeMap::default(),
+      max_lengths: BTreeMap::default(),
+      sensitives: BTreeSet::default(),
+      reinterpretations: BTreeMap::default(),
+      left_recursions: BTreeSet::default(),
+      bindings: pratt::Bindings::default(),
+      profile: None,
+      terms: HashMap::default(),
+    }
+  }
+
+  /// Define only the syntaxes of the `profile` and those not tagged by [`Syntax::profile()`] afterwards, so that a
+  /// function defining the schema can generate variants such as strict and lenient ones. All the syntaxes are defined
+  /// if no profile is specified. A rule whose whole definition is removed is left undefined.
+  ///
+  /// ```rust
+  /// use terp::schema::{Schema, id};
+  /// use terp::schema::chars::{ch, ascii_digit};
+  /// fn schema(profile: &str) -> Schema<&'static str, char> {
+  ///   Schema::new("List")
+  ///     .with_profile(profile)
+  ///     .define("List", ch('[') & id("Items") & (ch(',').profile("lenient") * (0..=1)) & ch(']'))
+  ///     .define("Items", id("Item") & ((ch(',') & id("Item")) * (0..)))
+  ///     .define("Item", ascii_digit() * (1..))
+  /// }
+  /// let (strict, lenient) = (schema("strict"), schema("lenient"));
+  ///
+  /// use terp::parser::Context;
+  /// let mut parser = Context::recognizer(&lenient, "List").unwrap();
+  /// parser.push_str("[1,2,]").unwrap();
+  /// assert!(parser.finish().is_ok());
+  /// let mut parser = Context::recognizer(&strict, "List").unwrap();
+  /// assert!(parser.push_str("[1,2,]").and_then(|_| parser.finish()).is_err());
+  /// ```
+  ///
+  pub fn with_profile(self, profile: &str) -> Self {
+    Self { profile: Some(profile.to_string()), ..self }
+  }
+
+  pub fn profile(&self) -> Option<&str> {
+    self.profile.as_deref()
   }
 
   pub fn name(&self) -> &str {
     &self.name
   }
 
+  /// The IDs of the defined syntaxes in the order of their definitions, where a redefined syntax is ordered by its
+  /// last definition.
+  ///
   pub fn ids(&self) -> impl Iterator<Item = &ID> {
-    self.defs.keys()
+    self.definitions().into_iter().map(|(id, _)| id)
+  }
+
+  /// Find the syntax whose ID is displayed as `name`, which is useful when the ID is an enum.
+  ///
+  pub fn get_by_name(&self, name: &str) -> Option<(&ID, &Syntax<ID, Σ>)>
+  where
+    ID: Display,
+  {
+    self.definitions().into_iter().find(|(id, _)| id.to_string() == name)
+  }
+}
+
+impl<ID, Σ: Symbol> Schema<ID, Σ> {
+  /// The definitions in the order of their definitions, according to the sequential IDs assigned to their syntaxes.
+  ///
+  fn definitions(&self) -> Vec<(&ID, &Syntax<ID, Σ>)> {
+    let mut defs = self.defs.iter().collect::<Vec<_>>();
+    defs.sort_by_key(|(_, syntax)| syntax.id);
+    defs
+  }
+}
+
+impl<ID, Σ: 'static + Symbol> Schema<ID, Σ> {
+  /// The schema that refers to the IDs of this schema, so that a context parsing with it delivers the events with
+  /// `&ID` instead of the clones of the IDs, which are heap allocations for the IDs such as `String`. The IDs of the
+  /// events can be cloned by [`Event::to_owned_ids()`](crate::parser::Event::to_owned_ids) if needed.
+  ///
+  /// ```rust
+  /// use terp::parser::{Context, Event, EventKind};
+  /// use terp::schema::{Schema, id};
+  /// use terp::schema::chars::{ch, ascii_digit};
+  ///
+  /// let schema = Schema::new("List")
+  ///   .define(String::from("List"), ch('[') & id(String::from("Item")) & ch(']'))
+  ///   .define(String::from("Item"), ascii_digit() * (1..));
+  /// let borrowed = schema.by_ref();
+  ///
+  /// let list = String::from("List");
+  /// let mut depth = 0;
+  /// let mut parser = Context::new(&borrowed, &list, |e: &Event<&String, char>| match e.kind {
+  ///   EventKind::Begin(_) => depth += 1,
+  ///   _ => (),
+  /// }).unwrap();
+  /// parser.push_str("[42]").unwrap();
+  /// parser.finish().unwrap();
+  /// assert_eq!(2, depth);
+  /// ```
+  ///
+  pub fn by_ref(&self) -> Schema<&ID, Σ>
+  where
+    ID: Ord,
+  {
+    self.map_ids(|id| id)
+  }
+
+  /// Translate the IDs of this schema by `f`, preserving the sequential IDs of the syntaxes to which the analyses of
+  /// the schema refer.
+  ///
+  pub(crate) fn map_ids<'a, J: Ord, F: FnMut(&'a ID) -> J>(&'a self, mut f: F) -> Schema<J, Σ> {
+    Schema {
+      name: self.name.clone(),
+      syntax_id_seq: self.syntax_id_seq,
+      defs: self.defs.iter().map(|(id, syntax)| (f(id), syntax.map_ids(&mut f))).collect(),
+      recoveries: self
+        .recoveries
+        .iter()
+        .map(|(id, seq)| (f(id), seq.iter().map(|s| s.map_ids(&mut f)).collect()))
+        .collect(),
+      max_lengths: self.max_lengths.iter().map(|(id, max)| (f(id), *max)).collect(),
+      sensitives: self.sensitives.iter().map(&mut f).collect(),
+      reinterpretations: self.reinterpretations.iter().map(|(id, r)| (f(id), r.clone())).collect(),
+      left_recursions: self.left_recursions.clone(),
+      bindings: self.bindings.clone(),
+      profile: self.profile.clone(),
+      terms: self.terms.clone(),
+    }
   }
 }
 
 impl<ID: Ord, Σ: 'static + Symbol> Schema<ID, Σ> {
+  /// Define the syntax of `id`. A directly left-recursive definition such as `Expr := Expr '+' Term | Term` is also
+  /// allowed, and is parsed by growing the parse of the non-recursive branches (seed) with the rest of the recursive
+  /// branches as many times as possible, so that the events nest to the left.
+  ///
   pub fn define(mut self, id: ID, syntax: Syntax<ID, Σ>) -> Self {
+    let syntax = match &self.profile {
+      Some(profile) => match syntax.select_profile(profile) {
+        Some(syntax) => syntax,
+        None => return self,
+      },
+      None => syntax,
+    };
     // the specified Syntax is wrapped in Primary::Seq if it's not a Primary::Seq
     let mut syntax = syntax.conv_to_non_repeating_seq();
     self.init_syntax_ids(&mut syntax);
+    if let Some(choice) = Self::left_recursive_choice(&id, &syntax) {
+      self.left_recursions.insert(choice.id);
+    }
+    if let Some(f) = self.reinterpretations.get(&id) {
+      syntax.reinterpret(f);
+    }
     self.defs.insert(id, syntax);
     self
   }
 
+  /// Let the terms in the definition of the syntax `id` evaluate the symbols mapped by `f`, such as a contextual
+  /// keyword that a lexer has reported as an identifier, e.g., `async` in Rust 2015. The mapping is scoped to the
+  /// terms that appear directly in the definition, not in the other syntaxes referred to by it, and the events report
+  /// the original symbols. It applies to `id` whether it's defined before or after this, and replaces the mapping of
+  /// the previous `reinterpret()` of `id` rather than being composed with it.
+  ///
+  /// ```rust
+  /// use terp::parser::Context;
+  /// use terp::schema::{Schema, id};
+  /// use terp::schema::spanned::{value, value_with, Spanned};
+  ///
+  /// #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+  /// enum Token { Async, Fn, Ident(&'static str), LParen, RParen }
+  /// impl std::fmt::Display for Token {
+  ///   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+  ///     write!(f, "{:?}", self)
+  ///   }
+  /// }
+  ///
+  /// let ident = || value_with("IDENT", |t| matches!(t, Token::Ident(_)));
+  /// let schema = Schema::new("Rust 2015")
+  ///   .define("ITEM", id("ASYNC_FN") | id("CALL"))
+  ///   .define("ASYNC_FN", value(Token::Async) & value(Token::Fn) & ident())
+  ///   .define("CALL", ident() & value(Token::LParen) & value(Token::RParen))
+  ///   .reinterpret("ASYNC_FN", |t: Spanned<Token>| match t.value {
+  ///     Token::Ident("async") => Spanned { value: Token::Async, ..t },
+  ///     _ => t,
+  ///   });
+  ///
+  /// // `async fn foo` and `async()`
+  /// let async_fn = [Token::Ident("async"), Token::Fn, Token::Ident("foo")];
+  /// let call = [Token::Ident("async"), Token::LParen, Token::RParen];
+  /// for tokens in [async_fn, call] {
+  ///   let tokens = tokens.iter().enumerate();
+  ///   let tokens = tokens.map(|(i, t)| Spanned::new(*t, i as u64, i as u64 + 1)).collect::<Vec<_>>();
+  ///   let mut parser = Context::recognizer(&schema, "ITEM").unwrap();
+  ///   parser.push_seq(&tokens).unwrap();
+  ///   parser.finish().unwrap();
+  /// }
+  /// ```
+  ///
+  pub fn reinterpret<F: Fn(Σ) -> Σ + Send + Sync + 'static>(mut self, id: ID, f: F) -> Self {
+    let f: Arc<Reinterpretation<Σ>> = Arc::new(f);
+    if let Some(syntax) = self.defs.get_mut(&id) {
+      syntax.reinterpret(&f);
+    }
+    self.reinterpretations.insert(id, f);
+    self
+  }
+
+  /// Declare the recovery set of the syntax `id`. When the parser fails within `id`, it skips the input until one of
+  /// the `symbols` appears, treats the skipped symbols as the content of `id`, and resumes parsing just after `id`.
+  /// The failures recovered are reported by [`Context::recovered_errors()`](crate::parser::Context::recovered_errors)
+  /// and as [`Error::Recovered`](crate::Error::Recovered), together with the error that has failed the parse, if any.
+  ///
+  /// ```rust
+  /// use terp::schema::{Schema, id};
+  /// use terp::schema::chars::{ch, ascii_digit};
+  /// let schema = Schema::new("List")
+  ///   .define("List", ch('[') & id("Item") & ((ch(',') & id("Item")) * (0..)) & ch(']'))
+  ///   .define("Item", ascii_digit() * (1..))
+  ///   .recover_at("Item", &[',', ']']);
+  /// ```
+  ///
+  pub fn recover_at(mut self, id: ID, symbols: &[Σ]) -> Self {
+    let label = format!("RECOVER_AT({})", symbols.iter().map(|s| Σ::debug_symbol(*s)).collect::<Vec<_>>().join("|"));
+    let symbols = symbols.to_vec();
+    let skip = Syntax::from_fn(&label, move |buffer: &[Σ]| -> Result<Σ, MatchResult> {
+      match buffer.iter().position(|s| symbols.contains(s)) {
+        Some(length) => Ok(MatchResult::Match(length)),
+        None => Ok(MatchResult::MatchAndCanAcceptMore(buffer.len())),
+      }
+    });
+    let mut syntax = skip.conv_to_non_repeating_seq();
+    self.init_syntax_ids(&mut syntax);
+    if let Primary::Seq(seq) = syntax.primary {
+      self.recoveries.insert(id, seq);
+    }
+    self
+  }
+
+  /// Limit the number of symbols that each appearance of the syntax `id` may match to `max`, e.g., to reject an
+  /// unterminated string literal before it buffers the rest of the input. The path on which `id` has matched or is
+  /// holding more symbols than `max` is dropped as soon as it does, and parsing fails with
+  /// [`Error::TooLong`](crate::Error::TooLong) unless another path gets farther. It doesn't limit `id` parsed as the
+  /// root of a context.
+  ///
+  /// ```rust
+  /// use terp::schema::{Schema, id};
+  /// use terp::schema::chars::{ch, none_of_chars};
+  /// let schema = Schema::new("Strings")
+  ///   .define("Strings", id("String") * (0..))
+  ///   .define("String", ch('"') & (none_of_chars("\"") * (0..)) & ch('"'))
+  ///   .limit_length("String", 1024 * 1024);
+  /// ```
+  ///
+  pub fn limit_length(mut self, id: ID, max: usize) -> Self {
+    self.max_lengths.insert(id, max);
+    self
+  }
+
+  /// Mark the syntax `id` as carrying sensitive data, such as a password or a token, so that the symbols matched
+  /// within it are redacted as `***` from the samples of the input in [`ErrorDetail`](crate::ErrorDetail) and from the
+  /// debug output of the parser.
+  ///
+  /// Only the errors and the parser's own logs are covered. The events are delivered to the handlers as they are, and
+  /// the `Debug` of an [`Event`](crate::parser::Event) prints its fragments verbatim since an event doesn't know the
+  /// syntax that it belongs to. A handler that logs the events should skip the fragments within the syntaxes for which
+  /// [`is_sensitive()`](Self::is_sensitive) is true.
+  ///
+  /// ```rust
+  /// use terp::parser::Context;
+  /// use terp::schema::{Schema, id};
+  /// use terp::schema::chars::{ascii_alphabetic, ascii_digit, ch, token};
+  /// let schema = Schema::new("Credential")
+  ///   .define("Credential", token("password=") & id("Password") & ch(';'))
+  ///   .define("Password", (ascii_alphabetic() | ascii_digit()) * (1..))
+  ///   .sensitive("Password");
+  /// let mut parser = Context::recognizer(&schema, "Credential").unwrap();
+  /// let error = parser.push_str("password=s3cr3t!").and_then(|_| parser.finish()).unwrap_err();
+  /// assert!(!error.to_string().contains("s3cr3t"));
+  /// ```
+  ///
+  pub fn sensitive(mut self, id: ID) -> Self {
+    self.sensitives.insert(id);
+    self
+  }
+
+  pub fn is_sensitive(&self, id: &ID) -> bool {
+    self.sensitives.contains(id)
+  }
+
+  pub(crate) fn has_sensitives(&self) -> bool {
+    !self.sensitives.is_empty()
+  }
+
   pub fn get(&self, id: &ID) -> Option<&Syntax<ID, Σ>> {
     self.defs.get(id)
   }
 
+  pub(crate) fn recovery(&self, id: &ID) -> Option<&Vec<Syntax<ID, Σ>>> {
+    self.recoveries.get(id)
+  }
+
+  pub(crate) fn max_length(&self, id: &ID) -> Option<usize> {
+    self.max_lengths.get(id).copied()
+  }
+
+  pub(crate) fn has_max_lengths(&self) -> bool {
+    !self.max_lengths.is_empty()
+  }
+
+  /// The rule that `syntax` makes directly left-recursive, if `syntax` is the choice of its definition.
+  ///
+  pub(crate) fn left_recursion<'a>(&self, syntax: &'a Syntax<ID, Σ>) -> Option<&'a ID> {
+    if !self.left_recursions.contains(&syntax.id) {
+      return None;
+    }
+    match &syntax.primary {
+      Primary::Or(branches) => branches.iter().find_map(|branch| match &branch.primary {
+        Primary::Seq(seq) => match &seq[0].primary {
+          Primary::Alias(id) if branch.is_left_recursive_on(id) => Some(id),
+          _ => None,
+        },
+        _ => None,
+      }),
+      _ => None,
+    }
+  }
+
+  /// Whether the rule `id` is directly left-recursive.
+  ///
+  pub(crate) fn is_left_recursive(&self, id: &ID) -> bool {
+    match self.defs.get(id) {
+      Some(Syntax { primary: Primary::Seq(seq), .. }) => seq.len() == 1 && self.left_recursions.contains(&seq[0].id),
+      _ => false,
+    }
+  }
+
+  /// The choice in the definition of `id` like `A := A α | β`, where at least one branch begins with `id` itself and
+  /// at least one doesn't.
+  ///
+  fn left_recursive_choice<'a>(id: &ID, syntax: &'a Syntax<ID, Σ>) -> Option<&'a Syntax<ID, Σ>> {
+    match &syntax.primary {
+      Primary::Seq(seq) if seq.len() == 1 && seq[0].repetition == (1..=1) => match &seq[0].primary {
+        Primary::Or(branches)
+          if branches.iter().any(|b| b.is_left_recursive_on(id)) && branches.iter().any(|b| !b.begins_with(id)) =>
+        {
+          Some(&seq[0])
+        }
+        _ => None,
+      },
+      _ => None,
+    }
+  }
+
   fn init_syntax_ids(&mut self, syntax: &mut Syntax<ID, Σ>) {
     syntax.id = self.syntax_id_seq;
     self.syntax_id_seq += 1;
     match &mut syntax.primary {
-      Primary::Term(..) => (),
+      Primary::Term(term) => {
+        if let Some(signature) = &term.signature {
+          let matcher = self.terms.entry(signature.clone()).or_insert_with(|| term.matcher.clone());
+          term.matcher = matcher.clone();
+        }
+      }
       Primary::Alias(_) => (),
       Primary::Seq(branches) => {
         for branch in branches {
@@ -72,7 +447,7 @@ impl<ID: Ord, Σ: 'static + Symbol> Schema<ID, Σ> {
 impl<ID: Display + Debug, Σ: Symbol> Display for Schema<ID, Σ> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     writeln!(f, "{}", self.name)?;
-    for (id, syntax) in self.defs.iter() {
+    for (id, syntax) in self.definitions() {
       writeln!(f, "  {:?} := {}", id, syntax)?;
     }
     Ok(())
@@ -81,7 +456,16 @@ impl<ID: Display + Debug, Σ: Symbol> Display for Schema<ID, Σ> {
 
 impl<ID: Debug, Σ: Symbol> Debug for Schema<ID, Σ> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    f.debug_struct("Schema").field("name", &self.name).field("definition_list", &self.defs).finish()
+    f.debug_struct("Schema")
+      .field("name", &self.name)
+      .field("definition_list", &self.defs)
+      .field("recoveries", &self.recoveries)
+      .field("max_lengths", &self.max_lengths)
+      .field("sensitives", &self.sensitives)
+      .field("reinterpretations", &self.reinterpretations.keys().collect::<Vec<_>>())
+      .field("left_recursions", &self.left_recursions)
+      .field("bindings", &self.bindings)
+      .finish()
   }
 }
 
@@ -133,6 +517,79 @@ pub trait Location<Σ: Symbol>: Default + Copy + Display + Debug + Ord + Partial
       self.increment_with(*item);
     }
   }
+
+  /// Move to the beginning of `next`, the symbol at this location. It's called before this location is reported for
+  /// an error or an event that starts with `next`, for the locations that don't know where the next symbol begins
+  /// from the preceding ones, such as [`spanned::Location`].
+  ///
+  fn seek(&mut self, next: &Σ) {
+    let _ = next;
+  }
+
+  /// The byte offset in the source, if the location knows it.
+  ///
+  fn byte_offset(&self) -> Option<u64> {
+    None
+  }
+
+  /// The line starting from 0, if the source consists of lines.
+  ///
+  fn line(&self) -> Option<u64> {
+    None
+  }
+
+  /// The column starting from 0, if the source consists of lines.
+  ///
+  fn column(&self) -> Option<u64> {
+    None
+  }
+
+  /// Format this location in `style`. A style that this location doesn't support falls back to [`Display`].
+  ///
+  fn to_string_with(&self, style: LocationStyle) -> String {
+    match (style, self.line(), self.column(), self.byte_offset()) {
+      (LocationStyle::Position, ..) => self.position().to_string(),
+      (LocationStyle::LineColumn, Some(line), Some(column), _) => format!("{}:{}", line + 1, column + 1),
+      (LocationStyle::ByteOffset, _, _, Some(offset)) => format!("@{}", offset),
+      _ => self.to_string(),
+    }
+  }
+}
+
+/// The format of a location in [`Location::to_string_with()`].
+///
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LocationStyle {
+  /// The [`Display`] of the location.
+  #[default]
+  Display,
+  /// The number of symbols before the location.
+  Position,
+  /// `line:column` starting from 1, as editors and compilers report.
+  LineColumn,
+  /// `@offset` in bytes.
+  ByteOffset,
+}
+
+/// The error of importing a grammar written in another notation, such as [`pest::import()`] or
+/// [`antlr::import_lexer()`].
+///
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+#[error("{location} {message}")]
+pub struct ImportError {
+  /// The location in the grammar where the error was detected.
+  pub location: chars::Location,
+  pub message: String,
+}
+
+impl ImportError {
+  /// The error at the `pos`-th character of the `grammar`.
+  ///
+  pub(crate) fn at(grammar: &[char], pos: usize, message: &str) -> Self {
+    let mut location = chars::Location::default();
+    location.increment_with_seq(&grammar[..std::cmp::min(pos, grammar.len())]);
+    ImportError { location, message: message.to_string() }
+  }
 }
 
 // ---------------------------------
@@ -142,11 +599,83 @@ pub struct Syntax<ID, Σ: Symbol> {
   pub location: Option<Σ::Location>,
   pub(crate) repetition: RangeInclusive<usize>,
   pub(crate) primary: Primary<ID, Σ>,
+  /// The profiles in which this syntax appears, or all profiles if empty.
+  pub(crate) profiles: Vec<String>,
+}
+
+/// The clone shares the matchers of the terms with the original, so that a sub-expression can be reused in several
+/// definitions such as `define(A, hex.clone() & hex)`. The clone isn't defined in any schema, so its `id` is reset.
+///
+impl<ID: Clone, Σ: Symbol> Clone for Syntax<ID, Σ> {
+  fn clone(&self) -> Self {
+    Self {
+      id: 0,
+      location: self.location,
+      repetition: self.repetition.clone(),
+      primary: self.primary.clone(),
+      profiles: self.profiles.clone(),
+    }
+  }
 }
 
+/// The number of the symbols that a reinterpreted matcher is first passed by [`Syntax::reinterpret()`], which is
+/// doubled while the matcher can accept more.
+const REINTERPRET_PREFIX: usize = 16;
+
 impl<ID, Σ: 'static + Symbol> Syntax<ID, Σ> {
+  fn map_ids<'a, J, F: FnMut(&'a ID) -> J>(&'a self, f: &mut F) -> Syntax<J, Σ> {
+    let primary = match &self.primary {
+      Primary::Term(term) => Primary::Term(term.clone()),
+      Primary::Alias(id) => Primary::Alias(f(id)),
+      Primary::Seq(seq) => Primary::Seq(seq.iter().map(|s| s.map_ids(f)).collect()),
+      Primary::Or(branches) => Primary::Or(branches.iter().map(|s| s.map_ids(f)).collect()),
+    };
+    Syntax {
+      id: self.id,
+      location: self.location,
+      repetition: self.repetition.clone(),
+      primary,
+      profiles: self.profiles.clone(),
+    }
+  }
+
+  /// Wrap the matchers of the terms in this syntax to evaluate the symbols mapped by `f`, replacing the mapping of
+  /// the previous reinterpretation if any.
+  ///
+  /// The symbols are mapped lazily: the matcher is first passed a short prefix of them as if more might follow, and
+  /// the prefix is doubled only while the matcher can accept more, so that a term without a window doesn't map the
+  /// whole buffer on every evaluation.
+  ///
+  fn reinterpret(&mut self, f: &Arc<Reinterpretation<Σ>>) {
+    match &mut self.primary {
+      Primary::Term(term) => {
+        let matcher = term.original.get_or_insert_with(|| term.matcher.clone()).clone();
+        let f = f.clone();
+        term.matcher = Arc::new(move |values: &[Σ], ctx: &MatchCtx<Σ>| {
+          let mut mapped = Vec::with_capacity(std::cmp::min(values.len(), REINTERPRET_PREFIX));
+          loop {
+            let len = std::cmp::min(values.len(), std::cmp::max(REINTERPRET_PREFIX, mapped.len() * 2));
+            mapped.extend(values[mapped.len()..len].iter().map(|value| f(*value)));
+            let eof = ctx.eof && len == values.len();
+            let result = matcher(&mapped, &MatchCtx { eof, ..*ctx })?;
+            let more = matches!(
+              result,
+              MatchResult::MatchAndCanAcceptMore(_) | MatchResult::UnmatchAndCanAcceptMore | MatchResult::Partial(_)
+            );
+            if !more || len == values.len() {
+              return Ok(result);
+            }
+          }
+        });
+        term.signature = None;
+      }
+      Primary::Alias(_) => (),
+      Primary::Seq(seq) | Primary::Or(seq) => seq.iter_mut().for_each(|s| s.reinterpret(f)),
+    }
+  }
+
   fn with_primary(primary: Primary<ID, Σ>) -> Self {
-    Self { id: 0, location: None, primary, repetition: 1..=1 }
+    Self { id: 0, location: None, primary, repetition: 1..=1, profiles: vec![] }
   }
 
   pub fn from_id(id: ID) -> Self {
@@ -154,78 +683,199 @@ impl<ID, Σ: 'static + Symbol> Syntax<ID, Σ> {
   }
 
   pub fn from_fn<FN: Fn(&[Σ]) -> Result<Σ, MatchResult> + Send + Sync + 'static>(label: &str, f: FN) -> Self {
-    Syntax::with_primary(Primary::Term(label.to_string(), Box::new(f)))
+    Syntax::from_fn_ctx(label, move |values: &[Σ], _: &MatchCtx<Σ>| f(values))
+  }
+
+  /// Create a term whose matcher also receives the [`MatchCtx`] of the evaluation, such as whether the input has
+  /// ended or the absolute position at which `values` begin. This is an escape hatch for the matchers that depend on
+  /// the surroundings, such as the indentation or a length prefix.
+  ///
+  pub fn from_fn_ctx<FN: Fn(&[Σ], &MatchCtx<Σ>) -> Result<Σ, MatchResult> + Send + Sync + 'static>(
+    label: &str, f: FN,
+  ) -> Self {
+    let term = Term { label: label.to_string(), matcher: Arc::new(f), original: None, window: None, signature: None };
+    Syntax::with_primary(Primary::Term(term))
+  }
+
+  /// Limit the symbols passed to the matcher of this term to at most `window`, which is enough for the matcher to
+  /// decide whether it matches, such as the length of a fixed-size literal. Otherwise, a matcher is passed all of the
+  /// symbols buffered after its position.
+  ///
+  /// # Panics
+  ///
+  /// If this syntax isn't a term created by [`from_fn()`](Self::from_fn) or [`from_fn_ctx()`](Self::from_fn_ctx).
+  ///
+  pub fn with_window(mut self, window: usize) -> Self {
+    match &mut self.primary {
+      Primary::Term(term) => term.window = Some(window),
+      _ => panic!("the window is only available for terms"),
+    }
+    self
+  }
+
+  /// Identify the behaviour of this term by `signature`, so that the terms with the same signature share a single
+  /// matcher in a [`Schema`]. The built-in terms such as [`single()`] or [`seq()`] are identified by their arguments.
+  ///
+  pub(crate) fn with_signature(mut self, signature: String) -> Self {
+    if let Primary::Term(term) = &mut self.primary {
+      term.signature = Some(signature);
+    }
+    self
   }
 
   pub fn repetition(&self) -> &RangeInclusive<usize> {
     &self.repetition
   }
 
+  /// Whether this branch begins with `id` just once, followed by at least one syntax.
+  ///
+  pub(crate) fn is_left_recursive_on(&self, id: &ID) -> bool
+  where
+    ID: PartialEq,
+  {
+    matches!(&self.primary, Primary::Seq(seq) if seq.len() > 1 && seq[0].repetition == (1..=1)) && self.begins_with(id)
+  }
+
+  pub(crate) fn begins_with(&self, id: &ID) -> bool
+  where
+    ID: PartialEq,
+  {
+    matches!(&self.primary, Primary::Seq(seq) if matches!(&seq[0].primary, Primary::Alias(head) if head == id))
+  }
+
+  /// The sequence of this syntax followed by `rhs`, which is flattened into the non-repeated sequence on either side.
+  /// A repeated sequence is kept as an element, so `a* & (b & c)*` is `a*, (b, c)*` rather than `(a*, b, c)*`.
+  ///
   pub fn and(self, rhs: Syntax<ID, Σ>) -> Self {
-    let Syntax { id: l_id, primary: l_arm, repetition: l_range, location: l_location } = self;
-    let Syntax { id: r_id, primary: r_arm, repetition: r_range, location: r_location } = rhs;
+    let Syntax { id: l_id, primary: l_arm, repetition: l_range, location: l_location, profiles: l_profiles } = self;
+    let Syntax { id: r_id, primary: r_arm, repetition: r_range, location: r_location, profiles: r_profiles } = rhs;
     debug_assert!(l_id == 0 && r_id == 0);
+    // the repeated sequences and the sequences tagged with profiles are never merged
+    let l_open = l_profiles.is_empty() && l_range == (1..=1);
+    let r_open = r_profiles.is_empty() && r_range == (1..=1);
     match (l_arm, r_arm) {
-      (Primary::Seq(mut lhs), Primary::Seq(mut rhs)) if l_range == r_range => {
+      (Primary::Seq(mut lhs), Primary::Seq(mut rhs)) if l_open && r_open => {
         lhs.append(&mut rhs);
         let arm = Primary::Seq(lhs);
-        Syntax { id: 0, location: l_location, primary: arm, repetition: l_range }
+        Syntax { id: 0, location: l_location, primary: arm, repetition: l_range, profiles: vec![] }
       }
-      (Primary::Seq(mut lhs), rhs) if l_range == r_range => {
-        lhs.push(Syntax { id: 0, primary: rhs, repetition: r_range, location: r_location });
+      (Primary::Seq(mut lhs), rhs) if l_open => {
+        lhs.push(Syntax { id: 0, primary: rhs, repetition: r_range, location: r_location, profiles: r_profiles });
         let arm = Primary::Seq(lhs);
-        Syntax { id: 0, location: l_location, primary: arm, repetition: l_range }
+        Syntax { id: 0, location: l_location, primary: arm, repetition: l_range, profiles: vec![] }
       }
-      (lhs, Primary::Seq(mut rhs)) if l_range == r_range => {
-        rhs.insert(0, Syntax { id: 0, primary: lhs, repetition: r_range, location: r_location });
+      (lhs, Primary::Seq(mut rhs)) if r_open => {
+        rhs.insert(0, Syntax { id: 0, primary: lhs, repetition: l_range, location: l_location, profiles: l_profiles });
         let arm = Primary::Seq(rhs);
-        Syntax { id: 0, location: l_location, primary: arm, repetition: l_range }
+        Syntax { id: 0, location: l_location, primary: arm, repetition: 1..=1, profiles: vec![] }
       }
       (lhs, rhs) => {
-        let lhs = Syntax { id: 0, primary: lhs, repetition: l_range, location: l_location };
-        let rhs = Syntax { id: 0, primary: rhs, repetition: r_range, location: r_location };
-        Syntax { id: 0, location: l_location, primary: Primary::Seq(vec![lhs, rhs]), repetition: 1..=1 }
+        let lhs = Syntax { id: 0, primary: lhs, repetition: l_range, location: l_location, profiles: l_profiles };
+        let rhs = Syntax { id: 0, primary: rhs, repetition: r_range, location: r_location, profiles: r_profiles };
+        Syntax {
+          id: 0,
+          location: l_location,
+          primary: Primary::Seq(vec![lhs, rhs]),
+          repetition: 1..=1,
+          profiles: vec![],
+        }
       }
     }
   }
 
+  /// The choice between this syntax and `rhs`, which is flattened into the non-repeated choice on either side. A
+  /// repeated choice is kept as a branch, so `a* | (b | c)*` is `a* | (b | c)*` rather than `(a* | b | c)*`.
+  ///
   pub fn or(self, rhs: Syntax<ID, Σ>) -> Self {
-    let Syntax { id: l_id, primary: l_arm, repetition: l_range, location: l_location } = self;
-    let Syntax { id: r_id, primary: r_arm, repetition: r_range, location: r_location } = rhs;
+    let Syntax { id: l_id, primary: l_arm, repetition: l_range, location: l_location, profiles: l_profiles } = self;
+    let Syntax { id: r_id, primary: r_arm, repetition: r_range, location: r_location, profiles: r_profiles } = rhs;
     debug_assert!(l_id == 0 && r_id == 0);
+    let l_open = l_profiles.is_empty() && l_range == (1..=1);
+    let r_open = r_profiles.is_empty() && r_range == (1..=1);
     match (l_arm, r_arm) {
-      (Primary::Or(mut lhs), Primary::Or(mut rhs)) if l_range == r_range => {
+      (Primary::Or(mut lhs), Primary::Or(mut rhs)) if l_open && r_open => {
         lhs.append(&mut rhs);
         let arm = Primary::Or(lhs);
-        Syntax { id: 0, primary: arm, repetition: l_range, location: l_location }
+        Syntax { id: 0, primary: arm, repetition: l_range, location: l_location, profiles: vec![] }
       }
-      (Primary::Or(mut lhs), rhs) if l_range == r_range => {
-        lhs.push(Syntax { id: 0, primary: rhs, repetition: r_range, location: r_location }.conv_to_non_repeating_seq());
+      (Primary::Or(mut lhs), rhs) if l_open => {
+        let rhs = Syntax { id: 0, primary: rhs, repetition: r_range, location: r_location, profiles: r_profiles };
+        lhs.push(rhs.conv_to_non_repeating_seq());
         let arm = Primary::Or(lhs);
-        Syntax { id: 0, primary: arm, repetition: l_range, location: l_location }
+        Syntax { id: 0, primary: arm, repetition: l_range, location: l_location, profiles: vec![] }
       }
-      (lhs, Primary::Or(mut rhs)) if l_range == r_range => {
-        rhs.insert(
-          0,
-          Syntax { id: 0, primary: lhs, repetition: r_range, location: r_location }.conv_to_non_repeating_seq(),
-        );
+      (lhs, Primary::Or(mut rhs)) if r_open => {
+        let lhs = Syntax { id: 0, primary: lhs, repetition: l_range, location: l_location, profiles: l_profiles };
+        rhs.insert(0, lhs.conv_to_non_repeating_seq());
         let arm = Primary::Or(rhs);
-        Syntax { id: 0, primary: arm, repetition: l_range, location: l_location }
+        Syntax { id: 0, primary: arm, repetition: 1..=1, location: l_location, profiles: vec![] }
       }
       (lhs, rhs) => {
-        let lhs = Syntax { id: 0, primary: lhs, repetition: l_range, location: l_location }.conv_to_non_repeating_seq();
-        let rhs = Syntax { id: 0, primary: rhs, repetition: r_range, location: r_location }.conv_to_non_repeating_seq();
-        Syntax { id: 0, primary: Primary::Or(vec![lhs, rhs]), repetition: 1..=1, location: l_location }
+        let lhs = Syntax { id: 0, primary: lhs, repetition: l_range, location: l_location, profiles: l_profiles };
+        let rhs = Syntax { id: 0, primary: rhs, repetition: r_range, location: r_location, profiles: r_profiles };
+        let arm = Primary::Or(vec![lhs.conv_to_non_repeating_seq(), rhs.conv_to_non_repeating_seq()]);
+        Syntax { id: 0, primary: arm, repetition: 1..=1, location: l_location, profiles: vec![] }
       }
     }
   }
 
   pub fn reps(self, reps: RangeInclusive<usize>) -> Self {
-    let Syntax { id, primary, repetition: range, location } = self;
+    let Syntax { id, primary, repetition: range, location, profiles } = self;
     debug_assert_eq!(0, id);
-    let min = *range.start() * reps.start();
-    let max = *range.end() * reps.end();
-    Syntax { id: 0, primary, repetition: RangeInclusive::new(min, max), location }
+    let min = range.start().saturating_mul(*reps.start());
+    let max = range.end().saturating_mul(*reps.end());
+    Syntax { id: 0, primary, repetition: RangeInclusive::new(min, max), location, profiles }
+  }
+
+  /// Zero or one appearance of this syntax, the same as `self * (0..=1)`.
+  ///
+  pub fn opt(self) -> Self {
+    self.reps(0..=1)
+  }
+
+  /// Zero or more appearances of this syntax, the same as `self * (0..)`.
+  ///
+  pub fn star(self) -> Self {
+    self.reps(0..=usize::MAX)
+  }
+
+  /// One or more appearances of this syntax, the same as `self * (1..)`.
+  ///
+  pub fn plus(self) -> Self {
+    self.reps(1..=usize::MAX)
+  }
+
+  /// Tag this syntax so that it only appears in the schemas of the `profile`, such as a trailing comma only accepted
+  /// by a lenient grammar, see [`Schema::with_profile()`]. Tagging it again with another profile makes it appear in
+  /// either of them.
+  ///
+  pub fn profile(self, profile: &str) -> Self {
+    let mut syntax = if self.profiles.is_empty() {
+      let location = self.location;
+      Syntax { id: 0, repetition: 1..=1, primary: Primary::Seq(vec![self]), location, profiles: vec![] }
+    } else {
+      self
+    };
+    syntax.profiles.push(profile.to_string());
+    syntax
+  }
+
+  /// Remove the syntaxes tagged with profiles other than `profile`. A sequence or a choice is also removed if all of
+  /// its elements are removed.
+  ///
+  fn select_profile(mut self, profile: &str) -> Option<Self> {
+    if !self.profiles.is_empty() && !self.profiles.iter().any(|p| p == profile) {
+      return None;
+    }
+    self.primary = match self.primary {
+      Primary::Seq(seq) => Primary::Seq(seq.into_iter().filter_map(|s| s.select_profile(profile)).collect()),
+      Primary::Or(branches) => Primary::Or(branches.into_iter().filter_map(|s| s.select_profile(profile)).collect()),
+      primary => primary,
+    };
+    match &self.primary {
+      Primary::Seq(items) | Primary::Or(items) if items.is_empty() => None,
+      _ => Some(self),
+    }
   }
 
   fn conv_to_non_repeating_seq(self) -> Self {
@@ -233,7 +883,7 @@ impl<ID, Σ: 'static + Symbol> Syntax<ID, Σ> {
       self
     } else {
       let location = self.location;
-      Syntax { id: 0, repetition: 1..=1, primary: Primary::Seq(vec![self]), location }
+      Syntax { id: 0, repetition: 1..=1, primary: Primary::Seq(vec![self]), location, profiles: vec![] }
     }
   }
 }
@@ -358,20 +1008,75 @@ impl<ID: Debug, Σ: 'static + Symbol> Mul<RangeToInclusive<usize>> for Syntax<ID
 pub(crate) const OP_CONCAT: &str = ",";
 pub(crate) const OP_CHOICE: &str = " |";
 
-pub type Matcher<Σ> = dyn Fn(&[Σ]) -> Result<Σ, MatchResult> + Send + Sync;
+pub type Matcher<Σ> = dyn Fn(&[Σ], &MatchCtx<Σ>) -> Result<Σ, MatchResult> + Send + Sync;
+
+/// The mapping of the symbols specified by [`Schema::reinterpret()`].
+///
+pub type Reinterpretation<Σ> = dyn Fn(Σ) -> Σ + Send + Sync;
+
+/// The context in which a [`Matcher`] evaluates the symbols.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatchCtx<Σ: Symbol> {
+  /// Whether the input has ended, so that the symbols passed to the matcher are all that remain.
+  pub eof: bool,
+  /// The position of the first symbol passed to the matcher from the beginning of the input.
+  pub absolute_offset: u64,
+  /// The location of the first symbol passed to the matcher.
+  pub location: Σ::Location,
+  /// The number of the leading symbols that the matcher reported as matched by [`MatchResult::Partial`] in the
+  /// previous evaluation at the same position, or 0.
+  pub matched: usize,
+}
+
+impl<Σ: Symbol> MatchCtx<Σ> {
+  pub fn new(eof: bool, location: Σ::Location) -> Self {
+    Self { eof, absolute_offset: location.position(), location, matched: 0 }
+  }
+}
 
+impl<Σ: Symbol> Default for MatchCtx<Σ> {
+  fn default() -> Self {
+    Self::new(false, Σ::Location::default())
+  }
+}
+
+#[derive(Clone)]
+pub(crate) struct Term<Σ: Symbol> {
+  pub label: String,
+  pub matcher: Arc<Matcher<Σ>>,
+  /// The matcher before it was wrapped by [`Schema::reinterpret()`], which a later reinterpretation wraps instead.
+  pub original: Option<Arc<Matcher<Σ>>>,
+  /// The maximum number of symbols that the matcher needs, if known.
+  pub window: Option<usize>,
+  /// The identity of the behaviour of the matcher, if known, by which the identical terms are interned.
+  pub signature: Option<String>,
+}
+
+#[derive(Clone)]
 pub(crate) enum Primary<ID, Σ: Symbol> {
-  Term(String, Box<Matcher<Σ>>),
+  Term(Term<Σ>),
   /// This corresponds to the so-called non-terminal character.
   Alias(ID),
   Seq(Vec<Syntax<ID, Σ>>),
   Or(Vec<Syntax<ID, Σ>>),
 }
 
+impl<ID, Σ: Symbol> Primary<ID, Σ> {
+  /// The leading symbols of `values` within the window of this term, or `values` itself if it has no window.
+  ///
+  pub(crate) fn window<'a>(&self, values: &'a [Σ]) -> &'a [Σ] {
+    match self {
+      Primary::Term(Term { window: Some(window), .. }) => &values[..std::cmp::min(*window, values.len())],
+      _ => values,
+    }
+  }
+}
+
 impl<ID: Display + Debug, Σ: Symbol> Display for Primary<ID, Σ> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
-      Primary::Term(name, ..) => Display::fmt(name, f),
+      Primary::Term(term) => Display::fmt(&term.label, f),
       Primary::Alias(id) => Display::fmt(id, f),
       Primary::Seq(terms) => display(f, terms, OP_CONCAT),
       Primary::Or(terms) => display(f, terms, OP_CHOICE),
@@ -394,7 +1099,7 @@ where
 impl<ID: Debug, Σ: Symbol> Debug for Primary<ID, Σ> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
-      Self::Term(name, ..) => f.debug_tuple("Term").field(name).finish(),
+      Self::Term(term) => f.debug_tuple("Term").field(&term.label).finish(),
       Self::Alias(id) => f.debug_tuple("Alias").field(id).finish(),
       Self::Seq(seq) => f.debug_tuple("Seq").field(seq).finish(),
       Self::Or(branches) => f.debug_tuple("Or").field(branches).finish(),
@@ -410,6 +1115,10 @@ pub enum MatchResult {
   Unmatch,
   MatchAndCanAcceptMore(usize),
   UnmatchAndCanAcceptMore,
+  /// The same as [`UnmatchAndCanAcceptMore`](Self::UnmatchAndCanAcceptMore), but the leading symbols of the given
+  /// length have already matched. They're passed to the next evaluation at the same position as
+  /// [`MatchCtx::matched`], so that a long literal can resume its comparison where it left off.
+  Partial(usize),
 }
 
 impl MatchResult {
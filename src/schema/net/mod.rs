@@ -0,0 +1,100 @@
+use crate::schema::chars::{ch, token};
+use crate::schema::{id, MatchResult, Schema, Syntax};
+use crate::Result;
+use std::fmt::Display;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ID {
+  Address,
+  Cidr,
+  IPv4,
+  IPv6,
+  DecOctet,
+  H16,
+  IPv4Prefix,
+  IPv6Prefix,
+}
+
+impl Display for ID {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// The IP addresses and the CIDR notations according to the ABNF of
+/// [RFC 3986 Section 3.2.2](https://www.rfc-editor.org/rfc/rfc3986#section-3.2.2) and
+/// [RFC 4632](https://www.rfc-editor.org/rfc/rfc4632).
+///
+/// - [`ID::IPv4`]: four [`ID::DecOctet`]s within `0..=255` without leading zeros, separated by `.`.
+/// - [`ID::IPv6`]: eight [`ID::H16`]s of 1 to 4 hexadecimal digits separated by `:`, where the consecutive groups of
+///   zeros may be compressed into `::` and the last two groups may be written as an IPv4 address.
+/// - [`ID::Cidr`]: an address followed by `/` and a prefix length within `0..=32` for IPv4 or `0..=128` for IPv6.
+///
+/// Since the numeric ranges are validated while parsing, an error such as `192.168.0.256` is reported at the position
+/// of the octet out of range.
+///
+pub fn schema() -> Schema<ID, char> {
+  let h16 = || id(ID::H16);
+  let h16c = || id(ID::H16) & ch(':');
+  let ls32 = || (h16() & ch(':') & h16()) | id(ID::IPv4);
+  // [ *n( h16 ":" ) h16 ] "::"
+  let compressed = |n: usize| {
+    let prefix = if n == 0 { h16() } else { (h16c() * (0..=n)) & h16() };
+    (prefix * (0..=1)) & token("::")
+  };
+  let ipv6 = ((h16c() * (6..=6)) & ls32())
+    | (token("::") & (h16c() * (5..=5)) & ls32())
+    | (compressed(0) & (h16c() * (4..=4)) & ls32())
+    | (compressed(1) & (h16c() * (3..=3)) & ls32())
+    | (compressed(2) & (h16c() * (2..=2)) & ls32())
+    | (compressed(3) & h16c() & ls32())
+    | (compressed(4) & ls32())
+    | (compressed(5) & h16())
+    | compressed(6);
+  let octet = || id(ID::DecOctet);
+  Schema::new("NET")
+    .define(ID::Address, id(ID::IPv4) | id(ID::IPv6))
+    .define(ID::Cidr, (id(ID::IPv4) & ch('/') & id(ID::IPv4Prefix)) | (id(ID::IPv6) & ch('/') & id(ID::IPv6Prefix)))
+    .define(ID::IPv4, octet() & ch('.') & octet() & ch('.') & octet() & ch('.') & octet())
+    .define(ID::IPv6, ipv6)
+    .define(ID::DecOctet, decimal(255))
+    .define(ID::H16, hex16())
+    .define(ID::IPv4Prefix, decimal(32))
+    .define(ID::IPv6Prefix, decimal(128))
+}
+
+/// A decimal number within `0..=max` without leading zeros.
+///
+fn decimal<ID>(max: u32) -> Syntax<ID, char> {
+  let label = format!("0..={}", max);
+  Syntax::from_fn(&label, move |values: &[char]| -> Result<char, MatchResult> {
+    let length = values.iter().position(|ch| !ch.is_ascii_digit());
+    let digits = &values[..length.unwrap_or(values.len())];
+    let valid = !digits.is_empty()
+      && (digits.len() == 1 || digits[0] != '0')
+      && digits.iter().collect::<String>().parse::<u32>().map(|n| n <= max).unwrap_or(false);
+    Ok(match length {
+      None if valid => MatchResult::MatchAndCanAcceptMore(values.len()),
+      None if digits.is_empty() => MatchResult::UnmatchAndCanAcceptMore,
+      Some(length) if valid => MatchResult::Match(length),
+      _ => MatchResult::Unmatch,
+    })
+  })
+}
+
+/// 1 to 4 hexadecimal digits.
+///
+fn hex16<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("H16", |values: &[char]| -> Result<char, MatchResult> {
+    let length = values.iter().position(|ch| !ch.is_ascii_hexdigit());
+    Ok(match length {
+      None if values.is_empty() => MatchResult::UnmatchAndCanAcceptMore,
+      None if values.len() <= 4 => MatchResult::MatchAndCanAcceptMore(values.len()),
+      Some(length) if (1..=4).contains(&length) => MatchResult::Match(length),
+      _ => MatchResult::Unmatch,
+    })
+  })
+}
@@ -0,0 +1,119 @@
+use super::{schema, ID};
+use crate::parser::{test::Events, Context, Event};
+use crate::schema::chars::Location;
+use crate::Error;
+
+#[test]
+fn ipv4() {
+  for addr in ["0.0.0.0", "127.0.0.1", "192.168.10.255", "255.255.255.255"] {
+    assert!(parse(ID::IPv4, addr).is_ok(), "{:?}", addr);
+  }
+  for addr in ["", "1.2.3", "1.2.3.4.5", "256.0.0.0", "01.2.3.4", "1.2.3.", "1..2.3", "a.b.c.d", " 1.2.3.4"] {
+    assert!(parse(ID::IPv4, addr).is_err(), "{:?}", addr);
+  }
+}
+
+#[test]
+fn ipv6() {
+  for addr in [
+    "1:2:3:4:5:6:7:8",
+    "2001:db8:0:0:0:0:2:1",
+    "2001:DB8::2:1",
+    "::",
+    "::1",
+    "fe80::",
+    "1::8",
+    "1:2:3:4:5:6:7::",
+    "::2:3:4:5:6:7:8",
+    "1:2:3::6:7:8",
+    "::ffff:192.0.2.128",
+    "64:ff9b::192.0.2.33",
+    "1:2:3:4:5:6:1.2.3.4",
+  ] {
+    assert!(parse(ID::IPv6, addr).is_ok(), "{:?}", addr);
+  }
+  for addr in [
+    "",
+    ":",
+    ":::",
+    "1:2:3:4:5:6:7",
+    "1:2:3:4:5:6:7:8:9",
+    "1::2::3",
+    "12345::",
+    "::g",
+    "1:2:3:4:5:6:7:8::",
+    "::1.2.3.256",
+    "1:2:3:4:5:6:7:1.2.3.4",
+  ] {
+    assert!(parse(ID::IPv6, addr).is_err(), "{:?}", addr);
+  }
+}
+
+#[test]
+fn cidr() {
+  for cidr in ["10.0.0.0/8", "0.0.0.0/0", "192.168.0.0/32", "2001:db8::/32", "::/0", "::1/128"] {
+    assert!(parse(ID::Cidr, cidr).is_ok(), "{:?}", cidr);
+  }
+  for cidr in ["10.0.0.0", "10.0.0.0/", "10.0.0.0/33", "10.0.0.0/08", "::/129", "::/-1"] {
+    assert!(parse(ID::Cidr, cidr).is_err(), "{:?}", cidr);
+  }
+}
+
+#[test]
+fn events() {
+  let events = parse(ID::Cidr, "::ffff:10.0.0.1/104").unwrap();
+  Events::new()
+    .begin(ID::Cidr)
+    .begin(ID::IPv6)
+    .fragments("::")
+    .begin(ID::H16)
+    .fragments("ffff")
+    .end()
+    .fragments(":")
+    .begin(ID::IPv4)
+    .begin(ID::DecOctet)
+    .fragments("10")
+    .end()
+    .fragments(".")
+    .begin(ID::DecOctet)
+    .fragments("0")
+    .end()
+    .fragments(".")
+    .begin(ID::DecOctet)
+    .fragments("0")
+    .end()
+    .fragments(".")
+    .begin(ID::DecOctet)
+    .fragments("1")
+    .end()
+    .end()
+    .end()
+    .fragments("/")
+    .begin(ID::IPv6Prefix)
+    .fragments("104")
+    .end()
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn error_position_of_out_of_range_value() {
+  for (text, position) in [("192.168.0.256", 10), ("2001:db8::12345", 10), ("10.0.0.0/33", 9)] {
+    match parse(ID::Cidr, text) {
+      Err(Error::Unmatched(detail)) => {
+        assert_eq!(Location::new(position, 0, position), detail.location, "{:?}", text)
+      }
+      other => panic!("{:?}: {:?}", text, other),
+    }
+  }
+}
+
+fn parse(id: ID, text: &str) -> crate::Result<char, Vec<Event<ID, char>>> {
+  let schema = schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, id, handler)?;
+  parser.push_str(text)?;
+  parser.finish()?;
+  Ok(Event::normalize(&events))
+}
@@ -0,0 +1,659 @@
+//! Import of the [pest](https://pest.rs/) grammars, so that an existing `.pest` file can be parsed by the streaming
+//! parser of terp. Each rule of the grammar becomes a syntax of the same name.
+//!
+//! ```rust
+//! use terp::parser::{Context, Event};
+//! use terp::schema::pest;
+//!
+//! let grammar = pest::import("CSV", r#"
+//!   file = { SOI ~ (record ~ ("\r\n" | "\n"))* ~ EOI }
+//!   record = { field ~ ("," ~ field)* }
+//!   field = { (ASCII_DIGIT | ".")+ }
+//! "#).unwrap();
+//!
+//! let mut parser = Context::new(&grammar.schema, "file".to_string(), |_: &Event<_, _>| ()).unwrap();
+//! parser.push_str("1,2.5\n3,4\n").unwrap();
+//! parser.finish().unwrap();
+//! ```
+//!
+//! The semantics of pest are imported as follows:
+//!
+//! * The ordered choice `|` becomes [`Syntax::or()`], which evaluates all the branches rather than taking the first
+//!   branch that matches, so an ambiguous grammar reports [`Error::MultipleMatches`](crate::Error::MultipleMatches).
+//! * The implicit `WHITESPACE` and `COMMENT` are inserted between the terms of the non-atomic rules. A rule that isn't
+//!   atomic by itself is treated as atomic only if it's referenced by the atomic rules alone.
+//! * The silent rules `_{ ... }` are listed in [`Grammar::silent`], to be passed to
+//!   [`Context::ignore_events_for()`](crate::parser::Context::ignore_events_for).
+//! * `SOI` matches nothing and `EOI` becomes [`eof()`]. The negative lookahead is only available as `!x ~ ANY` of
+//!   the characters `x`, or `(!"..." ~ ANY)*` that becomes [`until_token()`]. The other lookaheads, the stack
+//!   operations such as `PUSH` and the Unicode properties are reported as [`ImportError`]s.
+//!
+use crate::schema::chars::{
+  ascii_alphabetic, ascii_digit, ascii_lower_alphabetic, ascii_upper_alphabetic, ch, newline, none_of_chars, token,
+  until_token,
+};
+use crate::schema::{any_of_ranges_with_label, eof, id, one_of, range, ImportError, MatchResult, Schema, Syntax};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(test)]
+mod test;
+
+/// The schema imported from a pest grammar.
+///
+pub struct Grammar {
+  pub schema: Schema<String, char>,
+  /// The silent rules, whose events pest doesn't produce.
+  pub silent: Vec<String>,
+}
+
+/// Import the pest `grammar` as a schema named `name`.
+///
+pub fn import(name: &str, grammar: &str) -> Result<Grammar, ImportError> {
+  let chars = grammar.chars().collect::<Vec<_>>();
+  let rules = Parser { chars: &chars, pos: 0 }.rules()?;
+  let defined = rules.iter().map(|rule| rule.name.clone()).collect::<BTreeSet<_>>();
+  let atomics = atomic_rules(&rules);
+  let skip = ["WHITESPACE", "COMMENT"].into_iter().filter(|name| defined.contains(*name)).collect::<Vec<_>>();
+
+  let mut schema = Schema::new(name);
+  let mut silent = Vec::new();
+  for rule in &rules {
+    let skip = if atomics.contains(&rule.name) { &[][..] } else { &skip[..] };
+    let converter = Converter { chars: &chars, defined: &defined, skip };
+    let syntax = match converter.expr(&rule.expr)? {
+      Some(syntax) => syntax,
+      None => return Err(converter.error(&rule.expr, "the rule matches only an empty sequence")),
+    };
+    schema = schema.define(rule.name.clone(), syntax);
+    if rule.modifier == Some('_') {
+      silent.push(rule.name.clone());
+    }
+  }
+  Ok(Grammar { schema, silent })
+}
+
+/// The rules that are atomic by themselves, the implicit `WHITESPACE` and `COMMENT`, and the rules referenced by the
+/// atomic rules alone.
+///
+fn atomic_rules(rules: &[Rule]) -> BTreeSet<String> {
+  let mut atomics = rules
+    .iter()
+    .filter(|rule| matches!(rule.modifier, Some('@' | '$')) || rule.name == "WHITESPACE" || rule.name == "COMMENT")
+    .map(|rule| rule.name.clone())
+    .collect::<BTreeSet<_>>();
+  let mut referrers = BTreeMap::<&str, BTreeSet<&str>>::new();
+  for rule in rules {
+    rule.expr.visit_idents(&mut |ident| {
+      referrers.entry(ident).or_default().insert(&rule.name);
+    });
+  }
+  loop {
+    let found = rules
+      .iter()
+      .filter(|rule| rule.modifier != Some('!') && !atomics.contains(&rule.name))
+      .filter(|rule| match referrers.get(rule.name.as_str()) {
+        Some(referrers) => referrers.iter().all(|referrer| atomics.contains(*referrer)),
+        None => false,
+      })
+      .map(|rule| rule.name.clone())
+      .collect::<Vec<_>>();
+    if found.is_empty() {
+      return atomics;
+    }
+    atomics.extend(found);
+  }
+}
+
+// ---------------------------------
+
+struct Rule {
+  name: String,
+  /// One of `_`, `@`, `$` and `!`, if specified.
+  modifier: Option<char>,
+  expr: Expr,
+}
+
+struct Expr {
+  /// The position of the first character of this expression in the grammar.
+  pos: usize,
+  kind: ExprKind,
+}
+
+enum ExprKind {
+  /// The string, which is case-insensitive if the flag is true.
+  Str(String, bool),
+  Range(char, char),
+  Ident(String),
+  Seq(Vec<Expr>),
+  Choice(Vec<Expr>),
+  Repeat(Box<Expr>, usize, usize),
+  PositiveLookahead(Box<Expr>),
+  NegativeLookahead(Box<Expr>),
+}
+
+impl Expr {
+  fn visit_idents<'a, F: FnMut(&'a str)>(&'a self, f: &mut F) {
+    match &self.kind {
+      ExprKind::Ident(ident) => f(ident),
+      ExprKind::Seq(exprs) | ExprKind::Choice(exprs) => exprs.iter().for_each(|e| e.visit_idents(f)),
+      ExprKind::Repeat(e, ..) | ExprKind::PositiveLookahead(e) | ExprKind::NegativeLookahead(e) => e.visit_idents(f),
+      ExprKind::Str(..) | ExprKind::Range(..) => (),
+    }
+  }
+
+  /// The characters and the ranges of characters if this expression matches a single character of them.
+  ///
+  fn char_class(&self) -> Option<(String, Vec<(char, char)>)> {
+    match &self.kind {
+      ExprKind::Str(s, false) if s.chars().count() == 1 => Some((s.clone(), vec![])),
+      ExprKind::Range(begin, end) => Some((String::new(), vec![(*begin, *end)])),
+      ExprKind::Choice(branches) => {
+        let (mut chars, mut ranges) = (String::new(), Vec::new());
+        for branch in branches {
+          let (c, r) = branch.char_class()?;
+          chars.push_str(&c);
+          ranges.extend(r);
+        }
+        Some((chars, ranges))
+      }
+      _ => None,
+    }
+  }
+}
+
+// ---------------------------------
+
+struct Converter<'a> {
+  chars: &'a [char],
+  defined: &'a BTreeSet<String>,
+  /// The implicit rules inserted between the terms, which are empty in the atomic rules.
+  skip: &'a [&'a str],
+}
+
+impl Converter<'_> {
+  /// The syntax of `expr`, or `None` if it matches only an empty sequence such as `SOI`.
+  ///
+  fn expr(&self, expr: &Expr) -> Result<Option<Syntax<String, char>>, ImportError> {
+    let syntax = match &expr.kind {
+      ExprKind::Str(s, _) if s.is_empty() => return Ok(None),
+      ExprKind::Str(s, false) => token(s),
+      ExprKind::Str(s, true) => case_insensitive(s),
+      ExprKind::Range(begin, end) => range(*begin..=*end),
+      ExprKind::Ident(ident) => return self.ident(expr, ident),
+      ExprKind::Seq(exprs) => return self.seq(exprs),
+      ExprKind::Choice(branches) => {
+        let mut syntaxes = Vec::with_capacity(branches.len());
+        for branch in branches {
+          match self.expr(branch)? {
+            Some(syntax) => syntaxes.push(syntax),
+            None => return Err(self.error(branch, "an empty branch of the choice is not supported, use `?` instead")),
+          }
+        }
+        syntaxes.into_iter().reduce(|a, b| a | b).unwrap()
+      }
+      ExprKind::Repeat(e, min, max) => return self.repeat(e, *min, *max),
+      ExprKind::PositiveLookahead(_) => return Err(self.error(expr, "the positive lookahead `&` is not supported")),
+      ExprKind::NegativeLookahead(_) => {
+        return Err(self.error(expr, "the negative lookahead `!` is only supported as `!x ~ ANY`"));
+      }
+    };
+    Ok(Some(syntax))
+  }
+
+  fn ident(&self, expr: &Expr, ident: &str) -> Result<Option<Syntax<String, char>>, ImportError> {
+    if self.defined.contains(ident) {
+      return Ok(Some(id(ident.to_string())));
+    }
+    let syntax = match ident {
+      "SOI" => return Ok(None),
+      "EOI" => eof(),
+      "ANY" => none_of_chars(""),
+      "NEWLINE" => newline(),
+      "ASCII_DIGIT" => ascii_digit(),
+      "ASCII_NONZERO_DIGIT" => range('1'..='9'),
+      "ASCII_BIN_DIGIT" => range('0'..='1'),
+      "ASCII_OCT_DIGIT" => range('0'..='7'),
+      "ASCII_HEX_DIGIT" => any_of_ranges_with_label("ASCII_HEX_DIGIT", vec!['0'..='9', 'a'..='f', 'A'..='F']),
+      "ASCII_ALPHA_LOWER" => ascii_lower_alphabetic(),
+      "ASCII_ALPHA_UPPER" => ascii_upper_alphabetic(),
+      "ASCII_ALPHA" => ascii_alphabetic(),
+      "ASCII_ALPHANUMERIC" => any_of_ranges_with_label("ASCII_ALPHANUMERIC", vec!['0'..='9', 'a'..='z', 'A'..='Z']),
+      "ASCII" => range('\0'..='\x7F'),
+      "PUSH" | "POP" | "POP_ALL" | "PEEK" | "PEEK_ALL" | "DROP" => {
+        return Err(self.error(expr, &format!("the stack operation {} is not supported", ident)));
+      }
+      _ => return Err(self.error(expr, &format!("{} is not defined", ident))),
+    };
+    Ok(Some(syntax))
+  }
+
+  /// The sequence of `exprs`, where the implicit whitespaces are inserted at every `~`, even next to the terms that
+  /// match only an empty sequence such as `SOI ~ ...`. The whitespaces before an optional term are moved into it, so
+  /// that they don't overlap with the ones after the sequence when the term is absent.
+  ///
+  fn seq(&self, exprs: &[Expr]) -> Result<Option<Syntax<String, char>>, ImportError> {
+    let mut seq: Option<Syntax<String, char>> = None;
+    let mut junction = false;
+    let mut i = 0;
+    while i < exprs.len() {
+      junction |= i > 0;
+      let syntax = match (&exprs[i].kind, exprs.get(i + 1).map(|e| &e.kind)) {
+        (ExprKind::NegativeLookahead(e), Some(ExprKind::Ident(any))) if any == "ANY" => {
+          i += 1;
+          Some(self.none_of(e)?)
+        }
+        // the whitespaces before an optional term are skipped only if the term appears: (skip ~ e)*
+        (ExprKind::Repeat(e, 0, max), _)
+          if junction && self.skip().is_some() && until_token_terminator(e).is_none() =>
+        {
+          if let Some(syntax) = self.expr(e)? {
+            let syntax = (self.skip().unwrap() & syntax) * (0..=*max);
+            seq = Some(match seq {
+              Some(seq) => seq & syntax,
+              None => syntax,
+            });
+          }
+          junction = false;
+          i += 1;
+          continue;
+        }
+        _ => self.expr(&exprs[i])?,
+      };
+      if let Some(syntax) = syntax {
+        let syntax = match (junction, self.skip()) {
+          (true, Some(skip)) => skip & syntax,
+          _ => syntax,
+        };
+        seq = Some(match seq {
+          Some(seq) => seq & syntax,
+          None => syntax,
+        });
+        junction = false;
+      }
+      i += 1;
+    }
+    Ok(match (seq, junction, self.skip()) {
+      (Some(seq), true, Some(skip)) => Some(seq & skip),
+      (seq, ..) => seq,
+    })
+  }
+
+  /// The character that doesn't match `e` of `!e ~ ANY`.
+  ///
+  fn none_of(&self, e: &Expr) -> Result<Syntax<String, char>, ImportError> {
+    match e.char_class() {
+      Some((chars, ranges)) if ranges.is_empty() => Ok(none_of_chars(&chars)),
+      Some((chars, ranges)) => {
+        let mut alternatives = chars.chars().map(|c| format!("{:?}", c.to_string())).collect::<Vec<_>>();
+        alternatives.extend(ranges.iter().map(|(begin, end)| format!("{:?}..{:?}", begin, end)));
+        let label = format!("!({}) ~ ANY", alternatives.join(" | "));
+        Ok(
+          Syntax::from_fn(&label, move |values: &[char]| match values.first() {
+            None => Ok(MatchResult::UnmatchAndCanAcceptMore),
+            Some(c) if chars.contains(*c) || ranges.iter().any(|(begin, end)| begin <= c && c <= end) => {
+              Ok(MatchResult::Unmatch)
+            }
+            Some(_) => Ok(MatchResult::Match(1)),
+          })
+          .with_window(1),
+        )
+      }
+      None => Err(self.error(e, "the negative lookahead of `!x ~ ANY` is only supported for the characters")),
+    }
+  }
+
+  fn repeat(&self, e: &Expr, min: usize, max: usize) -> Result<Option<Syntax<String, char>>, ImportError> {
+    // (!"..." ~ ANY)* skips to the terminator
+    if let (0, usize::MAX, Some(terminator)) = (min, max, until_token_terminator(e)) {
+      return Ok(Some(until_token(terminator)));
+    }
+    let syntax = match self.expr(e)? {
+      Some(syntax) => syntax,
+      None => return Ok(None),
+    };
+    if max <= 1 || self.skip.is_empty() {
+      return Ok(Some(syntax * (min..=max)));
+    }
+
+    // the implicit whitespaces are inserted between the iterations: e ~ (skip ~ e)*
+    let max = if max == usize::MAX { max } else { max - 1 };
+    let rest = |min: usize| (self.skip().unwrap() & syntax.clone()) * (min..=max);
+    Ok(Some(if min == 0 { (syntax.clone() & rest(0)) * (0..=1) } else { syntax.clone() & rest(min - 1) }))
+  }
+
+  /// `(WHITESPACE | COMMENT)*` if this rule isn't atomic.
+  ///
+  fn skip(&self) -> Option<Syntax<String, char>> {
+    let skip = self.skip.iter().map(|name| id(name.to_string())).reduce(|a, b| a | b)?;
+    Some(skip * (0..))
+  }
+
+  fn error(&self, expr: &Expr, message: &str) -> ImportError {
+    ImportError::at(self.chars, expr.pos, message)
+  }
+}
+
+/// The terminator `"..."` of `(!"..." ~ ANY)*`.
+///
+fn until_token_terminator(e: &Expr) -> Option<&str> {
+  if let ExprKind::Seq(seq) = &e.kind {
+    if let [Expr { kind: ExprKind::NegativeLookahead(t), .. }, Expr { kind: ExprKind::Ident(any), .. }] = &seq[..] {
+      if let (ExprKind::Str(terminator, false), "ANY") = (&t.kind, any.as_str()) {
+        if terminator.chars().count() > 1 {
+          return Some(terminator);
+        }
+      }
+    }
+  }
+  None
+}
+
+fn case_insensitive(s: &str) -> Syntax<String, char> {
+  s.chars()
+    .map(|c| {
+      let (lower, upper) = (c.to_lowercase().collect::<Vec<_>>(), c.to_uppercase().collect::<Vec<_>>());
+      match (&lower[..], &upper[..]) {
+        ([lower], [upper]) if lower != upper => one_of(&[*lower, *upper]),
+        _ => ch(c),
+      }
+    })
+    .reduce(|a, b| a & b)
+    .unwrap()
+}
+
+// ---------------------------------
+
+struct Parser<'a> {
+  chars: &'a [char],
+  pos: usize,
+}
+
+impl Parser<'_> {
+  fn rules(&mut self) -> Result<Vec<Rule>, ImportError> {
+    let mut rules = Vec::new();
+    while self.skip_spaces() {
+      let name = self.ident()?;
+      self.expect('=')?;
+      let modifier = match self.peek() {
+        Some(c @ ('_' | '@' | '$' | '!')) => {
+          self.pos += 1;
+          Some(c)
+        }
+        _ => None,
+      };
+      self.expect('{')?;
+      let expr = self.choice()?;
+      self.expect('}')?;
+      rules.push(Rule { name, modifier, expr });
+    }
+    Ok(rules)
+  }
+
+  fn choice(&mut self) -> Result<Expr, ImportError> {
+    self.skip_spaces();
+    let pos = self.pos;
+    if self.peek() == Some('|') {
+      self.pos += 1;
+    }
+    let mut branches = vec![self.seq()?];
+    while self.peek() == Some('|') {
+      self.pos += 1;
+      branches.push(self.seq()?);
+    }
+    Ok(if branches.len() == 1 { branches.pop().unwrap() } else { Expr { pos, kind: ExprKind::Choice(branches) } })
+  }
+
+  fn seq(&mut self) -> Result<Expr, ImportError> {
+    self.skip_spaces();
+    let pos = self.pos;
+    let mut exprs = vec![self.term()?];
+    while self.peek() == Some('~') {
+      self.pos += 1;
+      exprs.push(self.term()?);
+    }
+    Ok(if exprs.len() == 1 { exprs.pop().unwrap() } else { Expr { pos, kind: ExprKind::Seq(exprs) } })
+  }
+
+  fn term(&mut self) -> Result<Expr, ImportError> {
+    self.skip_spaces();
+    let pos = self.pos;
+    match self.peek() {
+      Some('&') => {
+        self.pos += 1;
+        return Ok(Expr { pos, kind: ExprKind::PositiveLookahead(Box::new(self.term()?)) });
+      }
+      Some('!') => {
+        self.pos += 1;
+        return Ok(Expr { pos, kind: ExprKind::NegativeLookahead(Box::new(self.term()?)) });
+      }
+      _ => (),
+    }
+    let mut expr = self.primary()?;
+    loop {
+      let postfix = self.peek();
+      if matches!(postfix, Some('?' | '*' | '+' | '{')) {
+        self.pos += 1;
+      }
+      let (min, max) = match postfix {
+        Some('?') => (0, 1),
+        Some('*') => (0, usize::MAX),
+        Some('+') => (1, usize::MAX),
+        Some('{') => self.repetition()?,
+        _ => return Ok(expr),
+      };
+      expr = Expr { pos, kind: ExprKind::Repeat(Box::new(expr), min, max) };
+    }
+  }
+
+  /// The repetition `{n}`, `{n,}`, `{,m}` or `{n,m}` after the opening brace.
+  ///
+  fn repetition(&mut self) -> Result<(usize, usize), ImportError> {
+    let min = self.number()?;
+    if self.peek() != Some(',') {
+      let n = min.ok_or_else(|| self.error("a number expected"))?;
+      self.expect('}')?;
+      return Ok((n, n));
+    }
+    self.pos += 1;
+    let max = self.number()?;
+    self.expect('}')?;
+    match (min, max) {
+      (None, None) => Err(self.error("a number expected")),
+      (min, max) => Ok((min.unwrap_or(0), max.unwrap_or(usize::MAX))),
+    }
+  }
+
+  fn number(&mut self) -> Result<Option<usize>, ImportError> {
+    self.skip_spaces();
+    let begin = self.pos;
+    while self.peek_raw().map(|c| c.is_ascii_digit()).unwrap_or(false) {
+      self.pos += 1;
+    }
+    if begin == self.pos {
+      return Ok(None);
+    }
+    let digits = self.chars[begin..self.pos].iter().collect::<String>();
+    digits.parse().map(Some).map_err(|_| self.error("the number is too large"))
+  }
+
+  fn primary(&mut self) -> Result<Expr, ImportError> {
+    self.skip_spaces();
+    let pos = self.pos;
+    let kind = match self.peek() {
+      Some('(') => {
+        self.pos += 1;
+        let expr = self.choice()?;
+        self.expect(')')?;
+        return Ok(expr);
+      }
+      Some('"') => ExprKind::Str(self.string()?, false),
+      Some('^') => {
+        self.pos += 1;
+        self.skip_spaces();
+        ExprKind::Str(self.string()?, true)
+      }
+      Some('\'') => {
+        let begin = self.char()?;
+        self.expect('.')?;
+        self.expect_here('.')?;
+        self.skip_spaces();
+        let end = self.char()?;
+        ExprKind::Range(begin, end)
+      }
+      Some('#') => {
+        // the tag is ignored
+        self.pos += 1;
+        self.ident()?;
+        self.expect('=')?;
+        return self.primary();
+      }
+      Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+        let ident = self.ident()?;
+        // the arguments of the stack operations such as PUSH(...) and PEEK[..] are read to report the operation
+        match self.peek_raw() {
+          Some('(') => {
+            self.pos += 1;
+            self.choice()?;
+            self.expect(')')?;
+          }
+          Some('[') => while !matches!(self.next(), Some(']') | None) {},
+          _ => (),
+        }
+        ExprKind::Ident(ident)
+      }
+      _ => return Err(self.error("an expression expected")),
+    };
+    Ok(Expr { pos, kind })
+  }
+
+  fn ident(&mut self) -> Result<String, ImportError> {
+    self.skip_spaces();
+    let begin = self.pos;
+    while self.peek_raw().map(|c| c.is_ascii_alphanumeric() || c == '_').unwrap_or(false) {
+      self.pos += 1;
+    }
+    if begin == self.pos || self.chars[begin].is_ascii_digit() {
+      return Err(self.error("an identifier expected"));
+    }
+    Ok(self.chars[begin..self.pos].iter().collect())
+  }
+
+  fn string(&mut self) -> Result<String, ImportError> {
+    self.expect_here('"')?;
+    let mut s = String::new();
+    loop {
+      match self.peek_raw() {
+        None => return Err(self.error("the string is not closed")),
+        Some('"') => {
+          self.pos += 1;
+          return Ok(s);
+        }
+        Some(_) => s.push(self.escaped()?),
+      }
+    }
+  }
+
+  fn char(&mut self) -> Result<char, ImportError> {
+    self.expect_here('\'')?;
+    let c = self.escaped()?;
+    self.expect_here('\'')?;
+    Ok(c)
+  }
+
+  /// A character of a string or a character literal, which may be escaped.
+  ///
+  fn escaped(&mut self) -> Result<char, ImportError> {
+    let c = self.next().ok_or_else(|| self.error("a character expected"))?;
+    if c != '\\' {
+      return Ok(c);
+    }
+    let escaped = match self.next() {
+      Some('n') => '\n',
+      Some('r') => '\r',
+      Some('t') => '\t',
+      Some('0') => '\0',
+      Some(c @ ('\\' | '"' | '\'')) => c,
+      Some('x') => {
+        let hex = [self.next(), self.next()].into_iter().flatten().collect::<String>();
+        self.code_point(&hex)?
+      }
+      Some('u') => {
+        self.expect_here('{')?;
+        let mut hex = String::new();
+        while let Some(c) = self.next() {
+          if c == '}' {
+            break;
+          }
+          hex.push(c);
+        }
+        self.code_point(&hex)?
+      }
+      _ => return Err(self.error("an unknown escape sequence")),
+    };
+    Ok(escaped)
+  }
+
+  fn code_point(&self, hex: &str) -> Result<char, ImportError> {
+    u32::from_str_radix(hex, 16)
+      .ok()
+      .and_then(char::from_u32)
+      .ok_or_else(|| self.error(&format!("an invalid code point {:?}", hex)))
+  }
+
+  fn expect(&mut self, expected: char) -> Result<(), ImportError> {
+    self.skip_spaces();
+    self.expect_here(expected)
+  }
+
+  fn expect_here(&mut self, expected: char) -> Result<(), ImportError> {
+    match self.peek_raw() {
+      Some(c) if c == expected => {
+        self.pos += 1;
+        Ok(())
+      }
+      _ => Err(self.error(&format!("{:?} expected", expected))),
+    }
+  }
+
+  /// Skip the whitespaces and the comments, and return whether any character remains.
+  ///
+  fn skip_spaces(&mut self) -> bool {
+    loop {
+      match (self.peek_raw(), self.chars.get(self.pos + 1).copied()) {
+        (Some(c), _) if c.is_whitespace() => self.pos += 1,
+        (Some('/'), Some('/')) => {
+          while self.peek_raw().map(|c| c != '\n').unwrap_or(false) {
+            self.pos += 1;
+          }
+        }
+        (Some('/'), Some('*')) => {
+          self.pos += 2;
+          while self.pos < self.chars.len() && !self.chars[self.pos..].starts_with(&['*', '/']) {
+            self.pos += 1;
+          }
+          self.pos = std::cmp::min(self.pos + 2, self.chars.len());
+        }
+        (c, _) => return c.is_some(),
+      }
+    }
+  }
+
+  fn peek(&mut self) -> Option<char> {
+    self.skip_spaces();
+    self.peek_raw()
+  }
+
+  fn peek_raw(&self) -> Option<char> {
+    self.chars.get(self.pos).copied()
+  }
+
+  fn next(&mut self) -> Option<char> {
+    let c = self.peek_raw();
+    self.pos += 1;
+    c
+  }
+
+  fn error(&self, message: &str) -> ImportError {
+    ImportError::at(self.chars, self.pos, message)
+  }
+}
@@ -0,0 +1,192 @@
+use super::{import, Grammar};
+use crate::parser::{test::Events, Context, Event};
+use crate::schema::{ImportError, Location as _};
+
+/// The JSON grammar of the pest book.
+const JSON: &str = r#"
+WHITESPACE = _{ " " | "\t" | "\r" | "\n" }
+
+object = {
+    "{" ~ "}" |
+    "{" ~ pair ~ ("," ~ pair)* ~ "}"
+}
+pair = { string ~ ":" ~ value }
+
+array = {
+    "[" ~ "]" |
+    "[" ~ value ~ ("," ~ value)* ~ "]"
+}
+
+value = _{ object | array | string | number | boolean | null }
+
+boolean = { "true" | "false" }
+
+null = { "null" }
+
+string = ${ "\"" ~ inner ~ "\"" }
+inner = @{ char* }
+char = {
+    !("\"" | "\\") ~ ANY
+    | "\\" ~ ("\"" | "\\" | "/" | "b" | "f" | "n" | "r" | "t")
+    | "\\" ~ ("u" ~ ASCII_HEX_DIGIT{4})
+}
+
+number = @{
+    "-"?
+    ~ ("0" | ASCII_NONZERO_DIGIT ~ ASCII_DIGIT*)
+    ~ ("." ~ ASCII_DIGIT*)?
+    ~ (^"e" ~ ("+" | "-")? ~ ASCII_DIGIT+)?
+}
+
+json = _{ SOI ~ (object | array) ~ EOI }
+"#;
+
+#[test]
+fn import_json() {
+  let grammar = import("JSON", JSON).unwrap();
+  assert_eq!(vec!["WHITESPACE", "value", "json"], grammar.silent);
+  let ignore = ["WHITESPACE", "value", "char"];
+  let events = parse(&grammar, "json", &ignore, " {\"a\" : [1, -2.5E+3, true, null], \"b\\\"\": {}} ").unwrap();
+  Events::<String>::new()
+    .begin("json".into())
+    .fragments(" ")
+    .begin("object".into())
+    .fragments("{")
+    .begin("pair".into())
+    .begin("string".into())
+    .fragments("\"")
+    .begin("inner".into())
+    .fragments("a")
+    .end()
+    .fragments("\"")
+    .end()
+    .fragments(" : ")
+    .begin("array".into())
+    .fragments("[")
+    .begin("number".into())
+    .fragments("1")
+    .end()
+    .fragments(", ")
+    .begin("number".into())
+    .fragments("-2.5E+3")
+    .end()
+    .fragments(", ")
+    .begin("boolean".into())
+    .fragments("true")
+    .end()
+    .fragments(", ")
+    .begin("null".into())
+    .fragments("null")
+    .end()
+    .fragments("]")
+    .end()
+    .end()
+    .fragments(", ")
+    .begin("pair".into())
+    .begin("string".into())
+    .fragments("\"")
+    .begin("inner".into())
+    .fragments("b\\\"")
+    .end()
+    .fragments("\"")
+    .end()
+    .fragments(": ")
+    .begin("object".into())
+    .fragments("{}")
+    .end()
+    .end()
+    .fragments("}")
+    .end()
+    .fragments(" ")
+    .end()
+    .assert_eq(&events);
+
+  // the atomic rules don't skip the whitespaces
+  for text in ["{\"a\": 1 2}", "[\"a\" \"b\"]", "[- 1]", "[1 .5]"] {
+    assert!(parse(&grammar, "json", &ignore, text).is_err(), "{}", text);
+  }
+  for text in ["[]", "[ ]", "{ }", "[\"\\u00e9\", 0e1]"] {
+    assert!(parse(&grammar, "json", &ignore, text).is_ok(), "{}", text);
+  }
+}
+
+#[test]
+fn import_repetitions_and_literals() {
+  let grammar = import(
+    "Foo",
+    r#"
+    // the comments are skipped
+    a = { "x"{2} ~ "y"{,1} ~ "z"{1,} ~ 'a'..'c'{1, 2} ~ ^"ok" }  /* block comment */
+    b = @{ "/*" ~ (!"*/" ~ ANY)* ~ "*/" }
+    c = @{ (!('0'..'9' | "_") ~ ANY)+ }
+    d = @{ "\u{e9}\x41\t" ~ EOI }
+    "#,
+  )
+  .unwrap();
+  let accepts = |id: &str, text: &str| parse(&grammar, id, &[], text).is_ok();
+  assert!(accepts("a", "xxzzbOk"));
+  assert!(accepts("a", "xxyzacoK"));
+  assert!(!accepts("a", "xzaok"));
+  assert!(!accepts("a", "xxyyzaok"));
+  assert!(!accepts("a", "xxzabcok"));
+  assert!(accepts("b", "/* a * b / c */"));
+  assert!(!accepts("b", "/* a */ */"));
+  assert!(accepts("c", "abc-"));
+  assert!(!accepts("c", "ab_"));
+  assert!(!accepts("c", "a1"));
+  assert!(accepts("d", "éA\t"));
+}
+
+#[test]
+fn import_implicit_whitespaces() {
+  let grammar = import(
+    "Foo",
+    r##"
+    WHITESPACE = _{ " " }
+    COMMENT = _{ "#" ~ (!"\n" ~ ANY)* }
+    list = { SOI ~ item+ ~ EOI }
+    item = { word ~ ("=" ~ word)? }
+    word = @{ ASCII_ALPHA+ }
+    pair = { ASCII_ALPHA ~ ASCII_ALPHA }
+    tight = !{ "<" ~ word ~ ">" }
+    "##,
+  )
+  .unwrap();
+  let accepts = |id: &str, text: &str| parse(&grammar, id, &["WHITESPACE", "COMMENT"], text).is_ok();
+  assert!(accepts("list", " a = b c #comment"));
+  assert!(accepts("list", "a=b #x\tc"));
+  assert!(!accepts("list", ""));
+  assert!(!accepts("word", "a b"));
+  assert!(accepts("pair", "a b"));
+  assert!(accepts("tight", "< ab >"));
+}
+
+#[test]
+fn import_errors() {
+  let error = |grammar: &str| match import("Foo", grammar) {
+    Err(ImportError { location, message }) => (location.line().unwrap() + 1, location.column().unwrap() + 1, message),
+    Ok(_) => panic!("{}", grammar),
+  };
+  assert_eq!((2, 7, String::from("c is not defined")), error("a = { b }\nb = { c }"));
+  assert_eq!((1, 7, String::from("the stack operation PUSH is not supported")), error("a = { PUSH(\"x\") }"));
+  assert_eq!((1, 7, String::from("the positive lookahead `&` is not supported")), error("a = { &\"x\" ~ ANY }"));
+  assert_eq!((1, 8, String::from("the negative lookahead of `!x ~ ANY` is only supported for the characters")), {
+    error("a = { !\"xy\" ~ ANY }")
+  });
+  assert_eq!((1, 7, String::from("the rule matches only an empty sequence")), error("a = { SOI }"));
+  assert_eq!((1, 10, String::from("the string is not closed")), error("a = { \"xy"));
+  assert_eq!((1, 9, String::from("'}' expected")), error("a = { x y }"));
+  assert_eq!((1, 5, String::from("'{' expected")), error("a = ( x )"));
+  assert_eq!("(1,5) '{' expected", import("Foo", "a = ( x )").err().unwrap().to_string());
+  assert_eq!(6, import("Foo", "a = { x }").err().unwrap().location.position());
+}
+
+fn parse(grammar: &Grammar, id: &str, ignore: &[&str], text: &str) -> crate::Result<char, Vec<Event<String, char>>> {
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let ignore = ignore.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+  let mut parser = Context::new(&grammar.schema, id.to_string(), handler)?.ignore_events_for(&ignore);
+  parser.push_str(text)?;
+  parser.finish()?;
+  Ok(Event::normalize(&events))
+}
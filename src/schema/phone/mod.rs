@@ -0,0 +1,116 @@
+use crate::schema::chars::{ch, one_of_chars};
+use crate::schema::{id, MatchResult, Schema, Syntax};
+use crate::Result;
+use std::fmt::Display;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ID {
+  PhoneNumber,
+  CountryCode,
+  SubscriberNumber,
+}
+
+impl Display for ID {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// The maximum number of digits in a phone number including the country code.
+///
+pub const MAX_DIGITS: usize = 15;
+
+/// The two-digit country codes of [ITU-T E.164](https://www.itu.int/rec/T-REC-E.164). The country codes form a prefix
+/// code, so `1` and `7` are the only one-digit codes and any other code that doesn't begin with one of these has
+/// three digits.
+///
+pub const TWO_DIGIT_COUNTRY_CODES: [&str; 44] = [
+  "20", "27", "30", "31", "32", "33", "34", "36", "39", "40", "41", "43", "44", "45", "46", "47", "48", "49", "51",
+  "52", "53", "54", "55", "56", "57", "58", "60", "61", "62", "63", "64", "65", "66", "81", "82", "84", "86", "90",
+  "91", "92", "93", "94", "95", "98",
+];
+
+/// The phone numbers for form validation, which emit [`ID::CountryCode`] and [`ID::SubscriberNumber`] events. The
+/// number of digits is at most [`MAX_DIGITS`] in both modes.
+///
+/// - If `strict` is `true`, only the [E.164](https://www.itu.int/rec/T-REC-E.164) format such as `+14155552671` is
+///   accepted: `+`, a country code and the subscriber number consisting only of digits.
+/// - Otherwise the common human formats such as `+1 (415) 555-2671`, `+44 (0)20 7946 0958` or `415.555.2671` are also
+///   accepted: the `+` and the country code are optional, and the subscriber number consists of groups of digits,
+///   optionally enclosed in parentheses, separated by a space, `-` or `.`.
+///
+/// The length of the country code is determined by [`TWO_DIGIT_COUNTRY_CODES`], and the unassigned three-digit codes
+/// are also accepted.
+///
+pub fn schema(strict: bool) -> Schema<ID, char> {
+  let schema = Schema::new(if strict { "E.164" } else { "PHONE" }).define(ID::CountryCode, country_code());
+  if strict {
+    schema
+      .define(ID::PhoneNumber, ch('+') & digits_within(MAX_DIGITS) & id(ID::CountryCode) & id(ID::SubscriberNumber))
+      .define(ID::SubscriberNumber, digits())
+  } else {
+    let sep = || one_of_chars(" -.") * (0..=1);
+    let group = || digits() | (ch('(') & digits() & ch(')'));
+    schema
+      .define(
+        ID::PhoneNumber,
+        digits_within(MAX_DIGITS) & (((ch('+') & id(ID::CountryCode) & sep()) * (0..=1)) & id(ID::SubscriberNumber)),
+      )
+      .define(ID::SubscriberNumber, group() & ((sep() & group()) * (0..)))
+  }
+}
+
+/// Decimal digits, as many as possible.
+///
+fn digits<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("DIGITS", |values: &[char]| -> Result<char, MatchResult> {
+    Ok(match values.iter().position(|ch| !ch.is_ascii_digit()) {
+      None if values.is_empty() => MatchResult::UnmatchAndCanAcceptMore,
+      None => MatchResult::MatchAndCanAcceptMore(values.len()),
+      Some(0) => MatchResult::Unmatch,
+      Some(length) => MatchResult::Match(length),
+    })
+  })
+}
+
+/// A country code whose length is determined by the [`TWO_DIGIT_COUNTRY_CODES`].
+///
+fn country_code<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("COUNTRY_CODE", |values: &[char]| -> Result<char, MatchResult> {
+    let length = match values.first() {
+      None => return Ok(MatchResult::UnmatchAndCanAcceptMore),
+      Some('1' | '7') => 1,
+      Some('2'..='9') => match values.get(1) {
+        None => return Ok(MatchResult::UnmatchAndCanAcceptMore),
+        Some(ch) if !ch.is_ascii_digit() => return Ok(MatchResult::Unmatch),
+        Some(ch) if TWO_DIGIT_COUNTRY_CODES.contains(&String::from_iter([values[0], *ch]).as_str()) => 2,
+        Some(_) => 3,
+      },
+      Some(_) => return Ok(MatchResult::Unmatch),
+    };
+    Ok(match values.get(..length) {
+      None => MatchResult::UnmatchAndCanAcceptMore,
+      Some(code) if code.iter().all(|ch| ch.is_ascii_digit()) => MatchResult::Match(length),
+      Some(_) => MatchResult::Unmatch,
+    })
+  })
+}
+
+/// Look ahead whether the phone number has at most `max` digits, ignoring `+`, parentheses and separators, without
+/// consuming it.
+///
+fn digits_within<ID>(max: usize) -> Syntax<ID, char> {
+  let label = format!("&DIGITS{{,{}}}", max);
+  Syntax::from_fn(&label, move |values: &[char]| -> Result<char, MatchResult> {
+    let length = values.iter().position(|ch| !(ch.is_ascii_digit() || "+() -.".contains(*ch)));
+    let digits = values[..length.unwrap_or(values.len())].iter().filter(|ch| ch.is_ascii_digit()).count();
+    Ok(match length {
+      _ if digits > max => MatchResult::Unmatch,
+      None => MatchResult::MatchAndCanAcceptMore(0),
+      Some(_) => MatchResult::Match(0),
+    })
+  })
+}
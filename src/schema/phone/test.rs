@@ -0,0 +1,93 @@
+use super::{schema, ID};
+use crate::parser::{test::Events, Context, Event};
+
+#[test]
+fn strict() {
+  for number in ["+14155552671", "+442079460958", "+81312345678", "+8613800138000", "+999123456789012"] {
+    assert!(parse(true, number).is_ok(), "{:?}", number);
+  }
+  for number in [
+    "",
+    "+",
+    "14155552671",
+    "+1",
+    "+0123456789",
+    "+1 415 555 2671",
+    "+1-415-555-2671",
+    "+1(415)5552671",
+    "+1234567890123456",
+  ] {
+    assert!(parse(true, number).is_err(), "{:?}", number);
+  }
+}
+
+#[test]
+fn lenient() {
+  for number in [
+    "+14155552671",
+    "+1 (415) 555-2671",
+    "+44 (0)20 7946 0958",
+    "+81-3-1234-5678",
+    "(415) 555-2671",
+    "415.555.2671",
+    "5552671",
+  ] {
+    assert!(parse(false, number).is_ok(), "{:?}", number);
+  }
+  for number in
+    ["", "+", "+1", "+1  415", "415--555", "(415 555", "415-555-", "+1 415 555 2671 x12", "1234 5678 9012 3456"]
+  {
+    assert!(parse(false, number).is_err(), "{:?}", number);
+  }
+}
+
+#[test]
+fn country_code_length() {
+  for (number, country_code, subscriber_number) in [
+    ("+14155552671", "1", "4155552671"),
+    ("+74951234567", "7", "4951234567"),
+    ("+442079460958", "44", "2079460958"),
+    ("+353123456789", "353", "123456789"),
+    ("+8613800138000", "86", "13800138000"),
+  ] {
+    let events = parse(true, number).unwrap();
+    Events::new()
+      .begin(ID::PhoneNumber)
+      .fragments("+")
+      .begin(ID::CountryCode)
+      .fragments(country_code)
+      .end()
+      .begin(ID::SubscriberNumber)
+      .fragments(subscriber_number)
+      .end()
+      .end()
+      .assert_eq(&events);
+  }
+}
+
+#[test]
+fn events() {
+  let events = parse(false, "+1 (415) 555-2671").unwrap();
+  Events::new()
+    .begin(ID::PhoneNumber)
+    .fragments("+")
+    .begin(ID::CountryCode)
+    .fragments("1")
+    .end()
+    .fragments(" ")
+    .begin(ID::SubscriberNumber)
+    .fragments("(415) 555-2671")
+    .end()
+    .end()
+    .assert_eq(&events);
+}
+
+fn parse(strict: bool, text: &str) -> crate::Result<char, Vec<Event<ID, char>>> {
+  let schema = schema(strict);
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, ID::PhoneNumber, handler)?;
+  parser.push_str(text)?;
+  parser.finish()?;
+  Ok(Event::normalize(&events))
+}
@@ -0,0 +1,155 @@
+//! Operator-precedence expressions. [`Schema::define_pratt()`] defines a rule that combines atoms with prefix, infix
+//! and postfix operators according to their precedences, as a Pratt parser does, without encoding the precedences as
+//! a ladder of rules.
+//!
+//! ```rust
+//! use terp::schema::{Schema, id};
+//! use terp::schema::chars::{ch, ascii_digit};
+//! use terp::schema::pratt::{pratt, Assoc};
+//! use terp::parser::{Context, Event};
+//!
+//! let schema = Schema::new("Arithmetic")
+//!   .define("NUM", ascii_digit() * (1..))
+//!   .define_pratt("EXPR", pratt(id("NUM") | (ch('(') & id("EXPR") & ch(')')))
+//!     .prefix(ch('-'), 3)
+//!     .infix(ch('+'), 1, Assoc::Left)
+//!     .infix(ch('*'), 2, Assoc::Left)
+//!     .infix(ch('^'), 4, Assoc::Right)
+//!     .postfix(ch('!'), 5));
+//!
+//! let mut parser = Context::new(&schema, "EXPR", |_: &Event<_, _>| ()).unwrap();
+//! parser.push_str("-1+2*3^4^5!").unwrap();
+//! parser.finish().unwrap();
+//! ```
+//!
+use crate::schema::{id, Primary, Schema, Symbol, Syntax};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(test)]
+mod test;
+
+/// The associativity of an infix operator, which determines how the operators of the same precedence are nested.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Assoc {
+  /// `a - b - c` is parsed as `(a - b) - c`.
+  Left,
+  /// `a ^ b ^ c` is parsed as `a ^ (b ^ c)`.
+  Right,
+}
+
+/// The table of the operators applied to an atom. The operator with a higher precedence binds more tightly.
+///
+pub struct Pratt<ID, Σ: Symbol> {
+  atom: Syntax<ID, Σ>,
+  prefixes: Vec<(Syntax<ID, Σ>, usize)>,
+  infixes: Vec<(Syntax<ID, Σ>, usize, Assoc)>,
+  postfixes: Vec<(Syntax<ID, Σ>, usize)>,
+}
+
+/// Start an operator table whose operands are `atom`. To nest an expression in an atom, such as parentheses, refer
+/// to the rule being defined by [`id()`](crate::schema::id).
+///
+pub fn pratt<ID, Σ: Symbol>(atom: Syntax<ID, Σ>) -> Pratt<ID, Σ> {
+  Pratt { atom, prefixes: Vec::new(), infixes: Vec::new(), postfixes: Vec::new() }
+}
+
+impl<ID, Σ: 'static + Symbol> Pratt<ID, Σ> {
+  pub fn prefix(mut self, operator: Syntax<ID, Σ>, precedence: usize) -> Self {
+    self.prefixes.push((operator, precedence));
+    self
+  }
+
+  pub fn infix(mut self, operator: Syntax<ID, Σ>, precedence: usize, assoc: Assoc) -> Self {
+    self.infixes.push((operator, precedence, assoc));
+    self
+  }
+
+  pub fn postfix(mut self, operator: Syntax<ID, Σ>, precedence: usize) -> Self {
+    self.postfixes.push((operator, precedence));
+    self
+  }
+}
+
+/// The binding powers of the operators in the rules defined by [`Schema::define_pratt()`]. An operator binds its left
+/// operand only if its left binding power is greater than the minimum of the expression being parsed, and the right
+/// operand is parsed as an expression whose minimum is the right binding power.
+///
+#[derive(Clone, Default, Debug)]
+pub(crate) struct Bindings {
+  /// The syntax IDs of the choices of the rules.
+  rules: BTreeSet<usize>,
+  /// The left binding powers of the branches of infix and postfix operators.
+  left: BTreeMap<usize, usize>,
+  /// The right binding powers of the operands of infix and prefix operators.
+  right: BTreeMap<usize, usize>,
+}
+
+fn left_binding_power(precedence: usize) -> usize {
+  2 * (precedence + 1)
+}
+
+impl<ID: Ord + Clone, Σ: 'static + Symbol> Schema<ID, Σ> {
+  /// Define the rule `id` as the expressions of the operator table `pratt`. The rule is parsed as the left-recursive
+  /// choice `id := atom | prefix id | id infix id | id postfix`, where the branches that violate the precedences are
+  /// discarded, so every operator application is reported as a nested `id`.
+  ///
+  /// Note that the precedences are applied only by [`Engine::Streaming`](crate::parser::Engine::Streaming).
+  ///
+  pub fn define_pratt(self, id: ID, pratt: Pratt<ID, Σ>) -> Self {
+    let Pratt { atom, prefixes, infixes, postfixes } = pratt;
+    let branch = |seq: Vec<Syntax<ID, Σ>>| Syntax::with_primary(Primary::Seq(seq));
+    let mut branches = vec![atom.conv_to_non_repeating_seq()];
+    let mut powers = Vec::new();
+    for (operator, precedence) in prefixes {
+      branches.push(branch(vec![operator, self::id(id.clone())]));
+      powers.push((None, Some(left_binding_power(precedence) + 1)));
+    }
+    for (operator, precedence, assoc) in infixes {
+      branches.push(branch(vec![self::id(id.clone()), operator, self::id(id.clone())]));
+      let left = left_binding_power(precedence);
+      powers.push((Some(left), Some(if assoc == Assoc::Left { left + 1 } else { left - 1 })));
+    }
+    for (operator, precedence) in postfixes {
+      branches.push(branch(vec![self::id(id.clone()), operator]));
+      powers.push((Some(left_binding_power(precedence)), None));
+    }
+
+    let mut schema = self.define(id.clone(), Syntax::with_primary(Primary::Or(branches)));
+    if let Some(Syntax { primary: Primary::Seq(seq), .. }) = schema.defs.get(&id) {
+      if let Syntax { id: choice, primary: Primary::Or(branches), .. } = &seq[0] {
+        schema.bindings.rules.insert(*choice);
+        for (branch, (left, right)) in branches.iter().skip(1).zip(powers) {
+          if let Some(left) = left {
+            schema.bindings.left.insert(branch.id, left);
+          }
+          if let (Primary::Seq(seq), Some(right)) = (&branch.primary, right) {
+            schema.bindings.right.insert(seq.last().unwrap().id, right);
+          }
+        }
+      }
+    }
+    schema
+  }
+
+  /// Whether the rule `id` is defined by [`define_pratt()`](Self::define_pratt).
+  ///
+  pub(crate) fn is_pratt(&self, id: &ID) -> bool {
+    match self.defs.get(id) {
+      Some(Syntax { primary: Primary::Seq(seq), .. }) => self.bindings.rules.contains(&seq[0].id),
+      _ => false,
+    }
+  }
+
+  /// The left binding power of the branch of an infix or postfix operator.
+  ///
+  pub(crate) fn left_binding(&self, branch: &Syntax<ID, Σ>) -> Option<usize> {
+    self.bindings.left.get(&branch.id).copied()
+  }
+
+  /// The right binding power if `syntax` is the operand of an infix or prefix operator.
+  ///
+  pub(crate) fn right_binding(&self, syntax: &Syntax<ID, Σ>) -> Option<usize> {
+    self.bindings.right.get(&syntax.id).copied()
+  }
+}
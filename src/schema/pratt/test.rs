@@ -0,0 +1,98 @@
+use crate::parser::{Context, Event, EventKind};
+use crate::schema::chars::{ascii_digit, ch};
+use crate::schema::pratt::{pratt, Assoc};
+use crate::schema::{id, Schema};
+
+fn schema() -> Schema<&'static str, char> {
+  Schema::new("Arithmetic").define("NUM", ascii_digit() * (1..)).define_pratt(
+    "EXPR",
+    pratt(id("NUM") | (ch('(') & id("EXPR") & ch(')')))
+      .prefix(ch('-'), 3)
+      .infix(ch('+'), 1, Assoc::Left)
+      .infix(ch('-'), 1, Assoc::Left)
+      .infix(ch('*'), 2, Assoc::Left)
+      .infix(ch('^'), 4, Assoc::Right)
+      .postfix(ch('!'), 5),
+  )
+}
+
+/// Parse `expr` and enclose each EXPR in brackets.
+///
+fn bracket(schema: &Schema<&'static str, char>, expr: &str) -> String {
+  let mut bracketed = String::new();
+  let handler = |e: &Event<_, _>| match &e.kind {
+    EventKind::Begin(_) => bracketed.push('['),
+    EventKind::End(_) => bracketed.push(']'),
+    EventKind::Fragments(chars) => bracketed.extend(chars),
+  };
+  let mut parser = Context::new(schema, "EXPR", handler).unwrap().ignore_events_for(&["NUM"]);
+  parser.push_str(expr).unwrap();
+  parser.finish().unwrap();
+  bracketed
+}
+
+#[test]
+fn pratt_precedence() {
+  let schema = schema();
+  for (expr, expected) in [
+    ("1", "[1]"),
+    ("1+2*3", "[[1]+[[2]*[3]]]"),
+    ("1*2+3", "[[[1]*[2]]+[3]]"),
+    ("1*2+3*4", "[[[1]*[2]]+[[3]*[4]]]"),
+    ("1+2*3^4", "[[1]+[[2]*[[3]^[4]]]]"),
+  ] {
+    assert_eq!(expected, bracket(&schema, expr), "{}", expr);
+  }
+}
+
+#[test]
+fn pratt_associativity() {
+  let schema = schema();
+  for (expr, expected) in [
+    ("1-2-3", "[[[1]-[2]]-[3]]"),
+    ("1-2+3", "[[[1]-[2]]+[3]]"),
+    ("1^2^3", "[[1]^[[2]^[3]]]"),
+    ("1*2*3^4^5", "[[[1]*[2]]*[[3]^[[4]^[5]]]]"),
+  ] {
+    assert_eq!(expected, bracket(&schema, expr), "{}", expr);
+  }
+}
+
+#[test]
+fn pratt_prefix_and_postfix() {
+  let schema = schema();
+  for (expr, expected) in [
+    ("-1", "[-[1]]"),
+    ("--1", "[-[-[1]]]"),
+    ("-1+2", "[[-[1]]+[2]]"),
+    ("-1*2", "[[-[1]]*[2]]"),
+    ("-1^2", "[-[[1]^[2]]]"),
+    ("1!", "[[1]!]"),
+    ("-1!", "[-[[1]!]]"),
+    ("1+2!", "[[1]+[[2]!]]"),
+    ("1-2!!", "[[1]-[[[2]!]!]]"),
+    ("1--2", "[[1]-[-[2]]]"),
+  ] {
+    assert_eq!(expected, bracket(&schema, expr), "{}", expr);
+  }
+}
+
+#[test]
+fn pratt_parentheses() {
+  let schema = schema();
+  for (expr, expected) in
+    [("(1)", "[([1])]"), ("(1+2)*3", "[[([[1]+[2]])]*[3]]"), ("1*(2+3)^4", "[[1]*[[([[2]+[3]])]^[4]]]")]
+  {
+    assert_eq!(expected, bracket(&schema, expr), "{}", expr);
+  }
+}
+
+#[test]
+fn pratt_unmatch() {
+  let schema = schema();
+  for expr in ["", "+1", "1+", "1*(2+3", "1 + 2"] {
+    let mut parser = Context::new(&schema, "EXPR", |_: &Event<_, _>| ()).unwrap();
+    let result = parser.push_str(expr).and_then(|_| parser.finish());
+    assert!(result.is_err(), "{}", expr);
+  }
+}
@@ -0,0 +1,165 @@
+use crate::schema::chars::{ch, line, newline, one_of_chars, token};
+use crate::schema::{eof, id, MatchResult, Schema, Syntax};
+use crate::Result;
+use std::fmt::Display;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ID {
+  Robots,
+  Group,
+  UserAgent,
+  Allow,
+  Disallow,
+  CrawlDelay,
+  Sitemap,
+  Other,
+  Key,
+  Value,
+  Comment,
+  UrlList,
+  Url,
+  WS,
+}
+
+impl Display for ID {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// The keys of the records that have their own IDs, which are case-insensitive. The records with the other keys are
+/// [`ID::Other`].
+///
+pub const KEYS: [(&str, ID); 5] = [
+  ("user-agent", ID::UserAgent),
+  ("allow", ID::Allow),
+  ("disallow", ID::Disallow),
+  ("crawl-delay", ID::CrawlDelay),
+  ("sitemap", ID::Sitemap),
+];
+
+/// The robots.txt according to [RFC 9309](https://www.rfc-editor.org/rfc/rfc9309.html), a sequence of the records
+/// `key: value` each on its own line, the empty lines and the comments `#`.
+///
+/// - An [`ID::Group`] begins with one or more [`ID::UserAgent`] lines followed by the [`ID::Allow`],
+///   [`ID::Disallow`], [`ID::CrawlDelay`], [`ID::Sitemap`] and [`ID::Other`] records up to the next user-agent line.
+///   Only the last group may have no records other than its user-agents.
+/// - The records before the first group don't belong to any group.
+/// - The [`ID::Value`] is the text up to a comment or the end of the line without the trailing whitespaces, which
+///   may be empty such as `Disallow:`.
+///
+pub fn schema() -> Schema<ID, char> {
+  let ws = || id(ID::WS) * (0..=1);
+  let empty = || ws() & (id(ID::Comment) * (0..=1)) & newline();
+  let member = || id(ID::Allow) | id(ID::Disallow) | id(ID::CrawlDelay) | id(ID::Sitemap) | id(ID::Other);
+  let user_agents = || id(ID::UserAgent) & ((id(ID::UserAgent) | empty()) * (0..));
+  let schema = Schema::new("ROBOTS")
+    .define(ID::Robots, ((empty() | member()) * (0..)) & (id(ID::Group) * (0..)) & (empty_last_line() * (0..=1)))
+    .define(
+      ID::Group,
+      (user_agents() & member() & ((member() | empty()) * (0..))) | (user_agents() & (empty_last_line() | eof())),
+    )
+    .define(ID::Other, ws() & id(ID::Key) & record())
+    .define(ID::Key, other_key())
+    .define(ID::Value, value())
+    .define(ID::Comment, ch('#') & line())
+    .define(ID::WS, one_of_chars(" \t") * (1..));
+  KEYS.iter().fold(schema, |schema, (key, id)| schema.define(*id, ws() & keyword(key) & record()))
+}
+
+/// The URL list of the sitemap in the text format, one absolute `http` or `https` [`ID::Url`] per line, which may
+/// also be referred from [`ID::Sitemap`] of the robots.txt.
+///
+/// <https://www.sitemaps.org/protocol.html#otherformats>
+///
+pub fn url_list() -> Schema<ID, char> {
+  let ws = || id(ID::WS) * (0..=1);
+  let line = || ws() & ((id(ID::Url) & ws()) * (0..=1));
+  Schema::new("URL_LIST")
+    .define(ID::UrlList, ((line() & newline()) * (0..)) & line() & eof())
+    .define(ID::Url, (token("http://") | token("https://")) & url_chars())
+    .define(ID::WS, one_of_chars(" \t") * (1..))
+}
+
+/// The rest of a record after the key: `:`, the optional value, a comment and the end of the line.
+///
+fn record() -> Syntax<ID, char> {
+  let ws = || id(ID::WS) * (0..=1);
+  ws() & ch(':') & ((ws() & id(ID::Value)) * (0..=1)) & ws() & (id(ID::Comment) * (0..=1)) & (newline() | eof())
+}
+
+/// The last line of the input without a newline that has nothing but whitespaces and a comment.
+///
+fn empty_last_line() -> Syntax<ID, char> {
+  ((id(ID::WS) & (id(ID::Comment) * (0..=1))) | id(ID::Comment)) & eof()
+}
+
+fn is_key_char(ch: char) -> bool {
+  ch.is_ascii_alphanumeric() || ch == '-' || ch == '_'
+}
+
+/// The case-insensitive `key` that isn't followed by another character of a key.
+///
+fn keyword<ID>(key: &'static str) -> Syntax<ID, char> {
+  Syntax::from_fn(key, move |values: &[char]| -> Result<char, MatchResult> {
+    let length = values.iter().position(|ch| !is_key_char(*ch));
+    let word = values[..length.unwrap_or(values.len())].iter().collect::<String>();
+    Ok(match length {
+      Some(_) if key.eq_ignore_ascii_case(&word) => MatchResult::Match(word.len()),
+      None if key.eq_ignore_ascii_case(&word) => MatchResult::MatchAndCanAcceptMore(word.len()),
+      None if word.len() < key.len() && key[..word.len()].eq_ignore_ascii_case(&word) => {
+        MatchResult::UnmatchAndCanAcceptMore
+      }
+      _ => MatchResult::Unmatch,
+    })
+  })
+}
+
+/// A key other than the [`KEYS`].
+///
+fn other_key<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("KEY", |values: &[char]| -> Result<char, MatchResult> {
+    let length = values.iter().position(|ch| !is_key_char(*ch));
+    let word = values[..length.unwrap_or(values.len())].iter().collect::<String>();
+    Ok(match length {
+      None => MatchResult::UnmatchAndCanAcceptMore,
+      Some(0) => MatchResult::Unmatch,
+      Some(_) if KEYS.iter().any(|(key, _)| key.eq_ignore_ascii_case(&word)) => MatchResult::Unmatch,
+      Some(length) => MatchResult::Match(length),
+    })
+  })
+}
+
+/// The value up to a comment or the end of the line without the trailing whitespaces.
+///
+fn value<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("VALUE", |values: &[char]| -> Result<char, MatchResult> {
+    if matches!(values.first(), Some(' ' | '\t' | '#' | '\n' | '\r')) {
+      return Ok(MatchResult::Unmatch);
+    }
+    let end = values.iter().position(|ch| matches!(ch, '#' | '\n' | '\r'));
+    let length = values[..end.unwrap_or(values.len())].iter().rposition(|ch| *ch != ' ' && *ch != '\t');
+    Ok(match (end, length) {
+      (Some(_), Some(length)) => MatchResult::Match(length + 1),
+      (None, Some(length)) => MatchResult::MatchAndCanAcceptMore(length + 1),
+      (None, None) => MatchResult::UnmatchAndCanAcceptMore,
+      (Some(_), None) => MatchResult::Unmatch,
+    })
+  })
+}
+
+/// The characters of a URL up to a whitespace or the end of the line.
+///
+fn url_chars<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("URL", |values: &[char]| -> Result<char, MatchResult> {
+    Ok(match values.iter().position(|ch| ch.is_whitespace() || ch.is_control()) {
+      None if values.is_empty() => MatchResult::UnmatchAndCanAcceptMore,
+      None => MatchResult::MatchAndCanAcceptMore(values.len()),
+      Some(0) => MatchResult::Unmatch,
+      Some(length) => MatchResult::Match(length),
+    })
+  })
+}
@@ -0,0 +1,395 @@
+use super::{schema, url_list, ID};
+use crate::parser::{test::Events, Context, Event};
+use crate::schema::Schema;
+use crate::Error;
+
+#[test]
+fn groups() {
+  let text = concat!(
+    "# robots\n",
+    "Sitemap: https://example.com/sitemap.xml\n\n",
+    "User-agent: a-bot\n\n",
+    "user-agent: *  # all\n",
+    "Disallow: /private/ \n",
+    "allow: /public\n",
+    "Crawl-delay: 10\n",
+    "Host: example.com\n",
+    "User-agent: b-bot\n",
+    "Disallow:",
+  );
+  let events = parse(&schema(), ID::Robots, text).unwrap();
+  Events::new()
+    .begin(ID::Robots)
+    .begin(ID::Comment)
+    .fragments("# robots")
+    .end()
+    .fragments("\n")
+    .begin(ID::Sitemap)
+    .fragments("Sitemap: ")
+    .begin(ID::Value)
+    .fragments("https://example.com/sitemap.xml")
+    .end()
+    .fragments("\n")
+    .end()
+    .fragments("\n")
+    .begin(ID::Group)
+    .begin(ID::UserAgent)
+    .fragments("User-agent: ")
+    .begin(ID::Value)
+    .fragments("a-bot")
+    .end()
+    .fragments("\n")
+    .end()
+    .fragments("\n")
+    .begin(ID::UserAgent)
+    .fragments("user-agent: ")
+    .begin(ID::Value)
+    .fragments("*")
+    .end()
+    .fragments("  ")
+    .begin(ID::Comment)
+    .fragments("# all")
+    .end()
+    .fragments("\n")
+    .end()
+    .begin(ID::Disallow)
+    .fragments("Disallow: ")
+    .begin(ID::Value)
+    .fragments("/private/")
+    .end()
+    .fragments(" \n")
+    .end()
+    .begin(ID::Allow)
+    .fragments("allow: ")
+    .begin(ID::Value)
+    .fragments("/public")
+    .end()
+    .fragments("\n")
+    .end()
+    .begin(ID::CrawlDelay)
+    .fragments("Crawl-delay: ")
+    .begin(ID::Value)
+    .fragments("10")
+    .end()
+    .fragments("\n")
+    .end()
+    .begin(ID::Other)
+    .begin(ID::Key)
+    .fragments("Host")
+    .end()
+    .fragments(": ")
+    .begin(ID::Value)
+    .fragments("example.com")
+    .end()
+    .fragments("\n")
+    .end()
+    .end()
+    .begin(ID::Group)
+    .begin(ID::UserAgent)
+    .fragments("User-agent: ")
+    .begin(ID::Value)
+    .fragments("b-bot")
+    .end()
+    .fragments("\n")
+    .end()
+    .begin(ID::Disallow)
+    .fragments("Disallow:")
+    .end()
+    .end()
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn valid_robots() {
+  for (text, expected) in [
+    ("", Events::new()),
+    ("\n", Events::new().begin(ID::Robots).fragments("\n").end()),
+    ("  ", Events::new().begin(ID::Robots).fragments("  ").end()),
+    ("# comment", Events::new().begin(ID::Robots).begin(ID::Comment).fragments("# comment").end().end()),
+    (
+      "User-agent: *",
+      Events::new()
+        .begin(ID::Robots)
+        .begin(ID::Group)
+        .begin(ID::UserAgent)
+        .fragments("User-agent: ")
+        .begin(ID::Value)
+        .fragments("*")
+        .end()
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "User-agent: *\n",
+      Events::new()
+        .begin(ID::Robots)
+        .begin(ID::Group)
+        .begin(ID::UserAgent)
+        .fragments("User-agent: ")
+        .begin(ID::Value)
+        .fragments("*")
+        .end()
+        .fragments("\n")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "User-agent: *\n  ",
+      Events::new()
+        .begin(ID::Robots)
+        .begin(ID::Group)
+        .begin(ID::UserAgent)
+        .fragments("User-agent: ")
+        .begin(ID::Value)
+        .fragments("*")
+        .end()
+        .fragments("\n")
+        .end()
+        .fragments("  ")
+        .end()
+        .end(),
+    ),
+    (
+      "User-agent: a\nUser-agent: b\n",
+      Events::new()
+        .begin(ID::Robots)
+        .begin(ID::Group)
+        .begin(ID::UserAgent)
+        .fragments("User-agent: ")
+        .begin(ID::Value)
+        .fragments("a")
+        .end()
+        .fragments("\n")
+        .end()
+        .begin(ID::UserAgent)
+        .fragments("User-agent: ")
+        .begin(ID::Value)
+        .fragments("b")
+        .end()
+        .fragments("\n")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "User-agent: a\n\nUser-agent: b\nDisallow: /\n",
+      Events::new()
+        .begin(ID::Robots)
+        .begin(ID::Group)
+        .begin(ID::UserAgent)
+        .fragments("User-agent: ")
+        .begin(ID::Value)
+        .fragments("a")
+        .end()
+        .fragments("\n")
+        .end()
+        .fragments("\n")
+        .begin(ID::UserAgent)
+        .fragments("User-agent: ")
+        .begin(ID::Value)
+        .fragments("b")
+        .end()
+        .fragments("\n")
+        .end()
+        .begin(ID::Disallow)
+        .fragments("Disallow: ")
+        .begin(ID::Value)
+        .fragments("/")
+        .end()
+        .fragments("\n")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "User-agent: *\r\nDisallow: /\r\n",
+      Events::new()
+        .begin(ID::Robots)
+        .begin(ID::Group)
+        .begin(ID::UserAgent)
+        .fragments("User-agent: ")
+        .begin(ID::Value)
+        .fragments("*")
+        .end()
+        .fragments("\r\n")
+        .end()
+        .begin(ID::Disallow)
+        .fragments("Disallow: ")
+        .begin(ID::Value)
+        .fragments("/")
+        .end()
+        .fragments("\r\n")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "USER-AGENT : *\nDISALLOW :/",
+      Events::new()
+        .begin(ID::Robots)
+        .begin(ID::Group)
+        .begin(ID::UserAgent)
+        .fragments("USER-AGENT : ")
+        .begin(ID::Value)
+        .fragments("*")
+        .end()
+        .fragments("\n")
+        .end()
+        .begin(ID::Disallow)
+        .fragments("DISALLOW :")
+        .begin(ID::Value)
+        .fragments("/")
+        .end()
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "Disallow: /\nUser-agent: *\n",
+      Events::new()
+        .begin(ID::Robots)
+        .begin(ID::Disallow)
+        .fragments("Disallow: ")
+        .begin(ID::Value)
+        .fragments("/")
+        .end()
+        .fragments("\n")
+        .end()
+        .begin(ID::Group)
+        .begin(ID::UserAgent)
+        .fragments("User-agent: ")
+        .begin(ID::Value)
+        .fragments("*")
+        .end()
+        .fragments("\n")
+        .end()
+        .end()
+        .end(),
+    ),
+    (
+      "User-agent: *\nAllow: /*.php$\nDisallow: /\n# end",
+      Events::new()
+        .begin(ID::Robots)
+        .begin(ID::Group)
+        .begin(ID::UserAgent)
+        .fragments("User-agent: ")
+        .begin(ID::Value)
+        .fragments("*")
+        .end()
+        .fragments("\n")
+        .end()
+        .begin(ID::Allow)
+        .fragments("Allow: ")
+        .begin(ID::Value)
+        .fragments("/*.php$")
+        .end()
+        .fragments("\n")
+        .end()
+        .begin(ID::Disallow)
+        .fragments("Disallow: ")
+        .begin(ID::Value)
+        .fragments("/")
+        .end()
+        .fragments("\n")
+        .end()
+        .end()
+        .begin(ID::Comment)
+        .fragments("# end")
+        .end()
+        .end(),
+    ),
+    (
+      "Sitemap: https://example.com/a.xml",
+      Events::new()
+        .begin(ID::Robots)
+        .begin(ID::Sitemap)
+        .fragments("Sitemap: ")
+        .begin(ID::Value)
+        .fragments("https://example.com/a.xml")
+        .end()
+        .end()
+        .end(),
+    ),
+  ] {
+    expected.assert_eq(&parse(&schema(), ID::Robots, text).unwrap());
+  }
+}
+
+#[test]
+fn invalid_robots() {
+  for (text, position) in [
+    ("User-agent", 10),
+    ("User-agent *", 11),
+    ("Disallow /", 9),
+    (": value", 0),
+    ("User agent: *", 5),
+    ("User-agent: *\n  Disallow", 24),
+  ] {
+    match parse(&schema(), ID::Robots, text) {
+      Err(Error::Unmatched(detail)) => assert_eq!(position, detail.location.chars, "{:?}", text),
+      unexpected => panic!("{:?}: {:?}", text, unexpected),
+    }
+  }
+}
+
+#[test]
+fn urls() {
+  let events =
+    parse(&url_list(), ID::UrlList, "https://example.com/\n\n http://example.com/a?b=c \nhttps://example.com/b")
+      .unwrap();
+  Events::new()
+    .begin(ID::UrlList)
+    .begin(ID::Url)
+    .fragments("https://example.com/")
+    .end()
+    .fragments("\n\n ")
+    .begin(ID::Url)
+    .fragments("http://example.com/a?b=c")
+    .end()
+    .fragments(" \n")
+    .begin(ID::Url)
+    .fragments("https://example.com/b")
+    .end()
+    .end()
+    .assert_eq(&events);
+
+  for (text, expected) in [
+    ("", Events::new()),
+    ("\n", Events::new().begin(ID::UrlList).fragments("\n").end()),
+    ("https://a\n", Events::new().begin(ID::UrlList).begin(ID::Url).fragments("https://a").end().fragments("\n").end()),
+    (
+      "https://a\r\nhttps://b\r\n",
+      Events::new()
+        .begin(ID::UrlList)
+        .begin(ID::Url)
+        .fragments("https://a")
+        .end()
+        .fragments("\r\n")
+        .begin(ID::Url)
+        .fragments("https://b")
+        .end()
+        .fragments("\r\n")
+        .end(),
+    ),
+  ] {
+    expected.assert_eq(&parse(&url_list(), ID::UrlList, text).unwrap());
+  }
+  for (text, position) in [("example.com", 0), ("ftp://example.com", 0), ("https://", 8), ("https://a https://b", 10)] {
+    match parse(&url_list(), ID::UrlList, text) {
+      Err(Error::Unmatched(detail)) => assert_eq!(position, detail.location.chars, "{:?}", text),
+      unexpected => panic!("{:?}: {:?}", text, unexpected),
+    }
+  }
+}
+
+fn parse(schema: &Schema<ID, char>, id: ID, text: &str) -> crate::Result<char, Vec<Event<ID, char>>> {
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(schema, id, handler)?.ignore_events_for(&[ID::WS]);
+  parser.push_str(text)?;
+  parser.finish()?;
+  Ok(Event::normalize(&events))
+}
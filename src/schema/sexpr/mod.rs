@@ -0,0 +1,68 @@
+use crate::schema::chars::{ch, line, none_of_chars, one_of_chars};
+use crate::schema::{id, MatchResult, Schema, Syntax};
+use crate::Result;
+use std::fmt::Display;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ID {
+  SExpr,
+  List,
+  Atom,
+  String,
+  Escape,
+  Comment,
+  WS,
+}
+
+impl Display for ID {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// The S-expressions, a sequence of data each of which is an [`ID::Atom`], an [`ID::String`] or a parenthesized
+/// [`ID::List`] of data, such as `(define (square x) (* x x))`.
+///
+/// - An [`ID::Atom`] is one or more characters other than whitespaces, `(`, `)`, `"` and `;`, so `a(b)` is an atom
+///   followed by a list without a whitespace.
+/// - An [`ID::String`] is enclosed in `"` and may contain the [`ID::Escape`]s such as `\"` or `\\`, and may span
+///   multiple lines.
+/// - An [`ID::Comment`] begins with `;` and continues to the end of the line.
+///
+/// See `examples/sexpr.rs` for building a nested value from the events.
+///
+pub fn schema() -> Schema<ID, char> {
+  let ws = || id(ID::WS) * (0..=1);
+  let space = || ws() & ((id(ID::Comment) & ws()) * (0..));
+  let datum = || id(ID::Atom) | id(ID::String) | id(ID::List);
+  Schema::new("S-EXPRESSION")
+    .define(ID::SExpr, space() & ((datum() & space()) * (0..)))
+    .define(ID::List, ch('(') & space() & ((datum() & space()) * (0..)) & ch(')'))
+    .define(ID::Atom, atom())
+    .define(ID::String, ch('"') & ((none_of_chars("\"\\") | id(ID::Escape)) * (0..)) & ch('"'))
+    .define(ID::Escape, ch('\\') & none_of_chars(""))
+    .define(ID::Comment, ch(';') & line())
+    .define(ID::WS, one_of_chars(" \t\r\n") * (1..))
+}
+
+/// Whether `ch` delimits atoms.
+///
+fn is_delimiter(ch: char) -> bool {
+  ch.is_whitespace() || "()\";".contains(ch)
+}
+
+/// The characters of an atom, as long as possible.
+///
+fn atom<ID>() -> Syntax<ID, char> {
+  Syntax::from_fn("ATOM", |values: &[char]| -> Result<char, MatchResult> {
+    Ok(match values.iter().position(|ch| is_delimiter(*ch)) {
+      None if values.is_empty() => MatchResult::UnmatchAndCanAcceptMore,
+      None => MatchResult::MatchAndCanAcceptMore(values.len()),
+      Some(0) => MatchResult::Unmatch,
+      Some(length) => MatchResult::Match(length),
+    })
+  })
+}
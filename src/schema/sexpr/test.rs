@@ -0,0 +1,275 @@
+use super::{schema, ID};
+use crate::parser::{test::Events, Context, Event};
+use crate::Error;
+
+#[test]
+fn nested_lists() {
+  let events = parse("; square\n(define (sq x)\n  (* x x)) \"a\\\"b\"").unwrap();
+  Events::new()
+    .begin(ID::SExpr)
+    .begin(ID::Comment)
+    .fragments("; square")
+    .end()
+    .fragments("\n")
+    .begin(ID::List)
+    .fragments("(")
+    .begin(ID::Atom)
+    .fragments("define")
+    .end()
+    .fragments(" ")
+    .begin(ID::List)
+    .fragments("(")
+    .begin(ID::Atom)
+    .fragments("sq")
+    .end()
+    .fragments(" ")
+    .begin(ID::Atom)
+    .fragments("x")
+    .end()
+    .fragments(")")
+    .end()
+    .fragments("\n  ")
+    .begin(ID::List)
+    .fragments("(")
+    .begin(ID::Atom)
+    .fragments("*")
+    .end()
+    .fragments(" ")
+    .begin(ID::Atom)
+    .fragments("x")
+    .end()
+    .fragments(" ")
+    .begin(ID::Atom)
+    .fragments("x")
+    .end()
+    .fragments(")")
+    .end()
+    .fragments(")")
+    .end()
+    .fragments(" ")
+    .begin(ID::String)
+    .fragments("\"a")
+    .begin(ID::Escape)
+    .fragments("\\\"")
+    .end()
+    .fragments("b\"")
+    .end()
+    .end()
+    .assert_eq(&events);
+}
+
+#[test]
+fn valid_expressions() {
+  for (text, expected) in [
+    ("", Events::new()),
+    (" ", Events::new().begin(ID::SExpr).fragments(" ").end()),
+    ("; only a comment", Events::new().begin(ID::SExpr).begin(ID::Comment).fragments("; only a comment").end().end()),
+    ("atom", Events::new().begin(ID::SExpr).begin(ID::Atom).fragments("atom").end().end()),
+    ("()", Events::new().begin(ID::SExpr).begin(ID::List).fragments("()").end().end()),
+    (
+      "(())",
+      Events::new()
+        .begin(ID::SExpr)
+        .begin(ID::List)
+        .fragments("(")
+        .begin(ID::List)
+        .fragments("()")
+        .end()
+        .fragments(")")
+        .end()
+        .end(),
+    ),
+    (
+      "( a  b\tc\n)",
+      Events::new()
+        .begin(ID::SExpr)
+        .begin(ID::List)
+        .fragments("( ")
+        .begin(ID::Atom)
+        .fragments("a")
+        .end()
+        .fragments("  ")
+        .begin(ID::Atom)
+        .fragments("b")
+        .end()
+        .fragments("\t")
+        .begin(ID::Atom)
+        .fragments("c")
+        .end()
+        .fragments("\n)")
+        .end()
+        .end(),
+    ),
+    (
+      "a(b)c",
+      Events::new()
+        .begin(ID::SExpr)
+        .begin(ID::Atom)
+        .fragments("a")
+        .end()
+        .begin(ID::List)
+        .fragments("(")
+        .begin(ID::Atom)
+        .fragments("b")
+        .end()
+        .fragments(")")
+        .end()
+        .begin(ID::Atom)
+        .fragments("c")
+        .end()
+        .end(),
+    ),
+    (
+      "(a\"b\"c)",
+      Events::new()
+        .begin(ID::SExpr)
+        .begin(ID::List)
+        .fragments("(")
+        .begin(ID::Atom)
+        .fragments("a")
+        .end()
+        .begin(ID::String)
+        .fragments("\"b\"")
+        .end()
+        .begin(ID::Atom)
+        .fragments("c")
+        .end()
+        .fragments(")")
+        .end()
+        .end(),
+    ),
+    (
+      "(a ; comment\n b) ; trailing",
+      Events::new()
+        .begin(ID::SExpr)
+        .begin(ID::List)
+        .fragments("(")
+        .begin(ID::Atom)
+        .fragments("a")
+        .end()
+        .fragments(" ")
+        .begin(ID::Comment)
+        .fragments("; comment")
+        .end()
+        .fragments("\n ")
+        .begin(ID::Atom)
+        .fragments("b")
+        .end()
+        .fragments(")")
+        .end()
+        .fragments(" ")
+        .begin(ID::Comment)
+        .fragments("; trailing")
+        .end()
+        .end(),
+    ),
+    ("\"multi\nline\"", Events::new().begin(ID::SExpr).begin(ID::String).fragments("\"multi\nline\"").end().end()),
+    (
+      "(1 2.5 -3 #t nil :key |x|)",
+      Events::new()
+        .begin(ID::SExpr)
+        .begin(ID::List)
+        .fragments("(")
+        .begin(ID::Atom)
+        .fragments("1")
+        .end()
+        .fragments(" ")
+        .begin(ID::Atom)
+        .fragments("2.5")
+        .end()
+        .fragments(" ")
+        .begin(ID::Atom)
+        .fragments("-3")
+        .end()
+        .fragments(" ")
+        .begin(ID::Atom)
+        .fragments("#t")
+        .end()
+        .fragments(" ")
+        .begin(ID::Atom)
+        .fragments("nil")
+        .end()
+        .fragments(" ")
+        .begin(ID::Atom)
+        .fragments(":key")
+        .end()
+        .fragments(" ")
+        .begin(ID::Atom)
+        .fragments("|x|")
+        .end()
+        .fragments(")")
+        .end()
+        .end(),
+    ),
+    (
+      "((lambda (x) x) 'y)",
+      Events::new()
+        .begin(ID::SExpr)
+        .begin(ID::List)
+        .fragments("(")
+        .begin(ID::List)
+        .fragments("(")
+        .begin(ID::Atom)
+        .fragments("lambda")
+        .end()
+        .fragments(" ")
+        .begin(ID::List)
+        .fragments("(")
+        .begin(ID::Atom)
+        .fragments("x")
+        .end()
+        .fragments(")")
+        .end()
+        .fragments(" ")
+        .begin(ID::Atom)
+        .fragments("x")
+        .end()
+        .fragments(")")
+        .end()
+        .fragments(" ")
+        .begin(ID::Atom)
+        .fragments("'y")
+        .end()
+        .fragments(")")
+        .end()
+        .end(),
+    ),
+  ] {
+    expected.assert_eq(&parse(text).unwrap());
+  }
+  for (text, position) in
+    [("(", 1), (")", 0), ("(a))", 3), ("((a)", 4), ("\"abc", 4), ("\"abc\\\"", 6), ("(a ; comment)", 13)]
+  {
+    match parse(text) {
+      Err(Error::Unmatched(detail)) => assert_eq!(position, detail.location.chars, "{:?}", text),
+      unexpected => panic!("{:?}: {:?}", text, unexpected),
+    }
+  }
+}
+
+#[test]
+fn fragmented_input() {
+  let text = "(define (f x) \"x\\\\y\") ; end";
+  let expected = parse(text).unwrap();
+  for chunk in 1..text.len() {
+    let schema = schema();
+    let mut events = Vec::new();
+    let handler = |e: &Event<_, _>| events.push(e.clone());
+    let mut parser = Context::new(&schema, ID::SExpr, handler).unwrap().ignore_events_for(&[ID::WS]);
+    for part in text.chars().collect::<Vec<_>>().chunks(chunk) {
+      parser.push_seq(part).unwrap();
+    }
+    parser.finish().unwrap();
+    assert_eq!(expected, Event::normalize(&events), "{}", chunk);
+  }
+}
+
+fn parse(text: &str) -> crate::Result<char, Vec<Event<ID, char>>> {
+  let schema = schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, ID::SExpr, handler)?.ignore_events_for(&[ID::WS]);
+  parser.push_str(text)?;
+  parser.finish()?;
+  Ok(Event::normalize(&events))
+}
@@ -0,0 +1,90 @@
+use crate::parser::{Context, Event, EventKind};
+use crate::schema::chars::{ch, none_of_chars, one_of_chars};
+use crate::schema::{id, Schema};
+use crate::Result;
+use std::fmt::Display;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ID {
+  Line,
+  Word,
+  SingleQuoted,
+  DoubleQuoted,
+  Escaped,
+  WS,
+}
+
+impl Display for ID {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// The words of a command line split by whitespaces, as the POSIX shell does without expansions. A word may contain
+/// `'single-quoted'` and `"double-quoted"` strings, and characters escaped by a backslash. See [`decode()`] for the
+/// text of a [`ID::Word`] event, or [`split()`] for the words.
+/// <https://pubs.opengroup.org/onlinepubs/9699919799/utilities/V3_chap02.html#tag_18_02>
+///
+pub fn schema() -> Schema<ID, char> {
+  use ID::*;
+  Schema::new("SHELLWORDS")
+    .define(Line, (id(WS) * (0..=1)) & ((id(Word) & ((id(WS) & id(Word)) * (0..)) & (id(WS) * (0..=1))) * (0..=1)))
+    .define(Word, (id(SingleQuoted) | id(DoubleQuoted) | id(Escaped) | none_of_chars(" \t\r\n'\"\\")) * (1..))
+    .define(SingleQuoted, ch('\'') & (none_of_chars("'") * (0..)) & ch('\''))
+    .define(DoubleQuoted, ch('"') & ((id(Escaped) | none_of_chars("\"\\")) * (0..)) & ch('"'))
+    .define(Escaped, ch('\\') & none_of_chars(""))
+    .define(WS, one_of_chars(" \t\r\n") * (1..))
+}
+
+/// Remove the quotes and the escaping backslashes of the `word`. A backslash followed by a newline is removed as a
+/// line continuation, and a backslash in a double-quoted string escapes only `$`, `` ` ``, `"`, `\` and a newline.
+///
+pub fn decode(word: &str) -> String {
+  let mut decoded = String::with_capacity(word.len());
+  let mut quote = None;
+  let mut chars = word.chars();
+  while let Some(ch) = chars.next() {
+    match (quote, ch) {
+      (None, '\'' | '"') => quote = Some(ch),
+      (Some(q), _) if q == ch => quote = None,
+      (Some('\''), _) => decoded.push(ch),
+      (_, '\\') => match (quote, chars.next()) {
+        (_, Some('\n')) | (_, None) => (),
+        (None, Some(escaped)) => decoded.push(escaped),
+        (_, Some(escaped)) if "$`\"\\".contains(escaped) => decoded.push(escaped),
+        (_, Some(escaped)) => {
+          decoded.push('\\');
+          decoded.push(escaped);
+        }
+      },
+      _ => decoded.push(ch),
+    }
+  }
+  decoded
+}
+
+/// Parse the command `line` and return its decoded words.
+///
+pub fn split(line: &str) -> Result<char, Vec<String>> {
+  let schema = schema();
+  let mut words = Vec::new();
+  let mut word: Option<String> = None;
+  let handler = |e: &Event<ID, char>| match &e.kind {
+    EventKind::Begin(ID::Word) => word = Some(String::new()),
+    EventKind::Fragments(chars) => {
+      if let Some(word) = &mut word {
+        word.extend(chars);
+      }
+    }
+    EventKind::End(ID::Word) => words.push(decode(&word.take().unwrap_or_default())),
+    _ => (),
+  };
+  let mut parser =
+    Context::new(&schema, ID::Line, handler)?.ignore_events_for(&[ID::SingleQuoted, ID::DoubleQuoted, ID::Escaped]);
+  parser.push_str(line)?;
+  parser.finish()?;
+  Ok(words)
+}
@@ -0,0 +1,62 @@
+use super::{decode, schema, split, ID};
+use crate::parser::{test::Events, Context, Event};
+
+#[test]
+fn words() {
+  let schema = schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, ID::Line, handler).unwrap();
+  parser.push_str(" a\\ b 'c d'\"e\"").unwrap();
+  parser.finish().unwrap();
+  Events::new()
+    .begin(ID::Line)
+    .begin(ID::WS)
+    .fragments(" ")
+    .end()
+    .begin(ID::Word)
+    .fragments("a")
+    .begin(ID::Escaped)
+    .fragments("\\ ")
+    .end()
+    .fragments("b")
+    .end()
+    .begin(ID::WS)
+    .fragments(" ")
+    .end()
+    .begin(ID::Word)
+    .begin(ID::SingleQuoted)
+    .fragments("'c d'")
+    .end()
+    .begin(ID::DoubleQuoted)
+    .fragments("\"e\"")
+    .end()
+    .end()
+    .end()
+    .assert_eq(&Event::normalize(&events));
+}
+
+#[test]
+fn split_words() {
+  let words = |ws: &[&str]| ws.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+  for (line, expected) in [
+    ("", words(&[])),
+    ("  \t", words(&[])),
+    ("ls -la  /tmp ", words(&["ls", "-la", "/tmp"])),
+    ("echo 'it''s' \"a \\\"b\\\" \\c\" d\\ e", words(&["echo", "its", "a \"b\" \\c", "d e"])),
+    ("'' \"\" x", words(&["", "", "x"])),
+    ("a\\\nb \"$HOME\" '\\n'", words(&["ab", "$HOME", "\\n"])),
+  ] {
+    assert_eq!(expected, split(line).unwrap(), "{:?}", line);
+  }
+  assert!(split("echo 'unclosed").is_err());
+  assert!(split("echo \"unclosed").is_err());
+  assert!(split("trailing\\").is_err());
+}
+
+#[test]
+fn decode_word() {
+  assert_eq!("a b", decode("a\\ b"));
+  assert_eq!("\\$x", decode("'\\$x'"));
+  assert_eq!("$x", decode("\"\\$x\""));
+}
@@ -0,0 +1,107 @@
+//! Symbols for the second-stage parsers that handle token sequences produced by a lexer. [`Spanned`] holds a token
+//! with the byte range where it appeared in the original source, so the errors reported by the parser point to the
+//! original source coordinates.
+//!
+use crate::schema::{MatchResult, Symbol, Syntax};
+use crate::Result;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+#[cfg(test)]
+mod test;
+
+/// The bounds required for a token value to be wrapped by [`Spanned`].
+///
+pub trait Token: 'static + Copy + Send + Sync + Hash + PartialEq + Eq + Display + Debug {}
+
+impl<T: 'static + Copy + Send + Sync + Hash + PartialEq + Eq + Display + Debug> Token for T {}
+
+/// `Spanned` is a token `value` with the byte range `begin..end` in the original source.
+///
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Spanned<T: Token> {
+  pub value: T,
+  pub begin: u64,
+  pub end: u64,
+}
+
+impl<T: Token> Spanned<T> {
+  pub fn new(value: T, begin: u64, end: u64) -> Self {
+    debug_assert!(begin <= end);
+    Self { value, begin, end }
+  }
+}
+
+impl<T: Token> Display for Spanned<T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    Display::fmt(&self.value, f)
+  }
+}
+
+impl<T: Token> Symbol for Spanned<T> {
+  type Location = Location;
+  const SAMPLING_UNIT_AT_ERROR: usize = 4;
+
+  fn debug_symbols(values: &[Self]) -> String {
+    values.iter().map(|s| s.value.to_string()).collect::<Vec<_>>().join(" ")
+  }
+}
+
+/// The location in a token sequence. `tokens` is the number of tokens consumed, and `offset` is the byte offset in
+/// the original source at the beginning of the token at this location, as far as it's known. A location that has only
+/// been incremented points to the end of the last consumed token, until it's moved to the next one by
+/// [`seek()`](crate::schema::Location::seek), as the parser does for the errors and the Begin and Fragments events.
+///
+#[derive(Default, Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub struct Location {
+  pub tokens: u64,
+  pub offset: u64,
+}
+
+impl<T: Token> crate::schema::Location<Spanned<T>> for Location {
+  fn position(&self) -> u64 {
+    self.tokens
+  }
+  fn increment_with(&mut self, item: Spanned<T>) {
+    self.tokens += 1;
+    self.offset = item.end;
+  }
+  fn seek(&mut self, next: &Spanned<T>) {
+    self.offset = next.begin;
+  }
+  fn byte_offset(&self) -> Option<u64> {
+    Some(self.offset)
+  }
+}
+
+impl Display for Location {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "@{}", self.offset)
+  }
+}
+
+/// `value` matches a token whose value is equal to the specified one, regardless of its span.
+///
+pub fn value<ID, T: Token>(value: T) -> Syntax<ID, Spanned<T>> {
+  value_with(&value.to_string(), move |v| *v == value)
+}
+
+pub fn one_of_values<ID, T: Token>(values: &[T]) -> Syntax<ID, Spanned<T>> {
+  let label = values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join("|");
+  let values = values.to_vec();
+  value_with(&label, move |v| values.contains(v))
+}
+
+pub fn value_with<ID, T: Token, F: Fn(&T) -> bool + Send + Sync + 'static>(
+  label: &str, f: F,
+) -> Syntax<ID, Spanned<T>> {
+  Syntax::from_fn(label, move |values: &[Spanned<T>]| -> Result<Spanned<T>, MatchResult> {
+    if values.is_empty() {
+      Ok(MatchResult::UnmatchAndCanAcceptMore)
+    } else if f(&values[0].value) {
+      Ok(MatchResult::Match(1))
+    } else {
+      Ok(MatchResult::Unmatch)
+    }
+  })
+}
@@ -0,0 +1,203 @@
+use crate::parser::{Context, Engine, Event, EventKind};
+use crate::schema::spanned::{one_of_values, value, Location, Spanned};
+use crate::schema::{id, Location as L, MatchResult, Schema, Symbol, Syntax};
+use crate::Error;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+enum Token {
+  Num,
+  Plus,
+  Minus,
+}
+
+impl Display for Token {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(match self {
+      Token::Num => "NUM",
+      Token::Plus => "+",
+      Token::Minus => "-",
+    })
+  }
+}
+
+#[test]
+fn spanned_location() {
+  let mut l = Location::default();
+  assert_eq!(0, L::<Spanned<Token>>::position(&l));
+  l.increment_with(Spanned::new(Token::Num, 0, 3));
+  assert_eq!(Location { tokens: 1, offset: 3 }, l);
+  l.increment_with(Spanned::new(Token::Plus, 4, 5));
+  assert_eq!(Location { tokens: 2, offset: 5 }, l);
+  assert_eq!(2, L::<Spanned<Token>>::position(&l));
+  assert_eq!("@5", l.to_string());
+}
+
+#[test]
+fn spanned_debug_symbols() {
+  let tokens = [Spanned::new(Token::Num, 0, 3), Spanned::new(Token::Plus, 4, 5)];
+  assert_eq!("NUM +", Symbol::debug_symbols(&tokens));
+  assert_eq!("NUM", Symbol::debug_symbol(tokens[0]));
+  assert_eq!("+", tokens[1].to_string());
+}
+
+#[test]
+fn second_stage_parser() {
+  // 12 + 3 - 456
+  let tokens = [
+    Spanned::new(Token::Num, 0, 2),
+    Spanned::new(Token::Plus, 3, 4),
+    Spanned::new(Token::Num, 5, 6),
+    Spanned::new(Token::Minus, 7, 8),
+    Spanned::new(Token::Num, 9, 12),
+  ];
+  let schema = Schema::new("Expr")
+    .define("EXPR", id("TERM") & ((one_of_values(&[Token::Plus, Token::Minus]) & id("TERM")) * (0..)))
+    .define("TERM", value(Token::Num));
+
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, "EXPR", handler).unwrap();
+  parser.push_seq(&tokens).unwrap();
+  parser.finish().unwrap();
+  let terms = events
+    .iter()
+    .filter_map(|e| match &e.kind {
+      EventKind::Fragments(f) if f[0].value == Token::Num => Some((e.location.offset, f[0].begin, f[0].end)),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(vec![(0, 0, 2), (5, 5, 6), (9, 9, 12)], terms);
+  let begins = events
+    .iter()
+    .filter_map(|e| match &e.kind {
+      EventKind::Begin(id) => Some((*id, e.location.offset)),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(vec![("EXPR", 0), ("TERM", 0), ("TERM", 5), ("TERM", 9)], begins);
+  let ends = events
+    .iter()
+    .filter_map(|e| match &e.kind {
+      EventKind::End(id) => Some((*id, e.location.offset)),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(vec![("TERM", 2), ("TERM", 6), ("TERM", 12), ("EXPR", 12)], ends);
+
+  // 12 + + 456
+  let tokens = [
+    Spanned::new(Token::Num, 0, 2),
+    Spanned::new(Token::Plus, 3, 4),
+    Spanned::new(Token::Plus, 5, 6),
+    Spanned::new(Token::Num, 7, 10),
+  ];
+  let mut parser = Context::new(&schema, "EXPR", |_: &Event<_, _>| ()).unwrap();
+  match parser.push_seq(&tokens).and_then(|_| parser.finish()) {
+    Err(Error::Unmatched(detail)) => {
+      assert_eq!(Location { tokens: 2, offset: 5 }, detail.location);
+      assert_eq!(vec!["[NUM]"], detail.expecteds);
+      assert_eq!("[+]NUM...", detail.actual);
+    }
+    unexpected => panic!("{:?}", unexpected),
+  }
+  let mut parser = Context::new(&schema, "EXPR", |_: &Event<_, _>| ()).unwrap().with_engine(Engine::Earley);
+  match parser.push_seq(&tokens).and_then(|_| parser.finish()) {
+    Err(Error::Unmatched(detail)) => assert_eq!(Location { tokens: 2, offset: 5 }, detail.location),
+    unexpected => panic!("{:?}", unexpected),
+  }
+}
+
+#[test]
+fn reinterpreted_tokens_in_rule() {
+  // the operators of EXPR are all additions, while a minus in TERM is still the sign
+  let schema = Schema::new("Expr")
+    .reinterpret(
+      "EXPR",
+      |t: Spanned<Token>| if t.value == Token::Minus { Spanned { value: Token::Plus, ..t } } else { t },
+    )
+    .define("EXPR", id("TERM") & ((value(Token::Plus) & id("TERM")) * (0..)))
+    .define("TERM", (value(Token::Minus) * (0..=1)) & value(Token::Num));
+
+  // 1 - 2 - -3
+  let tokens = [Token::Num, Token::Minus, Token::Num, Token::Minus, Token::Minus, Token::Num]
+    .iter()
+    .enumerate()
+    .map(|(i, t)| Spanned::new(*t, i as u64, i as u64 + 1))
+    .collect::<Vec<_>>();
+  let mut events = Vec::new();
+  let mut parser = Context::new(&schema, "EXPR", |e: &Event<_, _>| events.push(e.clone())).unwrap();
+  parser.push_seq(&tokens).unwrap();
+  parser.finish().unwrap();
+  let fragments = Event::normalize(&events)
+    .iter()
+    .filter_map(|e| match &e.kind {
+      EventKind::Fragments(f) => Some(f.iter().map(|t| t.value).collect::<Vec<_>>()),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+  use Token::*;
+  assert_eq!(vec![vec![Num], vec![Minus], vec![Num], vec![Minus], vec![Minus, Num]], fragments);
+
+  let schema = Schema::new("Expr")
+    .define("EXPR", id("TERM") & ((value(Token::Plus) & id("TERM")) * (0..)))
+    .define("TERM", (value(Token::Minus) * (0..=1)) & value(Token::Num));
+  let mut parser = Context::new(&schema, "EXPR", |_: &Event<_, _>| ()).unwrap();
+  assert!(parser.push_seq(&tokens).is_err());
+}
+
+#[test]
+fn reinterpreted_twice_replaces_mapping() {
+  let swap = |t: Spanned<Token>| match t.value {
+    Token::Plus => Spanned { value: Token::Minus, ..t },
+    Token::Minus => Spanned { value: Token::Plus, ..t },
+    _ => t,
+  };
+  // 1 - 2 is read as 1 + 2 by a single swap, and would be read as it is if the swaps were composed
+  let tokens = [Token::Num, Token::Minus, Token::Num]
+    .iter()
+    .enumerate()
+    .map(|(i, t)| Spanned::new(*t, i as u64, i as u64 + 1))
+    .collect::<Vec<_>>();
+  let define = |schema: Schema<&'static str, Spanned<Token>>| {
+    schema.define("EXPR", value(Token::Num) & ((value(Token::Plus) & value(Token::Num)) * (0..)))
+  };
+  for schema in [
+    define(Schema::new("Expr").reinterpret("EXPR", swap).reinterpret("EXPR", swap)),
+    define(Schema::new("Expr").reinterpret("EXPR", swap)).reinterpret("EXPR", swap),
+    define(Schema::new("Expr").reinterpret("EXPR", |t| t)).reinterpret("EXPR", swap),
+  ] {
+    let mut parser = Context::recognizer(&schema, "EXPR").unwrap();
+    parser.push_seq(&tokens).unwrap();
+    parser.finish().unwrap();
+  }
+}
+
+#[test]
+fn reinterpreted_symbols_mapped_lazily() {
+  // a term without a window is passed all the buffered tokens, of which only the leading ones are mapped
+  let (mapped, passed) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+  let max_passed = passed.clone();
+  let num = Syntax::from_fn("NUM", move |values: &[Spanned<Token>]| {
+    max_passed.fetch_max(values.len(), Ordering::Relaxed);
+    Ok(match values.first() {
+      Some(t) if t.value == Token::Num => MatchResult::Match(1),
+      Some(_) => MatchResult::Unmatch,
+      None => MatchResult::UnmatchAndCanAcceptMore,
+    })
+  });
+  let counter = mapped.clone();
+  let schema = Schema::new("Nums").define("NUMS", num * (0..)).reinterpret("NUMS", move |t: Spanned<Token>| {
+    counter.fetch_add(1, Ordering::Relaxed);
+    t
+  });
+
+  let tokens = (0..1000).map(|i| Spanned::new(Token::Num, i, i + 1)).collect::<Vec<_>>();
+  let mut parser = Context::recognizer(&schema, "NUMS").unwrap();
+  parser.push_seq(&tokens).unwrap();
+  parser.finish().unwrap();
+  assert!(passed.load(Ordering::Relaxed) <= 16);
+  assert!(mapped.load(Ordering::Relaxed) <= 16 * 1001, "{}", mapped.load(Ordering::Relaxed));
+}
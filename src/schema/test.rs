@@ -6,9 +6,9 @@ use crate::schema::{Schema, Symbol, Syntax};
 fn create_new_schema() {
   let schema = Schema::new("Foo").define("X", ascii_digit() * (1..=3)).define("Y", ascii_digit() * 4);
   assert_eq!("Foo", schema.name());
-  assert!(matches!(schema.get(&"X"), Some(_)));
-  assert!(matches!(schema.get(&"Y"), Some(_)));
-  assert!(matches!(schema.get(&"Z"), None));
+  assert!(schema.get(&"X").is_some());
+  assert!(schema.get(&"Y").is_some());
+  assert!(schema.get(&"Z").is_none());
   let mut names = schema.ids().map(|s| s.to_string()).collect::<Vec<_>>();
   names.sort();
   assert_eq!(2, names.len());
@@ -147,7 +147,7 @@ fn syntax_display() {
 
 #[test]
 fn item_for_char_debug_symbol() {
-  for (expected, sample) in vec![
+  for (expected, sample) in [
     ("'A'", 'A'),
     ("'\\0'", '\u{0}'),
     ("'\\u{1}'", '\u{1}'),
@@ -188,7 +188,7 @@ fn match_result() {
 
 #[test]
 fn item_for_char_debug_symbols() {
-  for (expected, sample) in vec![("", ""), ("ABC", "ABC"), ("A\\tB\\nC\\0", "A\tB\nC\0")].iter() {
+  for (expected, sample) in [("", ""), ("ABC", "ABC"), ("A\\tB\\nC\\0", "A\tB\nC\0")].iter() {
     let sample = (*sample).chars().collect::<Vec<_>>();
     assert_eq!(*expected, Symbol::debug_symbols(&sample));
   }
@@ -209,3 +209,176 @@ fn item_for_u8_to_sampling_debug() {
     }
   }
 }
+
+#[test]
+fn location_accessors_and_styles() {
+  use crate::schema::{bytes, chars, spanned, Location, LocationStyle};
+
+  let mut l = chars::Location::default();
+  l.increment_with_seq(&"aあ\nb".chars().collect::<Vec<_>>());
+  assert_eq!((Some(6), Some(1), Some(1)), (l.byte_offset(), l.line(), l.column()));
+  assert_eq!("(2,2)", l.to_string_with(LocationStyle::Display));
+  assert_eq!("4", l.to_string_with(LocationStyle::Position));
+  assert_eq!("2:2", l.to_string_with(LocationStyle::LineColumn));
+  assert_eq!("@6", l.to_string_with(LocationStyle::ByteOffset));
+
+  let mut l = bytes::Location::default();
+  l.increment_with_seq(b"abc");
+  assert_eq!((Some(3), None, None), (l.byte_offset(), l.line(), l.column()));
+  assert_eq!("3", l.to_string_with(LocationStyle::Position));
+  assert_eq!("@3", l.to_string_with(LocationStyle::LineColumn));
+  assert_eq!("@3", l.to_string_with(LocationStyle::ByteOffset));
+
+  let mut l = spanned::Location::default();
+  l.increment_with(spanned::Spanned { value: 'x', begin: 2, end: 5 });
+  fn accessors<L: Location<spanned::Spanned<char>>>(l: &L) -> (Option<u64>, Option<u64>, Option<u64>, String) {
+    (l.byte_offset(), l.line(), l.column(), l.to_string_with(LocationStyle::LineColumn))
+  }
+  assert_eq!((Some(5), None, None, String::from("@5")), accessors(&l));
+}
+
+#[test]
+fn schema_ids_in_definition_order() {
+  #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+  enum Rule {
+    Alpha,
+    Beta,
+    Gamma,
+  }
+  impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      write!(f, "{:?}", self)
+    }
+  }
+
+  let schema = Schema::new("Foo")
+    .define(Rule::Gamma, ascii_digit() * 2)
+    .define(Rule::Alpha, ascii_alphabetic())
+    .define(Rule::Beta, ascii_digit());
+  assert_eq!(vec![Rule::Gamma, Rule::Alpha, Rule::Beta], schema.ids().copied().collect::<Vec<_>>());
+  assert_eq!("Foo\n  Gamma := ASCII_DIGIT{2}\n  Alpha := ASCII_ALPHA\n  Beta := ASCII_DIGIT\n", schema.to_string());
+
+  let schema = schema.define(Rule::Gamma, ascii_digit());
+  assert_eq!(vec![Rule::Alpha, Rule::Beta, Rule::Gamma], schema.ids().copied().collect::<Vec<_>>());
+
+  let (id, syntax) = schema.get_by_name("Beta").unwrap();
+  assert_eq!(Rule::Beta, *id);
+  assert!(std::ptr::eq(schema.get(&Rule::Beta).unwrap(), syntax));
+  assert!(schema.get_by_name("Delta").is_none());
+}
+
+#[test]
+fn schema_profiles() {
+  use crate::schema::chars::ch;
+  use crate::schema::id;
+
+  fn schema(profile: Option<&str>) -> Schema<&'static str, char> {
+    let schema = Schema::new("Foo");
+    let schema = match profile {
+      Some(profile) => schema.with_profile(profile),
+      None => schema,
+    };
+    schema
+      .define("A", id("B") & (ch(';').profile("lenient") | ch('.').profile("strict") | ch('!')))
+      .define("B", (ascii_digit() * (1..)) & (ch(',').profile("lenient") * (0..=1)))
+      .define("C", ascii_alphabetic().profile("lenient"))
+  }
+
+  let strict = schema(Some("strict"));
+  assert_eq!(Some("strict"), strict.profile());
+  assert_eq!("Foo\n  \"A\" := B, '.' | '!'\n  \"B\" := ASCII_DIGIT+\n", strict.to_string());
+  assert!(strict.get(&"C").is_none());
+
+  let lenient = schema(Some("lenient"));
+  assert_eq!(
+    "Foo\n  \"A\" := B, ';' | '!'\n  \"B\" := ASCII_DIGIT+, ','?\n  \"C\" := ASCII_ALPHA\n",
+    lenient.to_string()
+  );
+
+  let all = schema(None);
+  assert_eq!(None, all.profile());
+  assert_eq!(3, all.ids().count());
+}
+
+#[test]
+fn repeated_sequences_are_not_merged() {
+  use crate::schema::chars::ch;
+  let syntax: Syntax<&str, char> = ((ch('a') & ch('b')) * (0..=1)) & ((ch('c') & ch('d')) * (0..=1));
+  assert_eq!("('a', 'b')?, ('c', 'd')?", syntax.to_string());
+  let syntax: Syntax<&str, char> = (ch('a') * (0..=1)) & ((ch('c') & ch('d')) * (0..=1));
+  assert_eq!("'a'?, ('c', 'd')?", syntax.to_string());
+  let syntax: Syntax<&str, char> = ((ch('a') | ch('b')) * (0..)) | (ch('c') * (0..));
+  assert_eq!("('a' | 'b')* | 'c'*", syntax.to_string());
+}
+
+#[test]
+fn schema_interns_identical_terms() {
+  use crate::schema::chars::{ch, token};
+  use crate::schema::{id, Primary};
+  use std::sync::Arc;
+
+  let schema = Schema::new("Foo")
+    .define("A", ch('"') & id("B") & ch('"'))
+    .define("B", (ascii_digit() * (1..)) & ch('\''))
+    .define("C", token("\"") & ascii_digit());
+  let terms = |id: &str| match &schema.get(&id).unwrap().primary {
+    Primary::Seq(seq) => seq
+      .iter()
+      .filter_map(|s| match &s.primary {
+        Primary::Term(term) => Some(term.matcher.clone()),
+        _ => None,
+      })
+      .collect::<Vec<_>>(),
+    _ => unreachable!(),
+  };
+  let (a, b, c) = (terms("A"), terms("B"), terms("C"));
+  assert!(Arc::ptr_eq(&a[0], &a[1]));
+  assert!(!Arc::ptr_eq(&a[0], &b[1]));
+  assert!(!Arc::ptr_eq(&a[0], &c[0]));
+  assert!(Arc::ptr_eq(&b[0], &c[1]));
+  assert_eq!(4, schema.terms.len());
+}
+
+#[test]
+fn syntax_clone_reused_across_definitions() {
+  use crate::parser::{Context, Event};
+  use crate::schema::chars::{ch, one_of_chars};
+  use crate::schema::id;
+
+  let hex = one_of_chars::<&str>("0123456789abcdef");
+  let schema = Schema::new("Foo")
+    .define("A", id("Escape") | id("Color"))
+    .define("Escape", ch('u') & (hex.clone() * 4))
+    .define("Color", ch('#') & (hex.clone() * 6) & ((hex * 2) * (0..=1)));
+  for (text, expected) in [("u00ff", true), ("#00ff00", true), ("#00ff0080", true), ("u0f", false), ("#00ff0", false)] {
+    let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+    let result = parser.push_str(text).and_then(|_| parser.finish());
+    assert_eq!(expected, result.is_ok(), "{:?}", text);
+  }
+
+  // a clone of a defined syntax can be combined again
+  let escape = schema.get(&"Escape").unwrap().clone();
+  assert_eq!(0, escape.id);
+  let _ = escape & ch(';');
+}
+
+#[test]
+fn schema_by_ref() {
+  use crate::parser::{Context, Event};
+  let schema = crate::schema::json::schema().map_ids(|id| id.to_string());
+  let borrowed = schema.by_ref();
+  assert_eq!(schema.to_string(), borrowed.to_string());
+
+  let text = r#"{"a": [1, -2.5e3, "x\ny"], "b": {"c": null}}"#;
+  let root = String::from("JsonText");
+  let mut expected = Vec::new();
+  let mut parser = Context::new(&schema, root.clone(), |e: &Event<_, _>| expected.push(e.clone())).unwrap();
+  parser.push_str(text).unwrap();
+  parser.finish().unwrap();
+
+  let mut actual = Vec::new();
+  let mut parser = Context::new(&borrowed, &root, |e: &Event<&String, _>| actual.push(e.to_owned_ids())).unwrap();
+  parser.push_str(text).unwrap();
+  parser.finish().unwrap();
+  assert_eq!(expected, actual);
+}
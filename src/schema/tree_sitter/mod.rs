@@ -0,0 +1,345 @@
+//! Export of a [`Schema`] as a [tree-sitter](https://tree-sitter.github.io/) `grammar.js`, so that a language
+//! prototyped with terp can be carried over to the editor integrations without rewriting the grammar by hand.
+//!
+//! ```rust
+//! use terp::schema::{Schema, id};
+//! use terp::schema::chars::{ch, ascii_digit};
+//! use terp::schema::tree_sitter::grammar_js;
+//!
+//! let schema = Schema::new("List")
+//!   .define("List", ch('[') & id("Item") & ((ch(',') & id("Item")) * (0..)) & ch(']'))
+//!   .define("Item", ascii_digit() * (1..));
+//! let js = grammar_js(&schema, &"List");
+//! assert!(js.contains(r#"list: $ => seq("[", $.item, repeat(seq(",", $.item)), "]"),"#));
+//! assert!(js.contains(r#"item: $ => repeat1(/[0-9]/),"#));
+//! ```
+//!
+//! The export is best-effort:
+//!
+//! * The rule names are converted to snake case, as tree-sitter requires.
+//! * The built-in terms such as [`ch()`](crate::schema::chars::ch) or
+//!   [`ascii_digit()`](crate::schema::chars::ascii_digit) become strings and regular expressions. The other terms, such
+//!   as those created by [`Syntax::from_fn()`](crate::schema::Syntax::from_fn), become `externals` to be implemented by
+//!   an external scanner.
+//! * The operators of [`Schema::define_pratt()`] become `prec`, `prec.left` and `prec.right` by their binding powers.
+//! * The `extras` are empty, since the whitespaces are a part of the syntax in terp.
+//! * The nullable rules and the ambiguities that terp resolves by evaluating all the paths may need `conflicts` or
+//!   rewriting, which tree-sitter reports when generating the parser.
+//!
+use crate::pipelines::json_string;
+use crate::schema::{Primary, Schema, Syntax, Term};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+#[cfg(test)]
+mod test;
+
+/// Generate the `grammar.js` of `schema` whose first rule is `start`.
+///
+pub fn grammar_js<ID: Ord + Clone + Display>(schema: &Schema<ID, char>, start: &ID) -> String {
+  let mut defs = schema.definitions();
+  if let Some(i) = defs.iter().position(|(id, _)| *id == start) {
+    let def = defs.remove(i);
+    defs.insert(0, def);
+  }
+
+  let mut exporter = Exporter { schema, externals: Vec::new(), names: HashMap::new() };
+  let rules = defs
+    .iter()
+    .map(|(id, syntax)| format!("    {}: $ => {},\n", rule_name(&id.to_string()), exporter.syntax(syntax)))
+    .collect::<String>();
+
+  let mut js = String::from("module.exports = grammar({\n");
+  js.push_str(&format!("  name: {},\n\n", json_string(&rule_name(schema.name()))));
+  if !exporter.externals.is_empty() {
+    js.push_str("  externals: $ => [\n");
+    for name in &exporter.externals {
+      js.push_str(&format!("    $.{},\n", name));
+    }
+    js.push_str("  ],\n\n");
+  }
+  js.push_str("  extras: $ => [],\n\n");
+  js.push_str("  rules: {\n");
+  js.push_str(&rules);
+  js.push_str("  }\n});\n");
+  js
+}
+
+struct Exporter<'s, ID> {
+  schema: &'s Schema<ID, char>,
+  /// The names of the external tokens in the order of their appearances.
+  externals: Vec<String>,
+  /// The names of the external tokens by their matchers, which are shared by the identical terms.
+  names: HashMap<*const (), String>,
+}
+
+impl<ID: Ord + Clone + Display> Exporter<'_, ID> {
+  fn syntax(&mut self, syntax: &Syntax<ID, char>) -> String {
+    let js = match &syntax.primary {
+      Primary::Term(term) => self.term(term),
+      Primary::Alias(id) => format!("$.{}", rule_name(&id.to_string())),
+      Primary::Seq(seq) => seq_of(seq.iter().map(|s| self.syntax(s)).collect()),
+      Primary::Or(branches) => {
+        let branches = branches.iter().map(|branch| self.branch(branch)).collect::<Vec<_>>();
+        if branches.len() == 1 {
+          branches.into_iter().next().unwrap()
+        } else {
+          format!("choice({})", branches.join(", "))
+        }
+      }
+    };
+    repeat(js, &syntax.repetition)
+  }
+
+  /// The branch of a choice, with its precedence if it's an operator of [`Schema::define_pratt()`].
+  ///
+  fn branch(&mut self, branch: &Syntax<ID, char>) -> String {
+    let js = self.syntax(branch);
+    let left = self.schema.left_binding(branch);
+    let right = match &branch.primary {
+      Primary::Seq(seq) => seq.last().and_then(|operand| self.schema.right_binding(operand)),
+      _ => None,
+    };
+    match (left, right) {
+      (Some(left), Some(right)) if right > left => format!("prec.left({}, {})", left, js),
+      (Some(left), Some(_)) => format!("prec.right({}, {})", left, js),
+      (Some(power), None) | (None, Some(power)) => format!("prec({}, {})", power, js),
+      (None, None) => js,
+    }
+  }
+
+  fn term(&mut self, term: &Term<char>) -> String {
+    if let Some(js) = term.signature.as_deref().and_then(literal) {
+      return js;
+    }
+    let matcher = Arc::as_ptr(&term.matcher) as *const ();
+    if let Some(name) = self.names.get(&matcher) {
+      return format!("$.{}", name);
+    }
+    let base = format!("term_{}", rule_name(&term.label));
+    let mut name = base.clone();
+    let mut i = 1;
+    while self.externals.contains(&name) {
+      i += 1;
+      name = format!("{}_{}", base, i);
+    }
+    self.externals.push(name.clone());
+    self.names.insert(matcher, name.clone());
+    format!("$.{}", name)
+  }
+}
+
+fn seq_of(items: Vec<String>) -> String {
+  if items.len() == 1 {
+    items.into_iter().next().unwrap()
+  } else {
+    format!("seq({})", items.join(", "))
+  }
+}
+
+fn repeat(js: String, repetition: &RangeInclusive<usize>) -> String {
+  let (min, max) = (*repetition.start(), *repetition.end());
+  match (min, max) {
+    (1, 1) => js,
+    (0, 0) => String::from("blank()"),
+    (0, 1) => format!("optional({})", js),
+    (0, usize::MAX) => format!("repeat({})", js),
+    (_, usize::MAX) => {
+      let mut items = vec![js.clone(); min - 1];
+      items.push(format!("repeat1({})", js));
+      seq_of(items)
+    }
+    _ => {
+      // the optional iterations are nested so that the same input isn't split into them in several ways
+      let mut optional = None;
+      for _ in min..max {
+        optional = Some(match optional {
+          None => format!("optional({})", js),
+          Some(inner) => format!("optional(seq({}, {}))", js, inner),
+        });
+      }
+      let mut items = vec![js; min];
+      items.extend(optional);
+      seq_of(items)
+    }
+  }
+}
+
+/// The snake case of `name` that is available as a rule name of tree-sitter, e.g., `JsonText` to `json_text`.
+///
+fn rule_name(name: &str) -> String {
+  let chars = name.chars().collect::<Vec<_>>();
+  let mut snake = String::with_capacity(name.len() + 4);
+  for (i, ch) in chars.iter().enumerate() {
+    if ch.is_ascii_alphanumeric() {
+      let prev = if i > 0 { Some(chars[i - 1]) } else { None };
+      let next = chars.get(i + 1);
+      let boundary = ch.is_ascii_uppercase()
+        && match prev {
+          Some(prev) if prev.is_ascii_lowercase() || prev.is_ascii_digit() => true,
+          Some(prev) if prev.is_ascii_uppercase() => next.map(|n| n.is_ascii_lowercase()).unwrap_or(false),
+          _ => false,
+        };
+      if boundary && !snake.is_empty() && !snake.ends_with('_') {
+        snake.push('_');
+      }
+      snake.push(ch.to_ascii_lowercase());
+    } else if !snake.is_empty() && !snake.ends_with('_') {
+      snake.push('_');
+    }
+  }
+  let snake = snake.trim_end_matches('_');
+  match snake.chars().next() {
+    None => String::from("r"),
+    Some(ch) if ch.is_ascii_digit() => format!("r_{}", snake),
+    _ => snake.to_string(),
+  }
+}
+
+// ---------------------------------
+
+/// The string or regular expression of the term identified by `signature`, if it's one of the built-in terms.
+///
+fn literal(signature: &str) -> Option<String> {
+  match signature {
+    "newline" => return Some(String::from(r"/\r?\n|\r/")),
+    "line" => return Some(String::from(r"optional(/[^\r\n]+/)")),
+    _ => (),
+  }
+  let (name, args) = signature.strip_suffix(')')?.split_once('(')?;
+  let items = items(args)?;
+  match (name, items.as_slice()) {
+    ("single", [Item::Char(ch)]) => Some(string(&[*ch])),
+    ("seq", _) => Some(string(&chars_of(&items)?)),
+    ("one_of", _) => Some(format!("/[{}]/", class(&chars_of(&items)?))),
+    ("none_of", _) => Some(format!("/[^{}]/", class(&chars_of(&items)?))),
+    ("ranges", [Item::Open, ranges @ .., Item::Close]) => {
+      let mut js = String::from("/[");
+      for item in ranges {
+        match item {
+          Item::Range(begin, end) => js.push_str(&format!("{}-{}", class(&[*begin]), class(&[*end]))),
+          _ => return None,
+        }
+      }
+      js.push_str("]/");
+      Some(js)
+    }
+    ("one_of_seqs", [Item::Open, seqs @ .., Item::Close]) => {
+      let mut strings = Vec::new();
+      let mut rest = seqs;
+      while !rest.is_empty() {
+        let end = rest.iter().position(|item| *item == Item::Close)?;
+        strings.push(string(&chars_of(&rest[..=end])?));
+        rest = &rest[end + 1..];
+      }
+      Some(if strings.len() == 1 { strings.remove(0) } else { format!("choice({})", strings.join(", ")) })
+    }
+    _ => None,
+  }
+}
+
+/// The items of the arguments of a signature formatted by `Debug`, such as `['a', 'b']` or `['0'..='9']`.
+///
+#[derive(Debug, PartialEq)]
+enum Item {
+  Open,
+  Close,
+  Char(char),
+  Range(char, char),
+}
+
+fn items(args: &str) -> Option<Vec<Item>> {
+  let mut items = Vec::new();
+  let mut chars = args.chars().peekable();
+  while let Some(ch) = chars.next() {
+    match ch {
+      ' ' | ',' => (),
+      '[' => items.push(Item::Open),
+      ']' => items.push(Item::Close),
+      '\'' => {
+        let begin = char_literal(&mut chars)?;
+        if chars.peek() == Some(&'.') {
+          for expected in ['.', '.', '=', '\''] {
+            if chars.next() != Some(expected) {
+              return None;
+            }
+          }
+          items.push(Item::Range(begin, char_literal(&mut chars)?));
+        } else {
+          items.push(Item::Char(begin));
+        }
+      }
+      _ => return None,
+    }
+  }
+  Some(items)
+}
+
+/// The rest of a character literal escaped by `Debug` after the opening quote, including the closing quote.
+///
+fn char_literal<I: Iterator<Item = char>>(chars: &mut I) -> Option<char> {
+  let ch = match chars.next()? {
+    '\\' => match chars.next()? {
+      'n' => '\n',
+      'r' => '\r',
+      't' => '\t',
+      '0' => '\0',
+      'u' => {
+        if chars.next()? != '{' {
+          return None;
+        }
+        let hex = chars.by_ref().take_while(|ch| *ch != '}').collect::<String>();
+        char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+      }
+      ch => ch,
+    },
+    ch => ch,
+  };
+  (chars.next()? == '\'').then_some(ch)
+}
+
+/// The characters of the items `[...]` that consist only of characters.
+///
+fn chars_of(items: &[Item]) -> Option<Vec<char>> {
+  match items {
+    [Item::Open, chars @ .., Item::Close] => chars
+      .iter()
+      .map(|item| match item {
+        Item::Char(ch) => Some(*ch),
+        _ => None,
+      })
+      .collect(),
+    _ => None,
+  }
+}
+
+fn string(chars: &[char]) -> String {
+  if chars.is_empty() {
+    String::from("blank()")
+  } else {
+    json_string(&chars.iter().collect::<String>())
+  }
+}
+
+/// The characters escaped to be in a character class of a regular expression.
+///
+fn class(chars: &[char]) -> String {
+  let mut class = String::with_capacity(chars.len());
+  for ch in chars {
+    match ch {
+      '\\' | ']' | '[' | '^' | '-' | '/' => {
+        class.push('\\');
+        class.push(*ch);
+      }
+      '\n' => class.push_str(r"\n"),
+      '\r' => class.push_str(r"\r"),
+      '\t' => class.push_str(r"\t"),
+      ch if ch.is_control() => class.push_str(&format!("\\u{:04x}", *ch as u32)),
+      ch if (*ch as u32) > 0xFFFF => class.push_str(&format!("\\u{{{:x}}}", *ch as u32)),
+      ch => class.push(*ch),
+    }
+  }
+  class
+}
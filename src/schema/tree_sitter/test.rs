@@ -0,0 +1,124 @@
+use crate::schema::chars::{ascii_digit, ch, line, newline, none_of_chars, one_of_tokens, token};
+use crate::schema::pratt::{pratt, Assoc};
+use crate::schema::tree_sitter::{grammar_js, literal, rule_name};
+use crate::schema::{eof, id, json, MatchResult, Schema, Syntax};
+
+#[test]
+fn grammar_js_of_small_schema() {
+  let schema = Schema::new("KeyValue")
+    .define("Value", ascii_digit() * (1..=3))
+    .define("Pair", id("Key") & ch('=') & id("Value") & (ch(';') * (0..=1)))
+    .define("Key", token("key") | token("name"));
+  let expected = r#"module.exports = grammar({
+  name: "key_value",
+
+  extras: $ => [],
+
+  rules: {
+    pair: $ => seq($.key, "=", $.value, optional(";")),
+    value: $ => seq(/[0-9]/, optional(seq(/[0-9]/, optional(/[0-9]/)))),
+    key: $ => choice("key", "name"),
+  }
+});
+"#;
+  assert_eq!(expected, grammar_js(&schema, &"Pair"));
+}
+
+#[test]
+fn grammar_js_of_json() {
+  let schema = json::schema();
+  let js = grammar_js(&schema, &json::ID::JsonText);
+  assert!(js.contains("  name: \"json\",\n"));
+  assert!(js.contains("    json_text: $ => seq($.ws, $.value, $.ws),\n"), "{}", js);
+  assert!(js.contains("    begin_array: $ => seq($.ws, \"[\", $.ws),\n"), "{}", js);
+  assert!(js.contains("    hex_dig: $ => "), "{}", js);
+  assert!(js.contains("    digit1_9: $ => /[1-9]/,\n"), "{}", js);
+  assert!(!js.contains("externals"));
+  assert_eq!(schema.ids().count(), js.lines().filter(|l| l.contains(": $ => ")).count() - 1);
+}
+
+#[test]
+fn grammar_js_of_pratt() {
+  let schema = Schema::new("Arithmetic").define("NUM", ascii_digit() * (1..)).define_pratt(
+    "EXPR",
+    pratt(id("NUM"))
+      .prefix(ch('-'), 3)
+      .infix(ch('+'), 1, Assoc::Left)
+      .infix(ch('^'), 4, Assoc::Right)
+      .postfix(ch('!'), 5),
+  );
+  let js = grammar_js(&schema, &"EXPR");
+  let expected = concat!(
+    r#"    expr: $ => choice($.num, prec(9, seq("-", $.expr)), prec.left(4, seq($.expr, "+", $.expr)), "#,
+    r#"prec.right(10, seq($.expr, "^", $.expr)), prec(12, seq($.expr, "!"))),"#
+  );
+  assert!(js.lines().any(|l| l == expected), "{}", js);
+  assert!(js.find("expr: $ =>") < js.find("num: $ =>"));
+}
+
+#[test]
+fn grammar_js_with_externals() {
+  let indent = Syntax::from_fn("INDENT", |_: &[char]| Ok(MatchResult::Unmatch));
+  let schema = Schema::new("Foo")
+    .define("A", indent.clone() & id("B") & eof())
+    .define("B", indent & Syntax::from_fn("INDENT", |_: &[char]| Ok(MatchResult::Unmatch)) & line() & newline());
+  let js = grammar_js(&schema, &"A");
+  assert!(
+    js.contains("  externals: $ => [\n    $.term_indent,\n    $.term_eof,\n    $.term_indent_2,\n  ],\n"),
+    "{}",
+    js
+  );
+  assert!(js.contains("    a: $ => seq($.term_indent, $.b, $.term_eof),\n"), "{}", js);
+  assert!(
+    js.contains(r#"    b: $ => seq($.term_indent, $.term_indent_2, optional(/[^\r\n]+/), /\r?\n|\r/),"#),
+    "{}",
+    js
+  );
+}
+
+#[test]
+fn grammar_js_of_repetitions() {
+  let schema =
+    Schema::new("Foo").define("A", (ch('a') * (0..)) & (ch('b') * (2..)) & (ch('c') * 2) & (ch('d') * (0..=0)));
+  let js = grammar_js(&schema, &"A");
+  assert!(js.contains(r#"    a: $ => seq(repeat("a"), seq("b", repeat1("b")), seq("c", "c"), blank()),"#), "{}", js);
+}
+
+#[test]
+fn literals() {
+  assert_eq!(Some(r#""\"""#.to_string()), literal(&format!("single({:?})", '"')));
+  assert_eq!(Some(r#""a\n'""#.to_string()), literal(&format!("seq({:?})", vec!['a', '\n', '\''])));
+  assert_eq!(
+    Some(r"/[^\]\-\\\u0000é]/".to_string()),
+    literal(&format!("none_of({:?})", vec![']', '-', '\\', '\0', 'é']))
+  );
+  assert_eq!(
+    Some(r"/[a-z\u007f-\u{10ffff}]/".to_string()),
+    literal(&format!("ranges({:?})", vec!['a'..='z', '\u{7f}'..='\u{10ffff}']))
+  );
+  assert_eq!(
+    Some(r#"choice("ab", "c")"#.to_string()),
+    literal(&format!("one_of_seqs({:?})", vec![vec!['a', 'b'], vec!['c']]))
+  );
+  assert_eq!(None, literal("until_token(\"*/\")"));
+  assert_eq!(None, literal("eof"));
+
+  let schema = Schema::<&str, char>::new("Foo").define("A", none_of_chars("\"\\") | one_of_tokens(&["if", "else"]));
+  assert!(grammar_js(&schema, &"A").contains(r#"    a: $ => choice(/[^"\\]/, choice("if", "else")),"#));
+}
+
+#[test]
+fn rule_names() {
+  for (expected, name) in [
+    ("json_text", "JsonText"),
+    ("ws", "WS"),
+    ("http_header", "HTTPHeader"),
+    ("digit1_9", "Digit1_9"),
+    ("begin_array", "begin-array"),
+    ("r_1st", "1st"),
+    ("r", "--"),
+    ("e", "E"),
+  ] {
+    assert_eq!(expected, rule_name(name));
+  }
+}
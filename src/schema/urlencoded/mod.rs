@@ -0,0 +1,89 @@
+use crate::parser::{Context, Event, EventKind};
+use crate::schema::chars::{ch, none_of_chars};
+use crate::schema::{id, range, Schema};
+use crate::Result;
+use std::fmt::Display;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Hash, Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ID {
+  Query,
+  Pair,
+  Key,
+  Value,
+  PctEncoded,
+  HexDig,
+}
+
+impl Display for ID {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+/// The `application/x-www-form-urlencoded` format and the query strings of URLs, such as `a=1&b=x%20y`.
+/// <https://url.spec.whatwg.org/#application/x-www-form-urlencoded>
+///
+/// The [`ID::Key`] and [`ID::Value`] events hold the encoded symbols, which are decoded by [`decode()`]. Empty pairs
+/// such as `a=1&&b=2` are allowed, while a `%` not followed by two hexadecimal digits is an error.
+///
+pub fn schema() -> Schema<ID, char> {
+  use ID::*;
+  Schema::new("URLENCODED")
+    .define(Query, id(Pair) & ((ch('&') & id(Pair)) * (0..)))
+    .define(Pair, id(Key) & ((ch('=') & id(Value)) * (0..=1)))
+    .define(Key, (id(PctEncoded) | none_of_chars("&=#%")) * (0..))
+    .define(Value, (id(PctEncoded) | none_of_chars("&#%")) * (0..))
+    .define(PctEncoded, ch('%') & id(HexDig) & id(HexDig))
+    .define(HexDig, range('0'..='9') | range('a'..='f') | range('A'..='F'))
+}
+
+/// Decode the percent-encoded bytes and `+` as a space. The invalid UTF-8 sequences are replaced with U+FFFD.
+///
+pub fn decode(encoded: &str) -> String {
+  let mut bytes = Vec::with_capacity(encoded.len());
+  let mut chars = encoded.bytes();
+  while let Some(b) = chars.next() {
+    match b {
+      b'+' => bytes.push(b' '),
+      b'%' => {
+        let hex = [chars.next(), chars.next()];
+        match hex.map(|h| h.and_then(|h| (h as char).to_digit(16))) {
+          [Some(h), Some(l)] => bytes.push((h * 16 + l) as u8),
+          _ => {
+            bytes.push(b'%');
+            bytes.extend(hex.iter().flatten());
+          }
+        }
+      }
+      b => bytes.push(b),
+    }
+  }
+  String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Parse `text` and return the decoded key-value pairs in the order of appearance, where a pair without `=` has an
+/// empty value. The empty pairs are skipped.
+///
+pub fn pairs(text: &str) -> Result<char, Vec<(String, String)>> {
+  let schema = schema();
+  let mut pairs = Vec::new();
+  let mut current = None;
+  let handler = |e: &Event<ID, char>| match &e.kind {
+    EventKind::Begin(ID::Pair) => pairs.push((String::new(), String::new())),
+    EventKind::Begin(id @ (ID::Key | ID::Value)) => current = Some(*id),
+    EventKind::End(ID::Key | ID::Value) => current = None,
+    EventKind::Fragments(chars) => match (current, pairs.last_mut()) {
+      (Some(ID::Key), Some((key, _))) => key.extend(chars),
+      (Some(ID::Value), Some((_, value))) => value.extend(chars),
+      _ => (),
+    },
+    _ => (),
+  };
+  let mut parser = Context::new(&schema, ID::Query, handler)?.ignore_events_for(&[ID::PctEncoded, ID::HexDig]);
+  parser.push_str(text)?;
+  parser.finish()?;
+  Ok(pairs.into_iter().map(|(k, v)| (decode(&k), decode(&v))).collect())
+}
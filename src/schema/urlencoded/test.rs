@@ -0,0 +1,60 @@
+use super::{decode, pairs, schema, ID};
+use crate::parser::{test::Events, Context, Event};
+
+#[test]
+fn query() {
+  let schema = schema();
+  let mut events = Vec::new();
+  let handler = |e: &Event<_, _>| events.push(e.clone());
+  let mut parser = Context::new(&schema, ID::Query, handler).unwrap().ignore_events_for(&[ID::PctEncoded, ID::HexDig]);
+  parser.push_str("a=1&b%20c=x+y&d").unwrap();
+  parser.finish().unwrap();
+  Events::new()
+    .begin(ID::Query)
+    .begin(ID::Pair)
+    .begin(ID::Key)
+    .fragments("a")
+    .end()
+    .fragments("=")
+    .begin(ID::Value)
+    .fragments("1")
+    .end()
+    .end()
+    .fragments("&")
+    .begin(ID::Pair)
+    .begin(ID::Key)
+    .fragments("b%20c")
+    .end()
+    .fragments("=")
+    .begin(ID::Value)
+    .fragments("x+y")
+    .end()
+    .end()
+    .fragments("&")
+    .begin(ID::Pair)
+    .begin(ID::Key)
+    .fragments("d")
+    .end()
+    .end()
+    .end()
+    .assert_eq(&Event::normalize(&events));
+}
+
+#[test]
+fn decoded_pairs() {
+  let p = |k: &str, v: &str| (k.to_string(), v.to_string());
+  assert_eq!(Vec::<(String, String)>::new(), pairs("").unwrap());
+  assert_eq!(vec![p("a", "1"), p("b c", "x y"), p("d", "")], pairs("a=1&b%20c=x+y&d").unwrap());
+  assert_eq!(vec![p("a", "1=2"), p("", "x"), p("k", "")], pairs("a=1=2&&=x&k=").unwrap());
+  assert_eq!(vec![p("名前", "テスト")], pairs("%E5%90%8D%E5%89%8D=%e3%83%86%e3%82%b9%e3%83%88").unwrap());
+  assert!(pairs("a=%2").is_err());
+  assert!(pairs("a=%zz").is_err());
+}
+
+#[test]
+fn decode_percent_encoding() {
+  assert_eq!("a b+c", decode("a+b%2Bc"));
+  assert_eq!("100%", decode("100%"));
+  assert_eq!("%zz", decode("%zz"));
+  assert_eq!("\u{FFFD}", decode("%FF"));
+}
@@ -0,0 +1,134 @@
+//! The serialization of the errors, enabled by the `serde` feature, so that they can be returned to clients or logged
+//! in a structured form such as JSON.
+//!
+//! A location is serialized as a map of the numbers `position` and, if available, `line`, `column` and `byte_offset`.
+//! An error is serialized as a map with the variant name as `kind`, its fields and the Display string as `message`.
+//! An event is serialized in the same structure as [`JsonLines`](crate::parser::JsonLines).
+//!
+use crate::parser::{Encodable, Event, EventKind};
+use crate::schema::{Location, Symbol};
+use crate::{Error, ErrorDetail};
+use serde::ser::{SerializeMap, SerializeStruct};
+use serde::{Serialize, Serializer};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+struct SerializableLocation<'a, Σ: Symbol>(&'a Σ::Location);
+
+impl<Σ: Symbol> Serialize for SerializableLocation<'_, Σ> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let location = self.0;
+    let mut map = serializer.serialize_map(None)?;
+    map.serialize_entry("position", &location.position())?;
+    if let Some(line) = location.line() {
+      map.serialize_entry("line", &line)?;
+    }
+    if let Some(column) = location.column() {
+      map.serialize_entry("column", &column)?;
+    }
+    if let Some(bytes) = location.byte_offset() {
+      map.serialize_entry("byte_offset", &bytes)?;
+    }
+    map.end()
+  }
+}
+
+impl<Σ: Symbol> Serialize for ErrorDetail<Σ> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_struct("ErrorDetail", 5)?;
+    s.serialize_field("location", &SerializableLocation::<Σ>(&self.location))?;
+    s.serialize_field("prefix", &self.prefix)?;
+    s.serialize_field("expecteds", &self.expecteds)?;
+    s.serialize_field("expected_syntaxes", &self.expected_syntaxes)?;
+    s.serialize_field("actual", &self.actual)?;
+    s.end()
+  }
+}
+
+impl<Σ: Symbol> Serialize for Error<Σ> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(None)?;
+    match self {
+      Error::Unmatched(detail) | Error::MultipleMatches(detail) => {
+        let kind = if matches!(self, Error::Unmatched(_)) { "Unmatched" } else { "MultipleMatches" };
+        map.serialize_entry("kind", kind)?;
+        map.serialize_entry("location", &SerializableLocation::<Σ>(&detail.location))?;
+        map.serialize_entry("prefix", &detail.prefix)?;
+        map.serialize_entry("expecteds", &detail.expecteds)?;
+        map.serialize_entry("expected_syntaxes", &detail.expected_syntaxes)?;
+        map.serialize_entry("actual", &detail.actual)?;
+      }
+      Error::UndefinedID(_) => map.serialize_entry("kind", "UndefinedID")?,
+      Error::Previous => map.serialize_entry("kind", "Previous")?,
+      Error::LimitExceeded { location, name, limit } => {
+        map.serialize_entry("kind", "LimitExceeded")?;
+        map.serialize_entry("location", &SerializableLocation::<Σ>(location))?;
+        map.serialize_entry("name", name)?;
+        map.serialize_entry("limit", limit)?;
+      }
+      Error::TooLong { location, id, limit } => {
+        map.serialize_entry("kind", "TooLong")?;
+        map.serialize_entry("location", &SerializableLocation::<Σ>(location))?;
+        map.serialize_entry("id", id)?;
+        map.serialize_entry("limit", limit)?;
+      }
+      Error::Recovered(errors) => {
+        map.serialize_entry("kind", "Recovered")?;
+        map.serialize_entry("errors", errors)?;
+      }
+      Error::Unreadable { location, available } => {
+        map.serialize_entry("kind", "Unreadable")?;
+        map.serialize_entry("location", &SerializableLocation::<Σ>(location))?;
+        map.serialize_entry("available", available)?;
+      }
+      Error::Passthrough { location, remaining } => {
+        map.serialize_entry("kind", "Passthrough")?;
+        map.serialize_entry("location", &SerializableLocation::<Σ>(location))?;
+        map.serialize_entry("remaining", remaining)?;
+      }
+      Error::Handler(_) => map.serialize_entry("kind", "Handler")?,
+      Error::Unsupported { operation, engine } => {
+        map.serialize_entry("kind", "Unsupported")?;
+        map.serialize_entry("operation", operation)?;
+        map.serialize_entry("engine", &format!("{:?}", engine))?;
+      }
+      Error::Internal { location, .. } => {
+        map.serialize_entry("kind", "Internal")?;
+        map.serialize_entry("location", &SerializableLocation::<Σ>(location))?;
+      }
+    }
+    map.serialize_entry("message", &self.to_string())?;
+    map.end()
+  }
+}
+
+impl<ID, Σ: Encodable> Serialize for Event<ID, Σ>
+where
+  ID: Clone + Display + Debug + PartialEq + Eq + Hash,
+{
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(None)?;
+    match &self.kind {
+      EventKind::Begin(id) | EventKind::End(id) => {
+        let kind = if matches!(self.kind, EventKind::Begin(_)) { "Begin" } else { "End" };
+        map.serialize_entry("kind", kind)?;
+        map.serialize_entry("id", &id.to_string())?;
+      }
+      EventKind::Fragments(items) => {
+        map.serialize_entry("kind", "Fragments")?;
+        let mut bytes = Vec::new();
+        Σ::encode(items, &mut bytes);
+        if Σ::TEXT {
+          map.serialize_entry("fragments", &String::from_utf8_lossy(&bytes))?;
+        } else {
+          map.serialize_entry("fragments", &bytes)?;
+        }
+      }
+    }
+    map.serialize_entry("location", &SerializableLocation::<Σ>(&self.location))?;
+    if let Some(end) = &self.end {
+      map.serialize_entry("end", &SerializableLocation::<Σ>(end))?;
+    }
+    map.end()
+  }
+}
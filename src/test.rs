@@ -1,24 +1,33 @@
 use crate::schema::chars::Location;
-use crate::Error;
+use crate::{Error, ErrorDetail, HandlerError};
 
 #[test]
 fn error_attributes() {
   for err in [
-    Error::<char>::Unmatched {
+    Error::<char>::Unmatched(Box::new(ErrorDetail {
       location: Location::default(),
       prefix: String::default(),
       expecteds: Vec::default(),
       expected_syntaxes: Vec::default(),
       actual: String::default(),
-    },
-    Error::MultipleMatches {
+    })),
+    Error::MultipleMatches(Box::new(ErrorDetail {
       location: Location::default(),
       prefix: String::default(),
       expecteds: Vec::default(),
+      expected_syntaxes: Vec::default(),
       actual: String::default(),
-    },
+    })),
     Error::UndefinedID(String::default()),
     Error::Previous,
+    Error::LimitExceeded { location: Location::default(), name: "max_depth", limit: 0 },
+    Error::TooLong { location: Location::default(), id: String::from("A"), limit: 0 },
+    Error::Recovered(vec![Error::Previous]),
+    Error::Unreadable { location: Location::default(), available: 0 },
+    Error::Passthrough { location: Location::default(), remaining: 0 },
+    Error::Handler(HandlerError::new("broken pipe")),
+    Error::Unsupported { operation: "forest", engine: crate::parser::Engine::Streaming },
+    Error::Internal { location: Location::default(), message: String::from("inconsistent event") },
   ] {
     let _ = format!("{:?}", err);
     let _ = format!("{}", err);
@@ -27,3 +36,52 @@ fn error_attributes() {
     assert!(!err.ne(&err));
   }
 }
+
+#[cfg(feature = "serde")]
+#[test]
+fn error_serialization() {
+  use crate::parser::{Context, Event};
+  use crate::schema::chars::{ascii_digit, ch};
+  use crate::schema::Schema;
+  use serde_json::json;
+
+  let schema = Schema::new("Foo").define("A", ch('a') & ascii_digit());
+  let mut parser = Context::new(&schema, "A", |_: &Event<_, _>| ()).unwrap();
+  let err = parser.push_str("ax").and_then(|_| parser.finish()).unwrap_err();
+  let value = serde_json::to_value(&err).unwrap();
+  assert_eq!("Unmatched", value["kind"]);
+  assert_eq!(json!({ "position": 1, "line": 0, "column": 1, "byte_offset": 1 }), value["location"]);
+  assert_eq!(json!(["[ASCII_DIGIT]"]), value["expecteds"]);
+  assert_eq!(json!(err.to_string()), value["message"]);
+
+  let err = Error::<char>::Recovered(vec![Error::Passthrough { location: Location::new(3, 1, 2), remaining: 4 }]);
+  let value = serde_json::to_value(&err).unwrap();
+  assert_eq!("Recovered", value["kind"]);
+  assert_eq!("Passthrough", value["errors"][0]["kind"]);
+  assert_eq!(json!({ "position": 3, "line": 1, "column": 2, "byte_offset": 0 }), value["errors"][0]["location"]);
+  assert_eq!(4, value["errors"][0]["remaining"]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn event_serialization() {
+  use crate::parser::{Context, Event, JsonLines};
+  use crate::schema::chars::ascii_digit;
+  use crate::schema::Schema;
+
+  let schema = Schema::new("Foo").define("NUM", ascii_digit() * (1..));
+  let mut events = Vec::new();
+  let mut parser =
+    Context::new(&schema, "NUM", |e: &Event<_, _>| events.push(e.clone())).unwrap().with_fragment_ends(true);
+  parser.push_str("42").unwrap();
+  parser.finish().unwrap();
+
+  let mut sink = JsonLines::new(Vec::new());
+  let mut expected = String::new();
+  for e in &events {
+    sink.handle(e).unwrap();
+    expected.push_str(&serde_json::to_string(e).unwrap());
+    expected.push('\n');
+  }
+  assert_eq!(expected, String::from_utf8(sink.into_inner()).unwrap());
+}
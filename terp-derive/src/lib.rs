@@ -0,0 +1,153 @@
+//! `#[derive(Bind)]` that implements `terp::bind::Bind` for a struct whose fields are bound to the rules of a schema.
+//!
+//! ```text
+//! #[derive(Default, Bind)]
+//! struct Server {
+//!   #[terp(rule = "Port", with = "parse_port", guard = "nonzero")]
+//!   port: u16,
+//! }
+//! ```
+//!
+//! - `rule = "..."`: the name of the rule whose text is bound to the field, as displayed by the ID of the schema.
+//! - `with = "..."`: the path of `fn(&str) -> Result<T, E>` to decode the text, where `E: Display`. It's `FromStr` if
+//!   omitted.
+//! - `guard = "..."`: the path of `fn(&T) -> bool` that the decoded value must satisfy.
+//!
+//! The fields without `#[terp(...)]` are left as they are.
+//!
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, GenericArgument, LitStr, Path, PathArguments, Type};
+
+#[proc_macro_derive(Bind, attributes(terp))]
+pub fn derive_bind(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  match expand(input) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+/// The binding of a field specified by `#[terp(...)]`.
+///
+struct Binding {
+  rule: LitStr,
+  with: Option<Path>,
+  guard: Option<Path>,
+}
+
+/// How the decoded value is stored in the field.
+///
+enum Store<'a> {
+  Assign(&'a Type),
+  Some(&'a Type),
+  Push(&'a Type),
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+  let fields = match &input.data {
+    Data::Struct(data) => match &data.fields {
+      Fields::Named(fields) => &fields.named,
+      _ => return Err(syn::Error::new_spanned(&input.ident, "Bind can only be derived for structs with named fields")),
+    },
+    _ => return Err(syn::Error::new_spanned(&input.ident, "Bind can only be derived for structs")),
+  };
+
+  let mut rules = Vec::new();
+  let mut arms = Vec::new();
+  for field in fields {
+    let binding = match binding(field)? {
+      Some(binding) => binding,
+      None => continue,
+    };
+    let name = field.ident.as_ref().unwrap();
+    let (ty, store) = match store(&field.ty) {
+      Store::Assign(ty) => (ty, quote!(self.#name = value;)),
+      Store::Some(ty) => (ty, quote!(self.#name = ::std::option::Option::Some(value);)),
+      Store::Push(ty) => (ty, quote!(self.#name.push(value);)),
+    };
+    let decode = match &binding.with {
+      Some(with) => quote!(#with(text).map_err(|err| ::std::string::ToString::to_string(&err))?),
+      None => quote!(::terp::bind::decode::<#ty>(text)?),
+    };
+    let guard = binding.guard.as_ref().map(|guard| {
+      let message = format!("rejected by {}", quote!(#guard).to_string().replace(' ', ""));
+      quote! {
+        if !#guard(&value) {
+          return ::std::result::Result::Err(::std::string::String::from(#message));
+        }
+      }
+    });
+    let rule = &binding.rule;
+    arms.push(quote! {
+      if rule == #rule {
+        let value: #ty = #decode;
+        #guard
+        #store
+      }
+    });
+    if !rules.iter().any(|r: &LitStr| r.value() == rule.value()) {
+      rules.push(rule.clone());
+    }
+  }
+
+  let ident = &input.ident;
+  let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+  Ok(quote! {
+    impl #impl_generics ::terp::bind::Bind for #ident #ty_generics #where_clause {
+      fn rules() -> &'static [&'static str] {
+        &[#(#rules),*]
+      }
+
+      fn bind(&mut self, rule: &str, text: &str) -> ::std::result::Result<(), ::std::string::String> {
+        #(#arms)*
+        ::std::result::Result::Ok(())
+      }
+    }
+  })
+}
+
+fn binding(field: &syn::Field) -> syn::Result<Option<Binding>> {
+  let attr = match field.attrs.iter().find(|attr| attr.path().is_ident("terp")) {
+    Some(attr) => attr,
+    None => return Ok(None),
+  };
+  let mut rule = None;
+  let mut with = None;
+  let mut guard = None;
+  attr.parse_nested_meta(|meta| {
+    let value = meta.value()?.parse::<LitStr>()?;
+    if meta.path.is_ident("rule") {
+      rule = Some(value);
+    } else if meta.path.is_ident("with") {
+      with = Some(value.parse::<Path>()?);
+    } else if meta.path.is_ident("guard") {
+      guard = Some(value.parse::<Path>()?);
+    } else {
+      return Err(meta.error("expected `rule`, `with` or `guard`"));
+    }
+    Ok(())
+  })?;
+  match rule {
+    Some(rule) => Ok(Some(Binding { rule, with, guard })),
+    None => Err(syn::Error::new_spanned(attr, "`rule` must be specified")),
+  }
+}
+
+fn store(ty: &Type) -> Store<'_> {
+  if let Type::Path(path) = ty {
+    if let Some(segment) = path.path.segments.last() {
+      if let PathArguments::AngleBracketed(args) = &segment.arguments {
+        if let Some(GenericArgument::Type(inner)) = args.args.first() {
+          if segment.ident == "Option" {
+            return Store::Some(inner);
+          } else if segment.ident == "Vec" {
+            return Store::Push(inner);
+          }
+        }
+      }
+    }
+  }
+  Store::Assign(ty)
+}
@@ -1,6 +1,7 @@
 #![feature(test)]
 
 pub mod nom;
+pub mod verify;
 
 #[cfg(test)]
 mod bench;
@@ -3,8 +3,10 @@ extern crate test;
 
 use clap::{Parser, Subcommand};
 use std::fs;
+use std::path::PathBuf;
 use terp::parser::Context;
 use terp::schema::json::{schema, ID};
+use terp_prof::verify::{Oracle, ORACLES};
 use test::bench::iter;
 use test::stats::Summary;
 
@@ -24,7 +26,22 @@ enum Commands {
   Parse {
     #[clap(value_parser)]
     file: String,
-  }
+  },
+  Profile {
+    /// The JSON schema to profile, `tuned` or `naive`
+    #[clap(long, value_parser, default_value = "tuned")]
+    schema: String,
+    #[clap(value_parser)]
+    files: Vec<String>,
+  },
+  Verify {
+    /// The format whose schema is compared with its reference parser, `json`
+    #[clap(long, value_parser, default_value = "json")]
+    format: String,
+    /// The files or the directories of the corpora
+    #[clap(value_parser)]
+    paths: Vec<String>,
+  },
 }
 
 fn main() {
@@ -41,6 +58,34 @@ fn main() {
     Commands::Parse { file } => {
       parse(&file);
     }
+    Commands::Profile { schema, files } => {
+      if files.is_empty() {
+        eprintln!("ERROR: JSON files are not specified.");
+      }
+      let schema = match schema.as_str() {
+        "tuned" => self::schema(),
+        "naive" => naive_schema(),
+        _ => {
+          eprintln!("ERROR: unknown schema: {}", schema);
+          return;
+        }
+      };
+      for file in files {
+        profile(&schema, &file);
+      }
+    }
+    Commands::Verify { format, paths } => {
+      let oracle = match ORACLES.iter().find(|oracle| oracle.name == format.as_str()) {
+        Some(oracle) => oracle,
+        None => {
+          eprintln!("ERROR: unknown format: {}", format);
+          std::process::exit(2);
+        }
+      };
+      if !verify(oracle, paths) {
+        std::process::exit(1);
+      }
+    }
   }
 }
 
@@ -52,11 +97,70 @@ fn parse(filename: &str) {
   parser.finish().unwrap();
 }
 
+fn profile(schema: &terp::schema::Schema<ID, char>, filename: &str) {
+  let content = fs::read_to_string(filename).unwrap();
+  let mut parser = Context::new(schema, ID::JsonText, |_| ()).unwrap().profiling();
+  parser.push_str(&content).unwrap();
+  let profile = parser.finish_profile().unwrap();
+
+  let total = profile.total();
+  println!("[{}: {} chars, {} ns]", filename, num(content.chars().count() as u64), num(total.time.as_nanos() as u64));
+  println!("  {:16} {:>14} {:>6} {:>12} {:>12} {:>10}", "rule", "time (ns)", "%", "evaluations", "spawns", "merges");
+  for (id, rule) in profile.hottest() {
+    let ratio = rule.time.as_secs_f64() * 100.0 / total.time.as_secs_f64().max(f64::MIN_POSITIVE);
+    println!(
+      "  {:16} {:>14} {:>5.1}% {:>12} {:>12} {:>10}",
+      id.to_string(),
+      num(rule.time.as_nanos() as u64),
+      ratio,
+      num(rule.evaluations as u64),
+      num(rule.spawns as u64),
+      num(rule.merges as u64)
+    );
+  }
+}
+
+/// Compare the decisions of `oracle` for all the files in `paths`, and report the divergences. Returns false if any.
+///
+fn verify(oracle: &Oracle, paths: &[String]) -> bool {
+  let mut files = Vec::new();
+  let mut dirs = paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+  while let Some(path) = dirs.pop() {
+    if path.is_dir() {
+      dirs.extend(fs::read_dir(&path).unwrap().map(|entry| entry.unwrap().path()));
+    } else {
+      files.push(path);
+    }
+  }
+  files.sort();
+
+  let mut divergences = 0;
+  let mut skipped = 0;
+  for file in &files {
+    let input = match String::from_utf8(fs::read(file).unwrap()) {
+      Ok(input) => input,
+      Err(_) => {
+        skipped += 1;
+        continue;
+      }
+    };
+    if let Some(divergence) = oracle.verify(&input) {
+      let decision = |accepted: bool| if accepted { "accepted" } else { "rejected" };
+      println!("{}: terp {}, but the reference {}", file.display(), decision(divergence.terp), decision(!divergence.terp));
+      println!("  minimized: {:?}", divergence.minimized);
+      divergences += 1;
+    }
+  }
+  println!("[{}: {} files, {} divergences, {} skipped as non-UTF-8]", oracle.name, files.len(), divergences, skipped);
+  divergences == 0
+}
+
 fn bench(filename: &str) {
   let content = fs::read_to_string(filename).unwrap();
   println!("[{}: {} chars]", filename, num(content.chars().count() as u64));
   bench_report("  terp", bench_terp(&content));
   bench_report("  terp (naive)", bench_terp_naive(&content));
+  bench_report("  terp (recognizer)", bench_terp_recognizer(&content));
   bench_report("  nom", bench_nom(&content));
   bench_report("  serde_json", bench_serde_json(&content));
 }
@@ -79,6 +183,15 @@ fn bench_terp_naive(content: &str) -> Summary {
   })
 }
 
+fn bench_terp_recognizer(content: &str) -> Summary {
+  let schema = schema();
+  iter(&mut || {
+    let mut parser = Context::recognizer(&schema, ID::JsonText).unwrap();
+    parser.push_str(content).unwrap();
+    parser.finish().unwrap();
+  })
+}
+
 fn bench_nom(content: &str) -> Summary {
   iter(&mut || {
     terp_prof::nom::json_text(content).unwrap();
@@ -92,7 +205,7 @@ fn bench_serde_json(content: &str) -> Summary {
 }
 
 fn bench_report(target: &str, summary: Summary) {
-  println!("{target:15}: {:>10} ns/iter (±{:.1}%)", num(summary.median as u64), summary.median_abs_dev_pct);
+  println!("{target:20}: {:>10} ns/iter (±{:.1}%)", num(summary.median as u64), summary.median_abs_dev_pct);
 }
 
 fn num(n: u64) -> String {
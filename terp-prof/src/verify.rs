@@ -0,0 +1,85 @@
+//! Differential testing of the decisions of terp's bundled schemas, whether to accept or reject an input, against the
+//! reference parsers of the same formats.
+//!
+use std::sync::OnceLock;
+use terp::parser::Context;
+use terp::schema::json::{schema, ID};
+use terp::schema::Schema;
+
+/// A bundled schema paired with the reference parser of its format.
+///
+pub struct Oracle {
+  pub name: &'static str,
+  /// Whether terp accepts the input.
+  pub terp: fn(&str) -> bool,
+  /// Whether the reference parser accepts the input.
+  pub reference: fn(&str) -> bool,
+}
+
+/// The JSON schema of RFC 8259 against serde_json.
+///
+pub const JSON: Oracle = Oracle { name: "json", terp: terp_json, reference: serde_json_accepts };
+
+pub static ORACLES: [Oracle; 1] = [JSON];
+
+/// An input for which terp and the reference parser have made different decisions.
+///
+#[derive(Debug)]
+pub struct Divergence {
+  /// Whether terp accepted the input.
+  pub terp: bool,
+  /// The smallest input derived from the original one for which the decisions still diverge in the same way.
+  pub minimized: String,
+}
+
+impl Oracle {
+  /// Compare the decisions for `input`, and minimize it if they diverge.
+  ///
+  pub fn verify(&self, input: &str) -> Option<Divergence> {
+    let terp = (self.terp)(input);
+    if terp == (self.reference)(input) {
+      return None;
+    }
+    let minimized = minimize(input, |s| (self.terp)(s) == terp && (self.reference)(s) != terp);
+    Some(Divergence { terp, minimized })
+  }
+}
+
+fn terp_json(input: &str) -> bool {
+  static SCHEMA: OnceLock<Schema<ID, char>> = OnceLock::new();
+  let schema = SCHEMA.get_or_init(schema);
+  let mut parser = Context::recognizer(schema, ID::JsonText).unwrap();
+  parser.push_str(input).is_ok() && parser.finish().is_ok()
+}
+
+fn serde_json_accepts(input: &str) -> bool {
+  serde_json::from_str::<serde_json::Value>(input).is_ok()
+}
+
+/// Reduce `input` by removing the chunks of characters as long as `diverges` holds, with the delta debugging
+/// algorithm (ddmin). The result is 1-minimal: removing any single character of it makes `diverges` false.
+///
+pub fn minimize<F: Fn(&str) -> bool>(input: &str, diverges: F) -> String {
+  let mut chars = input.chars().collect::<Vec<_>>();
+  let mut n = 2;
+  while chars.len() >= 2 {
+    let chunk = chars.len().div_ceil(n);
+    let reduced = (0..n).map(|i| i * chunk).filter(|begin| *begin < chars.len()).find_map(|begin| {
+      let end = std::cmp::min(begin + chunk, chars.len());
+      let complement = chars[..begin].iter().chain(&chars[end..]).copied().collect::<Vec<_>>();
+      diverges(&complement.iter().collect::<String>()).then_some(complement)
+    });
+    match reduced {
+      Some(complement) => {
+        chars = complement;
+        n = std::cmp::max(n - 1, 2);
+      }
+      None if n >= chars.len() => break,
+      None => n = std::cmp::min(n * 2, chars.len()),
+    }
+  }
+  if chars.len() == 1 && diverges("") {
+    chars.clear();
+  }
+  chars.into_iter().collect()
+}